@@ -0,0 +1,25 @@
+#!/usr/bin/env python3
+"""
+Write the server's OpenAPI schema to openapi.json.
+
+openapi.json is the API contract the Rust client types are generated from
+(src-tauri/build.rs). Re-run this after changing a request or response model
+and commit the result together with the server change.
+"""
+
+import json
+from pathlib import Path
+
+from main import app
+
+OUTPUT = Path(__file__).parent / "openapi.json"
+
+
+def main():
+    schema = app.openapi()
+    OUTPUT.write_text(json.dumps(schema, indent=2, ensure_ascii=False) + "\n", encoding="utf-8")
+    print(f"Wrote {OUTPUT}")
+
+
+if __name__ == "__main__":
+    main()
@@ -146,6 +146,22 @@ class OcrResponse(BaseModel):
     raw_text: str  # Legacy: concatenated text for backward compatibility
 
 
+class HealthResponse(BaseModel):
+    """Server status"""
+    status: str
+    engine: str
+
+
+class ShutdownResponse(BaseModel):
+    """Shutdown acknowledgement"""
+    status: str
+
+
+class ErrorResponse(BaseModel):
+    """Error raised by an endpoint"""
+    detail: str
+
+
 # Helper functions
 def decode_base64_image(base64_str: str) -> np.ndarray:
     """Decode base64 string to numpy array"""
@@ -263,7 +279,7 @@ def _run_ocr_sync(image: np.ndarray, engine_idx: int) -> tuple[List[TextBox], st
     return (boxes, raw_text)
 
 
-@app.post("/ocr", response_model=OcrResponse)
+@app.post("/ocr", response_model=OcrResponse, responses={500: {"model": ErrorResponse}})
 async def recognize_text(request: ImageRequest):
     """
     Unified OCR endpoint - returns structured text boxes with bounding boxes.
@@ -300,13 +316,13 @@ async def recognize_text(request: ImageRequest):
         raise HTTPException(status_code=500, detail=f"OCR failed: {str(e)}")
 
 
-@app.get("/health")
+@app.get("/health", response_model=HealthResponse)
 async def health_check():
     """Health check endpoint"""
     return {"status": "ok", "engine": "RapidOCR"}
 
 
-@app.post("/shutdown")
+@app.post("/shutdown", response_model=ShutdownResponse)
 async def shutdown():
     """Graceful shutdown endpoint"""
     import asyncio
@@ -1,3 +1,101 @@
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// API contract of the Python OCR server (written by `python_ocr_server/export_openapi.py`)
+const OCR_API_SCHEMA: &str = "../python_ocr_server/openapi.json";
+
+/// Generated types the app uses; the rest (e.g. validation errors) are only
+/// checked by the contract tests and are allowed to be dead code
+const CLIENT_TYPES: &[&str] = &["ErrorResponse", "HealthResponse", "ImageRequest", "OcrResponse", "TextBox"];
+
 fn main() {
+    generate_ocr_api();
     tauri_build::build()
 }
+
+/// Generate the OCR server's request/response types from its OpenAPI schema
+/// into `$OUT_DIR/ocr_api.rs` (included by `services/ocr/api.rs`)
+fn generate_ocr_api() {
+    println!("cargo:rerun-if-changed={}", OCR_API_SCHEMA);
+
+    let content = std::fs::read_to_string(OCR_API_SCHEMA)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", OCR_API_SCHEMA, e));
+    let spec: Value = serde_json::from_str(&content)
+        .unwrap_or_else(|e| panic!("Failed to parse {}: {}", OCR_API_SCHEMA, e));
+    let schemas = spec["components"]["schemas"]
+        .as_object()
+        .unwrap_or_else(|| panic!("{} has no components.schemas", OCR_API_SCHEMA));
+
+    if let Some(missing) = CLIENT_TYPES.iter().find(|name| !schemas.contains_key(**name)) {
+        panic!("{} has no schema for {}", OCR_API_SCHEMA, missing);
+    }
+
+    let mut code = format!("// @generated by build.rs from {}, do not edit\n", OCR_API_SCHEMA);
+    for (name, schema) in schemas {
+        write_struct(&mut code, name, schema, CLIENT_TYPES.contains(&name.as_str()));
+    }
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR not set");
+    std::fs::write(Path::new(&out_dir).join("ocr_api.rs"), code).expect("Failed to write ocr_api.rs");
+}
+
+fn write_struct(code: &mut String, name: &str, schema: &Value, used: bool) {
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .map(|fields| fields.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    code.push('\n');
+    if let Some(description) = schema["description"].as_str() {
+        for line in description.lines() {
+            let _ = writeln!(code, "/// {}", line);
+        }
+    }
+    if !used {
+        let _ = writeln!(code, "#[allow(dead_code)]");
+    }
+    let _ = writeln!(code, "#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]");
+    let _ = writeln!(code, "pub struct {} {{", name);
+
+    if let Some(properties) = schema["properties"].as_object() {
+        for (field, property) in properties {
+            let mut ty = rust_type(property);
+            if !required.contains(&field.as_str()) {
+                ty = format!("Option<{}>", ty);
+                let _ = writeln!(code, "    #[serde(default, skip_serializing_if = \"Option::is_none\")]");
+            }
+            let ident = field_ident(field);
+            if ident != *field {
+                let _ = writeln!(code, "    #[serde(rename = \"{}\")]", field);
+            }
+            let _ = writeln!(code, "    pub {}: {},", ident, ty);
+        }
+    }
+    code.push_str("}\n");
+}
+
+/// Rust type of a property schema; anything not expressible stays JSON
+fn rust_type(schema: &Value) -> String {
+    if let Some(reference) = schema["$ref"].as_str() {
+        return reference.rsplit('/').next().unwrap_or(reference).to_string();
+    }
+    match schema["type"].as_str() {
+        Some("string") => "String".to_string(),
+        Some("number") => "f64".to_string(),
+        Some("integer") => "i64".to_string(),
+        Some("boolean") => "bool".to_string(),
+        Some("array") => format!("Vec<{}>", rust_type(&schema["items"])),
+        _ => "serde_json::Value".to_string(),
+    }
+}
+
+/// Field name usable as a Rust identifier ("box" -> "box_")
+fn field_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &["box", "type", "ref", "move", "match", "loop", "in", "fn", "mod", "use", "self"];
+    if KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
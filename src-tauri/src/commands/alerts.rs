@@ -0,0 +1,88 @@
+use crate::commands::config::ConfigManagerState;
+use crate::models::alert::AlertRule;
+use crate::models::config::AppConfig;
+use crate::services::ocr_tracker::TrackingStats;
+use tauri::State;
+
+/// Check a rule, including that its stat exists in this config
+fn validate_rule(rule: &AlertRule, config: &AppConfig) -> Result<(), String> {
+    rule.validate()?;
+
+    let stat = rule.condition.stat();
+    let known = TrackingStats::VARIABLES.contains(&stat)
+        || config.custom_metrics.iter().any(|m| m.name == stat)
+        || config.derived_metrics.iter().any(|m| m.name == stat);
+    if !known {
+        return Err(format!("Alert '{}' uses unknown stat '{}'", rule.name, stat));
+    }
+
+    Ok(())
+}
+
+/// Get all alert rules
+#[tauri::command]
+pub fn list_alert_rules(state: State<ConfigManagerState>) -> Result<Vec<AlertRule>, String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    Ok(manager.load()?.alerts)
+}
+
+/// Add an alert rule and return it with its assigned id
+#[tauri::command]
+pub fn create_alert_rule(state: State<ConfigManagerState>, mut rule: AlertRule) -> Result<AlertRule, String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    let mut config = manager.load()?;
+    validate_rule(&rule, &config)?;
+
+    // Creation time in ms, bumped if two rules are created within a millisecond
+    let mut id = chrono::Utc::now().timestamp_millis();
+    while config.alerts.iter().any(|r| r.id == id.to_string()) {
+        id += 1;
+    }
+    rule.id = id.to_string();
+
+    config.alerts.push(rule.clone());
+    manager.save(&config)?;
+
+    Ok(rule)
+}
+
+/// Replace an existing alert rule (matched by id)
+#[tauri::command]
+pub fn update_alert_rule(state: State<ConfigManagerState>, rule: AlertRule) -> Result<(), String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    let mut config = manager.load()?;
+    validate_rule(&rule, &config)?;
+
+    let existing = config.alerts.iter_mut()
+        .find(|r| r.id == rule.id)
+        .ok_or_else(|| format!("Alert rule with id '{}' not found", rule.id))?;
+    *existing = rule;
+
+    manager.save(&config)
+}
+
+/// Delete an alert rule
+#[tauri::command]
+pub fn delete_alert_rule(state: State<ConfigManagerState>, id: String) -> Result<(), String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    let mut config = manager.load()?;
+    let before = config.alerts.len();
+    config.alerts.retain(|r| r.id != id);
+    if config.alerts.len() == before {
+        return Err(format!("Alert rule with id '{}' not found", id));
+    }
+
+    manager.save(&config)
+}
@@ -1,11 +1,27 @@
-use crate::models::config::{AppConfig, PotionConfig};
+use crate::commands::ocr::{OcrService, OcrServiceState};
+use crate::commands::session::{load_display_config, SessionRecordsState};
+use crate::commands::tracking::TrackerState;
+use crate::models::config::{
+    AppConfig, PotionConfig, RoiConfig, StorageConfig, WindowDimensions, WindowMode,
+};
+use crate::services::screen_capture::{DisplayInfo, ScreenCapture};
+use crate::models::custom_metric::{validate_custom_metrics, CustomMetric, DerivedMetric};
 use crate::models::roi::Roi;
-use crate::services::config::ConfigManager;
+use crate::services::config::{ConfigManager, ConfigRecovery};
+use crate::services::event_log;
+use crate::services::expression::Expr;
+use crate::services::image_payload::{self, ImageOptions};
+use crate::services::matching_pool;
+use crate::services::ocr_tracker::TrackingStats;
+use crate::services::ocr::parser;
+use crate::services::preview_store::{PreviewEntry, PreviewStore, DEFAULT_PROFILE};
+use crate::services::sample_log;
+use crate::services::storage;
 use base64::Engine as _;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::sync::Mutex;
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 /// ROI type identifier
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -16,17 +32,53 @@ pub enum RoiType {
     Hp,
     Mp,
     Inventory,  // Auto-detected inventory region (read-only preview)
+    ItemGrid,   // Regular item inventory window grid
+    MapName,    // Map name label (session title only)
     // Meso, // Commented out temporarily
-    // MapLocation, // Commented out temporarily
+}
+
+impl RoiType {
+    /// Storage key, e.g. for preview folders
+    pub fn key(&self) -> &'static str {
+        match self {
+            RoiType::Level => "level",
+            RoiType::Exp => "exp",
+            RoiType::Hp => "hp",
+            RoiType::Mp => "mp",
+            RoiType::Inventory => "inventory",
+            RoiType::ItemGrid => "item_grid",
+            RoiType::MapName => "map_name",
+            // RoiType::Meso => "meso", // Commented out temporarily
+        }
+    }
 }
 
 /// State wrapper for configuration manager
 pub type ConfigManagerState = Mutex<ConfigManager>;
 
+/// Recovery that happened at startup (kept so the UI can ask after it loads)
+pub type ConfigRecoveryState = Mutex<Option<ConfigRecovery>>;
+
 /// Initialize config manager state
-pub fn init_config_manager() -> Result<ConfigManagerState, String> {
+/// A corrupt config file is moved aside and replaced by defaults instead of failing
+pub fn init_config_manager() -> Result<(ConfigManagerState, Option<ConfigRecovery>), String> {
     let manager = ConfigManager::new()?;
-    Ok(Mutex::new(manager))
+    let recovery = manager.recover_if_corrupt()?;
+    Ok((Mutex::new(manager), recovery))
+}
+
+/// Config manager in the temp directory, for when the platform config directory fails
+pub fn fallback_config_manager() -> ConfigManagerState {
+    Mutex::new(ConfigManager::in_dir(std::env::temp_dir().join("exp-tracker")))
+}
+
+/// Config recovery from this startup, if any
+#[tauri::command]
+pub fn get_config_recovery(state: State<ConfigRecoveryState>) -> Result<Option<ConfigRecovery>, String> {
+    let recovery = state
+        .lock()
+        .map_err(|e| format!("Failed to lock recovery state: {}", e))?;
+    Ok(recovery.clone())
 }
 
 /// Save ROI to configuration
@@ -40,6 +92,8 @@ pub fn save_roi(
         .lock()
         .map_err(|e| format!("Failed to lock config manager: {}", e))?;
 
+    roi.check_size()?;
+
     // Load current config
     let mut config = manager.load()?;
 
@@ -49,14 +103,16 @@ pub fn save_roi(
         RoiType::Exp => config.roi.exp = Some(roi),
         RoiType::Hp => config.roi.hp = Some(roi),
         RoiType::Mp => config.roi.mp = Some(roi),
+        RoiType::ItemGrid => config.potion.grid.roi = Some(roi),
         RoiType::Inventory => {
             // Inventory ROI is auto-detected, but we allow saving it temporarily
             // It won't be persisted to config file, just kept in memory
             return Ok(());
         }
         // RoiType::Meso => config.roi.meso = Some(roi), // Commented out temporarily
-        // RoiType::MapLocation => config.roi.map_location = Some(roi), // Commented out temporarily
+        RoiType::MapName => config.roi.map_name = Some(roi),
     }
+    config.sync_active_layout();
 
     // Save updated config
     manager.save(&config)?;
@@ -78,11 +134,12 @@ pub fn load_roi(state: State<ConfigManagerState>, roi_type: RoiType) -> Result<O
         RoiType::Exp => config.roi.exp,
         RoiType::Hp => config.roi.hp,
         RoiType::Mp => config.roi.mp,
+        RoiType::ItemGrid => config.potion.grid.roi,
         RoiType::Inventory => {
             return Err("Inventory ROI is auto-detected and cannot be manually loaded".to_string());
         }
         // RoiType::Meso => config.roi.meso, // Commented out temporarily
-        // RoiType::MapLocation => config.roi.map_location, // Commented out temporarily
+        RoiType::MapName => config.roi.map_name,
     };
 
     Ok(roi)
@@ -116,11 +173,12 @@ pub fn clear_roi(state: State<ConfigManagerState>, roi_type: RoiType) -> Result<
         RoiType::Exp => config.roi.exp = None,
         RoiType::Hp => config.roi.hp = None,
         RoiType::Mp => config.roi.mp = None,
+        RoiType::ItemGrid => config.potion.grid.roi = None,
         RoiType::Inventory => {
             return Err("Inventory ROI is auto-detected and cannot be manually cleared".to_string());
         }
         // RoiType::Meso => config.roi.meso = None, // Commented out temporarily
-        // RoiType::MapLocation => config.roi.map_location = None, // Commented out temporarily
+        RoiType::MapName => config.roi.map_name = None,
     }
 
     manager.save(&config)?;
@@ -135,7 +193,12 @@ pub fn save_config(state: State<ConfigManagerState>, config: AppConfig) -> Resul
         .lock()
         .map_err(|e| format!("Failed to lock config manager: {}", e))?;
 
-    manager.save(&config)
+    manager.save(&config)?;
+    parser::set_decimal_separator(config.tracking.client_language.decimal_separator());
+    matching_pool::configure(config.advanced.matching_threads);
+    event_log::configure_throttle(&config.advanced.event_intervals_ms);
+    sample_log::configure(config.advanced.sample_log, config.advanced.sample_log_capacity);
+    Ok(())
 }
 
 /// Load entire application configuration
@@ -162,79 +225,76 @@ pub fn get_config_path(state: State<ConfigManagerState>) -> Result<String, Strin
         .to_string())
 }
 
-/// Save ROI preview image to temp directory
+/// Save ROI preview image to the profile's preview history
+/// Keeps the last few previews per ROI type; returns the saved file path
 #[tauri::command]
-pub fn save_roi_preview(roi_type: RoiType, image_data: String) -> Result<String, String> {
-    // Get temp directory
-    let temp_dir = std::env::temp_dir().join("exp-tracker-previews");
-    fs::create_dir_all(&temp_dir)
-        .map_err(|e| format!("Failed to create preview directory: {}", e))?;
-
+pub fn save_roi_preview(roi_type: RoiType, image_data: String, profile: Option<String>) -> Result<String, String> {
     // Decode base64
     let image_bytes = base64::engine::general_purpose::STANDARD
         .decode(&image_data)
         .map_err(|e| format!("Failed to decode base64: {}", e))?;
 
-    // Save to file
-    let filename = format!("{}_preview.png", match roi_type {
-        RoiType::Level => "level",
-        RoiType::Exp => "exp",
-        RoiType::Hp => "hp",
-        RoiType::Mp => "mp",
-        RoiType::Inventory => "inventory",
-        // RoiType::Meso => "meso", // Commented out temporarily
-        // RoiType::MapLocation => "map_location", // Commented out temporarily
-    });
-    let file_path = temp_dir.join(&filename);
+    let profile = profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let preview = PreviewStore::open().save(profile, roi_type.key(), &image_bytes)?;
 
-    fs::write(&file_path, image_bytes)
-        .map_err(|e| format!("Failed to write preview file: {}", e))?;
-
-    Ok(file_path.to_str().unwrap_or("").to_string())
+    Ok(preview.path)
 }
 
-/// Get ROI preview as base64 encoded string
+/// Get ROI preview as a data URL (newest unless `id` is given)
+/// Stored PNGs are returned as-is unless `options` ask for another size or format.
+/// Map name previews are withheld in privacy mode.
 #[tauri::command]
-pub fn get_roi_preview(roi_type: RoiType) -> Result<String, String> {
-    let temp_dir = std::env::temp_dir().join("exp-tracker-previews");
-    let filename = format!("{}_preview.png", match roi_type {
-        RoiType::Level => "level",
-        RoiType::Exp => "exp",
-        RoiType::Hp => "hp",
-        RoiType::Mp => "mp",
-        RoiType::Inventory => "inventory",
-    });
-    let file_path = temp_dir.join(&filename);
-
-    if !file_path.exists() {
-        return Err("Preview file not found".to_string());
+pub fn get_roi_preview(
+    config_state: State<ConfigManagerState>,
+    roi_type: RoiType,
+    profile: Option<String>,
+    id: Option<String>,
+    options: Option<ImageOptions>,
+) -> Result<String, String> {
+    if roi_type == RoiType::MapName && load_display_config(&config_state).privacy_mode {
+        return Err("Map name preview is hidden in privacy mode".to_string());
     }
 
-    let image_bytes = fs::read(&file_path)
+    let profile = profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let preview = PreviewStore::open()
+        .get(profile, roi_type.key(), id.as_deref())
+        .ok_or("Preview file not found")?;
+
+    let image_bytes = fs::read(&preview.path)
         .map_err(|e| format!("Failed to read preview file: {}", e))?;
 
+    if let Some(options) = options {
+        let image = image::load_from_memory(&image_bytes)
+            .map_err(|e| format!("Failed to decode preview file: {}", e))?;
+        return Ok(image_payload::encode(&image, &options)?.data_url());
+    }
+
     let base64_str = base64::engine::general_purpose::STANDARD.encode(&image_bytes);
     Ok(format!("data:image/png;base64,{}", base64_str))
 }
 
-/// Open ROI preview in system viewer
+/// List a ROI type's preview history, newest first
 #[tauri::command]
-pub fn open_roi_preview(roi_type: RoiType) -> Result<(), String> {
-    let temp_dir = std::env::temp_dir().join("exp-tracker-previews");
-    let filename = format!("{}_preview.png", match roi_type {
-        RoiType::Level => "level",
-        RoiType::Exp => "exp",
-        RoiType::Hp => "hp",
-        RoiType::Mp => "mp",
-        RoiType::Inventory => "inventory",
-        // RoiType::Meso => "meso", // Commented out temporarily
-        // RoiType::MapLocation => "map_location", // Commented out temporarily
-    });
-    let file_path = temp_dir.join(&filename);
-
-    if !file_path.exists() {
-        return Err("Preview file not found".to_string());
-    }
+pub fn list_roi_previews(roi_type: RoiType, profile: Option<String>) -> Vec<PreviewEntry> {
+    let profile = profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    PreviewStore::open().list(profile, roi_type.key())
+}
+
+/// Delete one preview from the history
+#[tauri::command]
+pub fn delete_roi_preview(roi_type: RoiType, id: String, profile: Option<String>) -> Result<(), String> {
+    let profile = profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    PreviewStore::open().delete(profile, roi_type.key(), &id)
+}
+
+/// Open ROI preview in system viewer (newest unless `id` is given)
+#[tauri::command]
+pub fn open_roi_preview(roi_type: RoiType, profile: Option<String>, id: Option<String>) -> Result<(), String> {
+    let profile = profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let preview = PreviewStore::open()
+        .get(profile, roi_type.key(), id.as_deref())
+        .ok_or("Preview file not found")?;
+    let file_path = std::path::PathBuf::from(&preview.path);
 
     // Open with system default viewer
     #[cfg(target_os = "macos")]
@@ -289,5 +349,412 @@ pub fn set_potion_slot_config(
     Ok(())
 }
 
+/// Get the user-defined custom metrics
+#[tauri::command]
+pub fn get_custom_metrics(state: State<ConfigManagerState>) -> Result<Vec<CustomMetric>, String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    Ok(manager.load()?.custom_metrics)
+}
+
+/// Replace the custom metrics (applies the next time tracking starts)
+#[tauri::command]
+pub fn set_custom_metrics(
+    state: State<ConfigManagerState>,
+    metrics: Vec<CustomMetric>,
+) -> Result<(), String> {
+    validate_custom_metrics(&metrics)?;
+
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    let mut config = manager.load()?;
+    config.custom_metrics = metrics;
+    manager.save(&config)
+}
+
+/// Check derived metric expressions against the known stats and custom metrics
+fn validate_derived_metrics(metrics: &[DerivedMetric], custom_metrics: &[CustomMetric]) -> Result<(), String> {
+    let is_known = |name: &str| {
+        TrackingStats::VARIABLES.contains(&name) || custom_metrics.iter().any(|m| m.name == name)
+    };
+
+    let mut names = std::collections::HashSet::new();
+    for metric in metrics {
+        let name = metric.name.trim();
+        if name.is_empty() {
+            return Err("Derived metric name must not be empty".to_string());
+        }
+        if !names.insert(name) || is_known(name) {
+            return Err(format!("Derived metric name '{}' is already in use", name));
+        }
+
+        let expr = Expr::parse(&metric.expression)
+            .map_err(|e| format!("Derived metric '{}': {}", name, e))?;
+        if let Some(unknown) = expr.variables().into_iter().find(|var| !is_known(var)) {
+            return Err(format!("Derived metric '{}' uses unknown variable '{}'", name, unknown));
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the derived metric definitions
+#[tauri::command]
+pub fn get_derived_metrics(state: State<ConfigManagerState>) -> Result<Vec<DerivedMetric>, String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    Ok(manager.load()?.derived_metrics)
+}
+
+/// Replace the derived metrics (applies the next time tracking starts)
+#[tauri::command]
+pub fn set_derived_metrics(
+    state: State<ConfigManagerState>,
+    metrics: Vec<DerivedMetric>,
+) -> Result<(), String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    let mut config = manager.load()?;
+    validate_derived_metrics(&metrics, &config.custom_metrics)?;
+    config.derived_metrics = metrics;
+    manager.save(&config)
+}
+
+/// Rescale all saved ROIs after the display resolution or scale changed
+/// `previous` / `current` come from the "display:resolution-changed" event
+#[tauri::command]
+pub fn apply_rescaled_rois(
+    state: State<ConfigManagerState>,
+    previous: DisplayInfo,
+    current: DisplayInfo,
+) -> Result<RoiConfig, String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    let mut config = manager.load()?;
+    let (scale_x, scale_y) = previous.roi_scale_to(&current);
+    config.rescale_rois(scale_x, scale_y);
+    // Rescaled ROIs no longer match the saved layout
+    config.active_layout = None;
+    manager.save(&config)?;
+
+    Ok(config.roi)
+}
+
+/// Saved ROI layouts and the active one
+#[derive(Debug, Clone, Serialize)]
+pub struct LayoutList {
+    pub layouts: Vec<String>,
+    pub active: Option<String>,
+}
+
+/// Payload for "layout:changed"
+#[derive(Debug, Clone, Serialize)]
+pub struct LayoutChanged {
+    pub name: String,
+    pub roi: RoiConfig,
+}
+
+/// List saved ROI layouts (e.g. "fullscreen", "windowed-1280")
+#[tauri::command]
+pub fn list_layouts(state: State<ConfigManagerState>) -> Result<LayoutList, String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    let config = manager.load()?;
+    Ok(LayoutList {
+        layouts: config.layouts.keys().cloned().collect(),
+        active: config.active_layout,
+    })
+}
+
+/// Save the current ROIs as layout `name` (replacing it) and make it active
+#[tauri::command]
+pub fn save_layout(state: State<ConfigManagerState>, name: String) -> Result<(), String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    let mut config = manager.load()?;
+    config.save_layout(&name)?;
+    manager.save(&config)
+}
+
+/// Delete a saved layout; the current ROIs are kept
+#[tauri::command]
+pub fn delete_layout(state: State<ConfigManagerState>, name: String) -> Result<(), String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    let mut config = manager.load()?;
+    if config.layouts.remove(&name).is_none() {
+        return Err(format!("Layout '{}' not found", name));
+    }
+    if config.active_layout.as_deref() == Some(name.as_str()) {
+        config.active_layout = None;
+    }
+    manager.save(&config)
+}
+
+/// Switch to layout `name` (default: the next one), restarting running
+/// tracking on its ROIs; returns the now current ROIs
+#[tauri::command]
+pub async fn switch_layout(app: AppHandle, name: Option<String>) -> Result<RoiConfig, String> {
+    apply_layout(&app, name).await
+}
+
+/// `switch_layout`, also run by the layout shortcut
+pub(crate) async fn apply_layout(app: &AppHandle, name: Option<String>) -> Result<RoiConfig, String> {
+    let (name, config) = {
+        let state = app.state::<ConfigManagerState>();
+        let manager = state
+            .lock()
+            .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+        let mut config = manager.load()?;
+        let name = match name {
+            Some(name) => name,
+            None => config.next_layout().ok_or("No layouts saved")?,
+        };
+        config.switch_layout(&name)?;
+        manager.save(&config)?;
+        (name, config)
+    };
+
+    if let Some(tracker) = app.try_state::<TrackerState>() {
+        let is_tracking = tracker.0.lock().await.get_stats().await.is_tracking;
+        if is_tracking {
+            match (config.roi.level, config.roi.exp) {
+                (Some(level_roi), Some(exp_roi)) => {
+                    tracker.control().stop().await?;
+                    tracker.control().start(level_roi, exp_roi, true).await?;
+                }
+                _ => eprintln!("⚠️ Layout '{}' has no level or EXP ROI; tracking keeps the previous ones", name),
+            }
+        }
+        tracker.0.lock().await.set_layout(Some(name.clone())).await;
+    }
+
+    println!("🗺️ Switched to layout '{}'", name);
+    let _ = app.emit("layout:changed", LayoutChanged { name, roi: config.roi.clone() });
+
+    Ok(config.roi)
+}
+
+/// Payload for "layout:auto-switched"
+#[derive(Debug, Clone, Serialize)]
+pub struct LayoutAutoSwitch {
+    pub from: Option<String>,
+    pub to: String,
+    pub previous: DisplayInfo,
+    pub current: DisplayInfo,
+    /// Whether tracking was resumed on the new layout's ROIs
+    pub resumed: bool,
+}
+
+/// Find the saved layout matching the screen: the first one whose level ROI
+/// recognizes a level (None if there is none)
+#[tauri::command]
+pub async fn detect_layout(
+    state: State<'_, ConfigManagerState>,
+    ocr_service: State<'_, OcrServiceState>,
+) -> Result<Option<String>, String> {
+    let candidates = {
+        let manager = state
+            .lock()
+            .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+        manager.load()?.layout_candidates()
+    };
+
+    find_matching_layout(&ocr_service, candidates).await
+}
+
+/// Try each candidate's level ROI on one fresh capture, in order
+async fn find_matching_layout(
+    ocr_service: &OcrService,
+    candidates: Vec<(String, Roi)>,
+) -> Result<Option<String>, String> {
+    if candidates.is_empty() {
+        return Ok(None);
+    }
+
+    // Opened here so the capture uses the display's current scale factor
+    let screen_capture = ScreenCapture::new()?;
+    let screen = screen_capture.capture_full()?;
+
+    for (name, roi) in candidates {
+        // ROIs of a larger layout fall outside the screen
+        let Ok(cropped) = ScreenCapture::crop_logical(&screen, &roi, screen_capture.get_scale_factor()) else {
+            continue;
+        };
+        match ocr_service.recognize_level(&cropped).await {
+            Ok(result) => {
+                #[cfg(debug_assertions)]
+                println!("🗺️ Layout '{}' matches (level {})", name, result.level);
+                return Ok(Some(name));
+            }
+            Err(_e) => {
+                #[cfg(debug_assertions)]
+                println!("🗺️ Layout '{}' doesn't match: {}", name, _e);
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// After tracking paused for a display change, switch to the layout matching
+/// the new display and resume tracking on it
+/// Returns false (nothing changed) if auto-switching is off or no other layout matches.
+pub(crate) async fn auto_switch_layout(
+    app: &AppHandle,
+    previous: DisplayInfo,
+    current: DisplayInfo,
+) -> Result<bool, String> {
+    let config = {
+        let state = app.state::<ConfigManagerState>();
+        let manager = state
+            .lock()
+            .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+        manager.load()?
+    };
+    if !config.tracking.auto_switch_layout {
+        return Ok(false);
+    }
+
+    let ocr_service = app
+        .try_state::<OcrServiceState>()
+        .ok_or("OCR service not available")?
+        .inner()
+        .clone();
+    let Some(name) = find_matching_layout(&ocr_service, config.layout_candidates_after_display_change()).await? else {
+        return Ok(false);
+    };
+    // Still on the active layout: leave it to the rescale prompt
+    if Some(&name) == config.active_layout.as_ref() {
+        return Ok(false);
+    }
+
+    // Tracking is paused, so this only swaps the ROIs
+    let roi = apply_layout(app, Some(name.clone())).await?;
+
+    let mut resumed = false;
+    if let (Some(tracker), Some(level_roi), Some(exp_roi)) = (app.try_state::<TrackerState>(), roi.level, roi.exp) {
+        tracker.control().start(level_roi, exp_roi, true).await?;
+        resumed = true;
+    }
+
+    println!("🗺️ Display changed, auto-switched layout to '{}'", name);
+    let payload = LayoutAutoSwitch {
+        from: config.active_layout,
+        to: name,
+        previous,
+        current,
+        resumed,
+    };
+    if let Err(e) = event_log::emit(app, "layout:auto-switched", payload) {
+        eprintln!("Failed to emit layout switch: {}", e);
+    }
+
+    Ok(true)
+}
+
+/// Switch the window mode whose geometry gets saved on move/resize
+/// Returns the saved geometry of the new mode so the frontend can apply it.
+#[tauri::command]
+pub fn set_window_mode(
+    state: State<ConfigManagerState>,
+    mode: WindowMode,
+) -> Result<WindowDimensions, String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    let mut config = manager.load()?;
+    config.window.current_mode = mode;
+    manager.save(&config)?;
+
+    Ok(config.window.dimensions(&config.window.current_mode).clone())
+}
+
+/// Turn privacy mode on or off; the UI re-fetches on "privacy:changed"
+#[tauri::command]
+pub fn set_privacy_mode(
+    app: AppHandle,
+    state: State<ConfigManagerState>,
+    enabled: bool,
+) -> Result<(), String> {
+    {
+        let manager = state
+            .lock()
+            .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+        let mut config = manager.load()?;
+        config.display.privacy_mode = enabled;
+        manager.save(&config)?;
+    }
+
+    let _ = app.emit("privacy:changed", enabled);
+
+    Ok(())
+}
+
+/// Get the directory where session data and debug images are stored
+#[tauri::command]
+pub fn get_data_directory() -> Result<String, String> {
+    Ok(storage::data_dir().to_string_lossy().to_string())
+}
+
+/// Change the data directory and move existing files there
+/// `None` switches back to the platform data directory.
+/// Returns the names of the moved files/folders.
+#[tauri::command]
+pub fn set_data_directory(
+    state: State<ConfigManagerState>,
+    sessions: State<SessionRecordsState>,
+    path: Option<String>,
+) -> Result<Vec<String>, String> {
+    let manager = state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    // Block session saves while files are moving
+    let _sessions = sessions
+        .lock()
+        .map_err(|e| format!("Failed to lock session state: {}", e))?;
+
+    let mut config = manager.load()?;
+    config.storage = StorageConfig { data_directory: path };
+
+    let from = storage::data_dir();
+    let to = storage::resolve_data_dir(&config.storage)?;
+
+    // The mapped sample log can't be moved (on Windows) while open
+    sample_log::close();
+    let moved = storage::migrate_data_dir(&from, &to);
+    if moved.is_err() {
+        sample_log::configure(config.advanced.sample_log, config.advanced.sample_log_capacity);
+    }
+    let moved = moved?;
+
+    manager.save(&config)?;
+    storage::set_data_dir(to.clone());
+    sample_log::configure(config.advanced.sample_log, config.advanced.sample_log_capacity);
+
+    println!("🗂️ Data directory: {} → {} ({} moved)", from.display(), to.display(), moved.len());
+    Ok(moved)
+}
+
 // Note: Integration tests for these commands will be in tests/ directory
 // Unit tests for the underlying ConfigManager are in services/config.rs
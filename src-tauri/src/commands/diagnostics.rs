@@ -0,0 +1,138 @@
+use crate::commands::config::ConfigRecoveryState;
+use crate::commands::ocr::OcrServiceState;
+use crate::commands::tracking::TrackerState;
+use crate::models::ocr_failure::{failure_report, FailureReport};
+use crate::services::buffer_pool::{self, BufferPoolStats};
+use crate::services::config::ConfigRecovery;
+use crate::services::latency::{self, LatencyStats};
+use crate::services::ocr_failures::FailureStore;
+use crate::services::python_server::{self, ServerLocation};
+use crate::services::sample_log::{self, RawSample, ReplayResult};
+use crate::services::self_test::{self, SelfTestResult};
+use crate::services::shortcuts::{ShortcutFailure, ShortcutFailuresState};
+use crate::services::startup::{InitFailure, StartupReportState};
+use crate::services::storage;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+/// Backend health overview for the diagnostics panel
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostics {
+    /// Startup stages that failed (the app keeps running without them)
+    pub startup_failures: Vec<InitFailure>,
+    pub config_recovery: Option<ConfigRecovery>,
+    /// Global shortcuts that could not be registered
+    pub shortcut_failures: Vec<ShortcutFailure>,
+    pub data_directory: String,
+    pub buffer_pool: BufferPoolStats,
+    /// Capture-to-emit latency of recent EXP readings; None until one arrives
+    pub latency: Option<LatencyStats>,
+    /// Most recent OCR self-test; None if it never ran
+    pub last_self_test: Option<SelfTestResult>,
+}
+
+/// Saved sessions included in the failure report by default
+const DEFAULT_FAILURE_REPORT_SESSIONS: usize = 20;
+
+/// OCR failures per ROI over the last `sessions` saved sessions (default 20)
+/// plus the running one, most failing ROI first, with when in the session
+/// and at what hour of day they happened
+#[tauri::command]
+pub async fn get_failure_report(
+    sessions: Option<usize>,
+    tracker: State<'_, TrackerState>,
+) -> Result<FailureReport, String> {
+    let mut histograms = FailureStore::open().recent(sessions.unwrap_or(DEFAULT_FAILURE_REPORT_SESSIONS));
+    let current = tracker.0.lock().await.failure_histogram();
+    if !current.is_empty() {
+        histograms.push(current);
+    }
+    Ok(failure_report(&histograms))
+}
+
+/// Collect startup failures and other backend state for troubleshooting
+#[tauri::command]
+pub fn get_diagnostics(
+    startup: State<StartupReportState>,
+    recovery: State<ConfigRecoveryState>,
+    shortcuts: State<ShortcutFailuresState>,
+) -> Result<Diagnostics, String> {
+    let startup_failures = startup
+        .lock()
+        .map_err(|e| format!("Failed to lock startup report: {}", e))?
+        .failures
+        .clone();
+    let config_recovery = recovery
+        .lock()
+        .map_err(|e| format!("Failed to lock recovery state: {}", e))?
+        .clone();
+    let shortcut_failures = shortcuts
+        .lock()
+        .map_err(|e| format!("Failed to lock shortcut state: {}", e))?
+        .clone();
+
+    Ok(Diagnostics {
+        startup_failures,
+        config_recovery,
+        shortcut_failures,
+        data_directory: storage::data_dir().to_string_lossy().to_string(),
+        buffer_pool: buffer_pool::global().stats(),
+        latency: latency::global().stats(),
+        last_self_test: self_test::load_history().pop(),
+    })
+}
+
+/// Every path the bundled OCR server binary is looked for at, in order,
+/// and which one startup would launch
+#[tauri::command]
+pub fn locate_ocr_server(app: AppHandle) -> Result<ServerLocation, String> {
+    python_server::locate_server(app.path().resource_dir().ok().as_deref())
+}
+
+/// Run the OCR self-test over the bundled fixtures now and record it
+/// (alerts like the scheduled run if accuracy dropped)
+#[tauri::command]
+pub async fn run_self_test(
+    app: AppHandle,
+    ocr_service: State<'_, OcrServiceState>,
+    tracker: State<'_, TrackerState>,
+) -> Result<SelfTestResult, String> {
+    if tracker.0.lock().await.get_stats().await.is_tracking {
+        return Err("Stop tracking before running the self-test".to_string());
+    }
+    self_test::run_and_record(&app, &ocr_service).await
+}
+
+/// Recorded OCR self-test results, oldest first
+#[tauri::command]
+pub fn get_self_test_history() -> Vec<SelfTestResult> {
+    self_test::load_history()
+}
+
+/// Logged raw samples, oldest first, optionally only those after `since_seq`
+/// and at most the last `limit`
+#[tauri::command]
+pub fn get_sample_log(since_seq: Option<u64>, limit: Option<usize>) -> Result<Vec<RawSample>, String> {
+    let mut samples = sample_log::samples()?;
+    if let Some(since_seq) = since_seq {
+        samples.retain(|sample| sample.seq > since_seq);
+    }
+    if let Some(limit) = limit {
+        samples.drain(..samples.len().saturating_sub(limit));
+    }
+    Ok(samples)
+}
+
+/// Feed the logged readings (those with `from_seq..=to_seq`, default all)
+/// through a fresh tracker and report where the result differs from the log
+#[tauri::command]
+pub fn replay_sample_log(from_seq: Option<u64>, to_seq: Option<u64>) -> Result<ReplayResult, String> {
+    let mut samples = sample_log::samples()?;
+    samples.retain(|sample| {
+        from_seq.is_none_or(|from| sample.seq >= from) && to_seq.is_none_or(|to| sample.seq <= to)
+    });
+    if samples.is_empty() {
+        return Err("No logged samples to replay".to_string());
+    }
+    sample_log::replay(&samples)
+}
@@ -0,0 +1,74 @@
+use crate::commands::config::ConfigManagerState;
+use crate::commands::session::{display_record, load_display_config, SessionRecordsState};
+use crate::services::export::exporter_for_path;
+use crate::services::privacy;
+use crate::services::sample_log;
+use crate::services::timeline_store::TimelineStore;
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Export all session records to `path`; the format follows its extension
+/// (.csv, .jsonl, or .parquet with the `parquet` feature). Returns the number of records written.
+#[tauri::command]
+pub fn export_data(
+    state: State<SessionRecordsState>,
+    config_state: State<ConfigManagerState>,
+    path: String,
+) -> Result<usize, String> {
+    let path = PathBuf::from(path);
+    let exporter = exporter_for_path(&path)?;
+
+    let display = load_display_config(&config_state);
+    let records: Vec<_> = {
+        let records = state.lock()
+            .map_err(|e| format!("Failed to lock session state: {}", e))?;
+        records.iter().map(|record| display_record(record, &display)).collect()
+    };
+
+    let content = exporter.export(&records)?;
+    fs::write(&path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    println!("📤 Exported {} sessions to {}", records.len(), path.display());
+    Ok(records.len())
+}
+
+/// Export every saved session timeline to `path` (.csv with one row per point, or .jsonl)
+/// Returns the number of timelines written.
+#[tauri::command]
+pub fn export_timelines(config_state: State<ConfigManagerState>, path: String) -> Result<usize, String> {
+    let path = PathBuf::from(path);
+    let exporter = exporter_for_path(&path)?;
+
+    let privacy_mode = load_display_config(&config_state).privacy_mode;
+    let mut timelines = TimelineStore::open().all();
+    timelines.sort_by_key(|timeline| timeline.timestamp);
+    if privacy_mode {
+        for timeline in &mut timelines {
+            timeline.map_name = privacy::redact_map_name(timeline.map_name.take());
+        }
+    }
+
+    let content = exporter.export_timelines(&timelines)?;
+    fs::write(&path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    println!("📤 Exported {} timelines to {}", timelines.len(), path.display());
+    Ok(timelines.len())
+}
+
+/// Convert the raw sample log to `path` (.csv or .jsonl). Returns the number of samples written.
+#[tauri::command]
+pub fn export_sample_log(path: String) -> Result<usize, String> {
+    let path = PathBuf::from(path);
+    let exporter = exporter_for_path(&path)?;
+
+    let samples = sample_log::samples()?;
+    let content = exporter.export_samples(&samples)?;
+    fs::write(&path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    println!("📤 Exported {} raw samples to {}", samples.len(), path.display());
+    Ok(samples.len())
+}
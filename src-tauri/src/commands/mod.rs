@@ -4,3 +4,9 @@ pub mod ocr;
 pub mod exp;
 pub mod tracking;
 pub mod session;
+pub mod storage;
+pub mod diagnostics;
+pub mod window;
+pub mod alerts;
+pub mod report;
+pub mod export;
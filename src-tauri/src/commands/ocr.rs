@@ -1,14 +1,21 @@
+use crate::models::config::ItemGridConfig;
+use crate::models::custom_metric::{MetricParser, MetricValue};
+use crate::models::slot::{SlotId, SlotKey};
 use crate::models::ocr_result::{CombinedOcrResult, ExpResult, LevelResult, MapResult};
-use crate::services::ocr::{HttpOcrClient, InventoryTemplateMatcher};
+use crate::services::matching_pool;
+use crate::services::ocr::parser::parse_metric;
+use crate::services::ocr::{HttpOcrClient, InventoryTemplateMatcher, PotionSlotMatch, SlotReading, SlotState};
 use base64::Engine as _;
 use image::DynamicImage;
-use parking_lot::Mutex;
 use std::sync::Arc;
 use std::collections::HashMap;
 use tauri::State;
 
-/// State wrapper for OCR service (Arc for async sharing, parking_lot::Mutex for performance)
-pub type OcrServiceState = Arc<Mutex<OcrService>>;
+/// State wrapper for OCR service
+///
+/// The service is immutable after initialization, so commands and tracking loops
+/// share it through a plain Arc and clone the handles they need without locking.
+pub type OcrServiceState = Arc<OcrService>;
 
 /// OCR service using HTTP client to communicate with Python server
 pub struct OcrService {
@@ -75,6 +82,7 @@ impl OcrService {
                 println!("📂 Loading inventory templates from: {}", path);
                 match matcher.load_templates(path) {
                     Ok(_) => {
+                        Self::try_load_potion_icons(&mut matcher);
                         println!("✅ Inventory template matcher initialized successfully");
                         return Ok(Arc::new(matcher));
                     }
@@ -92,6 +100,31 @@ impl OcrService {
         Err("Inventory template directory not found in any expected location".to_string())
     }
 
+    /// Try to load potion icon templates (non-fatal, auto identification is disabled without them)
+    fn try_load_potion_icons(matcher: &mut InventoryTemplateMatcher) {
+        let possible_paths = vec![
+            "src-tauri/resources/potion_icons",   // Development (from project root)
+            "resources/potion_icons",             // Development (from src-tauri)
+            "../Resources/potion_icons",          // macOS bundled
+            "./resources/potion_icons",           // Windows/Linux bundled
+        ];
+
+        for path in possible_paths.iter() {
+            if std::path::Path::new(path).exists() {
+                match matcher.load_icon_templates(path) {
+                    Ok(_) => {
+                        println!("📂 Loaded potion icons from: {}", path);
+                        return;
+                    }
+                    Err(e) => eprintln!("❌ Failed to load potion icons from {}: {}", path, e),
+                }
+            }
+        }
+
+        #[cfg(debug_assertions)]
+        println!("⚠️ No potion icons found, potion slots must be set manually");
+    }
+
     /// Recognize and parse level from image
     pub async fn recognize_level(&self, image: &DynamicImage) -> Result<LevelResult, String> {
         self.http_client.recognize_level(image).await
@@ -103,9 +136,14 @@ impl OcrService {
     }
 
     /// Recognize and parse map name from image
-    pub async fn recognize_map(&self, _image: &DynamicImage) -> Result<MapResult, String> {
-        // TODO: Implement map recognition in Python server
-        Err("Map recognition not yet implemented in HTTP OCR server".to_string())
+    pub async fn recognize_map(&self, image: &DynamicImage) -> Result<MapResult, String> {
+        self.http_client.recognize_map_name(image).await
+    }
+
+    /// Recognize a user-defined metric and parse it with its parser type
+    pub async fn recognize_custom_metric(&self, image: &DynamicImage, parser: MetricParser) -> Result<MetricValue, String> {
+        let text = self.http_client.recognize_words(image).await?;
+        parse_metric(&text, parser)
     }
 
     /// Recognize HP potion count from inventory image (numbers only)
@@ -119,49 +157,52 @@ impl OcrService {
     }
 
     /// Recognize all 8 inventory slots (Rust native implementation)
-    /// Returns HashMap with slot names as keys and item counts as values
-    pub fn recognize_inventory(&self, image: &DynamicImage) -> Result<HashMap<String, u32>, String> {
-        // Try Rust native template matching first
-        if let Some(matcher) = &self.inventory_matcher {
-            match matcher.detect_inventory_region_with_coords(image) {
-                Ok((inventory_image, _coords)) => {
-                    if let Ok(results) = matcher.recognize_all_slots(&inventory_image) {
-                        return Ok(results);
-                    }
-                }
-                Err(_) => {}
-            }
-        }
-
-        // Fallback: Return empty inventory (Python HTTP fallback can be added later if needed)
-        let mut empty = HashMap::new();
-        for slot in &["shift", "ins", "home", "pup", "ctrl", "del", "end", "pdn"] {
-            empty.insert(slot.to_string(), 0);
-        }
-        Ok(empty)
+    /// Returns HashMap with slot keys and slot readings as values
+    pub fn recognize_inventory(&self, image: &DynamicImage) -> Result<HashMap<SlotKey, SlotReading>, String> {
+        self.recognize_specific_inventory(image, &SlotId::ALL)
     }
 
     /// Recognize specific inventory slots (Rust native implementation)
-    /// Returns HashMap with slot names as keys and item counts as values
-    pub fn recognize_specific_inventory(&self, image: &DynamicImage, slots: &[String]) -> Result<HashMap<String, u32>, String> {
-        // Try Rust native template matching first
-        if let Some(matcher) = &self.inventory_matcher {
-            match matcher.detect_inventory_region_with_coords(image) {
-                Ok((inventory_image, _coords)) => {
-                    if let Ok(results) = matcher.recognize_specific_slots(&inventory_image, slots) {
-                        return Ok(results);
-                    }
-                }
-                Err(_) => {}
-            }
-        }
+    /// Returns HashMap with slot keys and slot readings as values.
+    /// Errors when the inventory itself cannot be located, so callers never
+    /// mistake a failed detection for empty slots.
+    pub fn recognize_specific_inventory(&self, image: &DynamicImage, slots: &[SlotId]) -> Result<HashMap<SlotKey, SlotReading>, String> {
+        let matcher = self.inventory_matcher.as_ref()
+            .ok_or("Inventory template matcher not initialized")?;
+
+        let (inventory_image, _coords) = matcher.detect_inventory_region_with_coords(image)?;
+        let readings = matcher.recognize_specific_slots(&inventory_image, slots)?;
+        Ok(readings
+            .into_iter()
+            .map(|(slot, reading)| (SlotKey::Quickslot(slot), reading))
+            .collect())
+    }
 
-        // Fallback: Return empty inventory for requested slots
-        let mut empty = HashMap::new();
-        for slot in slots {
-            empty.insert(slot.to_string(), 0);
-        }
-        Ok(empty)
+    /// Whether potion slots can be identified by icon
+    pub fn can_identify_potion_slots(&self) -> bool {
+        self.inventory_matcher.as_ref().is_some_and(|m| m.has_icon_templates())
+    }
+
+    /// Identify which quickslots hold HP / MP potions by icon matching
+    pub fn identify_potion_slots(&self, image: &DynamicImage) -> Result<PotionSlotMatch, String> {
+        let matcher = self.inventory_matcher.as_ref()
+            .ok_or("Inventory template matcher not initialized")?;
+
+        let (_inventory_image, coords) = matcher.detect_inventory_region_with_coords(image)?;
+        matcher.identify_potion_slots(image, coords)
+    }
+
+    /// Recognize item counts in the regular inventory window grid
+    /// `grid_image` is the already cropped grid ROI
+    pub fn recognize_grid_inventory(&self, grid_image: &DynamicImage, grid: &ItemGridConfig, cells: &[u32]) -> Result<HashMap<SlotKey, SlotReading>, String> {
+        let matcher = self.inventory_matcher.as_ref()
+            .ok_or("Inventory template matcher not initialized")?;
+
+        let readings = matcher.recognize_grid_slots(grid_image, grid.rows, grid.columns, cells)?;
+        Ok(readings
+            .into_iter()
+            .map(|(cell, reading)| (SlotKey::Grid(cell), reading))
+            .collect())
     }
 
     /// Check if OCR server is healthy
@@ -173,7 +214,7 @@ impl OcrService {
 /// Initialize OCR service state
 pub fn init_ocr_service() -> Result<OcrServiceState, String> {
     let service = OcrService::new()?;
-    Ok(Arc::new(Mutex::new(service)))
+    Ok(Arc::new(service))
 }
 
 /// Decode base64 image to DynamicImage
@@ -198,10 +239,7 @@ pub async fn recognize_level(
     state: State<'_, OcrServiceState>,
     image_base64: String,
 ) -> Result<LevelResult, String> {
-    let http_client = {
-        let service = state.inner().lock();
-        service.http_client.clone()
-    };
+    let http_client = state.inner().http_client.clone();
     let image = decode_base64_image(&image_base64)?;
     http_client.recognize_level(&image).await
 }
@@ -212,10 +250,7 @@ pub async fn recognize_exp(
     state: State<'_, OcrServiceState>,
     image_base64: String,
 ) -> Result<ExpResult, String> {
-    let http_client = {
-        let service = state.inner().lock();
-        service.http_client.clone()
-    };
+    let http_client = state.inner().http_client.clone();
     let image = decode_base64_image(&image_base64)?;
     http_client.recognize_exp(&image).await
 }
@@ -223,11 +258,12 @@ pub async fn recognize_exp(
 /// Recognize map name from base64-encoded image (async to prevent UI blocking)
 #[tauri::command]
 pub async fn recognize_map(
-    _state: State<'_, OcrServiceState>,
-    _image_base64: String,
+    state: State<'_, OcrServiceState>,
+    image_base64: String,
 ) -> Result<MapResult, String> {
-    // TODO: Implement map recognition in Python server
-    Err("Map recognition not yet implemented in HTTP OCR server".to_string())
+    let http_client = state.inner().http_client.clone();
+    let image = decode_base64_image(&image_base64)?;
+    http_client.recognize_map_name(&image).await
 }
 
 /// Tauri command: Recognize HP potion count from base64 image
@@ -236,10 +272,7 @@ pub async fn recognize_hp_potion_count(
     state: State<'_, OcrServiceState>,
     image_base64: String,
 ) -> Result<u32, String> {
-    let http_client = {
-        let service = state.inner().lock();
-        service.http_client.clone()
-    };
+    let http_client = state.inner().http_client.clone();
     let image = decode_base64_image(&image_base64)?;
     http_client.recognize_hp_potion_count(&image).await
 }
@@ -250,10 +283,7 @@ pub async fn recognize_mp_potion_count(
     state: State<'_, OcrServiceState>,
     image_base64: String,
 ) -> Result<u32, String> {
-    let http_client = {
-        let service = state.inner().lock();
-        service.http_client.clone()
-    };
+    let http_client = state.inner().http_client.clone();
     let image = decode_base64_image(&image_base64)?;
     http_client.recognize_mp_potion_count(&image).await
 }
@@ -268,10 +298,7 @@ pub async fn recognize_all_parallel(
     hp_base64: String,
     mp_base64: String,
 ) -> Result<CombinedOcrResult, String> {
-    let http_client = {
-        let service = state.inner().lock();
-        service.http_client.clone()
-    };
+    let http_client = state.inner().http_client.clone();
 
     // Decode images
     let level_image = decode_base64_image(&level_base64).ok();
@@ -318,10 +345,7 @@ pub async fn recognize_all_parallel(
 /// Tauri command: Check OCR server health
 #[tauri::command]
 pub async fn check_ocr_health(state: State<'_, OcrServiceState>) -> Result<bool, String> {
-    let http_client = {
-        let service = state.inner().lock();
-        service.http_client.clone()
-    };
+    let http_client = state.inner().http_client.clone();
 
     match http_client.health_check().await {
         Ok(_) => Ok(true),
@@ -377,20 +401,16 @@ pub async fn auto_detect_rois(
 
     // Step 2: Detect Level ROI with matched boxes
     {
-        let service = ocr_state.inner().lock();
+        let service = ocr_state.inner();
         if let Ok((left, top, right, bottom, matched_boxes)) = service.http_client.detect_level_roi_with_boxes(&image) {
             // Template matching works on physical pixels from xcap
             // Convert to logical pixels for consistent storage
-            let logical_left = (left as f64 / scale_factor) as i32;
-            let logical_top = (top as f64 / scale_factor) as i32;
-            let logical_width = ((right - left + 1) as f64 / scale_factor) as u32;
-            let logical_height = ((bottom - top + 1) as f64 / scale_factor) as u32;
-            
-            result.level = Some(crate::models::roi::Roi::new(
-                logical_left,
-                logical_top,
-                logical_width,
-                logical_height,
+            result.level = Some(crate::models::roi::Roi::from_physical(
+                left,
+                top,
+                right + 1,
+                bottom + 1,
+                scale_factor,
             ));
 
             // Convert matched boxes to logical coordinates
@@ -409,7 +429,7 @@ pub async fn auto_detect_rois(
 
     // Step 3: Detect Inventory ROI
     {
-        let service = ocr_state.inner().lock();
+        let service = ocr_state.inner();
         if let Some(matcher) = &service.inventory_matcher {
             if let Ok((_, coords)) = matcher.detect_inventory_region_with_coords(&image) {
                 let (left, top, right, bottom) = coords;
@@ -435,3 +455,90 @@ pub async fn auto_detect_rois(
     Ok(result)
 }
 
+
+/// Size of slot thumbnails returned by scan_inventory (width in pixels)
+const SLOT_THUMBNAIL_WIDTH: u32 = 64;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScannedSlot {
+    pub slot: SlotId,  // Serialized as config key (e.g. "del")
+    pub label: String, // Display label (e.g. "Del")
+    pub state: SlotState,
+    pub count: Option<u32>,
+    pub confidence: f32,
+    pub thumbnail: String, // data:image/png;base64,...
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InventoryScanResult {
+    pub inventory: crate::models::roi::Roi,
+    pub slots: Vec<ScannedSlot>,
+}
+
+/// Tauri command: Detect the inventory once and read every quickslot
+/// Lets the settings UI show the live slot grid instead of asking for key names
+#[tauri::command]
+pub async fn scan_inventory(
+    ocr_state: State<'_, OcrServiceState>,
+    screen_state: State<'_, crate::commands::screen_capture::ScreenCaptureState>,
+) -> Result<InventoryScanResult, String> {
+    let (image, scale_factor) = {
+        let state_guard = screen_state.inner().lock()
+            .map_err(|e| format!("Failed to lock screen state: {}", e))?;
+        let capture = state_guard.as_ref()
+            .ok_or("Screen capture not initialized")?;
+
+        (capture.capture_full()?, capture.get_scale_factor())
+    };
+
+    let service = Arc::clone(ocr_state.inner());
+    tokio::task::spawn_blocking(move || {
+        let matcher = service.inventory_matcher.as_ref()
+            .ok_or("Inventory template matcher not initialized")?;
+
+        let (coords, readings) = matching_pool::install(|| {
+            let (inventory_image, coords) = matcher.detect_inventory_region_with_coords(&image)?;
+            let readings = matcher.recognize_all_slots(&inventory_image)?;
+            Ok::<_, String>((coords, readings))
+        })?;
+
+        let mut slots = Vec::with_capacity(SlotId::ALL.len());
+        for slot in SlotId::ALL {
+            let reading = readings.get(&slot)
+                .cloned()
+                .unwrap_or_else(|| SlotReading::failed("Slot not recognized"));
+
+            let thumbnail = matcher.crop_slot_image(&image, coords, slot)?;
+            let thumbnail_height = (thumbnail.height() * SLOT_THUMBNAIL_WIDTH / thumbnail.width().max(1)).max(1);
+            let thumbnail = DynamicImage::ImageRgb8(image::imageops::resize(
+                &thumbnail,
+                SLOT_THUMBNAIL_WIDTH,
+                thumbnail_height,
+                image::imageops::FilterType::Triangle,
+            ));
+            let png = crate::services::screen_capture::ScreenCapture::image_to_png_bytes(&thumbnail)?;
+
+            slots.push(ScannedSlot {
+                slot,
+                label: slot.label().to_string(),
+                state: reading.state,
+                count: reading.count(),
+                confidence: reading.confidence,
+                thumbnail: format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(&png)),
+            });
+        }
+
+        // Convert physical pixels to logical pixels
+        let (left, top, right, bottom) = coords;
+        let inventory = crate::models::roi::Roi::new(
+            (left as f64 / scale_factor) as i32,
+            (top as f64 / scale_factor) as i32,
+            ((right - left + 1) as f64 / scale_factor) as u32,
+            ((bottom - top + 1) as f64 / scale_factor) as u32,
+        );
+
+        Ok::<_, String>(InventoryScanResult { inventory, slots })
+    })
+    .await
+    .map_err(|e| format!("Inventory scan task failed: {}", e))?
+}
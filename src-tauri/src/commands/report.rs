@@ -0,0 +1,59 @@
+use crate::commands::config::ConfigManagerState;
+use crate::commands::session::{display_record, load_display_config, load_rate_warmup_secs, SessionRecordsState};
+use crate::services::report::{self, ReportFormat, ReportRange};
+use crate::services::storage;
+use chrono::{Local, TimeZone};
+use std::fs;
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+
+/// Render a report of the sessions in `range` and save it to the reports folder
+/// Returns the file path; `open` also opens it with the system's default app.
+#[tauri::command]
+pub fn generate_report(
+    app: AppHandle,
+    state: State<SessionRecordsState>,
+    config_state: State<ConfigManagerState>,
+    range: ReportRange,
+    format: ReportFormat,
+    open: Option<bool>,
+) -> Result<String, String> {
+    let (from, to) = range.bounds(chrono::Utc::now().timestamp_millis());
+    if from >= to {
+        return Err("Report range is empty".to_string());
+    }
+
+    let display = load_display_config(&config_state);
+    let records: Vec<_> = {
+        let records = state.lock()
+            .map_err(|e| format!("Failed to lock session state: {}", e))?;
+        records.iter()
+            .filter(|record| record.timestamp >= from && record.timestamp < to)
+            .map(|record| display_record(record, &display))
+            .collect()
+    };
+
+    let content = report::render(&records, from, to, format, load_rate_warmup_secs(&config_state));
+
+    let local_date = |millis: i64| {
+        Local.timestamp_millis_opt(millis)
+            .single()
+            .map(|datetime| datetime.format("%Y%m%d").to_string())
+            .unwrap_or_default()
+    };
+    let file_name = format!("report_{}-{}.{}", local_date(from), local_date(to - 1), format.extension());
+    let path = storage::data_subdir(storage::REPORTS_DIR)?.join(file_name);
+
+    fs::write(&path, content)
+        .map_err(|e| format!("Failed to write report: {}", e))?;
+    println!("📄 Report saved: {} ({} sessions)", path.display(), records.len());
+
+    let path = path.to_string_lossy().to_string();
+    if open.unwrap_or(false) {
+        app.opener()
+            .open_path(path.clone(), None::<&str>)
+            .map_err(|e| format!("Failed to open report: {}", e))?;
+    }
+
+    Ok(path)
+}
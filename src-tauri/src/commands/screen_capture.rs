@@ -1,11 +1,59 @@
+use crate::commands::config::ConfigManagerState;
+use crate::commands::session::load_display_config;
 use crate::models::roi::Roi;
+use crate::services::image_payload::{self, ImageOptions, PayloadFormat};
 use crate::services::screen_capture::ScreenCapture;
-use tauri::State;
-use std::sync::Mutex;
+use base64::{engine::general_purpose, Engine as _};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::ipc::Response;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 /// State wrapper for screen capture service
 pub type ScreenCaptureState = Mutex<Option<ScreenCapture>>;
 
+/// Running live preview stream (ROI setup), if any
+pub type CapturePreviewState = Mutex<Option<tauri::async_runtime::JoinHandle<()>>>;
+
+/// Live preview frame rate: default and allowed range
+const PREVIEW_FPS: u32 = 3;
+const PREVIEW_FPS_RANGE: std::ops::RangeInclusive<u32> = 2..=4;
+
+/// Preview frames are shrunk to this width; ROIs are still drawn in screen coordinates
+const PREVIEW_MAX_WIDTH: u32 = 960;
+const PREVIEW_JPEG_QUALITY: u8 = 70;
+
+fn preview_options() -> ImageOptions {
+    ImageOptions {
+        max_width: Some(PREVIEW_MAX_WIDTH),
+        format: PayloadFormat::Jpeg,
+        quality: Some(PREVIEW_JPEG_QUALITY),
+        max_bytes: None,
+    }
+}
+
+/// Payload for "capture:preview-frame"
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewFrame {
+    pub seq: u64,
+    /// Base64 JPEG
+    pub image_base64: String,
+    pub width: u32,
+    pub height: u32,
+    /// Logical screen size the frame covers, for mapping drawn ROIs back
+    pub screen_width: u32,
+    pub screen_height: u32,
+}
+
+/// Screenshots may show the map and character, so privacy mode withholds them
+fn ensure_screenshots_allowed(config_state: &ConfigManagerState) -> Result<(), String> {
+    if load_display_config(config_state).privacy_mode {
+        return Err("Screenshots are hidden in privacy mode".to_string());
+    }
+    Ok(())
+}
+
 /// Initialize screen capture with primary monitor
 #[tauri::command]
 pub fn init_screen_capture(state: State<ScreenCaptureState>) -> Result<(), String> {
@@ -25,31 +73,126 @@ pub fn get_screen_dimensions(state: State<ScreenCaptureState>) -> Result<(u32, u
     capture.get_dimensions()
 }
 
-/// Capture a specific region and return as PNG bytes (base64 encoded)
+/// Capture a specific region as a raw binary response (PNG unless `options` say otherwise)
+/// The body starts with the encoded width and height; see `EncodedImage::into_framed`.
 #[tauri::command]
 pub fn capture_region(
     state: State<ScreenCaptureState>,
+    config_state: State<ConfigManagerState>,
     roi: Roi,
-) -> Result<Vec<u8>, String> {
+    options: Option<ImageOptions>,
+) -> Result<Response, String> {
+    ensure_screenshots_allowed(&config_state)?;
     let state_guard = state.inner().lock().map_err(|e| format!("Failed to lock state: {}", e))?;
     let capture = state_guard
         .as_ref()
         .ok_or("Screen capture not initialized")?;
 
     let image = capture.capture_region(&roi)?;
-    ScreenCapture::image_to_png_bytes(&image)
+    let encoded = image_payload::encode(&image, &options.unwrap_or_default())?;
+    Ok(Response::new(encoded.into_framed()))
 }
 
-/// Capture full screen and return as PNG bytes (base64 encoded)
+/// Capture full screen as a raw binary response framed like `capture_region`
+/// Shrunk to fit `image_payload::DEFAULT_MAX_BYTES` unless `options.max_bytes` is raised.
 #[tauri::command]
-pub fn capture_full_screen(state: State<ScreenCaptureState>) -> Result<Vec<u8>, String> {
+pub fn capture_full_screen(
+    state: State<ScreenCaptureState>,
+    config_state: State<ConfigManagerState>,
+    options: Option<ImageOptions>,
+) -> Result<Response, String> {
+    ensure_screenshots_allowed(&config_state)?;
     let state_guard = state.inner().lock().map_err(|e| format!("Failed to lock state: {}", e))?;
     let capture = state_guard
         .as_ref()
         .ok_or("Screen capture not initialized")?;
 
     let image = capture.capture_full()?;
-    ScreenCapture::image_to_png_bytes(&image)
+    let encoded = image_payload::encode(&image, &options.unwrap_or_default())?;
+    Ok(Response::new(encoded.into_framed()))
+}
+
+/// Stream downscaled JPEG frames of the screen as "capture:preview-frame" events
+/// so ROIs can be drawn over a live picture. Replaces a running stream.
+/// `fps` is clamped to 2-4 (default 3). Refused, and frames withheld, in privacy mode.
+#[tauri::command]
+pub fn start_capture_preview(
+    app: AppHandle,
+    state: State<CapturePreviewState>,
+    config_state: State<ConfigManagerState>,
+    fps: Option<u32>,
+) -> Result<(), String> {
+    ensure_screenshots_allowed(&config_state)?;
+    let capture = Arc::new(ScreenCapture::new()?);
+    let (screen_width, screen_height) = capture.get_dimensions()?;
+    let fps = fps.unwrap_or(PREVIEW_FPS).clamp(*PREVIEW_FPS_RANGE.start(), *PREVIEW_FPS_RANGE.end());
+
+    let task = tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_millis(1000 / fps as u64));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        let mut seq = 0;
+
+        loop {
+            ticker.tick().await;
+
+            // Privacy mode may be switched on while the stream runs
+            let privacy_mode = app.try_state::<ConfigManagerState>()
+                .is_some_and(|config_state| load_display_config(&config_state).privacy_mode);
+            if privacy_mode {
+                continue;
+            }
+
+            let capture = capture.clone();
+            let frame = tokio::task::spawn_blocking(move || {
+                let image = capture.capture_full()?;
+                image_payload::encode(&image, &preview_options())
+            })
+            .await
+            .map_err(|e| format!("Preview task failed: {}", e))
+            .and_then(|result| result);
+
+            let result = match frame {
+                Ok(frame) => {
+                    seq += 1;
+                    app.emit("capture:preview-frame", PreviewFrame {
+                        seq,
+                        image_base64: general_purpose::STANDARD.encode(frame.bytes),
+                        width: frame.width,
+                        height: frame.height,
+                        screen_width,
+                        screen_height,
+                    })
+                }
+                Err(e) => app.emit("capture:preview-error", e),
+            };
+            if let Err(e) = result {
+                eprintln!("Failed to emit preview frame: {}", e);
+            }
+        }
+    });
+
+    let mut running = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    if let Some(previous) = running.replace(task) {
+        previous.abort();
+    }
+
+    #[cfg(debug_assertions)]
+    println!("🎥 Capture preview started ({} fps)", fps);
+
+    Ok(())
+}
+
+/// Stop the live preview stream (no-op if none is running)
+#[tauri::command]
+pub fn stop_capture_preview(state: State<CapturePreviewState>) -> Result<(), String> {
+    let mut running = state.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+    if let Some(task) = running.take() {
+        task.abort();
+
+        #[cfg(debug_assertions)]
+        println!("🎥 Capture preview stopped");
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -1,11 +1,35 @@
+use crate::commands::config::ConfigManagerState;
+use crate::commands::ocr::OcrServiceState;
+use crate::commands::screen_capture::ScreenCaptureState;
+use crate::commands::tracking::TrackerState;
+use crate::services::ocr_tracker::TrackingStats;
+use crate::models::config::{DisplayConfig, TimeFormat, DEFAULT_RATE_WARMUP_SECS};
+use crate::models::ocr_result::MapResult;
+use crate::models::ocr_failure::FailureHistogram;
+use crate::models::timeline::{Timeline, TimelinePoint};
+use crate::services::privacy;
+use crate::services::progress_ledger::{self, ProgressHistory};
+use crate::services::storage;
+use crate::services::ocr_failures::FailureStore;
+use crate::services::session_configs::{SessionConfig, SessionConfigStore};
+use crate::services::timeline_store::TimelineStore;
+use chrono::{DateTime, Local, TimeZone, Timelike};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use tauri::State;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager, State};
 
+/// Saved session
+///
+/// `timestamp` is always a UTC Unix timestamp in milliseconds. Local times are
+/// only produced when records are handed to the UI, so they follow the current
+/// time zone and `DisplayConfig::time_format`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionRecord {
     pub id: String,
+    /// User-given title; empty means the default date title, rendered on read
     pub title: String,
     pub timestamp: i64,
     pub combat_time: i32,
@@ -14,33 +38,171 @@ pub struct SessionRecord {
     pub avg_exp_per_second: f64,
     pub hp_potions_used: i32,
     pub mp_potions_used: i32,
+    /// Map name read at session start (prefixes the default title)
+    #[serde(default)]
+    pub map_name: Option<String>,
+    #[serde(default)]
+    pub hp_potions_per_minute: f64,
+    #[serde(default)]
+    pub mp_potions_per_minute: f64,
+    /// Tracker statistics when the session was saved; None for older records
+    #[serde(default)]
+    pub final_stats: Option<TrackingStats>,
+    /// Wall-clock start (first timeline point), UTC ms; None for older records
+    /// Differs from `timestamp - combat_time` when tracking was stopped or the machine slept.
+    #[serde(default)]
+    pub started_at: Option<i64>,
+}
+
+#[cfg(test)]
+impl SessionRecord {
+    /// Ten minutes at level 100 ending at `timestamp`, for tests to adjust
+    pub(crate) fn sample(timestamp: i64) -> Self {
+        Self {
+            id: timestamp.to_string(),
+            title: String::new(),
+            timestamp,
+            combat_time: 600,
+            exp_gained: 1000,
+            current_level: 100,
+            avg_exp_per_second: 1.5,
+            hp_potions_used: 0,
+            mp_potions_used: 0,
+            map_name: None,
+            hp_potions_per_minute: 0.0,
+            mp_potions_per_minute: 0.0,
+            final_stats: None,
+            started_at: Some(timestamp - 600_000),
+        }
+    }
 }
 
 pub type SessionRecordsState = std::sync::Mutex<Vec<SessionRecord>>;
 
+/// Map name read at session start, used for the default session title
+pub type SessionMapState = std::sync::Mutex<Option<String>>;
+
+/// Timestamps below this are in seconds, not milliseconds (year 5138 in seconds)
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
 pub fn init_session_records() -> SessionRecordsState {
     match load_sessions_from_file() {
-        Ok(records) => std::sync::Mutex::new(records),
+        Ok(mut records) => {
+            if migrate_records(&mut records) {
+                println!("🗂️ Migrated session records to the current format");
+                if let Err(e) = save_sessions_to_file(&records) {
+                    eprintln!("Failed to save migrated session records: {}", e);
+                }
+            }
+            std::sync::Mutex::new(records)
+        }
         Err(_) => std::sync::Mutex::new(Vec::new()),
     }
 }
 
-fn format_timestamp_to_title(timestamp_millis: i64) -> String {
-    use chrono::{Local, TimeZone};
-    
-    let datetime = Local.timestamp_millis_opt(timestamp_millis).unwrap();
-    datetime.format("%Y년 %m월 %d일 %H:%M 전투").to_string()
+/// Date title in the given time zone, e.g. "2025년 01월 02일 14:30 전투"
+fn format_title<Tz: TimeZone>(datetime: &DateTime<Tz>, time_format: &TimeFormat) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    match time_format {
+        TimeFormat::TwentyFourHour => datetime.format("%Y년 %m월 %d일 %H:%M 전투").to_string(),
+        TimeFormat::TwelveHour => {
+            let period = if datetime.hour() < 12 { "오전" } else { "오후" };
+            format!("{} {} {} 전투", datetime.format("%Y년 %m월 %d일"), period, datetime.format("%I:%M"))
+        }
+    }
+}
+
+/// Default title: map name (if read at session start) + local date
+fn default_session_title(timestamp_millis: i64, map_name: Option<&str>, time_format: &TimeFormat) -> String {
+    let title = Local.timestamp_millis_opt(timestamp_millis)
+        .single()
+        .map(|datetime| format_title(&datetime, time_format))
+        .unwrap_or_default();
+    match map_name {
+        Some(map_name) => format!("{} · {}", map_name, title),
+        None => title,
+    }
+}
+
+/// Match a baked-in local date title (not user-given), optionally prefixed by a map name
+fn generated_title_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^(?:(.+) · )?\d{4}년 \d{2}월 \d{2}일 (?:오전 |오후 )?\d{2}:\d{2} 전투$").unwrap()
+    })
+}
+
+/// Normalize a record to the stored format: millisecond UTC timestamp, no
+/// local time baked into the title, and potion rates (missing before they were saved)
+fn normalize_record(record: &mut SessionRecord) -> bool {
+    let mut changed = false;
+
+    if record.final_stats.is_none() && record.hp_potions_per_minute == 0.0 && record.mp_potions_per_minute == 0.0 {
+        let (hp, mp) = (potions_per_minute(record, record.hp_potions_used), potions_per_minute(record, record.mp_potions_used));
+        if hp > 0.0 || mp > 0.0 {
+            record.hp_potions_per_minute = hp;
+            record.mp_potions_per_minute = mp;
+            changed = true;
+        }
+    }
+
+    if record.timestamp > 0 && record.timestamp < MILLIS_THRESHOLD {
+        record.timestamp *= 1000;
+        changed = true;
+    }
+    if let Some(caps) = generated_title_pattern().captures(record.title.trim()) {
+        if record.map_name.is_none() {
+            record.map_name = caps.get(1).map(|m| m.as_str().to_string());
+        }
+        record.title.clear();
+        changed = true;
+    }
+
+    changed
+}
+
+/// Migrate records saved by older versions; returns true if anything changed
+fn migrate_records(records: &mut [SessionRecord]) -> bool {
+    records.iter_mut().fold(false, |changed, record| normalize_record(record) || changed)
+}
+
+/// Record as shown in the UI (and exported): default titles rendered in local
+/// time, map names hidden in privacy mode
+pub(crate) fn display_record(record: &SessionRecord, display: &DisplayConfig) -> SessionRecord {
+    let mut record = record.clone();
+    if record.title.is_empty() {
+        record.title = default_session_title(record.timestamp, record.map_name.as_deref(), &display.time_format);
+    }
+    if display.privacy_mode {
+        record = privacy::redact_record(record);
+    }
+    record
+}
+
+/// Display preferences from config (defaults if unavailable)
+pub(crate) fn load_display_config(config_state: &ConfigManagerState) -> DisplayConfig {
+    match config_state.lock() {
+        Ok(manager) => match manager.load() {
+            Ok(config) => config.display,
+            Err(_) => DisplayConfig::default()
+        },
+        Err(_) => DisplayConfig::default()
+    }
+}
+
+/// Seconds at the start of a session whose rates are only estimates
+pub(crate) fn load_rate_warmup_secs(config_state: &ConfigManagerState) -> u64 {
+    config_state.lock()
+        .ok()
+        .and_then(|manager| manager.load().ok())
+        .map(|config| config.tracking.rate_warmup_secs)
+        .unwrap_or(DEFAULT_RATE_WARMUP_SECS)
 }
 
 fn get_sessions_file_path() -> Result<PathBuf, String> {
-    let app_dir = dirs::config_dir()
-        .ok_or("Failed to get config directory")?
-        .join("exp-tracker");
-    
-    fs::create_dir_all(&app_dir)
-        .map_err(|e| format!("Failed to create app directory: {}", e))?;
-    
-    Ok(app_dir.join("session_records.json"))
+    storage::session_records_path()
 }
 
 fn load_sessions_from_file() -> Result<Vec<SessionRecord>, String> {
@@ -71,31 +233,174 @@ fn save_sessions_to_file(records: &[SessionRecord]) -> Result<(), String> {
     Ok(())
 }
 
-/// Get all session records
+/// Get all session records (default titles rendered in local time)
 #[tauri::command]
-pub fn get_session_records(state: State<SessionRecordsState>) -> Result<Vec<SessionRecord>, String> {
+pub fn get_session_records(
+    state: State<SessionRecordsState>,
+    config_state: State<ConfigManagerState>,
+) -> Result<Vec<SessionRecord>, String> {
+    let display = load_display_config(&config_state);
     let records = state.lock()
         .map_err(|e| format!("Failed to lock session state: {}", e))?;
     
-    Ok(records.clone())
+    Ok(records.iter().map(|record| display_record(record, &display)).collect())
 }
 
-/// Save a new session record
+/// Save a new session record, with the current session's EXP timeline and final stats
+/// An empty or generated date title is stored empty and rendered on read
 #[tauri::command]
-pub fn save_session_record(
-    state: State<SessionRecordsState>,
+pub async fn save_session_record(
+    app: AppHandle,
+    state: State<'_, SessionRecordsState>,
+    session_map: State<'_, SessionMapState>,
     record: SessionRecord,
 ) -> Result<(), String> {
+    let (points, stats, failures, config) = match app.try_state::<TrackerState>() {
+        Some(tracker) => {
+            let tracker = tracker.0.lock().await;
+            (
+                tracker.timeline().await.unwrap_or_default(),
+                Some(tracker.get_stats().await),
+                tracker.take_failure_histogram(),
+                tracker.session_config(),
+            )
+        }
+        None => (Vec::new(), None, FailureHistogram::default(), None),
+    };
+
+    store_record(&state, &session_map, record, points, stats, failures, config)?;
+    Ok(())
+}
+
+/// End the session in one step: stop tracking, save the record (with its
+/// timeline) and reset for the next session
+///
+/// The tracker stays locked throughout, so no reading or command lands between
+/// the steps. Returns the saved record as `get_session_records` shows it.
+#[tauri::command]
+pub async fn finish_session(
+    title: Option<String>,
+    tracker: State<'_, TrackerState>,
+    state: State<'_, SessionRecordsState>,
+    session_map: State<'_, SessionMapState>,
+    config_state: State<'_, ConfigManagerState>,
+) -> Result<SessionRecord, String> {
+    let record = finish_and_save(&tracker, &state, &session_map, title.unwrap_or_default()).await?;
+    Ok(display_record(&record, &load_display_config(&config_state)))
+}
+
+/// `finish_session` without the display rendering (also run on app exit)
+pub(crate) async fn finish_and_save(
+    tracker: &TrackerState,
+    state: &SessionRecordsState,
+    session_map: &SessionMapState,
+    title: String,
+) -> Result<SessionRecord, String> {
+    let mut tracker = tracker.0.lock().await;
+    let finished = tracker.finish().await?;
+
+    let record = record_from_stats(&finished.stats, title, chrono::Utc::now().timestamp_millis());
+    let failures = tracker.take_failure_histogram();
+    let config = tracker.session_config();
+    let record = store_record(state, session_map, record, finished.timeline, Some(finished.stats), failures, config)?;
+
+    tracker.reset().await?;
+    println!("🏁 Session {} saved and tracking reset", record.id);
+
+    Ok(record)
+}
+
+/// Record of a session ending at `timestamp` (UTC ms) with the given stats
+fn record_from_stats(stats: &TrackingStats, title: String, timestamp: i64) -> SessionRecord {
+    let mut record = SessionRecord {
+        id: timestamp.to_string(),
+        title,
+        timestamp,
+        combat_time: stats.elapsed_seconds.min(i32::MAX as u64) as i32,
+        exp_gained: stats.total_exp.min(i64::MAX as u64) as i64,
+        current_level: stats.level.unwrap_or(0) as i32,
+        avg_exp_per_second: 0.0,
+        hp_potions_used: stats.hp_potions_used as i32,
+        mp_potions_used: stats.mp_potions_used as i32,
+        map_name: None,
+        hp_potions_per_minute: 0.0,
+        mp_potions_per_minute: 0.0,
+        final_stats: None,
+        started_at: None,
+    };
+    record.avg_exp_per_second = average_exp_per_second(&record);
+    record
+}
+
+/// Normalize and store a new record, and its timeline, OCR failures and
+/// config snapshot next to it
+/// Takes the map read at session start; returns the stored record.
+fn store_record(
+    state: &SessionRecordsState,
+    session_map: &SessionMapState,
+    mut record: SessionRecord,
+    points: Vec<TimelinePoint>,
+    stats: Option<TrackingStats>,
+    mut failures: FailureHistogram,
+    config: Option<SessionConfig>,
+) -> Result<SessionRecord, String> {
+    // The map reading belongs to this session only
+    let map_name = session_map.lock()
+        .map_err(|e| format!("Failed to lock session map: {}", e))?
+        .take();
+
+    record.title = record.title.trim().to_string();
+    if record.map_name.is_none() {
+        record.map_name = map_name;
+    }
+    if record.started_at.is_none() {
+        record.started_at = points.first().and_then(|point| point.recorded_at);
+    }
+    if let Some(stats) = stats {
+        record.hp_potions_per_minute = stats.hp_potions_per_minute;
+        record.mp_potions_per_minute = stats.mp_potions_per_minute;
+        record.final_stats = Some(stats);
+    }
+    normalize_record(&mut record);
+
     let mut records = state.lock()
         .map_err(|e| format!("Failed to lock session state: {}", e))?;
     
+    let timeline = Timeline {
+        session_id: record.id.clone(),
+        timestamp: record.timestamp,
+        map_name: record.map_name.clone(),
+        points,
+    };
+
     // Add new record at the beginning (most recent first)
-    records.insert(0, record);
+    records.insert(0, record.clone());
     
     // Save to file
     save_sessions_to_file(&records)?;
+
+    // The record is what matters; a missing timeline only drops it from baselines
+    if timeline.points.len() > 1 {
+        if let Err(e) = TimelineStore::open().save(&timeline) {
+            eprintln!("Failed to save session timeline: {}", e);
+        }
+    }
+    if !failures.is_empty() {
+        failures.session_id = record.id.clone();
+        failures.timestamp = record.timestamp;
+        if let Err(e) = FailureStore::open().save(&failures) {
+            eprintln!("Failed to save session OCR failures: {}", e);
+        }
+    }
+    if let Some(mut config) = config {
+        config.session_id = record.id.clone();
+        config.timestamp = record.timestamp;
+        if let Err(e) = SessionConfigStore::open().save(&config) {
+            eprintln!("Failed to save session config: {}", e);
+        }
+    }
     
-    Ok(())
+    Ok(record)
 }
 
 /// Delete a session record by ID
@@ -112,10 +417,35 @@ pub fn delete_session_record(
     
     // Save to file
     save_sessions_to_file(&records)?;
+
+    SessionFiles::open().delete(&id);
     
     Ok(())
 }
 
+/// Settings a saved session was tracked with (ROIs, intervals, thresholds, engines)
+/// Sessions saved without tracking, or by older versions, have none.
+#[tauri::command]
+pub fn get_session_config(state: State<SessionRecordsState>, session_id: String) -> Result<SessionConfig, String> {
+    let exists = state.lock()
+        .map_err(|e| format!("Failed to lock session state: {}", e))?
+        .iter()
+        .any(|record| record.id == session_id);
+    if !exists {
+        return Err(format!("Session record with id '{}' not found", session_id));
+    }
+    SessionConfigStore::open().load(&session_id)
+}
+
+/// Level progression across all saved sessions, with the untracked progress
+/// between them, for the long-term progression chart
+#[tauri::command]
+pub fn get_progress_history(state: State<SessionRecordsState>) -> Result<ProgressHistory, String> {
+    let records = state.lock()
+        .map_err(|e| format!("Failed to lock session state: {}", e))?;
+    Ok(progress_ledger::progress_history(&records))
+}
+
 /// Update the title of a session record
 #[tauri::command]
 pub fn update_session_title(
@@ -127,8 +457,10 @@ pub fn update_session_title(
         .map_err(|e| format!("Failed to lock session state: {}", e))?;
     
     // Find and update the record with matching ID
+    // An empty title falls back to the default date title
     if let Some(record) = records.iter_mut().find(|r| r.id == id) {
-        record.title = new_title;
+        record.title = new_title.trim().to_string();
+        normalize_record(record);
     } else {
         return Err(format!("Session record with id '{}' not found", id));
     }
@@ -139,3 +471,564 @@ pub fn update_session_title(
     Ok(())
 }
 
+
+/// Start of a record's session in ms (`timestamp` is when it was saved, i.e. its end)
+/// Older records without `started_at` are estimated from the combat time.
+pub(crate) fn session_start(record: &SessionRecord) -> i64 {
+    record.started_at
+        .unwrap_or_else(|| record.timestamp - i64::from(record.combat_time.max(0)) * 1000)
+}
+
+/// Part of `total` proportional to `part` of `whole`
+fn prorate(total: i64, part: i64, whole: i64) -> i64 {
+    if whole <= 0 {
+        return 0;
+    }
+    (total as i128 * part as i128 / whole as i128) as i64
+}
+
+/// Split a record at `at` (ms) into the part before and the part after
+///
+/// Records only keep totals, so EXP and potions are divided in proportion to
+/// the combat time on each side. `at` is mapped to combat time through the
+/// session's `timeline` when it has wall-clock times, so stopped and slept
+/// time isn't counted. The later part keeps the record's id. The map
+/// was read at session start, so it stays with the first part; the second
+/// gets `second_map`.
+fn split_record(
+    record: &SessionRecord,
+    at: i64,
+    first_id: String,
+    second_map: Option<String>,
+    timeline: Option<&Timeline>,
+) -> Result<(SessionRecord, SessionRecord), String> {
+    let start = session_start(record);
+    if at <= start || at >= record.timestamp {
+        return Err(format!("Split time must be within session '{}'", record.id));
+    }
+
+    let total_secs = record.combat_time as i64;
+    let first_secs = timeline
+        .and_then(|timeline| timeline.elapsed_at(at))
+        .map_or((at - start) / 1000, |elapsed| elapsed.min(i64::MAX as u64) as i64)
+        .clamp(0, total_secs.max(0));
+    let first_share = |total: i64| prorate(total, first_secs, total_secs);
+
+    let mut first = record.clone();
+    first.id = first_id;
+    first.timestamp = at;
+    first.combat_time = first_secs as i32;
+    first.exp_gained = first_share(record.exp_gained);
+    first.hp_potions_used = first_share(record.hp_potions_used as i64) as i32;
+    first.mp_potions_used = first_share(record.mp_potions_used as i64) as i32;
+
+    let mut second = record.clone();
+    second.map_name = second_map;
+    second.started_at = Some(at);
+    second.combat_time = record.combat_time - first.combat_time;
+    second.exp_gained = record.exp_gained - first.exp_gained;
+    second.hp_potions_used = record.hp_potions_used - first.hp_potions_used;
+    second.mp_potions_used = record.mp_potions_used - first.mp_potions_used;
+
+    // The snapshot describes the end of the session, which is in the second part
+    first.final_stats = None;
+    for part in [&mut first, &mut second] {
+        update_rates(part);
+    }
+
+    Ok((first, second))
+}
+
+/// Combine records into one; the latest record's id, end time and level are kept
+/// The title and map name come from the earliest record that has one.
+fn merge_records(mut records: Vec<SessionRecord>) -> Result<SessionRecord, String> {
+    if records.len() < 2 {
+        return Err("Select at least two sessions to merge".to_string());
+    }
+    records.sort_by_key(|record| record.timestamp);
+
+    let mut merged = records.last().cloned().ok_or("No sessions to merge")?;
+    merged.title = records.iter()
+        .map(|record| record.title.clone())
+        .find(|title| !title.is_empty())
+        .unwrap_or_default();
+    merged.map_name = records.iter().find_map(|record| record.map_name.clone());
+    merged.started_at = records.first().and_then(|record| record.started_at);
+    merged.combat_time = records.iter().map(|record| record.combat_time).sum();
+    merged.exp_gained = records.iter().map(|record| record.exp_gained).sum();
+    merged.hp_potions_used = records.iter().map(|record| record.hp_potions_used).sum();
+    merged.mp_potions_used = records.iter().map(|record| record.mp_potions_used).sum();
+    update_rates(&mut merged);
+
+    Ok(merged)
+}
+
+fn potions_per_minute(record: &SessionRecord, used: i32) -> f64 {
+    if record.combat_time > 0 {
+        used as f64 * 60.0 / record.combat_time as f64
+    } else {
+        0.0
+    }
+}
+
+/// Recompute the averages after the totals changed
+fn update_rates(record: &mut SessionRecord) {
+    record.avg_exp_per_second = average_exp_per_second(record);
+    record.hp_potions_per_minute = potions_per_minute(record, record.hp_potions_used);
+    record.mp_potions_per_minute = potions_per_minute(record, record.mp_potions_used);
+}
+
+fn average_exp_per_second(record: &SessionRecord) -> f64 {
+    if record.combat_time > 0 {
+        record.exp_gained as f64 / record.combat_time as f64
+    } else {
+        0.0
+    }
+}
+
+/// Keep records most recent first, as they are shown
+fn sort_records(records: &mut [SessionRecord]) {
+    records.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+}
+
+/// The files kept next to each record: timeline, OCR failures and config snapshot
+///
+/// Splitting or merging records splits or merges these too, so none are left
+/// behind under ids that no longer have a record. Failures are logged; the
+/// records are what matter.
+struct SessionFiles {
+    timelines: TimelineStore,
+    failures: FailureStore,
+    configs: SessionConfigStore,
+}
+
+impl SessionFiles {
+    fn open() -> Self {
+        Self {
+            timelines: TimelineStore::open(),
+            failures: FailureStore::open(),
+            configs: SessionConfigStore::open(),
+        }
+    }
+
+    #[cfg(test)]
+    fn in_dir(root: &std::path::Path) -> Self {
+        Self {
+            timelines: TimelineStore::new(root.join(storage::TIMELINES_DIR)),
+            failures: FailureStore::new(root.join(storage::OCR_FAILURES_DIR)),
+            configs: SessionConfigStore::new(root.join(storage::SESSION_CONFIGS_DIR)),
+        }
+    }
+
+    fn delete(&self, id: &str) {
+        if let Err(e) = self.timelines.delete(id) {
+            eprintln!("Failed to delete session timeline: {}", e);
+        }
+        if let Err(e) = self.failures.delete(id) {
+            eprintln!("Failed to delete session OCR failures: {}", e);
+        }
+        if let Err(e) = self.configs.delete(id) {
+            eprintln!("Failed to delete session config: {}", e);
+        }
+    }
+
+    /// Divide the files of `original` between its parts (`second` keeps its id)
+    fn split(&self, original: &SessionRecord, first: &SessionRecord, second: &SessionRecord) {
+        let at = first.combat_time.max(0) as u64;
+
+        if let Ok(timeline) = self.timelines.load(&original.id) {
+            let (first_points, second_points) = timeline.split_at(at);
+            for (part, points) in [(first, first_points), (second, second_points)] {
+                let timeline = Timeline {
+                    session_id: part.id.clone(),
+                    timestamp: part.timestamp,
+                    map_name: part.map_name.clone(),
+                    points,
+                };
+                if let Err(e) = self.timelines.save(&timeline) {
+                    eprintln!("Failed to save session timeline: {}", e);
+                }
+            }
+        }
+
+        if let Ok(histogram) = self.failures.load(&original.id) {
+            let (before, after) = histogram.split_at(at, original.combat_time.max(0) as u64);
+            for (part, mut histogram) in [(first, before), (second, after)] {
+                histogram.session_id = part.id.clone();
+                histogram.timestamp = part.timestamp;
+                // An empty part must not keep the original's file
+                let result = if histogram.is_empty() {
+                    self.failures.delete(&part.id)
+                } else {
+                    self.failures.save(&histogram)
+                };
+                if let Err(e) = result {
+                    eprintln!("Failed to save session OCR failures: {}", e);
+                }
+            }
+        }
+
+        // Both parts were tracked with the same settings
+        if let Ok(config) = self.configs.load(&original.id) {
+            for part in [first, second] {
+                let config = SessionConfig { session_id: part.id.clone(), timestamp: part.timestamp, ..config.clone() };
+                if let Err(e) = self.configs.save(&config) {
+                    eprintln!("Failed to save session config: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Combine the files of `parts` (oldest first) into `merged`'s and delete the rest
+    fn merge(&self, parts: &[SessionRecord], merged: &SessionRecord) {
+        // A timeline with a part missing would put the later parts at the wrong time
+        let timelines: Vec<Timeline> = parts.iter().filter_map(|part| self.timelines.load(&part.id).ok()).collect();
+        if timelines.len() == parts.len() {
+            let timeline = Timeline {
+                session_id: merged.id.clone(),
+                timestamp: merged.timestamp,
+                map_name: merged.map_name.clone(),
+                points: Timeline::concat(&timelines),
+            };
+            if let Err(e) = self.timelines.save(&timeline) {
+                eprintln!("Failed to save session timeline: {}", e);
+            }
+        } else if let Err(e) = self.timelines.delete(&merged.id) {
+            eprintln!("Failed to delete session timeline: {}", e);
+        }
+
+        let mut histogram = FailureHistogram {
+            session_id: merged.id.clone(),
+            timestamp: merged.timestamp,
+            ..Default::default()
+        };
+        let mut offset_seconds = 0;
+        for part in parts {
+            if let Ok(part_histogram) = self.failures.load(&part.id) {
+                histogram.append(&part_histogram, offset_seconds);
+            }
+            offset_seconds += part.combat_time.max(0) as u64;
+        }
+        if !histogram.is_empty() {
+            if let Err(e) = self.failures.save(&histogram) {
+                eprintln!("Failed to save session OCR failures: {}", e);
+            }
+        }
+
+        // The latest settings, as for the record's other end-of-session values
+        if let Some(config) = parts.iter().rev().find_map(|part| self.configs.load(&part.id).ok()) {
+            let config = SessionConfig { session_id: merged.id.clone(), timestamp: merged.timestamp, ..config };
+            if let Err(e) = self.configs.save(&config) {
+                eprintln!("Failed to save session config: {}", e);
+            }
+        }
+
+        for part in parts.iter().filter(|part| part.id != merged.id) {
+            self.delete(&part.id);
+        }
+    }
+}
+
+/// Split one session into two at `timestamp` (UTC ms), e.g. when it spans two maps
+/// The map read at session start stays with the first part; `map_name` labels the second.
+/// Returns the two new records, earlier first.
+#[tauri::command]
+pub fn split_session(
+    state: State<SessionRecordsState>,
+    config_state: State<ConfigManagerState>,
+    session_id: String,
+    timestamp: i64,
+    map_name: Option<String>,
+) -> Result<Vec<SessionRecord>, String> {
+    let mut records = state.lock()
+        .map_err(|e| format!("Failed to lock session state: {}", e))?;
+
+    let index = records.iter()
+        .position(|r| r.id == session_id)
+        .ok_or_else(|| format!("Session record with id '{}' not found", session_id))?;
+
+    // Ids are creation times in ms; bump if another record already uses it
+    let mut first_id = timestamp;
+    while records.iter().any(|r| r.id == first_id.to_string()) {
+        first_id += 1;
+    }
+
+    let original = records[index].clone();
+    let files = SessionFiles::open();
+    let timeline = files.timelines.load(&original.id).ok();
+    let (first, second) = split_record(&original, timestamp, first_id.to_string(), map_name, timeline.as_ref())?;
+    records[index] = second.clone();
+    records.push(first.clone());
+    sort_records(&mut records);
+
+    save_sessions_to_file(&records)?;
+    files.split(&original, &first, &second);
+
+    let display = load_display_config(&config_state);
+    Ok(vec![display_record(&first, &display), display_record(&second, &display)])
+}
+
+/// Merge sessions into one, e.g. a session accidentally split by an app restart
+#[tauri::command]
+pub fn merge_sessions(
+    state: State<SessionRecordsState>,
+    config_state: State<ConfigManagerState>,
+    ids: Vec<String>,
+) -> Result<SessionRecord, String> {
+    let mut records = state.lock()
+        .map_err(|e| format!("Failed to lock session state: {}", e))?;
+
+    if let Some(missing) = ids.iter().find(|id| !records.iter().any(|r| &r.id == *id)) {
+        return Err(format!("Session record with id '{}' not found", missing));
+    }
+
+    let mut selected: Vec<SessionRecord> = records.iter().filter(|r| ids.contains(&r.id)).cloned().collect();
+    selected.sort_by_key(|record| record.timestamp);
+    let merged = merge_records(selected.clone())?;
+
+    records.retain(|r| !ids.contains(&r.id));
+    records.push(merged.clone());
+    sort_records(&mut records);
+
+    save_sessions_to_file(&records)?;
+    SessionFiles::open().merge(&selected, &merged);
+
+    Ok(display_record(&merged, &load_display_config(&config_state)))
+}
+
+
+/// OCR the map name ROI once (at session start) to label the session
+#[tauri::command]
+pub async fn capture_session_map_name(
+    config_state: State<'_, ConfigManagerState>,
+    screen_state: State<'_, ScreenCaptureState>,
+    ocr_state: State<'_, OcrServiceState>,
+    session_map: State<'_, SessionMapState>,
+) -> Result<MapResult, String> {
+    let roi = {
+        let manager = config_state.lock()
+            .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+        manager.load()?.roi.map_name
+            .ok_or("Map name ROI not configured")?
+    };
+
+    let image = {
+        let state_guard = screen_state.inner().lock()
+            .map_err(|e| format!("Failed to lock screen state: {}", e))?;
+        let capture = state_guard.as_ref()
+            .ok_or("Screen capture not initialized")?;
+        capture.capture_region(&roi)?
+    };
+
+    let http_client = ocr_state.inner().http_client.clone();
+    let result = http_client.recognize_map_name(&image).await?;
+
+    println!("🗺️ Session map: {}", result.map_name);
+    *session_map.lock()
+        .map_err(|e| format!("Failed to lock session map: {}", e))? = Some(result.map_name.clone());
+
+    if load_display_config(&config_state).privacy_mode {
+        return Ok(MapResult { map_name: privacy::REDACTED.to_string(), raw_text: privacy::REDACTED.to_string() });
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn record(title: &str, timestamp: i64) -> SessionRecord {
+        SessionRecord { title: title.to_string(), ..SessionRecord::sample(timestamp) }
+    }
+
+    #[test]
+    fn test_format_title_follows_time_format() {
+        let kst = FixedOffset::east_opt(9 * 3600).unwrap();
+        let datetime = kst.with_ymd_and_hms(2025, 3, 30, 14, 5, 0).unwrap();
+
+        assert_eq!(format_title(&datetime, &TimeFormat::TwentyFourHour), "2025년 03월 30일 14:05 전투");
+        assert_eq!(format_title(&datetime, &TimeFormat::TwelveHour), "2025년 03월 30일 오후 02:05 전투");
+    }
+
+    #[test]
+    fn test_default_session_title_prefixes_map_name() {
+        let timestamp = 1_700_000_000_000;
+        let plain = default_session_title(timestamp, None, &TimeFormat::TwentyFourHour);
+        assert!(generated_title_pattern().is_match(&plain));
+        assert_eq!(
+            default_session_title(timestamp, Some("헤네시스"), &TimeFormat::TwentyFourHour),
+            format!("헤네시스 · {}", plain)
+        );
+    }
+
+    #[test]
+    fn test_migration_clears_baked_titles_and_fixes_seconds() {
+        let mut records = vec![
+            record("2024년 11월 03일 01:30 전투", 1_730_565_000),
+            record("보스 트라이", 1_730_565_000_000),
+            record("헤네시스 · 2024년 11월 03일 오전 01:30 전투", 1_730_565_000_000),
+        ];
+
+        assert!(migrate_records(&mut records));
+        assert_eq!(records[0].title, "");
+        assert_eq!(records[0].timestamp, 1_730_565_000_000);
+        assert_eq!(records[1].title, "보스 트라이");
+        assert_eq!(records[2].title, "");
+        assert_eq!(records[2].map_name.as_deref(), Some("헤네시스"));
+
+        // Already migrated records are left alone
+        assert!(!migrate_records(&mut records));
+    }
+
+    #[test]
+    fn test_legacy_record_without_map_name_loads() {
+        let json = r#"{"id":"1","title":"t","timestamp":1700000000000,"combat_time":1,
+            "exp_gained":2,"current_level":3,"avg_exp_per_second":0.5,
+            "hp_potions_used":0,"mp_potions_used":0}"#;
+        let record: SessionRecord = serde_json::from_str(json).unwrap();
+        assert_eq!(record.map_name, None);
+        assert!(record.final_stats.is_none());
+    }
+
+    #[test]
+    fn test_migration_derives_potion_rates() {
+        let mut legacy = record("보스 트라이", 1_700_000_000_000);
+        legacy.hp_potions_used = 30;
+        legacy.mp_potions_used = 6;
+        let mut records = vec![legacy];
+
+        assert!(migrate_records(&mut records));
+        assert_eq!(records[0].hp_potions_per_minute, 3.0);
+        assert_eq!(records[0].mp_potions_per_minute, 0.6);
+        assert!(!migrate_records(&mut records));
+    }
+
+    #[test]
+    fn test_split_record_prorates_by_time() {
+        // 600s session ending at 1_700_000_600_000
+        let original = record("", 1_700_000_600_000);
+        let at = 1_700_000_000_000 + 150_000;
+
+        let mut original = original;
+        original.map_name = Some("헤네시스".to_string());
+        let (first, second) = split_record(&original, at, at.to_string(), Some("커닝시티".to_string()), None).unwrap();
+        assert_eq!(first.timestamp, at);
+        assert_eq!(first.map_name.as_deref(), Some("헤네시스"));
+        assert_eq!(second.map_name.as_deref(), Some("커닝시티"));
+        assert_eq!((first.combat_time, second.combat_time), (150, 450));
+        assert_eq!((first.exp_gained, second.exp_gained), (250, 750));
+        assert_eq!(second.id, original.id);
+        assert_eq!(first.avg_exp_per_second, 250.0 / 150.0);
+
+        assert!(split_record(&original, 1_700_000_000_000, String::new(), None, None).is_err());
+        assert!(split_record(&original, 1_700_000_600_000, String::new(), None, None).is_err());
+    }
+
+    #[test]
+    fn test_split_record_skips_stopped_time() {
+        // 600s of combat over 20 minutes: stopped for 10 minutes after 300s
+        let start = 1_700_000_000_000;
+        let mut original = record("", start + 1_200_000);
+        original.started_at = Some(start);
+        // (elapsed seconds, wall-clock seconds since start)
+        let timeline = Timeline {
+            session_id: original.id.clone(),
+            timestamp: original.timestamp,
+            map_name: None,
+            points: [(0, 0), (300, 300), (330, 930), (600, 1200)]
+                .map(|(elapsed_seconds, wall_secs): (u64, i64)| TimelinePoint {
+                    elapsed_seconds,
+                    total_exp: elapsed_seconds,
+                    latency_ms: None,
+                    recorded_at: Some(start + wall_secs * 1000),
+                })
+                .to_vec(),
+        };
+
+        // Split while stopped: everything tracked so far is in the first part
+        let at = start + 900_000;
+        let (first, second) = split_record(&original, at, at.to_string(), None, Some(&timeline)).unwrap();
+        assert_eq!((first.combat_time, second.combat_time), (300, 300));
+        assert_eq!((first.started_at, second.started_at), (Some(start), Some(at)));
+
+        // The old estimate (end minus combat time) would reject this split
+        assert!(split_record(&original, start + 60_000, String::new(), None, Some(&timeline)).is_ok());
+    }
+
+    #[test]
+    fn test_merge_records_sums_totals() {
+        let mut earlier = record("보스 트라이", 1_700_000_000_000);
+        earlier.map_name = Some("헤네시스".to_string());
+        let later = record("", 1_700_001_000_000);
+
+        let merged = merge_records(vec![later.clone(), earlier]).unwrap();
+        assert_eq!(merged.id, later.id);
+        assert_eq!(merged.timestamp, later.timestamp);
+        assert_eq!(merged.title, "보스 트라이");
+        assert_eq!(merged.map_name.as_deref(), Some("헤네시스"));
+        assert_eq!(merged.combat_time, 1200);
+        assert_eq!(merged.exp_gained, 2000);
+
+        assert!(merge_records(vec![later]).is_err());
+    }
+
+    #[test]
+    fn test_split_and_merge_move_session_files() {
+        use crate::models::config::AppConfig;
+        use crate::models::roi::Roi;
+        use crate::services::screen_capture::DisplayInfo;
+
+        let root = std::env::temp_dir().join(format!("exp-tracker-session-files-{}", std::process::id()));
+        let files = SessionFiles::in_dir(&root);
+
+        // An hour ending at 1_700_003_600_000, split after 30 minutes
+        let mut original = record("", 1_700_003_600_000);
+        original.combat_time = 3600;
+        original.started_at = Some(1_700_000_000_000);
+        files.timelines.save(&Timeline {
+            session_id: original.id.clone(),
+            timestamp: original.timestamp,
+            map_name: None,
+            points: [(0, 0), (1200, 500), (3600, 1000)]
+                .map(|(elapsed_seconds, total_exp)| TimelinePoint { elapsed_seconds, total_exp, latency_ms: None, recorded_at: None })
+                .to_vec(),
+        }).unwrap();
+        let mut histogram = FailureHistogram { session_id: original.id.clone(), ..Default::default() };
+        histogram.record_failure("exp", 2400, 0, "no digits");
+        files.failures.save(&histogram).unwrap();
+        files.configs.save(&SessionConfig {
+            session_id: original.id.clone(),
+            timestamp: original.timestamp,
+            started_at: 1_700_000_000_000,
+            level_roi: Roi::new(10, 10, 50, 20),
+            exp_roi: Roi::new(0, 1000, 300, 20),
+            display: DisplayInfo { width: 1920, height: 1080, scale_factor: 1.0 },
+            config: AppConfig::default(),
+        }).unwrap();
+
+        let at = 1_700_001_800_000;
+        let (first, second) = split_record(&original, at, at.to_string(), None, None).unwrap();
+        files.split(&original, &first, &second);
+
+        let first_timeline = files.timelines.load(&first.id).unwrap();
+        assert_eq!(first_timeline.points.last().map(|p| (p.elapsed_seconds, p.total_exp)), Some((1800, 625)));
+        assert_eq!(files.timelines.load(&second.id).unwrap().points.last().unwrap().total_exp, 375);
+        // The only failure was 40 minutes in, in the second part
+        assert!(files.failures.load(&first.id).is_err());
+        assert_eq!(files.failures.load(&second.id).unwrap().rois["exp"].failures, 1);
+        assert_eq!(files.configs.load(&first.id).unwrap().timestamp, at);
+
+        let merged = merge_records(vec![first.clone(), second.clone()]).unwrap();
+        files.merge(&[first.clone(), second], &merged);
+
+        assert_eq!(files.timelines.load(&merged.id).unwrap().points.last().map(|p| (p.elapsed_seconds, p.total_exp)), Some((3600, 1000)));
+        assert_eq!(files.failures.load(&merged.id).unwrap().rois["exp"].by_elapsed_minutes.keys().copied().collect::<Vec<_>>(), vec![40]);
+        assert!(files.configs.load(&merged.id).is_ok());
+        // Nothing is left under the first part's id
+        assert!(files.timelines.load(&first.id).is_err());
+        assert!(files.configs.load(&first.id).is_err());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
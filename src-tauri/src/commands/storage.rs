@@ -0,0 +1,25 @@
+use crate::services::storage::{self, CategoryUsage, CleanupResult, StorageCategory};
+
+/// Disk usage per storage category (bytes and file counts)
+#[tauri::command]
+pub fn get_storage_usage() -> Vec<CategoryUsage> {
+    StorageCategory::ALL.iter().map(|category| storage::usage(*category)).collect()
+}
+
+/// Delete all files in the given categories
+/// Returns how many files and bytes were removed per category
+#[tauri::command]
+pub async fn cleanup_storage(categories: Vec<StorageCategory>) -> Result<Vec<CleanupResult>, String> {
+    // Validate first so a bad category doesn't leave a partial cleanup
+    if let Some(category) = categories.iter().find(|category| !category.is_cleanable()) {
+        return Err(format!("{:?} cannot be cleaned up in bulk", category));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        categories.into_iter()
+            .map(|category| storage::cleanup(category, None))
+            .collect::<Result<Vec<_>, String>>()
+    })
+    .await
+    .map_err(|e| format!("Cleanup task failed: {}", e))?
+}
@@ -1,36 +1,73 @@
+use crate::models::checkpoint::Checkpoint;
 use crate::models::roi::Roi;
-use crate::services::ocr_tracker::{OcrTracker, TrackingStats};
+use crate::models::timeline::{baseline_curve, rate_curve, RatePoint, DEFAULT_BASELINE_SESSIONS};
+use crate::commands::config::ConfigManagerState;
+use crate::commands::session::{load_display_config, load_rate_warmup_secs, SessionMapState};
+use crate::models::exp_data::LevelExpTable;
+use crate::services::buffer_pool::{self, BufferPoolStats};
+use crate::services::level_eta::{self, LevelEta, Progress, DEFAULT_ETA_LEVELS};
+use crate::services::event_log::{EventLogState, RecordedEvent};
+use crate::services::ocr_tracker::{CheckpointEvent, OcrTracker, TrackingStats};
+use crate::services::preview_tracking;
+use crate::services::privacy;
+use crate::services::timeline_store::TimelineStore;
+use crate::services::tracking_control::TrackingControl;
 use crate::commands::ocr::OcrServiceState;
 use std::sync::Arc;
+use serde::Serialize;
 use tauri::{AppHandle, State};
 use tokio::sync::Mutex;
 
 /// Global OCR Tracker instance (shared across all commands)
-pub struct TrackerState(pub Arc<Mutex<OcrTracker>>);
+/// Start, stop and reset go through its `TrackingControl`.
+pub struct TrackerState(pub Arc<Mutex<OcrTracker>>, TrackingControl);
 
 impl TrackerState {
     pub fn new(app: AppHandle, ocr_service: OcrServiceState) -> Result<Self, String> {
-        Ok(Self(Arc::new(Mutex::new(OcrTracker::new(app, ocr_service)?))))
+        let tracker = Arc::new(Mutex::new(OcrTracker::new(app.clone(), ocr_service)?));
+        let control = TrackingControl::spawn(app, tracker.clone());
+        Ok(Self(tracker, control))
+    }
+
+    pub fn control(&self) -> &TrackingControl {
+        &self.1
+    }
+
+    /// Take a checkpoint; the tracker isn't locked while the OCR runs,
+    /// so stats and other commands aren't blocked by it
+    pub async fn capture_checkpoint(&self) -> Result<CheckpointEvent, String> {
+        let reader = self.0.lock().await.checkpoint_reader()?;
+        let run = reader.run();
+        let checkpoint = reader.read().await?;
+        self.0.lock().await.record_checkpoint(run, checkpoint).await
     }
 }
 
-/// Start OCR tracking with 3 parallel tasks (Level, EXP, Inventory with auto ROI)
+/// Start a fresh OCR tracking session with 3 parallel tasks (Level, EXP, Inventory with auto ROI)
 #[tauri::command]
 pub async fn start_ocr_tracking(
     level_roi: Roi,
     exp_roi: Roi,
     tracker: State<'_, TrackerState>,
 ) -> Result<(), String> {
-    let mut tracker = tracker.inner().0.lock().await;
-    tracker.start_tracking(level_roi, exp_roi).await
+    tracker.control().start(level_roi, exp_roi, false).await
+}
+
+/// Continue the stopped session (totals and elapsed time carry over)
+/// Starts a fresh session if there is nothing to resume.
+#[tauri::command]
+pub async fn resume_ocr_tracking(
+    level_roi: Roi,
+    exp_roi: Roi,
+    tracker: State<'_, TrackerState>,
+) -> Result<(), String> {
+    tracker.control().start(level_roi, exp_roi, true).await
 }
 
 /// Stop OCR tracking
 #[tauri::command]
 pub async fn stop_ocr_tracking(tracker: State<'_, TrackerState>) -> Result<(), String> {
-    let mut tracker = tracker.inner().0.lock().await;
-    tracker.stop_tracking().await;
-    Ok(())
+    tracker.control().stop().await
 }
 
 /// Get current tracking statistics
@@ -43,7 +80,181 @@ pub async fn get_tracking_stats(tracker: State<'_, TrackerState>) -> Result<Trac
 /// Reset tracking session
 #[tauri::command]
 pub async fn reset_tracking(tracker: State<'_, TrackerState>) -> Result<(), String> {
-    let mut tracker = tracker.inner().0.lock().await;
-    tracker.reset().await?;
+    tracker.control().reset().await
+}
+
+/// Manually set the current level (fallback when OCR can't read it)
+#[tauri::command]
+pub async fn set_manual_level(level: u32, tracker: State<'_, TrackerState>) -> Result<(), String> {
+    let tracker = tracker.inner().0.lock().await;
+    tracker.set_manual_level(level).await
+}
+
+/// Manually set the current EXP (fallback when OCR can't read it)
+#[tauri::command]
+pub async fn set_manual_exp(
+    exp: u64,
+    percentage: f64,
+    tracker: State<'_, TrackerState>,
+) -> Result<(), String> {
+    let tracker = tracker.inner().0.lock().await;
+    tracker.set_manual_exp(exp, percentage).await
+}
+
+/// Running preview tracking (dry run), if any
+pub type PreviewTrackingState = std::sync::Mutex<Option<tauri::async_runtime::JoinHandle<()>>>;
+
+/// Run capture + OCR on the saved level and EXP ROIs and emit what is
+/// recognized ("preview:readings", with OCR confidences) without starting the
+/// timer or touching session statistics. Replaces a running preview.
+#[tauri::command]
+pub async fn start_preview_tracking(
+    app: AppHandle,
+    preview: State<'_, PreviewTrackingState>,
+    tracker: State<'_, TrackerState>,
+    config_state: State<'_, ConfigManagerState>,
+    ocr_service: State<'_, OcrServiceState>,
+) -> Result<(), String> {
+    if tracker.0.lock().await.get_stats().await.is_tracking {
+        return Err("Stop tracking before previewing".to_string());
+    }
+
+    let roi = {
+        let manager = config_state
+            .lock()
+            .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+        manager.load()?.roi
+    };
+
+    let task = preview_tracking::spawn(app, ocr_service.inner().clone(), roi.level, roi.exp)?;
+    let mut running = preview.lock().map_err(|e| format!("Failed to lock preview state: {}", e))?;
+    if let Some(previous) = running.replace(task) {
+        previous.abort();
+    }
+
+    println!("🔍 Preview tracking started");
+    Ok(())
+}
+
+/// Stop preview tracking (no-op if none is running)
+#[tauri::command]
+pub fn stop_preview_tracking(preview: State<PreviewTrackingState>) -> Result<(), String> {
+    let mut running = preview.lock().map_err(|e| format!("Failed to lock preview state: {}", e))?;
+    if let Some(task) = running.take() {
+        task.abort();
+        println!("🔍 Preview tracking stopped");
+    }
     Ok(())
 }
+
+/// Take a one-shot checkpoint of all tracked values (checkpoint mode)
+#[tauri::command]
+pub async fn capture_checkpoint(tracker: State<'_, TrackerState>) -> Result<CheckpointEvent, String> {
+    tracker.capture_checkpoint().await
+}
+
+/// Get checkpoints recorded in the current session
+#[tauri::command]
+pub async fn get_checkpoints(tracker: State<'_, TrackerState>) -> Result<Vec<Checkpoint>, String> {
+    let tracker = tracker.inner().0.lock().await;
+    Ok(tracker.get_checkpoints())
+}
+
+/// Recent tracking events for windows opened mid-session
+/// Pass the last `seq` already seen to only get newer events.
+#[tauri::command]
+pub fn get_recent_events(
+    log: State<EventLogState>,
+    since: Option<u64>,
+) -> Result<Vec<RecordedEvent>, String> {
+    let log = log
+        .lock()
+        .map_err(|e| format!("Failed to lock event log: {}", e))?;
+    Ok(log.since(since))
+}
+
+/// Get image buffer pool counters (allocations vs reuses) for diagnostics
+#[tauri::command]
+pub fn get_buffer_pool_stats() -> BufferPoolStats {
+    buffer_pool::global().stats()
+}
+
+/// Usual pace on a map next to the current session's, for the live graph
+#[derive(Debug, Clone, Serialize)]
+pub struct RateBaseline {
+    pub map_name: Option<String>,
+    /// Previous sessions the baseline averages
+    pub sessions: usize,
+    pub baseline: Vec<RatePoint>,
+    pub current: Vec<RatePoint>,
+}
+
+/// Average rate curve of the previous `sessions` sessions on `map_name`
+/// (default: the map read at session start) alongside the current session's curve
+#[tauri::command]
+pub async fn get_rate_baseline(
+    map_name: Option<String>,
+    sessions: Option<usize>,
+    tracker: State<'_, TrackerState>,
+    session_map: State<'_, SessionMapState>,
+    config_state: State<'_, ConfigManagerState>,
+) -> Result<RateBaseline, String> {
+    let map_name = match map_name {
+        Some(map_name) => Some(map_name),
+        None => session_map.lock()
+            .map_err(|e| format!("Failed to lock session map: {}", e))?
+            .clone(),
+    };
+
+    let timelines = TimelineStore::open()
+        .recent_on_map(map_name.as_deref(), sessions.unwrap_or(DEFAULT_BASELINE_SESSIONS));
+    let current = tracker.inner().0.lock().await.timeline().await?;
+
+    let warmup_secs = load_rate_warmup_secs(&config_state);
+    let map_name = if load_display_config(&config_state).privacy_mode {
+        privacy::redact_map_name(map_name)
+    } else {
+        map_name
+    };
+
+    Ok(RateBaseline {
+        map_name,
+        sessions: timelines.len(),
+        baseline: baseline_curve(&timelines, warmup_secs),
+        current: rate_curve(&current, warmup_secs),
+    })
+}
+
+/// Projected time of reaching the next `count` levels (and `target_level`) at the current rate
+/// Fails if the level EXP table lacks a level on the way; without it only the next level can be projected.
+#[tauri::command]
+pub async fn get_level_etas(
+    count: Option<u32>,
+    target_level: Option<u32>,
+    tracker: State<'_, TrackerState>,
+) -> Result<Vec<LevelEta>, String> {
+    let stats = tracker.inner().0.lock().await.get_stats().await;
+    let (Some(level), Some(exp), Some(percentage)) = (stats.level, stats.exp, stats.percentage) else {
+        return Err("Level and EXP haven't been read yet".to_string());
+    };
+
+    let progress = Progress {
+        level,
+        exp,
+        percentage,
+        exp_per_hour: stats.exp_per_hour,
+        percentage_per_hour: stats.percentage_per_hour,
+    };
+    let levels = level_eta::milestone_levels(level, count.unwrap_or(DEFAULT_ETA_LEVELS), target_level);
+
+    let table = LevelExpTable::load()?;
+    if let Some(missing) = level_eta::missing_table_level(&table, level, &levels) {
+        return Err(format!(
+            "Level EXP table has no entry for level {}; only level {} can be projected",
+            missing,
+            level + 1
+        ));
+    }
+
+    Ok(level_eta::level_etas(&table, progress, &levels, chrono::Utc::now().timestamp_millis()))
+}
@@ -0,0 +1,148 @@
+use crate::commands::config::ConfigManagerState;
+use crate::models::config::{WindowConfig, WindowDimensions, OVERLAY_MIN_OPACITY};
+use crate::services::window_state::{self, OVERLAY_WINDOW, STATS_WINDOW};
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
+
+/// Show and focus an already open window
+/// Returns false if no window with that label exists.
+fn focus_existing(app: &AppHandle, label: &str) -> Result<bool, String> {
+    match app.get_webview_window(label) {
+        Some(window) => {
+            window.show().map_err(|e| format!("Failed to show window '{}': {}", label, e))?;
+            window.set_focus().map_err(|e| format!("Failed to focus window '{}': {}", label, e))?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+fn load_window_config(config_state: &ConfigManagerState) -> Result<WindowConfig, String> {
+    let manager = config_state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+    Ok(manager.load()?.window)
+}
+
+/// Builder for a secondary window at its saved geometry
+/// Off-screen positions (monitor unplugged) fall back to the OS default placement.
+fn secondary_window<'a>(
+    app: &'a AppHandle,
+    label: &str,
+    url: String,
+    dims: &WindowDimensions,
+) -> WebviewWindowBuilder<'a, tauri::Wry, AppHandle> {
+    let builder = WebviewWindowBuilder::new(app, label, WebviewUrl::App(url.into()))
+        .inner_size(dims.width as f64, dims.height as f64);
+
+    if window_state::is_on_screen(dims, &window_state::monitor_bounds(app)) {
+        builder.position(dims.x as f64, dims.y as f64)
+    } else {
+        builder
+    }
+}
+
+/// Open the frameless, semi-transparent overlay (or focus it if already open)
+/// The overlay page reads its background opacity from the `opacity` query parameter
+/// and listens to "overlay:opacity-changed" afterwards.
+#[tauri::command]
+pub async fn open_overlay_window(
+    app: AppHandle,
+    config_state: State<'_, ConfigManagerState>,
+) -> Result<(), String> {
+    if focus_existing(&app, OVERLAY_WINDOW)? {
+        return Ok(());
+    }
+
+    let window_config = load_window_config(&config_state)?;
+    let url = format!("index.html?view=overlay&opacity={}", window_config.overlay_opacity);
+
+    secondary_window(&app, OVERLAY_WINDOW, url, &window_config.overlay)
+        .title("exp-tracker overlay")
+        .decorations(false)
+        .transparent(true)
+        .shadow(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(true)
+        .build()
+        .map_err(|e| format!("Failed to create overlay window: {}", e))?;
+
+    #[cfg(debug_assertions)]
+    println!("🪟 Overlay window opened");
+
+    Ok(())
+}
+
+/// Close the overlay window (no-op if it isn't open)
+#[tauri::command]
+pub fn close_overlay_window(app: AppHandle) -> Result<(), String> {
+    match app.get_webview_window(OVERLAY_WINDOW) {
+        Some(window) => window.close().map_err(|e| format!("Failed to close overlay: {}", e)),
+        None => Ok(()),
+    }
+}
+
+/// Open the live graph/stats window next to the compact tracker (or focus it)
+/// Tracking events are emitted app-wide, so this window receives the same
+/// "ocr:*" / "tracking:*" events as the main window.
+#[tauri::command]
+pub async fn open_stats_window(
+    app: AppHandle,
+    config_state: State<'_, ConfigManagerState>,
+) -> Result<(), String> {
+    if focus_existing(&app, STATS_WINDOW)? {
+        return Ok(());
+    }
+
+    let window_config = load_window_config(&config_state)?;
+
+    // Not always-on-top: the compact window stays above the game, this one doesn't
+    secondary_window(&app, STATS_WINDOW, "index.html?view=stats".to_string(), &window_config.stats)
+        .title("exp-tracker stats")
+        .min_inner_size(480.0, 320.0)
+        .resizable(true)
+        .build()
+        .map_err(|e| format!("Failed to create stats window: {}", e))?;
+
+    #[cfg(debug_assertions)]
+    println!("🪟 Stats window opened");
+
+    Ok(())
+}
+
+/// Close the stats window (no-op if it isn't open)
+#[tauri::command]
+pub fn close_stats_window(app: AppHandle) -> Result<(), String> {
+    match app.get_webview_window(STATS_WINDOW) {
+        Some(window) => window.close().map_err(|e| format!("Failed to close stats window: {}", e)),
+        None => Ok(()),
+    }
+}
+
+/// Set and persist the overlay background opacity
+/// Returns the stored value after clamping to OVERLAY_MIN_OPACITY..=1.0.
+#[tauri::command]
+pub fn set_overlay_opacity(
+    app: AppHandle,
+    state: State<ConfigManagerState>,
+    opacity: f64,
+) -> Result<f64, String> {
+    if !opacity.is_finite() {
+        return Err(format!("Invalid opacity: {}", opacity));
+    }
+    let opacity = opacity.clamp(OVERLAY_MIN_OPACITY, 1.0);
+
+    {
+        let manager = state
+            .lock()
+            .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+        let mut config = manager.load()?;
+        config.window.overlay_opacity = opacity;
+        manager.save(&config)?;
+    }
+
+    let _ = app.emit("overlay:opacity-changed", opacity);
+
+    Ok(opacity)
+}
@@ -4,34 +4,59 @@ mod services;
 mod utils;
 
 use tauri::{Emitter, Manager};
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
 
 use commands::config::{
     clear_roi, get_all_rois, get_config_path, init_config_manager, load_config, load_roi,
     get_roi_preview, open_roi_preview, save_config, save_roi, save_roi_preview,
-    get_potion_slot_config, set_potion_slot_config,
+    list_roi_previews, delete_roi_preview,
+    get_potion_slot_config, set_potion_slot_config, apply_rescaled_rois, ConfigManagerState,
+    get_data_directory, set_data_directory, get_config_recovery, ConfigRecoveryState, set_privacy_mode,
+    list_layouts, save_layout, delete_layout, switch_layout, detect_layout,
+    fallback_config_manager, set_window_mode, get_custom_metrics, set_custom_metrics,
+    get_derived_metrics, set_derived_metrics,
 };
 use commands::ocr::{
     init_ocr_service, recognize_all_parallel, recognize_exp, recognize_hp_potion_count, recognize_level,
-    check_ocr_health, auto_detect_rois,
+    check_ocr_health, auto_detect_rois, scan_inventory,
     recognize_map, recognize_mp_potion_count,
 };
 use commands::screen_capture::{
     capture_full_screen, capture_region, get_screen_dimensions, init_screen_capture,
-    ScreenCaptureState,
+    start_capture_preview, stop_capture_preview,
+    CapturePreviewState, ScreenCaptureState,
 };
 use commands::exp::{
     add_exp_data, reset_exp_session, start_exp_session, ExpCalculatorState,
 };
 use commands::tracking::{
-    get_tracking_stats, reset_tracking, start_ocr_tracking, stop_ocr_tracking, TrackerState,
+    get_tracking_stats, reset_tracking, start_ocr_tracking, resume_ocr_tracking, stop_ocr_tracking, TrackerState,
+    get_buffer_pool_stats, set_manual_level, set_manual_exp, capture_checkpoint, get_checkpoints,
+    get_recent_events, get_rate_baseline, get_level_etas,
+    start_preview_tracking, stop_preview_tracking, PreviewTrackingState,
+};
+use commands::storage::{cleanup_storage, get_storage_usage};
+use commands::diagnostics::{
+    get_diagnostics, get_failure_report, get_sample_log, get_self_test_history, locate_ocr_server, replay_sample_log,
+    run_self_test,
+};
+use commands::alerts::{create_alert_rule, delete_alert_rule, list_alert_rules, update_alert_rule};
+use commands::report::generate_report;
+use commands::export::{export_data, export_sample_log, export_timelines};
+use commands::window::{
+    close_overlay_window, close_stats_window, open_overlay_window, open_stats_window,
+    set_overlay_opacity,
 };
 use commands::session::{
-    get_session_records, save_session_record, delete_session_record, update_session_title,
-    init_session_records,
+    get_session_records, save_session_record, finish_session, delete_session_record, update_session_title,
+    init_session_records, capture_session_map_name, split_session, merge_sessions, SessionMapState,
+    get_session_config, get_progress_history,
 };
+use services::event_log::EventLogState;
 use services::exp_calculator::ExpCalculator;
 use services::python_server::PythonServerManager;
+use services::shortcuts::ShortcutFailuresState;
+use services::startup::{InitStage, StartupReport, StartupReportState};
+use services::window_state::{self, WindowGeometryState};
 use std::sync::Mutex;
 use tokio::sync::Mutex as AsyncMutex;
 
@@ -43,15 +68,41 @@ fn greet(name: &str) -> String {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize config manager
-    let config_manager = init_config_manager().expect("Failed to initialize config manager");
+    // Staged init: a failing stage is recorded and the UI starts anyway,
+    // so users see an actionable error instead of a panic dialog
+    let mut startup = StartupReport::default();
+
+    // Initialize config manager (falls back to a temp folder)
+    let (config_manager, config_recovery) = startup
+        .stage(InitStage::Config, init_config_manager())
+        .unwrap_or_else(|| (fallback_config_manager(), None));
+
+    let app_config = match config_manager.lock() {
+        Ok(manager) => manager.load().unwrap_or_default(),
+        Err(_) => Default::default(),
+    };
+
+    // Read OCR'd numbers with the game client's separators
+    services::ocr::parser::set_decimal_separator(app_config.tracking.client_language.decimal_separator());
+
+    // Keep template matching off some cores so the game doesn't stutter
+    services::matching_pool::configure(app_config.advanced.matching_threads);
+
+    // Coalesce fast-changing events so the webview isn't flooded
+    services::event_log::configure_throttle(&app_config.advanced.event_intervals_ms);
+
+    // Resolve data directory (session records, debug images) before loading sessions
+    startup.stage(InitStage::DataDirectory, services::storage::init(&app_config.storage));
+    services::storage::cleanup_stale_temp_files();
+
+    // Raw sample ring log for reconstructing odd readings (power users, off by default)
+    services::sample_log::configure(app_config.advanced.sample_log, app_config.advanced.sample_log_capacity);
 
     // Initialize OCR service
-    let ocr_service = init_ocr_service().expect("Failed to initialize OCR service");
+    let ocr_service = startup.stage(InitStage::OcrService, init_ocr_service());
 
     // Initialize EXP calculator
-    let exp_calculator = ExpCalculator::new().expect("Failed to initialize EXP calculator");
-    let exp_calculator_state = ExpCalculatorState(Mutex::new(exp_calculator));
+    let exp_calculator = startup.stage(InitStage::ExpCalculator, ExpCalculator::new());
 
     // Initialize Python server manager
     let python_server = AsyncMutex::new(PythonServerManager::new());
@@ -63,31 +114,68 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .manage(ScreenCaptureState::default())
+        .manage(CapturePreviewState::default())
+        .manage(PreviewTrackingState::default())
         .manage(config_manager)
-        .manage(ocr_service.clone())  // Clone for .manage()
-        .manage(exp_calculator_state)
+        .manage(ConfigRecoveryState::new(config_recovery.clone()))
         .manage(python_server)
         .manage(session_records)
+        .manage(SessionMapState::default())
+        .manage(WindowGeometryState::default())
+        .manage(EventLogState::default())
         .setup(move |app| {  // Move closure to capture ocr_service
-            // Initialize OCR Tracker with AppHandle
-            let tracker_state = TrackerState::new(app.handle().clone(), ocr_service.clone())
-                .expect("Failed to initialize OCR tracker");
-            app.manage(tracker_state);
+            // Optional states: commands that need a missing one return an error
+            if let Some(exp_calculator) = exp_calculator {
+                app.manage(ExpCalculatorState(Mutex::new(exp_calculator)));
+            }
 
-            // Register global shortcut for ` (backtick/tilde) key
-            let handle = app.handle().clone();
-            app.global_shortcut().on_shortcut("`", move |_app, _shortcut, event| {
-                if event.state == ShortcutState::Pressed {
-                    #[cfg(debug_assertions)]
-                    println!("🎹 Global shortcut triggered: `");
-                    
-                    // Emit event to frontend
-                    let _ = handle.emit("global-shortcut-toggle-timer", ());
+            if let Some(ocr_service) = &ocr_service {
+                app.manage(ocr_service.clone());
+
+                // Initialize OCR Tracker with AppHandle
+                let tracker_state = TrackerState::new(app.handle().clone(), ocr_service.clone());
+                if let Some(tracker_state) = startup.stage(InitStage::Tracker, tracker_state) {
+                    app.manage(tracker_state);
+                }
+
+                // Daily OCR self-test while idle (if enabled in advanced settings)
+                services::self_test::spawn_self_test_loop(app.handle().clone(), ocr_service.clone());
+            }
+
+            // Surface failed stages (also available via get_diagnostics)
+            for failure in &startup.failures {
+                let _ = app.emit("startup:init-failed", failure);
+            }
+            app.manage(StartupReportState::new(std::mem::take(&mut startup)));
+
+            // Restore the last window position (skipped if that monitor is gone)
+            if let Some(window) = app.get_webview_window(window_state::MAIN_WINDOW) {
+                let window_config = app
+                    .state::<ConfigManagerState>()
+                    .lock()
+                    .ok()
+                    .and_then(|manager| manager.load().ok())
+                    .map(|config| config.window);
+                if let Some(window_config) = window_config {
+                    if let Err(e) = window_state::restore(&window, &window_config) {
+                        eprintln!("⚠️ {}", e);
+                    }
                 }
-            }).expect("Failed to register global shortcut");
+            }
+
+            // Tell the UI its settings were reset (also available via get_config_recovery)
+            if let Some(recovery) = &config_recovery {
+                let _ = app.emit("config:recovered", recovery);
+            }
 
-            #[cfg(debug_assertions)]
-            println!("✅ Global shortcut registered: `");
+            // Register global shortcuts; a key taken by another app only loses that binding
+            let shortcut_failures = services::shortcuts::register_all(app.handle());
+            app.manage(ShortcutFailuresState::new(shortcut_failures));
+
+            // Tray icon (Show window / Quit); without it closing the window always quits
+            if let Err(e) = services::tray::init(app.handle()) {
+                eprintln!("⚠️ {}", e);
+            }
 
             // Start Python OCR server on app startup
             let handle = app.handle().clone();
@@ -95,6 +183,7 @@ pub fn run() {
             tauri::async_runtime::spawn(async move {
                 let server_state = handle.state::<AsyncMutex<PythonServerManager>>();
                 let mut server = server_state.lock().await;
+                server.set_resource_dir(handle.path().resource_dir().ok());
 
                 match server.start().await {
                     Ok(_) => {
@@ -111,40 +200,38 @@ pub fn run() {
             Ok(())
         })
         .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) = event {
+                window_state::schedule_save(window);
+            }
+
+            // Only closing the main window shuts the app down (the overlay just closes)
+            if window.label() != window_state::MAIN_WINDOW {
+                return;
+            }
+
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 // Prevent immediate close - we need to cleanup first
                 api.prevent_close();
-                
-                let app = window.app_handle().clone();
-                
-                // Spawn async cleanup task to avoid blocking the event loop
-                tauri::async_runtime::spawn(async move {
-                    // Stop OCR tracking
-                    let tracker_state = app.state::<TrackerState>();
-                    {
-                        let mut tracker = tracker_state.inner().0.lock().await;
-                        tracker.stop_tracking().await;
 
-                        #[cfg(debug_assertions)]
-                        println!("🛑 OCR tracking stopped");
-                    }
-
-                    // Shutdown Python OCR server
-                    let server_state = app.state::<AsyncMutex<PythonServerManager>>();
-                    {
-                        let mut server = server_state.lock().await;
-                        server.stop_async().await;
+                let app = window.app_handle().clone();
 
-                        #[cfg(debug_assertions)]
-                        println!("🛑 Python server shutdown signal sent");
-                    }
+                // Tray-only mode: hide the window and keep tracking; Quit in the tray menu shuts down
+                let close_to_tray = {
+                    let config_state = app.state::<ConfigManagerState>();
+                    let close_to_tray = match config_state.lock() {
+                        Ok(manager) => manager.load().map(|config| config.window.close_to_tray).ok(),
+                        Err(_) => None,
+                    };
+                    close_to_tray.unwrap_or_default()
+                };
+                if close_to_tray && services::tray::is_available(&app) {
+                    let _ = window.hide();
+                    println!("🫥 Main window hidden to tray (tracking continues)");
+                    return;
+                }
 
-                    #[cfg(debug_assertions)]
-                    println!("👋 Application closing");
-                    
-                    // Now that cleanup is complete, exit the app
-                    app.exit(0);
-                });
+                // Spawn async cleanup task to avoid blocking the event loop
+                tauri::async_runtime::spawn(services::shutdown::run(app));
             }
         })
         .invoke_handler(tauri::generate_handler![
@@ -153,6 +240,8 @@ pub fn run() {
             get_screen_dimensions,
             capture_region,
             capture_full_screen,
+            start_capture_preview,
+            stop_capture_preview,
             save_roi,
             load_roi,
             get_all_rois,
@@ -162,9 +251,15 @@ pub fn run() {
             get_config_path,
             get_potion_slot_config,
             set_potion_slot_config,
+            apply_rescaled_rois,
+            get_data_directory,
+            set_data_directory,
+            get_config_recovery,
             save_roi_preview,
             get_roi_preview,
             open_roi_preview,
+            list_roi_previews,
+            delete_roi_preview,
             recognize_level,
             recognize_exp,
             recognize_map,
@@ -173,17 +268,68 @@ pub fn run() {
             recognize_all_parallel,
             check_ocr_health,
             auto_detect_rois,
+            scan_inventory,
             start_exp_session,
             add_exp_data,
             reset_exp_session,
             start_ocr_tracking,
+            resume_ocr_tracking,
             stop_ocr_tracking,
             get_tracking_stats,
             reset_tracking,
+            get_buffer_pool_stats,
+            set_manual_level,
+            set_manual_exp,
+            capture_checkpoint,
+            start_preview_tracking,
+            stop_preview_tracking,
+            get_checkpoints,
             get_session_records,
             save_session_record,
+            finish_session,
             delete_session_record,
-            update_session_title
+            update_session_title,
+            split_session,
+            merge_sessions,
+            capture_session_map_name,
+            get_storage_usage,
+            cleanup_storage,
+            get_diagnostics,
+            locate_ocr_server,
+            run_self_test,
+            get_self_test_history,
+            get_sample_log,
+            replay_sample_log,
+            get_failure_report,
+            get_session_config,
+            get_progress_history,
+            set_window_mode,
+            open_overlay_window,
+            close_overlay_window,
+            set_overlay_opacity,
+            set_privacy_mode,
+            list_layouts,
+            save_layout,
+            delete_layout,
+            switch_layout,
+            detect_layout,
+            open_stats_window,
+            close_stats_window,
+            get_recent_events,
+            get_custom_metrics,
+            set_custom_metrics,
+            get_derived_metrics,
+            set_derived_metrics,
+            list_alert_rules,
+            create_alert_rule,
+            update_alert_rule,
+            delete_alert_rule,
+            generate_report,
+            export_data,
+            export_sample_log,
+            export_timelines,
+            get_rate_baseline,
+            get_level_etas
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+
+/// Which side of the limit triggers a rule
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+impl Comparison {
+    pub fn matches(&self, value: f64, limit: f64) -> bool {
+        match self {
+            Comparison::Above => value > limit,
+            Comparison::Below => value < limit,
+        }
+    }
+}
+
+/// When an alert rule fires
+/// `stat` is a TrackingStats variable, custom metric or derived metric name.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// The stat crosses a fixed value (e.g. HP potions below 50)
+    Threshold {
+        stat: String,
+        comparison: Comparison,
+        value: f64,
+    },
+    /// The stat changes faster/slower than `per_minute` over the last `window_secs`
+    RateOfChange {
+        stat: String,
+        comparison: Comparison,
+        per_minute: f64,
+        window_secs: u64,
+    },
+    /// The stat hasn't changed for `seconds` while tracking (e.g. EXP stuck)
+    Stale { stat: String, seconds: u64 },
+}
+
+impl AlertCondition {
+    pub fn stat(&self) -> &str {
+        match self {
+            AlertCondition::Threshold { stat, .. }
+            | AlertCondition::RateOfChange { stat, .. }
+            | AlertCondition::Stale { stat, .. } => stat,
+        }
+    }
+}
+
+/// What happens when an alert rule fires
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertAction {
+    /// "alert:triggered" event for the UI
+    Event,
+    /// "alert:notification" event; the frontend shows a system notification
+    Notification,
+    /// "alert:sound" event; the frontend plays the sound (respects AudioConfig)
+    Sound {
+        #[serde(default)]
+        sound: Option<String>,
+    },
+    /// POST the alert as JSON
+    Webhook { url: String },
+}
+
+/// A user-configured alert
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AlertRule {
+    /// Assigned on create when empty
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub condition: AlertCondition,
+    pub actions: Vec<AlertAction>,
+    /// Minimum time between two firings of this rule
+    #[serde(default = "default_cooldown")]
+    pub cooldown_secs: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_cooldown() -> u64 {
+    60
+}
+
+impl AlertRule {
+    /// Check the rule's own fields (stat names are checked by the caller)
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Alert name must not be empty".to_string());
+        }
+        if self.actions.is_empty() {
+            return Err(format!("Alert '{}' has no actions", self.name));
+        }
+
+        match &self.condition {
+            AlertCondition::RateOfChange { window_secs: 0, .. } => {
+                return Err(format!("Alert '{}' needs a rate window of at least 1 second", self.name));
+            }
+            AlertCondition::Stale { seconds: 0, .. } => {
+                return Err(format!("Alert '{}' needs a stale time of at least 1 second", self.name));
+            }
+            _ => {}
+        }
+
+        for action in &self.actions {
+            if let AlertAction::Webhook { url } = action {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(format!("Webhook URL must start with http:// or https://: {}", url));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_json_format() {
+        let json = r#"{
+            "name": "Low HP potions",
+            "condition": {"type": "threshold", "stat": "hp_potion_count", "comparison": "below", "value": 50},
+            "actions": [{"type": "sound"}, {"type": "webhook", "url": "https://example.com/hook"}]
+        }"#;
+
+        let rule: AlertRule = serde_json::from_str(json).unwrap();
+        assert!(rule.enabled);
+        assert_eq!(rule.cooldown_secs, 60);
+        assert_eq!(rule.condition.stat(), "hp_potion_count");
+        assert_eq!(rule.actions[0], AlertAction::Sound { sound: None });
+        assert!(rule.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_webhook() {
+        let rule = AlertRule {
+            id: String::new(),
+            name: "EXP stuck".to_string(),
+            enabled: true,
+            condition: AlertCondition::Stale { stat: "exp".to_string(), seconds: 120 },
+            actions: vec![AlertAction::Webhook { url: "ftp://example.com".to_string() }],
+            cooldown_secs: 60,
+        };
+
+        assert!(rule.validate().is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+/// One-shot reading of all tracked values, taken on hotkey press in checkpoint mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Checkpoint {
+    pub timestamp: i64, // Unix timestamp in milliseconds
+    pub level: Option<u32>,
+    pub exp: Option<u64>,
+    pub percentage: Option<f64>,
+    pub hp_potion_count: Option<u32>,
+    pub mp_potion_count: Option<u32>,
+}
+
+/// Progress between two consecutive checkpoints
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CheckpointDelta {
+    pub elapsed_seconds: u64,
+    pub levels_gained: i32,
+    /// Level-ups count as 100% each
+    pub percentage_gained: Option<f64>,
+    pub percentage_per_hour: Option<f64>,
+    pub hp_potions_used: Option<u32>,
+    pub mp_potions_used: Option<u32>,
+}
+
+impl Checkpoint {
+    /// Progress from `previous` to this checkpoint
+    /// Values missing from either checkpoint stay None
+    pub fn delta_since(&self, previous: &Checkpoint) -> CheckpointDelta {
+        let elapsed_seconds = self.timestamp.saturating_sub(previous.timestamp).max(0) as u64 / 1000;
+
+        let levels_gained = match (self.level, previous.level) {
+            (Some(current), Some(prev)) => current as i32 - prev as i32,
+            _ => 0,
+        };
+
+        let percentage_gained = match (self.percentage, previous.percentage) {
+            (Some(current), Some(prev)) => Some(levels_gained as f64 * 100.0 + current - prev),
+            _ => None,
+        };
+
+        let percentage_per_hour = percentage_gained
+            .filter(|_| elapsed_seconds > 0)
+            .map(|gained| gained * 3600.0 / elapsed_seconds as f64);
+
+        // Counts going up mean a refill, not negative usage
+        let used = |current: Option<u32>, prev: Option<u32>| match (current, prev) {
+            (Some(current), Some(prev)) => Some(prev.saturating_sub(current)),
+            _ => None,
+        };
+
+        CheckpointDelta {
+            elapsed_seconds,
+            levels_gained,
+            percentage_gained,
+            percentage_per_hour,
+            hp_potions_used: used(self.hp_potion_count, previous.hp_potion_count),
+            mp_potions_used: used(self.mp_potion_count, previous.mp_potion_count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(timestamp: i64, level: u32, percentage: f64, hp: u32) -> Checkpoint {
+        Checkpoint {
+            timestamp,
+            level: Some(level),
+            exp: None,
+            percentage: Some(percentage),
+            hp_potion_count: Some(hp),
+            mp_potion_count: None,
+        }
+    }
+
+    #[test]
+    fn test_delta_within_level() {
+        let first = checkpoint(0, 120, 10.0, 300);
+        let second = checkpoint(1_800_000, 120, 15.0, 280);
+
+        let delta = second.delta_since(&first);
+        assert_eq!(delta.elapsed_seconds, 1800);
+        assert_eq!(delta.percentage_gained, Some(5.0));
+        assert_eq!(delta.percentage_per_hour, Some(10.0));
+        assert_eq!(delta.hp_potions_used, Some(20));
+        assert_eq!(delta.mp_potions_used, None);
+    }
+
+    #[test]
+    fn test_delta_across_level_up_and_refill() {
+        let first = checkpoint(0, 120, 90.0, 10);
+        let second = checkpoint(3_600_000, 121, 5.0, 500);
+
+        let delta = second.delta_since(&first);
+        assert_eq!(delta.levels_gained, 1);
+        assert_eq!(delta.percentage_gained, Some(15.0));
+        assert_eq!(delta.hp_potions_used, Some(0));
+    }
+}
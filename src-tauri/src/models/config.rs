@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::models::alert::AlertRule;
+use crate::models::custom_metric::{CustomMetric, DerivedMetric};
 use crate::models::roi::Roi;
+use crate::models::slot::{SlotId, SlotKey};
 
 /// Window dimensions and position
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -42,6 +46,44 @@ pub struct WindowConfig {
     pub dashboard: WindowDimensions,
     pub current_mode: WindowMode,
     pub always_on_top: bool,
+    /// Frameless overlay window shown over the game
+    #[serde(default = "default_overlay_dimensions")]
+    pub overlay: WindowDimensions,
+    /// Overlay background opacity (OVERLAY_MIN_OPACITY..=1.0)
+    #[serde(default = "default_overlay_opacity")]
+    pub overlay_opacity: f64,
+    /// Separate live graph/stats window
+    #[serde(default = "default_stats_dimensions")]
+    pub stats: WindowDimensions,
+    /// Closing the main window hides it to the tray and keeps tracking;
+    /// the app only quits from the tray menu
+    #[serde(default)]
+    pub close_to_tray: bool,
+}
+
+/// Lowest overlay opacity; below this the overlay is hard to find again
+pub const OVERLAY_MIN_OPACITY: f64 = 0.2;
+
+fn default_overlay_dimensions() -> WindowDimensions {
+    WindowDimensions {
+        width: 320,
+        height: 90,
+        x: 100,
+        y: 100,
+    }
+}
+
+fn default_stats_dimensions() -> WindowDimensions {
+    WindowDimensions {
+        width: 800,
+        height: 600,
+        x: 200,
+        y: 200,
+    }
+}
+
+fn default_overlay_opacity() -> f64 {
+    0.7
 }
 
 impl Default for WindowConfig {
@@ -56,6 +98,27 @@ impl Default for WindowConfig {
             },
             current_mode: WindowMode::Compact,
             always_on_top: true,
+            overlay: default_overlay_dimensions(),
+            overlay_opacity: default_overlay_opacity(),
+            stats: default_stats_dimensions(),
+            close_to_tray: false,
+        }
+    }
+}
+
+impl WindowConfig {
+    /// Saved geometry for a window mode
+    pub fn dimensions(&self, mode: &WindowMode) -> &WindowDimensions {
+        match mode {
+            WindowMode::Compact => &self.compact,
+            WindowMode::Dashboard => &self.dashboard,
+        }
+    }
+
+    pub fn dimensions_mut(&mut self, mode: &WindowMode) -> &mut WindowDimensions {
+        match mode {
+            WindowMode::Compact => &mut self.compact,
+            WindowMode::Dashboard => &mut self.dashboard,
         }
     }
 }
@@ -68,7 +131,23 @@ pub struct RoiConfig {
     pub hp: Option<Roi>,
     pub mp: Option<Roi>,
     // pub meso: Option<Roi>, // Commented out temporarily
-    // pub map_location: Option<Roi>, // Commented out temporarily
+    /// Map name label, OCR'd once at session start for the session title
+    #[serde(default)]
+    pub map_name: Option<Roi>,
+}
+
+impl RoiConfig {
+    /// All configured ROIs scaled by independent x/y factors
+    pub fn scaled(&self, scale_x: f64, scale_y: f64) -> Self {
+        let scale = |roi: &Option<Roi>| roi.map(|r| r.scaled(scale_x, scale_y));
+        Self {
+            level: scale(&self.level),
+            exp: scale(&self.exp),
+            hp: scale(&self.hp),
+            mp: scale(&self.mp),
+            map_name: scale(&self.map_name),
+        }
+    }
 }
 
 /// Tracking configuration
@@ -78,6 +157,97 @@ pub struct TrackingConfig {
     pub track_meso: bool,
     pub auto_start: bool,
     pub auto_pause_threshold: u64,
+    /// Per-metric switches; disabled metrics don't spawn their OCR loops
+    #[serde(default = "default_true")]
+    pub track_level: bool,
+    #[serde(default = "default_true")]
+    pub track_exp: bool,
+    #[serde(default = "default_true")]
+    pub track_potions: bool,
+    #[serde(default)]
+    pub mode: TrackingMode,
+    /// Global shortcut that takes a checkpoint in checkpoint mode
+    #[serde(default = "default_checkpoint_shortcut")]
+    pub checkpoint_shortcut: String,
+    /// Game client language; decides how OCR'd number separators are read
+    #[serde(default)]
+    pub client_language: ClientLanguage,
+    /// Global shortcut that switches to the next saved layout
+    #[serde(default = "default_layout_shortcut")]
+    pub layout_shortcut: String,
+    /// Save the running session (default date title) when the app is closed
+    #[serde(default = "default_true")]
+    pub save_session_on_exit: bool,
+    /// After a display change (e.g. toggling fullscreen), switch to the saved
+    /// layout whose level ROI reads instead of offering rescaled ROIs
+    #[serde(default = "default_true")]
+    pub auto_switch_layout: bool,
+    /// Rates from the first seconds of a session are flagged as estimating
+    /// and left out of best-map and rate curve comparisons
+    #[serde(default = "default_rate_warmup_secs")]
+    pub rate_warmup_secs: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_checkpoint_shortcut() -> String {
+    "F9".to_string()
+}
+
+fn default_layout_shortcut() -> String {
+    "F10".to_string()
+}
+
+/// Rates jump around until a couple of minutes of EXP are in
+pub const DEFAULT_RATE_WARMUP_SECS: u64 = 120;
+
+fn default_rate_warmup_secs() -> u64 {
+    DEFAULT_RATE_WARMUP_SECS
+}
+
+/// How tracking readings are taken
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackingMode {
+    /// OCR loops read every metric on an interval
+    Continuous,
+    /// Nothing runs in the background; the checkpoint hotkey reads everything once
+    Checkpoint,
+}
+
+impl Default for TrackingMode {
+    fn default() -> Self {
+        Self::Continuous
+    }
+}
+
+/// Language of the game client
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientLanguage {
+    #[default]
+    Ko,
+    En,
+    Ja,
+    Zh,
+    De,
+    Fr,
+    Es,
+    Pt,
+}
+
+impl ClientLanguage {
+    /// Decimal separator the client renders numbers with
+    /// Every other separator-like character is a thousands separator
+    /// (1,234,567 / 12.34% for ko/en, 1.234.567 / 12,34% for de).
+    pub fn decimal_separator(&self) -> char {
+        match self {
+            Self::De | Self::Fr | Self::Es | Self::Pt => ',',
+            Self::Ko | Self::En | Self::Ja | Self::Zh => '.',
+        }
+    }
 }
 
 impl Default for TrackingConfig {
@@ -87,6 +257,16 @@ impl Default for TrackingConfig {
             track_meso: false,
             auto_start: false,
             auto_pause_threshold: 300,
+            track_level: true,
+            track_exp: true,
+            track_potions: true,
+            mode: TrackingMode::Continuous,
+            checkpoint_shortcut: default_checkpoint_shortcut(),
+            layout_shortcut: default_layout_shortcut(),
+            client_language: ClientLanguage::Ko,
+            save_session_on_exit: true,
+            auto_switch_layout: true,
+            rate_warmup_secs: DEFAULT_RATE_WARMUP_SECS,
         }
     }
 }
@@ -114,6 +294,10 @@ pub struct DisplayConfig {
     pub show_expected_time: bool,
     pub graph_time_window: u64,
     pub show_trend_line: bool,
+    /// Star out map names in everything shown or shared (UI, exports, reports)
+    /// for streaming; stored sessions keep them
+    #[serde(default)]
+    pub privacy_mode: bool,
 }
 
 impl Default for DisplayConfig {
@@ -124,6 +308,7 @@ impl Default for DisplayConfig {
             show_expected_time: true,
             graph_time_window: 600,
             show_trend_line: true,
+            privacy_mode: false,
         }
     }
 }
@@ -186,6 +371,35 @@ pub struct AdvancedConfig {
     pub preprocessing: PreprocessingConfig,
     pub spike_threshold: f64,
     pub data_retention_days: u32,
+    /// Threads template matching may use at once; 0 = half the cores
+    #[serde(default)]
+    pub matching_threads: u32,
+    /// Minimum milliseconds between two emissions of an event, by event name
+    /// Updates in between are coalesced; the latest is sent when the interval ends.
+    #[serde(default = "default_event_intervals_ms")]
+    pub event_intervals_ms: BTreeMap<String, u64>,
+    /// Run the OCR self-test over the bundled fixtures once a day while idle
+    #[serde(default)]
+    pub nightly_self_test: bool,
+    /// Log every raw reading to a fixed-size ring file (see services/sample_log.rs)
+    #[serde(default)]
+    pub sample_log: bool,
+    /// Readings the sample log keeps before overwriting the oldest (96 bytes each, at most 4 million)
+    #[serde(default = "default_sample_log_capacity")]
+    pub sample_log_capacity: u64,
+}
+
+/// ~9.6 MB, a few hours of readings
+fn default_sample_log_capacity() -> u64 {
+    100_000
+}
+
+/// At most 4 updates per second of the fast-changing readings
+fn default_event_intervals_ms() -> BTreeMap<String, u64> {
+    ["ocr:exp-update", "ocr:hp-potion-update", "ocr:mp-potion-update"]
+        .into_iter()
+        .map(|event| (event.to_string(), 250))
+        .collect()
 }
 
 impl Default for AdvancedConfig {
@@ -195,22 +409,108 @@ impl Default for AdvancedConfig {
             preprocessing: PreprocessingConfig::default(),
             spike_threshold: 2.0,
             data_retention_days: 30,
+            matching_threads: 0,
+            event_intervals_ms: default_event_intervals_ms(),
+            nightly_self_test: false,
+            sample_log: false,
+            sample_log_capacity: default_sample_log_capacity(),
         }
     }
 }
 
+/// Where session data, debug images and timelines are stored
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct StorageConfig {
+    /// User-chosen data directory; None uses the platform data directory
+    pub data_directory: Option<String>,
+}
+
+/// Inventory layout a tracked item is read from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InventoryLayout {
+    /// Hotkey quickslot bar (auto-detected, 8 named slots)
+    Quickslot,
+    /// Regular item inventory window (user-selected ROI, rows x columns grid)
+    Grid,
+}
+
+impl Default for InventoryLayout {
+    fn default() -> Self {
+        Self::Quickslot
+    }
+}
+
+/// Item inventory window grid configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ItemGridConfig {
+    /// Region covering exactly the slot grid (logical pixels)
+    pub roi: Option<Roi>,
+    pub rows: u32,
+    pub columns: u32,
+    /// Row-major cell index of the HP potion (0 = top-left)
+    #[serde(default)]
+    pub hp_cell: u32,
+    /// Row-major cell index of the MP potion
+    #[serde(default = "default_mp_cell")]
+    pub mp_cell: u32,
+}
+
+fn default_mp_cell() -> u32 {
+    1
+}
+
+impl Default for ItemGridConfig {
+    fn default() -> Self {
+        Self {
+            roi: None,
+            rows: 6,
+            columns: 4,
+            hp_cell: 0,
+            mp_cell: 1,
+        }
+    }
+}
+
+/// Most rows or columns an item grid can have
+pub const MAX_GRID_SIZE: u32 = 32;
+
+impl ItemGridConfig {
+    /// Total number of cells in the grid; None if it doesn't fit a u32
+    pub fn slot_count(&self) -> Option<u32> {
+        self.rows.checked_mul(self.columns)
+    }
+}
+
 /// Potion slot configuration
+///
+/// Quickslot layout uses `hp_potion_slot` / `mp_potion_slot`; grid layout uses
+/// the cells in `grid`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PotionConfig {
-    pub hp_potion_slot: String,
-    pub mp_potion_slot: String,
+    pub hp_potion_slot: SlotId,
+    pub mp_potion_slot: SlotId,
+    #[serde(default)]
+    pub hp_potion_layout: InventoryLayout,
+    #[serde(default)]
+    pub mp_potion_layout: InventoryLayout,
+    #[serde(default)]
+    pub grid: ItemGridConfig,
+    /// Locate potions by icon; the slots above are used when no icon matches
+    /// Off by default: it needs icon templates in resources/potion_icons.
+    #[serde(default)]
+    pub auto_identify_slots: bool,
 }
 
 impl Default for PotionConfig {
     fn default() -> Self {
         Self {
-            hp_potion_slot: "shift".to_string(),
-            mp_potion_slot: "ins".to_string(),
+            hp_potion_slot: SlotId::Shift,
+            mp_potion_slot: SlotId::Ins,
+            hp_potion_layout: InventoryLayout::Quickslot,
+            mp_potion_layout: InventoryLayout::Quickslot,
+            grid: ItemGridConfig::default(),
+            auto_identify_slots: false,
         }
     }
 }
@@ -218,22 +518,105 @@ impl Default for PotionConfig {
 impl PotionConfig {
     /// Validate that slots are different and valid
     pub fn validate(&self) -> Result<(), String> {
-        const VALID_SLOTS: &[&str] = &["shift", "ins", "home", "pup", "ctrl", "del", "end", "pdn"];
-
-        if !VALID_SLOTS.contains(&self.hp_potion_slot.as_str()) {
-            return Err(format!("Invalid HP potion slot: {}", self.hp_potion_slot));
+        if self.hp_slot_key() == self.mp_slot_key() {
+            return Err("HP and MP potion slots must be different".to_string());
         }
 
-        if !VALID_SLOTS.contains(&self.mp_potion_slot.as_str()) {
-            return Err(format!("Invalid MP potion slot: {}", self.mp_potion_slot));
+        // Values come from the webview; the grid image is scaled by the column count
+        if self.grid.rows > MAX_GRID_SIZE || self.grid.columns > MAX_GRID_SIZE {
+            return Err(format!("Item grid can have at most {} rows and columns", MAX_GRID_SIZE));
         }
 
-        if self.hp_potion_slot == self.mp_potion_slot {
-            return Err("HP and MP potion slots must be different".to_string());
+        if self.uses_grid() {
+            if self.grid.rows == 0 || self.grid.columns == 0 {
+                return Err("Item grid must have at least one row and column".to_string());
+            }
+            if self.grid.roi.is_none() {
+                return Err("Item grid ROI must be selected before using grid slots".to_string());
+            }
+            for (label, key) in [("HP", self.hp_slot_key()), ("MP", self.mp_slot_key())] {
+                if let SlotKey::Grid(cell) = key {
+                    if self.grid.slot_count().is_none_or(|count| cell >= count) {
+                        return Err(format!("Invalid {} potion grid cell: {}", label, cell));
+                    }
+                }
+            }
         }
 
         Ok(())
     }
+
+    /// Whether any tracked item is read from the item grid
+    pub fn uses_grid(&self) -> bool {
+        self.hp_potion_layout == InventoryLayout::Grid || self.mp_potion_layout == InventoryLayout::Grid
+    }
+
+    /// Reading key for the HP potion slot
+    pub fn hp_slot_key(&self) -> SlotKey {
+        match self.hp_potion_layout {
+            InventoryLayout::Quickslot => SlotKey::Quickslot(self.hp_potion_slot),
+            InventoryLayout::Grid => SlotKey::Grid(self.grid.hp_cell),
+        }
+    }
+
+    /// Reading key for the MP potion slot
+    pub fn mp_slot_key(&self) -> SlotKey {
+        match self.mp_potion_layout {
+            InventoryLayout::Quickslot => SlotKey::Quickslot(self.mp_potion_slot),
+            InventoryLayout::Grid => SlotKey::Grid(self.grid.mp_cell),
+        }
+    }
+
+    /// Quickslots that need to be read
+    pub fn quickslot_slots(&self) -> Vec<SlotId> {
+        [self.hp_slot_key(), self.mp_slot_key()]
+            .into_iter()
+            .filter_map(|key| match key {
+                SlotKey::Quickslot(slot) => Some(slot),
+                SlotKey::Grid(_) => None,
+            })
+            .collect()
+    }
+
+    /// Grid cell indices that need to be read
+    pub fn grid_slots(&self) -> Vec<u32> {
+        [self.hp_slot_key(), self.mp_slot_key()]
+            .into_iter()
+            .filter_map(|key| match key {
+                SlotKey::Grid(cell) => Some(cell),
+                SlotKey::Quickslot(_) => None,
+            })
+            .collect()
+    }
+
+    /// Override quickslot assignments with slots identified by icon matching
+    /// Manual slots stay in effect when auto identification is disabled, an
+    /// item uses the grid layout, or the override would make HP and MP collide.
+    pub fn apply_identified_slots(&mut self, hp_slot: Option<SlotId>, mp_slot: Option<SlotId>) {
+        if !self.auto_identify_slots {
+            return;
+        }
+
+        let mut identified = self.clone();
+        if let (Some(slot), InventoryLayout::Quickslot) = (hp_slot, self.hp_potion_layout) {
+            identified.hp_potion_slot = slot;
+        }
+        if let (Some(slot), InventoryLayout::Quickslot) = (mp_slot, self.mp_potion_layout) {
+            identified.mp_potion_slot = slot;
+        }
+
+        if identified.validate().is_ok() {
+            *self = identified;
+        }
+    }
+}
+
+/// Capture regions saved for one game window layout (e.g. "fullscreen", "windowed-1280")
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Layout {
+    pub roi: RoiConfig,
+    #[serde(default)]
+    pub item_grid: Option<Roi>,
 }
 
 /// Complete application configuration
@@ -247,6 +630,102 @@ pub struct AppConfig {
     pub advanced: AdvancedConfig,
     #[serde(default)]
     pub potion: PotionConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    /// User-defined OCR readings (see models::custom_metric)
+    #[serde(default)]
+    pub custom_metrics: Vec<CustomMetric>,
+    /// Stats computed from expressions each update
+    #[serde(default)]
+    pub derived_metrics: Vec<DerivedMetric>,
+    /// Alert rules evaluated while tracking (see services::alert_engine)
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+    /// Saved ROI sets by layout name; `roi` and the item grid hold the active one
+    #[serde(default)]
+    pub layouts: BTreeMap<String, Layout>,
+    /// Layout the current ROIs belong to; None once they were changed some other way
+    #[serde(default)]
+    pub active_layout: Option<String>,
+}
+
+impl AppConfig {
+    fn current_layout(&self) -> Layout {
+        Layout {
+            roi: self.roi.clone(),
+            item_grid: self.potion.grid.roi,
+        }
+    }
+
+    /// Save the current ROIs as layout `name` (replacing it) and make it active
+    pub fn save_layout(&mut self, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Layout name must not be empty".to_string());
+        }
+        self.layouts.insert(name.to_string(), self.current_layout());
+        self.active_layout = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Replace the current ROIs with layout `name`
+    pub fn switch_layout(&mut self, name: &str) -> Result<(), String> {
+        let layout = self.layouts.get(name)
+            .ok_or_else(|| format!("Layout '{}' not found", name))?
+            .clone();
+        self.roi = layout.roi;
+        self.potion.grid.roi = layout.item_grid;
+        self.active_layout = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Layout after the active one (by name, wrapping around)
+    pub fn next_layout(&self) -> Option<String> {
+        let after_active = self.active_layout.as_ref().and_then(|active| {
+            self.layouts.range::<String, _>((std::ops::Bound::Excluded(active), std::ops::Bound::Unbounded)).next()
+        });
+        after_active.or_else(|| self.layouts.iter().next()).map(|(name, _)| name.clone())
+    }
+
+    /// Level ROIs of the saved layouts to try when detecting the current one,
+    /// active layout first (layouts without a level ROI can't be detected)
+    pub fn layout_candidates(&self) -> Vec<(String, Roi)> {
+        let mut candidates: Vec<(String, Roi)> = self.layouts.iter()
+            .filter_map(|(name, layout)| layout.roi.level.map(|roi| (name.clone(), roi)))
+            .collect();
+        if let Some(index) = candidates.iter().position(|(name, _)| Some(name) == self.active_layout.as_ref()) {
+            let active = candidates.remove(index);
+            candidates.insert(0, active);
+        }
+        candidates
+    }
+
+    /// Candidates after a display change: the active layout was made for the
+    /// old display, so the others are tried first
+    pub fn layout_candidates_after_display_change(&self) -> Vec<(String, Roi)> {
+        let mut candidates = self.layout_candidates();
+        if candidates.first().is_some_and(|(name, _)| Some(name) == self.active_layout.as_ref()) {
+            candidates.rotate_left(1);
+        }
+        candidates
+    }
+
+    /// Keep the active layout up to date after one of its ROIs was edited
+    pub fn sync_active_layout(&mut self) {
+        let layout = self.current_layout();
+        if let Some(saved) = self.active_layout.as_ref().and_then(|name| self.layouts.get_mut(name)) {
+            *saved = layout;
+        }
+    }
+
+    /// Rescale every saved ROI (e.g. after the display resolution changed)
+    pub fn rescale_rois(&mut self, scale_x: f64, scale_y: f64) {
+        self.roi = self.roi.scaled(scale_x, scale_y);
+        self.potion.grid.roi = self.potion.grid.roi.map(|r| r.scaled(scale_x, scale_y));
+        for metric in &mut self.custom_metrics {
+            metric.roi = metric.roi.scaled(scale_x, scale_y);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -274,6 +753,10 @@ mod tests {
         // Tracking config
         assert_eq!(config.tracking.update_interval, 1);
         assert!(!config.tracking.track_meso);
+        assert!(config.tracking.track_level);
+        assert!(config.tracking.track_exp);
+        assert!(config.tracking.track_potions);
+        assert_eq!(config.tracking.mode, TrackingMode::Continuous);
 
         // Display config
         assert_eq!(config.display.time_format, TimeFormat::TwentyFourHour);
@@ -288,6 +771,54 @@ mod tests {
         assert_eq!(config.advanced.spike_threshold, 2.0);
     }
 
+    #[test]
+    fn test_layouts_switch_rois() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.next_layout(), None);
+
+        config.roi.exp = Some(Roi::new(0, 1000, 300, 20));
+        config.save_layout("fullscreen").unwrap();
+        config.roi.exp = Some(Roi::new(0, 700, 200, 14));
+        config.save_layout("windowed-1280").unwrap();
+        assert!(config.save_layout("  ").is_err());
+
+        assert_eq!(config.next_layout().as_deref(), Some("fullscreen"));
+        config.switch_layout("fullscreen").unwrap();
+        assert_eq!(config.roi.exp, Some(Roi::new(0, 1000, 300, 20)));
+        assert_eq!(config.next_layout().as_deref(), Some("windowed-1280"));
+
+        // Edits go to the active layout only
+        config.roi.level = Some(Roi::new(10, 10, 50, 20));
+        config.sync_active_layout();
+        assert_eq!(config.layouts["fullscreen"].roi.level, Some(Roi::new(10, 10, 50, 20)));
+        assert_eq!(config.layouts["windowed-1280"].roi.level, None);
+
+        assert!(config.switch_layout("missing").is_err());
+    }
+
+    #[test]
+    fn test_layout_candidates_active_first() {
+        let mut config = AppConfig::default();
+        config.save_layout("no-level").unwrap();
+        config.roi.level = Some(Roi::new(10, 10, 50, 20));
+        config.save_layout("fullscreen").unwrap();
+        config.roi.level = Some(Roi::new(5, 5, 30, 12));
+        config.save_layout("windowed").unwrap();
+
+        let names = |config: &AppConfig| -> Vec<String> {
+            config.layout_candidates().into_iter().map(|(name, _)| name).collect()
+        };
+        assert_eq!(names(&config), vec!["windowed", "fullscreen"]);
+        config.switch_layout("fullscreen").unwrap();
+        assert_eq!(names(&config), vec!["fullscreen", "windowed"]);
+
+        let after_change: Vec<String> = config.layout_candidates_after_display_change()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(after_change, vec!["windowed", "fullscreen"]);
+    }
+
     #[test]
     fn test_app_config_serialization() {
         let config = AppConfig::default();
@@ -314,6 +845,22 @@ mod tests {
         assert!(deserialized.roi.mp.is_none());
     }
 
+    #[test]
+    fn test_window_config_legacy_json_gets_overlay_defaults() {
+        let json = r#"{
+            "compact": {"width": 540, "height": 130, "x": 10, "y": 20},
+            "dashboard": {"width": 1000, "height": 700, "x": 100, "y": 100},
+            "current_mode": "compact",
+            "always_on_top": true
+        }"#;
+
+        let window: WindowConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(window.compact.x, 10);
+        assert_eq!(window.overlay, default_overlay_dimensions());
+        assert_eq!(window.overlay_opacity, default_overlay_opacity());
+        assert_eq!(window.stats, default_stats_dimensions());
+    }
+
     #[test]
     fn test_window_mode_serialization() {
         let compact = WindowMode::Compact;
@@ -329,6 +876,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_potion_config_legacy_json_defaults_to_quickslot() {
+        let json = r#"{"hp_potion_slot": "shift", "mp_potion_slot": "ins"}"#;
+        let config: PotionConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.hp_potion_layout, InventoryLayout::Quickslot);
+        assert_eq!(config.grid, ItemGridConfig::default());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_potion_config_accepts_keyboard_slot_names() {
+        let json = r#"{"hp_potion_slot": "Delete", "mp_potion_slot": "End"}"#;
+        let config: PotionConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.hp_potion_slot, SlotId::Del);
+        assert_eq!(config.mp_potion_slot, SlotId::End);
+
+        // Saved back in the canonical short form
+        let saved = serde_json::to_string(&config).unwrap();
+        assert!(saved.contains(r#""hp_potion_slot":"del""#));
+    }
+
+    #[test]
+    fn test_potion_config_grid_validation() {
+        let mut config = PotionConfig {
+            hp_potion_layout: InventoryLayout::Grid,
+            grid: ItemGridConfig { hp_cell: 5, ..ItemGridConfig::default() },
+            ..PotionConfig::default()
+        };
+
+        // Grid ROI not selected yet
+        assert!(config.validate().is_err());
+
+        config.grid.roi = Some(Roi::new(100, 100, 200, 300));
+        assert!(config.validate().is_ok());
+        assert_eq!(config.hp_slot_key(), SlotKey::Grid(5));
+        assert_eq!(config.quickslot_slots(), vec![SlotId::Ins]);
+        assert_eq!(config.grid_slots(), vec![5]);
+
+        // Index beyond the 24-cell grid
+        config.grid.hp_cell = 24;
+        assert!(config.validate().is_err());
+
+        // Sizes that would overflow the cell count or the scaled image
+        config.grid.hp_cell = 5;
+        config.grid.columns = MAX_GRID_SIZE + 1;
+        assert!(config.validate().is_err());
+        config.grid = ItemGridConfig { rows: u32::MAX, columns: 2, ..config.grid };
+        assert!(config.validate().is_err());
+        assert_eq!(config.grid.slot_count(), None);
+    }
+
+    #[test]
+    fn test_apply_identified_slots_respects_manual_override() {
+        let auto = PotionConfig { auto_identify_slots: true, ..PotionConfig::default() };
+        let mut config = auto.clone();
+        config.apply_identified_slots(Some(SlotId::Del), Some(SlotId::End));
+        assert_eq!(config.hp_potion_slot, SlotId::Del);
+        assert_eq!(config.mp_potion_slot, SlotId::End);
+
+        // Identified HP slot collides with the manual MP slot -> keep manual slots
+        let mut config = auto.clone();
+        config.apply_identified_slots(Some(SlotId::Ins), None);
+        assert_eq!(config.hp_potion_slot, SlotId::Shift);
+
+        let mut config = PotionConfig::default();
+        config.apply_identified_slots(Some(SlotId::Del), Some(SlotId::End));
+        assert_eq!(config.hp_potion_slot, SlotId::Shift);
+    }
+
     #[test]
     fn test_time_format_serialization() {
         let twelve = TimeFormat::TwelveHour;
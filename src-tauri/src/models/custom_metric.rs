@@ -0,0 +1,107 @@
+use crate::models::roi::Roi;
+use serde::{Deserialize, Serialize};
+
+/// How a custom metric's OCR text is interpreted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricParser {
+    /// Whole number, thousands separators ignored (e.g. fame, kill counters)
+    Integer,
+    /// Number followed by % (or the first number if there is no %)
+    Percentage,
+    /// Trimmed text as recognized
+    Text,
+}
+
+/// User-defined OCR reading tracked alongside level/EXP (fame, spawn counters, ...)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomMetric {
+    /// Unique name; used as the key in events and stats
+    pub name: String,
+    pub roi: Roi,
+    pub parser: MetricParser,
+    /// Seconds between readings
+    #[serde(default = "default_metric_interval")]
+    pub interval_secs: u64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_metric_interval() -> u64 {
+    5
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A parsed custom metric reading
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum MetricValue {
+    Integer(i64),
+    Percentage(f64),
+    Text(String),
+}
+
+/// User-defined stat computed from other stats, e.g. `(meso - potions_cost) / hours`
+/// Variables are TrackingStats fields and numeric custom metric names.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DerivedMetric {
+    pub name: String,
+    pub expression: String,
+}
+
+/// Check a list of custom metrics before saving
+pub fn validate_custom_metrics(metrics: &[CustomMetric]) -> Result<(), String> {
+    let mut names = std::collections::HashSet::new();
+
+    for metric in metrics {
+        let name = metric.name.trim();
+        if name.is_empty() {
+            return Err("Custom metric name must not be empty".to_string());
+        }
+        if !names.insert(name) {
+            return Err(format!("Duplicate custom metric name '{}'", name));
+        }
+        if metric.interval_secs == 0 {
+            return Err(format!("Custom metric '{}' needs an interval of at least 1 second", name));
+        }
+        metric.roi.check_size()
+            .map_err(|e| format!("Custom metric '{}': {}", name, e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metric(name: &str, interval_secs: u64) -> CustomMetric {
+        CustomMetric {
+            name: name.to_string(),
+            roi: Roi::new(0, 0, 100, 20),
+            parser: MetricParser::Integer,
+            interval_secs,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_metric_value_serialization() {
+        let json = serde_json::to_string(&MetricValue::Integer(1200)).unwrap();
+        assert_eq!(json, r#"{"type":"integer","value":1200}"#);
+    }
+
+    #[test]
+    fn test_validate_custom_metrics() {
+        assert!(validate_custom_metrics(&[metric("fame", 5), metric("spawns", 10)]).is_ok());
+        assert!(validate_custom_metrics(&[metric("fame", 5), metric("fame", 10)]).is_err());
+        assert!(validate_custom_metrics(&[metric(" ", 5)]).is_err());
+        assert!(validate_custom_metrics(&[metric("fame", 0)]).is_err());
+
+        let tiny = CustomMetric { roi: Roi::new(0, 0, 3, 3), ..metric("fame", 5) };
+        assert!(validate_custom_metrics(&[tiny]).is_err());
+    }
+}
@@ -2,3 +2,9 @@ pub mod config;
 pub mod exp_data;
 pub mod roi;
 pub mod ocr_result;
+pub mod slot;
+pub mod checkpoint;
+pub mod custom_metric;
+pub mod alert;
+pub mod timeline;
+pub mod ocr_failure;
@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Minutes of session time per elapsed-time bucket
+pub const ELAPSED_BUCKET_MINUTES: u64 = 10;
+
+/// Attempts and failures of one ROI
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RoiFailures {
+    pub attempts: u64,
+    pub failures: u64,
+    /// Failures per `ELAPSED_BUCKET_MINUTES` of session time, keyed by the bucket's first minute
+    pub by_elapsed_minutes: BTreeMap<u64, u64>,
+    /// Failures per local hour of day (0-23)
+    pub by_hour: BTreeMap<u32, u64>,
+    /// Most recent failure message
+    pub last_error: Option<String>,
+}
+
+impl RoiFailures {
+    pub fn failure_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.attempts as f64
+        }
+    }
+
+    fn merge(&mut self, other: &RoiFailures) {
+        self.attempts += other.attempts;
+        self.failures += other.failures;
+        for (&minute, &count) in &other.by_elapsed_minutes {
+            *self.by_elapsed_minutes.entry(minute).or_default() += count;
+        }
+        for (&hour, &count) in &other.by_hour {
+            *self.by_hour.entry(hour).or_default() += count;
+        }
+        if other.last_error.is_some() {
+            self.last_error = other.last_error.clone();
+        }
+    }
+
+    /// Failures before and after `minute` of the session; the later part starts at zero
+    ///
+    /// Only failures are time-stamped (to the bucket), so attempts are divided by
+    /// `time_share`, the first part's share of the session, and the hour-of-day
+    /// counts by each part's share of the failures.
+    fn split_at(&self, minute: u64, time_share: f64) -> (RoiFailures, RoiFailures) {
+        let mut first = RoiFailures { last_error: self.last_error.clone(), ..Default::default() };
+        let mut second = first.clone();
+
+        for (&bucket, &count) in &self.by_elapsed_minutes {
+            if bucket < minute {
+                *first.by_elapsed_minutes.entry(bucket).or_default() += count;
+                first.failures += count;
+            } else {
+                *second.by_elapsed_minutes.entry(elapsed_bucket(bucket - minute)).or_default() += count;
+                second.failures += count;
+            }
+        }
+
+        let failure_share = if self.failures > 0 { first.failures as f64 / self.failures as f64 } else { 0.0 };
+        for (&hour, &count) in &self.by_hour {
+            let first_count = (count as f64 * failure_share).round() as u64;
+            if first_count > 0 {
+                first.by_hour.insert(hour, first_count);
+            }
+            if count > first_count {
+                second.by_hour.insert(hour, count - first_count);
+            }
+        }
+
+        first.attempts = ((self.attempts as f64 * time_share).round() as u64)
+            .clamp(first.failures, self.attempts.saturating_sub(second.failures).max(first.failures));
+        second.attempts = self.attempts.saturating_sub(first.attempts).max(second.failures);
+        for part in [&mut first, &mut second] {
+            if part.failures == 0 {
+                part.last_error = None;
+            }
+        }
+        (first, second)
+    }
+
+    /// Add `other`, which began `offset_minutes` into this session
+    fn append(&mut self, other: &RoiFailures, offset_minutes: u64) {
+        let shifted = RoiFailures {
+            by_elapsed_minutes: other.by_elapsed_minutes.iter()
+                .fold(BTreeMap::new(), |mut buckets, (&bucket, &count)| {
+                    *buckets.entry(elapsed_bucket(bucket + offset_minutes)).or_default() += count;
+                    buckets
+                }),
+            ..other.clone()
+        };
+        self.merge(&shifted);
+    }
+}
+
+/// First minute of the `ELAPSED_BUCKET_MINUTES` bucket holding `minute`
+fn elapsed_bucket(minute: u64) -> u64 {
+    minute / ELAPSED_BUCKET_MINUTES * ELAPSED_BUCKET_MINUTES
+}
+
+/// OCR failures of one session by ROI, stored next to its record
+///
+/// ROIs are named like the OCR loops: "level", "exp", "inventory", "custom:<name>".
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FailureHistogram {
+    /// Id of the session record; empty while the session is running
+    pub session_id: String,
+    /// Session end, UTC Unix timestamp in milliseconds (same as the record)
+    pub timestamp: i64,
+    pub rois: BTreeMap<String, RoiFailures>,
+}
+
+impl FailureHistogram {
+    pub fn record_success(&mut self, roi: &str) {
+        self.rois.entry(roi.to_string()).or_default().attempts += 1;
+    }
+
+    /// Count a failed read `elapsed_seconds` into the session at local `hour`
+    pub fn record_failure(&mut self, roi: &str, elapsed_seconds: u64, hour: u32, error: &str) {
+        let failures = self.rois.entry(roi.to_string()).or_default();
+        failures.attempts += 1;
+        failures.failures += 1;
+
+        *failures.by_elapsed_minutes.entry(elapsed_bucket(elapsed_seconds / 60)).or_default() += 1;
+        *failures.by_hour.entry(hour).or_default() += 1;
+        failures.last_error = Some(error.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rois.is_empty()
+    }
+
+    /// Failures before and after `elapsed_seconds` of a `total_seconds` session
+    /// (for splitting its record); ids and timestamps are left to the caller
+    pub fn split_at(&self, elapsed_seconds: u64, total_seconds: u64) -> (FailureHistogram, FailureHistogram) {
+        let time_share = if total_seconds > 0 { elapsed_seconds as f64 / total_seconds as f64 } else { 0.0 };
+        let mut first = FailureHistogram { session_id: self.session_id.clone(), timestamp: self.timestamp, rois: BTreeMap::new() };
+        let mut second = first.clone();
+
+        for (roi, failures) in &self.rois {
+            let (before, after) = failures.split_at(elapsed_seconds / 60, time_share);
+            if before.attempts > 0 {
+                first.rois.insert(roi.clone(), before);
+            }
+            if after.attempts > 0 {
+                second.rois.insert(roi.clone(), after);
+            }
+        }
+        (first, second)
+    }
+
+    /// Add the failures of a session that began `offset_seconds` into this one
+    /// (for merging records)
+    pub fn append(&mut self, other: &FailureHistogram, offset_seconds: u64) {
+        for (roi, failures) in &other.rois {
+            self.rois.entry(roi.clone()).or_default().append(failures, offset_seconds / 60);
+        }
+    }
+}
+
+/// One ROI's failures across the reported sessions
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RoiFailureSummary {
+    pub roi: String,
+    pub failure_rate: f64,
+    #[serde(flatten)]
+    pub failures: RoiFailures,
+}
+
+/// Payload of `get_failure_report`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FailureReport {
+    pub sessions: usize,
+    /// Most failures first
+    pub rois: Vec<RoiFailureSummary>,
+}
+
+/// Sum histograms (oldest first, so `last_error` is the latest) per ROI
+pub fn failure_report(histograms: &[FailureHistogram]) -> FailureReport {
+    let mut totals: BTreeMap<String, RoiFailures> = BTreeMap::new();
+    for histogram in histograms {
+        for (roi, failures) in &histogram.rois {
+            totals.entry(roi.clone()).or_default().merge(failures);
+        }
+    }
+
+    let mut rois: Vec<RoiFailureSummary> = totals.into_iter()
+        .map(|(roi, failures)| RoiFailureSummary {
+            roi,
+            failure_rate: failures.failure_rate(),
+            failures,
+        })
+        .collect();
+    rois.sort_by(|a, b| b.failures.failures.cmp(&a.failures.failures));
+
+    FailureReport {
+        sessions: histograms.len(),
+        rois,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failures_are_bucketed() {
+        let mut histogram = FailureHistogram::default();
+        histogram.record_success("exp");
+        histogram.record_failure("exp", 25 * 60, 21, "no digits");
+        histogram.record_failure("exp", 29 * 60, 22, "clipped");
+
+        let exp = &histogram.rois["exp"];
+        assert_eq!((exp.attempts, exp.failures), (3, 2));
+        assert_eq!(exp.by_elapsed_minutes, BTreeMap::from([(20, 2)]));
+        assert_eq!(exp.by_hour, BTreeMap::from([(21, 1), (22, 1)]));
+        assert_eq!(exp.last_error.as_deref(), Some("clipped"));
+    }
+
+    #[test]
+    fn test_report_sums_sessions() {
+        let mut first = FailureHistogram::default();
+        first.record_failure("exp", 0, 10, "a");
+        first.record_success("level");
+        let mut second = FailureHistogram::default();
+        second.record_failure("exp", 0, 10, "b");
+        second.record_failure("level", 600, 11, "c");
+        second.record_success("level");
+
+        let report = failure_report(&[first, second]);
+        assert_eq!(report.sessions, 2);
+        assert_eq!(report.rois[0].roi, "exp");
+        assert_eq!(report.rois[0].failures.by_hour, BTreeMap::from([(10, 2)]));
+        assert_eq!(report.rois[0].failures.last_error.as_deref(), Some("b"));
+        assert_eq!(report.rois[1].failure_rate, 1.0 / 3.0);
+    }
+
+    #[test]
+    fn test_split_and_append() {
+        let mut histogram = FailureHistogram::default();
+        for _ in 0..6 {
+            histogram.record_success("exp");
+        }
+        histogram.record_failure("exp", 5 * 60, 21, "a");
+        histogram.record_failure("exp", 35 * 60, 21, "b");
+        histogram.record_failure("exp", 45 * 60, 22, "c");
+
+        // Split at 30 of 60 minutes
+        let (first, second) = histogram.split_at(30 * 60, 60 * 60);
+        let (before, after) = (&first.rois["exp"], &second.rois["exp"]);
+        assert_eq!((before.attempts, before.failures), (5, 1));
+        assert_eq!((after.attempts, after.failures), (4, 2));
+        assert_eq!(before.by_elapsed_minutes, BTreeMap::from([(0, 1)]));
+        assert_eq!(after.by_elapsed_minutes, BTreeMap::from([(0, 1), (10, 1)]));
+        assert_eq!(before.by_hour.values().sum::<u64>() + after.by_hour.values().sum::<u64>(), 3);
+
+        let mut merged = first.clone();
+        merged.append(&second, 30 * 60);
+        let exp = &merged.rois["exp"];
+        assert_eq!((exp.attempts, exp.failures), (9, 3));
+        assert_eq!(exp.by_elapsed_minutes, BTreeMap::from([(0, 1), (30, 1), (40, 1)]));
+    }
+}
@@ -1,5 +1,25 @@
 use serde::{Deserialize, Serialize};
 
+/// Smallest width/height (logical pixels) that can still hold a line of text
+pub const MIN_ROI_SIZE: u32 = 8;
+
+/// Horizontal side of an ROI (numbers grow sideways, so only these can clip)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RoiEdge {
+    Left,
+    Right,
+}
+
+/// Rectangle in physical pixels of a captured image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhysicalRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
 /// Region of Interest for screen capture
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub struct Roi {
@@ -42,6 +62,17 @@ impl Roi {
         self.width > 0 && self.height > 0
     }
 
+    /// Reject ROIs too small to OCR (e.g. a stray click saved as 0x0 or 3x3)
+    pub fn check_size(&self) -> Result<(), String> {
+        if self.width < MIN_ROI_SIZE || self.height < MIN_ROI_SIZE {
+            return Err(format!(
+                "ROI is too small ({}x{}); it must be at least {}x{} pixels",
+                self.width, self.height, MIN_ROI_SIZE, MIN_ROI_SIZE
+            ));
+        }
+        Ok(())
+    }
+
     /// Get the end coordinates
     pub fn x2(&self) -> i32 {
         self.x + self.width as i32
@@ -61,6 +92,84 @@ impl Roi {
         x >= self.x && x < self.x2() && y >= self.y && y < self.y2()
     }
 
+    /// Scale by independent x/y factors (e.g. after a resolution change)
+    /// Edges are scaled and rounded rather than the size, so neighbouring ROIs stay flush.
+    pub fn scaled(&self, scale_x: f64, scale_y: f64) -> Self {
+        let x = (self.x as f64 * scale_x).round() as i32;
+        let y = (self.y as f64 * scale_y).round() as i32;
+        let x2 = (self.x2() as f64 * scale_x).round() as i32;
+        let y2 = (self.y2() as f64 * scale_y).round() as i32;
+
+        Self {
+            x,
+            y,
+            width: (x2 - x).max(1) as u32,
+            height: (y2 - y).max(1) as u32,
+        }
+    }
+
+    /// Pixels of this (logical) ROI in a capture taken at `scale_factor`
+    ///
+    /// Left/top and right/bottom edges are each scaled and rounded, instead of
+    /// truncating the position and scaling the size separately, which drifts
+    /// the crop by 1-2 pixels at fractional factors like 1.25.
+    pub fn to_physical(&self, scale_factor: f64) -> PhysicalRect {
+        let edge = |v: i32| (v as f64 * scale_factor).round().max(0.0) as u32;
+        let (x1, y1, x2, y2) = (edge(self.x), edge(self.y), edge(self.x2()), edge(self.y2()));
+
+        PhysicalRect {
+            x: x1,
+            y: y1,
+            width: x2 - x1,
+            height: y2 - y1,
+        }
+    }
+
+    /// Smallest logical ROI covering physical pixels `x1..x2` x `y1..y2` (end exclusive)
+    pub fn from_physical(x1: u32, y1: u32, x2: u32, y2: u32, scale_factor: f64) -> Self {
+        let start = |v: u32| (v as f64 / scale_factor).floor() as i32;
+        let end = |v: u32| (v as f64 / scale_factor).ceil() as i32;
+        let (x, y) = (start(x1), start(y1));
+
+        Self {
+            x,
+            y,
+            width: (end(x2) - x).max(1) as u32,
+            height: (end(y2) - y).max(1) as u32,
+        }
+    }
+
+    /// Widen toward `edges` by `step`, staying within x = 0..`screen_width`
+    /// and at most `max_width` wide (growth is taken back from the widened sides)
+    pub fn expanded(&self, edges: &[RoiEdge], step: u32, max_width: u32, screen_width: u32) -> Self {
+        let grow_left = edges.contains(&RoiEdge::Left);
+        let grow_right = edges.contains(&RoiEdge::Right);
+
+        let mut x1 = self.x as i64 - if grow_left { step as i64 } else { 0 };
+        let mut x2 = self.x2() as i64 + if grow_right { step as i64 } else { 0 };
+        x1 = x1.max(0).min(self.x as i64);
+        x2 = x2.min(screen_width as i64).max(self.x2() as i64);
+
+        let excess = (x2 - x1) - max_width.max(self.width) as i64;
+        if excess > 0 {
+            match (grow_left, grow_right) {
+                (true, true) => {
+                    x1 += excess / 2;
+                    x2 -= excess - excess / 2;
+                }
+                (true, false) => x1 += excess,
+                _ => x2 -= excess,
+            }
+        }
+
+        Self {
+            x: x1 as i32,
+            y: self.y,
+            width: (x2 - x1) as u32,
+            height: self.height,
+        }
+    }
+
     /// Check if ROI intersects with another ROI
     pub fn intersects(&self, other: &Roi) -> bool {
         self.x < other.x2()
@@ -118,6 +227,15 @@ mod tests {
         assert!(!zero_height.is_valid());
     }
 
+    #[test]
+    fn test_roi_check_size() {
+        assert!(Roi::new(0, 0, 120, 12).check_size().is_ok());
+        assert!(Roi::new(0, 0, MIN_ROI_SIZE, MIN_ROI_SIZE).check_size().is_ok());
+        assert!(Roi::new(0, 0, 0, 0).check_size().is_err());
+        assert!(Roi::new(0, 0, 3, 3).check_size().is_err());
+        assert!(Roi::new(0, 0, 200, 3).check_size().is_err());
+    }
+
     #[test]
     fn test_roi_bounds() {
         let roi = Roi::new(100, 200, 300, 400);
@@ -168,6 +286,47 @@ mod tests {
         assert!(!roi1.intersects(&roi4));
     }
 
+    #[test]
+    fn test_roi_scaled() {
+        let roi = Roi::new(100, 200, 300, 400);
+        assert_eq!(roi.scaled(2.0, 1.5), Roi::new(200, 300, 600, 600));
+
+        // Never collapses to an empty ROI
+        let tiny = Roi::new(1, 1, 1, 1);
+        assert!(tiny.scaled(0.1, 0.1).is_valid());
+
+        // Right edge follows the scaled right edge, not the rounded width
+        assert_eq!(Roi::new(101, 37, 203, 15).scaled(1.25, 1.25), Roi::new(126, 46, 254, 19));
+    }
+
+    #[test]
+    fn test_to_physical_fractional_scale() {
+        let roi = Roi::new(101, 37, 203, 15);
+
+        assert_eq!(roi.to_physical(1.0), PhysicalRect { x: 101, y: 37, width: 203, height: 15 });
+        assert_eq!(roi.to_physical(1.25), PhysicalRect { x: 126, y: 46, width: 254, height: 19 });
+        assert_eq!(roi.to_physical(1.5), PhysicalRect { x: 152, y: 56, width: 304, height: 22 });
+        assert_eq!(roi.to_physical(2.0), PhysicalRect { x: 202, y: 74, width: 406, height: 30 });
+    }
+
+    #[test]
+    fn test_adjacent_rois_stay_flush_when_scaled() {
+        let left = Roi::new(0, 0, 101, 15);
+        let right = Roi::new(101, 0, 100, 15);
+
+        for scale in [1.25, 1.5, 2.0] {
+            let (left, right) = (left.to_physical(scale), right.to_physical(scale));
+            assert_eq!(left.x + left.width, right.x, "gap or overlap at {}", scale);
+        }
+    }
+
+    #[test]
+    fn test_from_physical_covers_pixels() {
+        assert_eq!(Roi::from_physical(126, 46, 380, 65, 1.25), Roi::new(100, 36, 204, 16));
+        assert_eq!(Roi::from_physical(150, 60, 300, 90, 1.5), Roi::new(100, 40, 100, 20));
+        assert_eq!(Roi::from_physical(202, 74, 608, 104, 2.0), Roi::new(101, 37, 203, 15));
+    }
+
     #[test]
     fn test_roi_serialization() {
         let roi = Roi::new(100, 200, 300, 400);
@@ -175,4 +334,18 @@ mod tests {
         let deserialized: Roi = serde_json::from_str(&json).unwrap();
         assert_eq!(roi, deserialized);
     }
+
+    #[test]
+    fn test_expanded_respects_limits() {
+        let roi = Roi::new(100, 50, 100, 20);
+
+        assert_eq!(roi.expanded(&[RoiEdge::Right], 10, 150, 1920), Roi::new(100, 50, 110, 20));
+        assert_eq!(roi.expanded(&[RoiEdge::Left, RoiEdge::Right], 10, 150, 1920), Roi::new(90, 50, 120, 20));
+        assert_eq!(roi.expanded(&[], 10, 150, 1920), roi);
+
+        // Capped at max_width, screen edges
+        assert_eq!(roi.expanded(&[RoiEdge::Right], 100, 150, 1920), Roi::new(100, 50, 150, 20));
+        assert_eq!(roi.expanded(&[RoiEdge::Left], 500, 500, 1920), Roi::new(0, 50, 200, 20));
+        assert_eq!(roi.expanded(&[RoiEdge::Right], 10, 150, 205), Roi::new(100, 50, 105, 20));
+    }
 }
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Hotkey quickslot identifier
+///
+/// Serialized as the short lowercase key ("shift", "ins", ...). Aliases accept
+/// the keyboard names ("Delete", "PageUp", ...) so older configs keep loading.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum SlotId {
+    #[serde(alias = "Shift")]
+    Shift,
+    #[serde(alias = "Ins", alias = "Insert", alias = "insert")]
+    Ins,
+    #[serde(alias = "Home")]
+    Home,
+    #[serde(alias = "PageUp", alias = "pageup", alias = "PgUp", alias = "pgup")]
+    Pup,
+    #[serde(alias = "Ctrl", alias = "Control", alias = "control")]
+    Ctrl,
+    #[serde(alias = "Del", alias = "Delete", alias = "delete")]
+    Del,
+    #[serde(alias = "End")]
+    End,
+    #[serde(alias = "PageDown", alias = "pagedown", alias = "PgDn", alias = "pgdn")]
+    Pdn,
+}
+
+impl SlotId {
+    /// All quickslots in display order (top row, then bottom row)
+    pub const ALL: [SlotId; 8] = [
+        SlotId::Shift,
+        SlotId::Ins,
+        SlotId::Home,
+        SlotId::Pup,
+        SlotId::Ctrl,
+        SlotId::Del,
+        SlotId::End,
+        SlotId::Pdn,
+    ];
+
+    /// Config key (e.g. "del")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlotId::Shift => "shift",
+            SlotId::Ins => "ins",
+            SlotId::Home => "home",
+            SlotId::Pup => "pup",
+            SlotId::Ctrl => "ctrl",
+            SlotId::Del => "del",
+            SlotId::End => "end",
+            SlotId::Pdn => "pdn",
+        }
+    }
+
+    /// Keyboard label shown in the UI (e.g. "Del")
+    pub fn label(&self) -> &'static str {
+        match self {
+            SlotId::Shift => "Shift",
+            SlotId::Ins => "Ins",
+            SlotId::Home => "Home",
+            SlotId::Pup => "PgUp",
+            SlotId::Ctrl => "Ctrl",
+            SlotId::Del => "Del",
+            SlotId::End => "End",
+            SlotId::Pdn => "PgDn",
+        }
+    }
+}
+
+impl fmt::Display for SlotId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SlotId {
+    type Err = String;
+
+    /// Parse any accepted spelling, case-insensitive
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "shift" => Ok(SlotId::Shift),
+            "ins" | "insert" => Ok(SlotId::Ins),
+            "home" => Ok(SlotId::Home),
+            "pup" | "pageup" | "pgup" => Ok(SlotId::Pup),
+            "ctrl" | "control" => Ok(SlotId::Ctrl),
+            "del" | "delete" => Ok(SlotId::Del),
+            "end" => Ok(SlotId::End),
+            "pdn" | "pagedown" | "pgdn" => Ok(SlotId::Pdn),
+            _ => Err(format!("Invalid slot: {}", s)),
+        }
+    }
+}
+
+/// Key of a single inventory reading, across both inventory layouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlotKey {
+    Quickslot(SlotId),
+    /// Row-major cell index in the item inventory window
+    Grid(u32),
+}
+
+impl fmt::Display for SlotKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SlotKey::Quickslot(slot) => write!(f, "{}", slot),
+            SlotKey::Grid(index) => write!(f, "grid:{}", index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_id_serializes_to_short_key() {
+        assert_eq!(serde_json::to_string(&SlotId::Del).unwrap(), "\"del\"");
+        assert_eq!(serde_json::to_string(&SlotId::Pup).unwrap(), "\"pup\"");
+    }
+
+    #[test]
+    fn test_slot_id_accepts_keyboard_names() {
+        let slot: SlotId = serde_json::from_str("\"Delete\"").unwrap();
+        assert_eq!(slot, SlotId::Del);
+        let slot: SlotId = serde_json::from_str("\"PageUp\"").unwrap();
+        assert_eq!(slot, SlotId::Pup);
+        assert!(serde_json::from_str::<SlotId>("\"f1\"").is_err());
+    }
+
+    #[test]
+    fn test_slot_id_from_str_round_trip() {
+        for slot in SlotId::ALL {
+            assert_eq!(slot.as_str().parse::<SlotId>().unwrap(), slot);
+        }
+        assert_eq!("END".parse::<SlotId>().unwrap(), SlotId::End);
+    }
+}
@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+
+/// Seconds of tracked time between two timeline points
+pub const TIMELINE_INTERVAL_SECS: u64 = 30;
+
+/// Previous sessions averaged into the rate baseline by default
+pub const DEFAULT_BASELINE_SESSIONS: usize = 5;
+
+/// Cumulative progress at one moment of a session
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TimelinePoint {
+    pub elapsed_seconds: u64,
+    pub total_exp: u64,
+    /// Capture-to-emit latency of the reading that produced this point
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u32>,
+    /// When the point was taken, UTC Unix timestamp in milliseconds
+    /// None in timelines saved by older versions and for points made by splitting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recorded_at: Option<i64>,
+}
+
+/// EXP progress of a saved session, stored next to its record
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Timeline {
+    /// Id of the session record this timeline belongs to
+    pub session_id: String,
+    /// Session end, UTC Unix timestamp in milliseconds (same as the record)
+    pub timestamp: i64,
+    #[serde(default)]
+    pub map_name: Option<String>,
+    pub points: Vec<TimelinePoint>,
+}
+
+impl Timeline {
+    /// Total EXP at `elapsed_seconds`, interpolated between points
+    /// None past the end of the session.
+    pub fn exp_at(&self, elapsed_seconds: u64) -> Option<f64> {
+        let after = self.points.iter().position(|p| p.elapsed_seconds >= elapsed_seconds)?;
+        let next = self.points[after];
+        if after == 0 || next.elapsed_seconds == elapsed_seconds {
+            return Some(next.total_exp as f64);
+        }
+
+        let prev = self.points[after - 1];
+        let fraction = (elapsed_seconds - prev.elapsed_seconds) as f64
+            / (next.elapsed_seconds - prev.elapsed_seconds) as f64;
+        Some(prev.total_exp as f64 + (next.total_exp as f64 - prev.total_exp as f64) * fraction)
+    }
+
+    /// Tracked seconds at `at` (UTC ms), from the wall-clock times of the points
+    ///
+    /// Stopped and slept-through time isn't tracked, so it's assumed to lie at
+    /// the start of the interval it falls in (stopping adds a point, so a
+    /// resumed session's gap does). None without wall-clock times or before the first point.
+    pub fn elapsed_at(&self, at: i64) -> Option<u64> {
+        let mut timed = self.points.iter().filter_map(|p| Some((p.recorded_at?, p.elapsed_seconds)));
+        let (mut last_at, mut last_elapsed) = timed.next().filter(|(recorded_at, _)| *recorded_at <= at)?;
+
+        for (recorded_at, elapsed_seconds) in timed {
+            if recorded_at >= at {
+                let untracked = ((recorded_at - at) / 1000) as u64;
+                let interval = elapsed_seconds.saturating_sub(last_elapsed);
+                return Some(elapsed_seconds - untracked.min(interval));
+            }
+            (last_at, last_elapsed) = (recorded_at, elapsed_seconds);
+        }
+
+        // Past the last point: tracked until the end
+        Some(last_elapsed + ((at - last_at) / 1000) as u64)
+    }
+
+    /// Points before and after `elapsed_seconds`; the later part starts again at zero
+    /// Both parts share an interpolated point at the split.
+    pub fn split_at(&self, elapsed_seconds: u64) -> (Vec<TimelinePoint>, Vec<TimelinePoint>) {
+        let at_exp = self.exp_at(elapsed_seconds)
+            .or_else(|| self.points.last().map(|p| p.total_exp as f64))
+            .unwrap_or_default()
+            .round() as u64;
+
+        let mut first: Vec<TimelinePoint> = self.points.iter()
+            .copied()
+            .filter(|p| p.elapsed_seconds < elapsed_seconds)
+            .collect();
+        first.push(TimelinePoint { elapsed_seconds, total_exp: at_exp, latency_ms: None, recorded_at: None });
+
+        let mut second = vec![TimelinePoint { elapsed_seconds: 0, total_exp: 0, latency_ms: None, recorded_at: None }];
+        second.extend(self.points.iter()
+            .filter(|p| p.elapsed_seconds > elapsed_seconds)
+            .map(|p| TimelinePoint {
+                elapsed_seconds: p.elapsed_seconds - elapsed_seconds,
+                total_exp: p.total_exp.saturating_sub(at_exp),
+                ..*p
+            }));
+
+        (first, second)
+    }
+
+    /// Points of `timelines` (oldest first) played one after another
+    pub fn concat(timelines: &[Timeline]) -> Vec<TimelinePoint> {
+        let mut points: Vec<TimelinePoint> = Vec::new();
+        for timeline in timelines {
+            let previous_end = points.last().map(|p| (p.elapsed_seconds, p.total_exp));
+            let (offset_secs, offset_exp) = previous_end.unwrap_or_default();
+            points.extend(timeline.points.iter()
+                // Each part starts where the previous one ended
+                .filter(|p| previous_end.is_none() || p.elapsed_seconds > 0)
+                .map(|p| TimelinePoint {
+                    elapsed_seconds: p.elapsed_seconds + offset_secs,
+                    total_exp: p.total_exp.saturating_add(offset_exp),
+                    ..*p
+                }));
+        }
+        points
+    }
+}
+
+/// Point of a rate curve: cumulative EXP and the average rate so far
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct RatePoint {
+    pub elapsed_seconds: u64,
+    pub total_exp: f64,
+    pub exp_per_hour: f64,
+    /// Sessions that lasted this long (1 for the current session)
+    pub sessions: usize,
+    /// Within the rate warm-up: too early for the rate to mean much
+    pub estimating: bool,
+}
+
+/// Rate curve of a single session; points before `warmup_secs` are flagged as estimating
+pub fn rate_curve(points: &[TimelinePoint], warmup_secs: u64) -> Vec<RatePoint> {
+    points.iter()
+        .filter(|p| p.elapsed_seconds > 0)
+        .map(|p| RatePoint {
+            elapsed_seconds: p.elapsed_seconds,
+            total_exp: p.total_exp as f64,
+            exp_per_hour: p.total_exp as f64 * 3600.0 / p.elapsed_seconds as f64,
+            sessions: 1,
+            estimating: p.elapsed_seconds < warmup_secs,
+        })
+        .collect()
+}
+
+/// Average rate curve of several sessions, one point per timeline interval
+/// Each point averages the sessions that lasted at least that long.
+pub fn baseline_curve(timelines: &[Timeline], warmup_secs: u64) -> Vec<RatePoint> {
+    let mut curve = Vec::new();
+    let mut elapsed_seconds = TIMELINE_INTERVAL_SECS;
+
+    loop {
+        let values: Vec<f64> = timelines.iter().filter_map(|t| t.exp_at(elapsed_seconds)).collect();
+        if values.is_empty() {
+            return curve;
+        }
+
+        let total_exp = values.iter().sum::<f64>() / values.len() as f64;
+        curve.push(RatePoint {
+            elapsed_seconds,
+            total_exp,
+            exp_per_hour: total_exp * 3600.0 / elapsed_seconds as f64,
+            sessions: values.len(),
+            estimating: elapsed_seconds < warmup_secs,
+        });
+        elapsed_seconds += TIMELINE_INTERVAL_SECS;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timeline(points: &[(u64, u64)]) -> Timeline {
+        Timeline {
+            session_id: "1".to_string(),
+            timestamp: 0,
+            map_name: None,
+            points: points.iter()
+                .map(|&(elapsed_seconds, total_exp)| TimelinePoint { elapsed_seconds, total_exp, latency_ms: None, recorded_at: None })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_exp_at_interpolates() {
+        let timeline = timeline(&[(0, 0), (30, 300), (60, 900)]);
+
+        assert_eq!(timeline.exp_at(0), Some(0.0));
+        assert_eq!(timeline.exp_at(45), Some(600.0));
+        assert_eq!(timeline.exp_at(60), Some(900.0));
+        assert_eq!(timeline.exp_at(61), None);
+    }
+
+    #[test]
+    fn test_baseline_averages_sessions_that_lasted() {
+        let short = timeline(&[(0, 0), (30, 300)]);
+        let long = timeline(&[(0, 0), (30, 900), (60, 1800)]);
+
+        let curve = baseline_curve(&[short, long], 60);
+        assert_eq!(curve.len(), 2);
+        assert_eq!((curve[0].total_exp, curve[0].sessions), (600.0, 2));
+        assert_eq!(curve[0].exp_per_hour, 72_000.0);
+        assert_eq!((curve[1].total_exp, curve[1].sessions), (1800.0, 1));
+        assert_eq!((curve[0].estimating, curve[1].estimating), (true, false));
+    }
+
+    #[test]
+    fn test_split_and_concat() {
+        let original = timeline(&[(0, 0), (30, 300), (60, 900), (90, 1200)]);
+
+        let (first, second) = original.split_at(45);
+        let pairs = |points: &[TimelinePoint]| -> Vec<(u64, u64)> {
+            points.iter().map(|p| (p.elapsed_seconds, p.total_exp)).collect()
+        };
+        assert_eq!(pairs(&first), vec![(0, 0), (30, 300), (45, 600)]);
+        assert_eq!(pairs(&second), vec![(0, 0), (15, 300), (45, 600)]);
+
+        let parts = [timeline(&pairs(&first)), timeline(&pairs(&second))];
+        assert_eq!(pairs(&Timeline::concat(&parts)), vec![(0, 0), (30, 300), (45, 600), (60, 900), (90, 1200)]);
+    }
+
+    #[test]
+    fn test_elapsed_at_skips_untracked_time() {
+        // 60s tracked, stopped for 10 minutes after the first 30s
+        let start = 1_700_000_000_000;
+        let mut tracked = timeline(&[(0, 0), (30, 300), (60, 900)]);
+        for (point, recorded_at) in tracked.points.iter_mut().zip([start, start + 30_000, start + 660_000]) {
+            point.recorded_at = Some(recorded_at);
+        }
+
+        assert_eq!(tracked.elapsed_at(start - 1), None);
+        assert_eq!(tracked.elapsed_at(start + 15_000), Some(15));
+        assert_eq!(tracked.elapsed_at(start + 300_000), Some(30));
+        assert_eq!(tracked.elapsed_at(start + 650_000), Some(50));
+        assert_eq!(tracked.elapsed_at(start + 670_000), Some(70));
+        assert_eq!(timeline(&[(0, 0), (30, 300)]).elapsed_at(start), None);
+    }
+}
@@ -0,0 +1,394 @@
+use crate::models::alert::{AlertAction, AlertCondition, AlertRule, Comparison};
+use crate::services::config::ConfigManager;
+use crate::services::event_log;
+use crate::services::ocr_tracker::TrackingStats;
+use crate::services::tracker_actor::TrackerHandle;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+/// How often rules are evaluated against the latest stats
+pub const ALERT_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Webhook requests give up after this long
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Payload of every alert event and webhook
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AlertFired {
+    pub rule_id: String,
+    pub name: String,
+    pub stat: String,
+    /// Stat value (threshold/stale) or rate per minute (rate of change)
+    pub value: f64,
+    pub message: String,
+    pub timestamp: i64, // Unix timestamp in milliseconds (UTC)
+}
+
+/// Value of any stat a rule can refer to
+pub fn stat_value(stats: &TrackingStats, stat: &str) -> Option<f64> {
+    stats.variable(stat).or_else(|| stats.derived_metrics.get(stat).copied())
+}
+
+/// Stats that are only estimates while `TrackingStats::rates_estimating`
+const RATE_STATS: [&str; 2] = ["exp_per_hour", "percentage_per_hour"];
+
+/// Recent samples of one stat
+struct StatHistory {
+    samples: VecDeque<(Instant, f64)>,
+    changed_at: Instant,
+}
+
+/// Evaluates alert rules; rules fire once when their condition becomes true
+/// and again only after it cleared and the cooldown passed
+#[derive(Default)]
+pub struct AlertEngine {
+    history: HashMap<String, StatHistory>,
+    active: HashSet<String>,
+    last_fired: HashMap<String, Instant>,
+}
+
+impl AlertEngine {
+    /// Feed the latest stats and return the rules that fired
+    pub fn evaluate(&mut self, rules: &[AlertRule], stats: &TrackingStats, now: Instant) -> Vec<(AlertRule, AlertFired)> {
+        // Alerts only make sense while tracking; start fresh on the next session
+        if !stats.is_tracking {
+            self.history.clear();
+            self.active.clear();
+            return Vec::new();
+        }
+
+        // Warm-up rates swing wildly; rules on them wait until they settle
+        let rules: Vec<&AlertRule> = rules.iter()
+            .filter(|rule| rule.enabled)
+            .filter(|rule| !(stats.rates_estimating && RATE_STATS.contains(&rule.condition.stat())))
+            .collect();
+        self.record_samples(&rules, stats, now);
+
+        let mut fired = Vec::new();
+        for rule in rules {
+            let Some(value) = self.check(&rule.condition, stats, now) else {
+                self.active.remove(&rule.id);
+                continue;
+            };
+
+            let cooled_down = self.last_fired.get(&rule.id)
+                .is_none_or(|last| now.duration_since(*last) >= Duration::from_secs(rule.cooldown_secs));
+            // Still held from the last alert, or back within the cooldown
+            // (stays inactive so it fires once the cooldown passes)
+            if self.active.contains(&rule.id) || !cooled_down {
+                continue;
+            }
+
+            self.active.insert(rule.id.clone());
+            self.last_fired.insert(rule.id.clone(), now);
+            fired.push((rule.clone(), AlertFired {
+                rule_id: rule.id.clone(),
+                name: rule.name.clone(),
+                stat: rule.condition.stat().to_string(),
+                value,
+                message: describe(rule, value),
+                timestamp: chrono::Utc::now().timestamp_millis(),
+            }));
+        }
+
+        fired
+    }
+
+    fn record_samples(&mut self, rules: &[&AlertRule], stats: &TrackingStats, now: Instant) {
+        // Keep enough history for the longest rate window of each stat
+        let mut retention: HashMap<&str, Duration> = HashMap::new();
+        for rule in rules {
+            let window = match &rule.condition {
+                AlertCondition::RateOfChange { window_secs, .. } => Duration::from_secs(*window_secs),
+                _ => Duration::ZERO,
+            };
+            let keep = retention.entry(rule.condition.stat()).or_default();
+            *keep = (*keep).max(window + ALERT_CHECK_INTERVAL * 2);
+        }
+
+        self.history.retain(|stat, _| retention.contains_key(stat.as_str()));
+
+        for (stat, keep) in retention {
+            let Some(value) = stat_value(stats, stat) else {
+                continue;
+            };
+
+            let history = self.history.entry(stat.to_string()).or_insert_with(|| StatHistory {
+                samples: VecDeque::new(),
+                changed_at: now,
+            });
+            if history.samples.back().is_some_and(|(_, last)| *last != value) {
+                history.changed_at = now;
+            }
+            history.samples.push_back((now, value));
+            while history.samples.len() > 1
+                && history.samples.front().is_some_and(|(at, _)| now.duration_since(*at) > keep)
+            {
+                history.samples.pop_front();
+            }
+        }
+    }
+
+    /// Value to report if the condition currently holds
+    fn check(&self, condition: &AlertCondition, stats: &TrackingStats, now: Instant) -> Option<f64> {
+        match condition {
+            AlertCondition::Threshold { stat, comparison, value: limit } => {
+                stat_value(stats, stat).filter(|value| comparison.matches(*value, *limit))
+            }
+            AlertCondition::RateOfChange { stat, comparison, per_minute, window_secs } => {
+                let samples = &self.history.get(stat)?.samples;
+                let window = Duration::from_secs(*window_secs);
+
+                // Need a sample at least one full window old
+                let (then, old) = samples.iter().rev().find(|(at, _)| now.duration_since(*at) >= window)?;
+                let (_, latest) = samples.back()?;
+                let minutes = now.duration_since(*then).as_secs_f64() / 60.0;
+                let rate = (latest - old) / minutes;
+
+                comparison.matches(rate, *per_minute).then_some(rate)
+            }
+            AlertCondition::Stale { stat, seconds } => {
+                let history = self.history.get(stat)?;
+                if now.duration_since(history.changed_at) < Duration::from_secs(*seconds) {
+                    return None;
+                }
+                history.samples.back().map(|(_, value)| *value)
+            }
+        }
+    }
+}
+
+fn describe(rule: &AlertRule, value: f64) -> String {
+    let value = format!("{:.2}", value);
+    match &rule.condition {
+        AlertCondition::Threshold { stat, comparison, value: limit } => {
+            let comparison = match comparison {
+                Comparison::Above => "above",
+                Comparison::Below => "below",
+            };
+            format!("{}: {} is {} {} ({})", rule.name, stat, comparison, limit, value)
+        }
+        AlertCondition::RateOfChange { stat, per_minute, .. } => {
+            format!("{}: {} changing {}/min (limit {})", rule.name, stat, value, per_minute)
+        }
+        AlertCondition::Stale { stat, seconds } => {
+            format!("{}: {} unchanged for {}s", rule.name, stat, seconds)
+        }
+    }
+}
+
+/// Run the actions of a fired rule
+fn run_actions(app: &AppHandle, client: &reqwest::Client, rule: &AlertRule, alert: &AlertFired, sounds_enabled: bool) {
+    println!("🔔 Alert: {}", alert.message);
+
+    for action in &rule.actions {
+        let result = match action {
+            AlertAction::Event => event_log::emit(app, "alert:triggered", alert),
+            AlertAction::Notification => event_log::emit(app, "alert:notification", alert),
+            AlertAction::Sound { sound } => {
+                if !sounds_enabled {
+                    continue;
+                }
+                event_log::emit(app, "alert:sound", serde_json::json!({ "alert": alert, "sound": sound }))
+            }
+            AlertAction::Webhook { url } => {
+                let request = client.post(url).timeout(WEBHOOK_TIMEOUT).json(alert);
+                let url = url.clone();
+                tauri::async_runtime::spawn(async move {
+                    match request.send().await {
+                        Ok(response) if !response.status().is_success() => {
+                            eprintln!("⚠️ Alert webhook {} returned {}", url, response.status());
+                        }
+                        Err(e) => eprintln!("⚠️ Alert webhook {} failed: {}", url, e),
+                        Ok(_) => {}
+                    }
+                });
+                Ok(())
+            }
+        };
+
+        if let Err(e) = result {
+            eprintln!("Failed to emit alert event: {}", e);
+        }
+    }
+}
+
+/// Evaluate the configured alert rules for the lifetime of the app
+pub fn spawn_alert_loop(app: AppHandle, tracker: TrackerHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut engine = AlertEngine::default();
+        let client = reqwest::Client::new();
+        // Config revision the cached rules were loaded at
+        let mut loaded_revision = None;
+        let mut rules: Vec<AlertRule> = Vec::new();
+        let mut sounds_enabled = false;
+
+        loop {
+            tokio::time::sleep(ALERT_CHECK_INTERVAL).await;
+
+            // Rules are reloaded after every config save so CRUD changes apply immediately
+            if let Some(config_state) = app.try_state::<std::sync::Mutex<ConfigManager>>() {
+                if let Ok(manager) = config_state.lock() {
+                    let revision = manager.revision();
+                    if loaded_revision != Some(revision) {
+                        let config = manager.load().unwrap_or_default();
+                        rules = config.alerts;
+                        sounds_enabled = config.audio.enable_sounds;
+                        loaded_revision = Some(revision);
+                    }
+                }
+            }
+            if rules.is_empty() {
+                continue;
+            }
+
+            for (rule, alert) in engine.evaluate(&rules, &tracker.stats(), std::time::Instant::now()) {
+                run_actions(&app, &client, &rule, &alert, sounds_enabled);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::custom_metric::MetricValue;
+    use std::collections::BTreeMap;
+
+    fn stats(hp_potion_count: Option<u32>, exp: Option<u64>) -> TrackingStats {
+        TrackingStats {
+            level: Some(100),
+            exp,
+            percentage: None,
+            hp_potion_count,
+            mp_potion_count: None,
+            total_exp: 0,
+            total_percentage: 0.0,
+            elapsed_seconds: 0,
+            exp_per_hour: 0,
+            percentage_per_hour: 0.0,
+            rates_estimating: false,
+            is_tracking: true,
+            resumed: false,
+            error: None,
+            hp_potions_used: 0,
+            mp_potions_used: 0,
+            hp_potions_per_minute: 0.0,
+            mp_potions_per_minute: 0.0,
+            ocr_server_healthy: true,
+            custom_metrics: BTreeMap::new(),
+            derived_metrics: BTreeMap::new(),
+            layout: None,
+        }
+    }
+
+    fn rule(id: &str, condition: AlertCondition) -> AlertRule {
+        AlertRule {
+            id: id.to_string(),
+            name: id.to_string(),
+            enabled: true,
+            condition,
+            actions: vec![AlertAction::Event],
+            cooldown_secs: 0,
+        }
+    }
+
+    #[test]
+    fn test_threshold_fires_once_until_cleared() {
+        let rules = [rule("low-hp", AlertCondition::Threshold {
+            stat: "hp_potion_count".to_string(),
+            comparison: Comparison::Below,
+            value: 50.0,
+        })];
+        let mut engine = AlertEngine::default();
+        let start = Instant::now();
+
+        assert!(engine.evaluate(&rules, &stats(Some(100), None), start).is_empty());
+        assert_eq!(engine.evaluate(&rules, &stats(Some(40), None), start + Duration::from_secs(1)).len(), 1);
+        assert!(engine.evaluate(&rules, &stats(Some(30), None), start + Duration::from_secs(2)).is_empty());
+
+        // Refilled, then low again
+        engine.evaluate(&rules, &stats(Some(500), None), start + Duration::from_secs(3));
+        assert_eq!(engine.evaluate(&rules, &stats(Some(10), None), start + Duration::from_secs(4)).len(), 1);
+    }
+
+    #[test]
+    fn test_condition_back_during_cooldown_fires_after_it() {
+        let mut low_hp = rule("low-hp", AlertCondition::Threshold {
+            stat: "hp_potion_count".to_string(),
+            comparison: Comparison::Below,
+            value: 50.0,
+        });
+        low_hp.cooldown_secs = 60;
+        let rules = [low_hp];
+        let mut engine = AlertEngine::default();
+        let start = Instant::now();
+
+        assert_eq!(engine.evaluate(&rules, &stats(Some(40), None), start).len(), 1);
+        engine.evaluate(&rules, &stats(Some(500), None), start + Duration::from_secs(10));
+
+        // Low again within the cooldown, and stays low
+        assert!(engine.evaluate(&rules, &stats(Some(30), None), start + Duration::from_secs(20)).is_empty());
+        assert!(engine.evaluate(&rules, &stats(Some(30), None), start + Duration::from_secs(59)).is_empty());
+        assert_eq!(engine.evaluate(&rules, &stats(Some(30), None), start + Duration::from_secs(60)).len(), 1);
+        assert!(engine.evaluate(&rules, &stats(Some(30), None), start + Duration::from_secs(200)).is_empty());
+    }
+
+    #[test]
+    fn test_stale_and_rate_of_change() {
+        let rules = [
+            rule("exp-stuck", AlertCondition::Stale { stat: "exp".to_string(), seconds: 60 }),
+            rule("fast-exp", AlertCondition::RateOfChange {
+                stat: "exp".to_string(),
+                comparison: Comparison::Above,
+                per_minute: 1000.0,
+                window_secs: 60,
+            }),
+        ];
+        let mut engine = AlertEngine::default();
+        let start = Instant::now();
+
+        engine.evaluate(&rules, &stats(None, Some(1000)), start);
+        let fired = engine.evaluate(&rules, &stats(None, Some(4000)), start + Duration::from_secs(60));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].1.rule_id, "fast-exp");
+        assert_eq!(fired[0].1.value, 3000.0);
+
+        let fired = engine.evaluate(&rules, &stats(None, Some(4000)), start + Duration::from_secs(125));
+        assert!(fired.iter().any(|(_, alert)| alert.rule_id == "exp-stuck"));
+    }
+
+    #[test]
+    fn test_rate_rules_wait_for_warmup() {
+        let rules = [rule("slow", AlertCondition::Threshold {
+            stat: "exp_per_hour".to_string(),
+            comparison: Comparison::Below,
+            value: 1000.0,
+        })];
+        let mut engine = AlertEngine::default();
+        let start = Instant::now();
+
+        let mut current = stats(None, Some(1000));
+        current.rates_estimating = true;
+        assert!(engine.evaluate(&rules, &current, start).is_empty());
+
+        current.rates_estimating = false;
+        assert_eq!(engine.evaluate(&rules, &current, start + Duration::from_secs(1)).len(), 1);
+    }
+
+    #[test]
+    fn test_rules_can_use_custom_metrics() {
+        let rules = [rule("fame", AlertCondition::Threshold {
+            stat: "fame".to_string(),
+            comparison: Comparison::Above,
+            value: 10.0,
+        })];
+        let mut current = stats(None, None);
+        current.custom_metrics.insert("fame".to_string(), MetricValue::Integer(11));
+
+        let fired = AlertEngine::default().evaluate(&rules, &current, Instant::now());
+        assert_eq!(fired.len(), 1);
+    }
+}
@@ -0,0 +1,144 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// Maximum number of idle buffers kept for reuse
+const MAX_POOLED_BUFFERS: usize = 8;
+
+/// Diagnostic counters for the buffer pool
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct BufferPoolStats {
+    /// Buffers that had to be freshly allocated (or grown)
+    pub allocations: u64,
+    /// Buffers served from the pool without allocating
+    pub reuses: u64,
+    /// Idle buffers currently held by the pool
+    pub pooled: usize,
+}
+
+/// Pool of byte buffers for per-cycle image processing
+///
+/// Frames have the same size every tracking cycle, so returning buffers after
+/// use lets the next cycle skip the allocation entirely.
+pub struct BufferPool {
+    free: Mutex<Vec<Vec<u8>>>,
+    allocations: AtomicU64,
+    reuses: AtomicU64,
+}
+
+impl BufferPool {
+    pub fn new() -> Self {
+        Self {
+            free: Mutex::new(Vec::new()),
+            allocations: AtomicU64::new(0),
+            reuses: AtomicU64::new(0),
+        }
+    }
+
+    /// Take a zero-filled buffer of exactly `len` bytes
+    pub fn take(&self, len: usize) -> Vec<u8> {
+        let pooled = match self.free.lock() {
+            Ok(mut free) => {
+                // Prefer a buffer that is already large enough
+                match free.iter().position(|buf| buf.capacity() >= len) {
+                    Some(index) => Some(free.swap_remove(index)),
+                    None => free.pop(),
+                }
+            }
+            Err(_) => None,
+        };
+
+        let mut buffer = match pooled {
+            Some(buffer) if buffer.capacity() >= len => {
+                self.reuses.fetch_add(1, Ordering::Relaxed);
+                buffer
+            }
+            other => {
+                self.allocations.fetch_add(1, Ordering::Relaxed);
+                let mut buffer = other.unwrap_or_default();
+                buffer.clear();
+                buffer.reserve_exact(len);
+                buffer
+            }
+        };
+
+        buffer.clear();
+        buffer.resize(len, 0);
+        buffer
+    }
+
+    /// Return a buffer for reuse
+    pub fn give(&self, buffer: Vec<u8>) {
+        if let Ok(mut free) = self.free.lock() {
+            if free.len() < MAX_POOLED_BUFFERS {
+                free.push(buffer);
+            }
+        }
+    }
+
+    /// Copy `source` into `target`, reusing its capacity when possible
+    pub fn copy_into(&self, target: &mut Vec<u8>, source: &[u8]) {
+        if target.capacity() >= source.len() {
+            self.reuses.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+        }
+        target.clear();
+        target.extend_from_slice(source);
+    }
+
+    pub fn stats(&self) -> BufferPoolStats {
+        BufferPoolStats {
+            allocations: self.allocations.load(Ordering::Relaxed),
+            reuses: self.reuses.load(Ordering::Relaxed),
+            pooled: self.free.lock().map(|free| free.len()).unwrap_or(0),
+        }
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Process-wide pool shared by the capture and preprocessing path
+pub fn global() -> &'static BufferPool {
+    static POOL: OnceLock<BufferPool> = OnceLock::new();
+    POOL.get_or_init(BufferPool::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_returned_buffer_is_reused() {
+        let pool = BufferPool::new();
+
+        let buffer = pool.take(1024);
+        assert_eq!(buffer.len(), 1024);
+        pool.give(buffer);
+
+        let buffer = pool.take(512);
+        assert_eq!(buffer.len(), 512);
+        assert!(buffer.iter().all(|&b| b == 0));
+
+        let stats = pool.stats();
+        assert_eq!(stats.allocations, 1);
+        assert_eq!(stats.reuses, 1);
+    }
+
+    #[test]
+    fn test_copy_into_counts_growth() {
+        let pool = BufferPool::new();
+        let mut target = Vec::new();
+
+        pool.copy_into(&mut target, &[1, 2, 3]);
+        pool.copy_into(&mut target, &[4, 5]);
+
+        assert_eq!(target, vec![4, 5]);
+        assert_eq!(pool.stats().allocations, 1);
+        assert_eq!(pool.stats().reuses, 1);
+    }
+}
@@ -1,11 +1,24 @@
 use crate::models::config::AppConfig;
+use serde::Serialize;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Payload for "config:recovered": an unreadable config was replaced by defaults
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigRecovery {
+    /// Where the unreadable file was moved
+    pub backup_path: String,
+    /// Parse error of the original file
+    pub reason: String,
+}
 
 /// Configuration manager for app settings
 pub struct ConfigManager {
     config_dir: PathBuf,
     config_path: PathBuf,
+    /// Bumped on every save, so readers can cache the config between saves
+    revision: AtomicU64,
 }
 
 impl ConfigManager {
@@ -28,9 +41,21 @@ impl ConfigManager {
         Ok(Self {
             config_dir,
             config_path,
+            revision: AtomicU64::new(0),
         })
     }
 
+    /// Manager for a specific directory (created on first save)
+    /// Used as a fallback when the platform config directory is unusable
+    pub fn in_dir(config_dir: PathBuf) -> Self {
+        let config_path = config_dir.join("config.json");
+        Self {
+            config_dir,
+            config_path,
+            revision: AtomicU64::new(0),
+        }
+    }
+
     /// Save configuration to disk
     pub fn save(&self, config: &AppConfig) -> Result<(), String> {
         // Ensure config directory exists
@@ -45,9 +70,15 @@ impl ConfigManager {
         fs::write(&self.config_path, json)
             .map_err(|e| format!("Failed to write config file: {}", e))?;
 
+        self.revision.fetch_add(1, Ordering::SeqCst);
         Ok(())
     }
 
+    /// Number of saves through this manager; changes whenever the config may have
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::SeqCst)
+    }
+
     /// Load configuration from disk
     ///
     /// If config file doesn't exist, returns default configuration
@@ -68,6 +99,40 @@ impl ConfigManager {
         Ok(config)
     }
 
+    /// Replace an unparsable config file with defaults
+    ///
+    /// The bad file is renamed to `config.corrupt-<timestamp>.json` so nothing
+    /// the user wrote is lost. Returns None when the file is fine or missing;
+    /// I/O errors are still returned as errors.
+    pub fn recover_if_corrupt(&self) -> Result<Option<ConfigRecovery>, String> {
+        if !self.config_exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&self.config_path)
+            .map_err(|e| format!("Failed to read config file: {}", e))?;
+
+        let reason = match serde_json::from_str::<AppConfig>(&content) {
+            Ok(_) => return Ok(None),
+            Err(e) => e.to_string(),
+        };
+
+        let backup_path = self.config_dir.join(format!(
+            "config.corrupt-{}.json",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        fs::rename(&self.config_path, &backup_path)
+            .map_err(|e| format!("Failed to move corrupt config aside: {}", e))?;
+
+        self.save(&AppConfig::default())?;
+
+        eprintln!("⚠️ Config file was corrupt ({}), restored defaults. Backup: {}", reason, backup_path.display());
+        Ok(Some(ConfigRecovery {
+            backup_path: backup_path.to_string_lossy().to_string(),
+            reason,
+        }))
+    }
+
     /// Get the config file path
     pub fn config_file_path(&self) -> &PathBuf {
         &self.config_path
@@ -99,6 +164,7 @@ mod tests {
         ConfigManager {
             config_dir: temp_dir.clone(),
             config_path: temp_dir.join("config.json"),
+            revision: AtomicU64::new(0),
         }
     }
 
@@ -282,4 +348,31 @@ mod tests {
 
         cleanup_test_files(&manager);
     }
+
+    #[test]
+    fn test_save_bumps_revision() {
+        let manager = create_test_manager();
+        assert_eq!(manager.revision(), 0);
+
+        manager.save(&AppConfig::default()).unwrap();
+        manager.save(&AppConfig::default()).unwrap();
+        assert_eq!(manager.revision(), 2);
+
+        cleanup_test_files(&manager);
+    }
+
+    #[test]
+    fn test_corrupt_config_is_moved_aside() {
+        let manager = create_test_manager();
+        fs::create_dir_all(&manager.config_dir).unwrap();
+        fs::write(&manager.config_path, "{ not json").unwrap();
+
+        let recovery = manager.recover_if_corrupt().unwrap().expect("should recover");
+
+        assert_eq!(fs::read_to_string(&recovery.backup_path).unwrap(), "{ not json");
+        assert_eq!(manager.load().unwrap(), AppConfig::default());
+        assert!(manager.recover_if_corrupt().unwrap().is_none());
+
+        cleanup_test_files(&manager);
+    }
 }
@@ -0,0 +1,298 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Events kept for windows that open mid-session
+pub const REPLAY_CAPACITY: usize = 500;
+
+/// A tracking event as it was sent to the frontend
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RecordedEvent {
+    /// Increasing sequence number; pass the last one seen to get_recent_events
+    pub seq: u64,
+    pub timestamp: i64, // Unix timestamp in milliseconds (UTC)
+    pub event: String,
+    pub payload: serde_json::Value,
+}
+
+/// Bounded replay buffer of recent events (oldest dropped first)
+pub struct EventLog {
+    events: VecDeque<RecordedEvent>,
+    next_seq: u64,
+    capacity: usize,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(REPLAY_CAPACITY)
+    }
+}
+
+impl EventLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            next_seq: 1,
+            capacity,
+        }
+    }
+
+    /// Record an event and return its sequence number
+    pub fn push(&mut self, event: &str, payload: serde_json::Value) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        if self.events.len() >= self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(RecordedEvent {
+            seq,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            event: event.to_string(),
+            payload,
+        });
+
+        seq
+    }
+
+    /// Events after `since` (all buffered events for None), oldest first
+    pub fn since(&self, since: Option<u64>) -> Vec<RecordedEvent> {
+        let since = since.unwrap_or(0);
+        self.events.iter().filter(|e| e.seq > since).cloned().collect()
+    }
+}
+
+pub type EventLogState = std::sync::Mutex<EventLog>;
+
+/// What to do with an event offered to the throttle
+#[derive(Debug, PartialEq)]
+pub enum Throttled {
+    /// Interval has passed, send now
+    Send(serde_json::Value),
+    /// Held as the pending value; flush it after this long
+    Hold(Duration),
+    /// Replaced the pending value, whose flush is already scheduled
+    Coalesced,
+}
+
+/// Last emission and pending (coalesced) payload of one event
+struct ThrottleSlot {
+    last_sent: Instant,
+    pending: Option<serde_json::Value>,
+}
+
+/// Per-event minimum intervals with latest-value coalescing
+pub struct EventThrottle {
+    intervals: BTreeMap<String, Duration>,
+    slots: BTreeMap<String, ThrottleSlot>,
+}
+
+impl EventThrottle {
+    pub const fn new() -> Self {
+        Self {
+            intervals: BTreeMap::new(),
+            slots: BTreeMap::new(),
+        }
+    }
+
+    /// Replace the intervals (milliseconds by event name; 0 disables throttling)
+    pub fn configure(&mut self, intervals_ms: &BTreeMap<String, u64>) {
+        self.intervals = intervals_ms
+            .iter()
+            .filter(|(_, &ms)| ms > 0)
+            .map(|(event, &ms)| (event.clone(), Duration::from_millis(ms)))
+            .collect();
+        // A payload still pending goes out at its scheduled flush, even if its event
+        // is no longer throttled, so the final value isn't lost
+        let intervals = &self.intervals;
+        self.slots.retain(|event, slot| intervals.contains_key(event) || slot.pending.is_some());
+    }
+
+    /// Whether `event` has to go through `offer` (throttled, or a payload is still pending)
+    pub fn is_throttled(&self, event: &str) -> bool {
+        self.intervals.contains_key(event) || self.slots.contains_key(event)
+    }
+
+    pub fn offer(&mut self, event: &str, payload: serde_json::Value, now: Instant) -> Throttled {
+        let Some(&interval) = self.intervals.get(event) else {
+            // No longer throttled: this payload supersedes any still pending
+            self.slots.remove(event);
+            return Throttled::Send(payload);
+        };
+
+        let Some(slot) = self.slots.get_mut(event) else {
+            self.slots.insert(event.to_string(), ThrottleSlot { last_sent: now, pending: None });
+            return Throttled::Send(payload);
+        };
+
+        let since_last = now.saturating_duration_since(slot.last_sent);
+        if slot.pending.is_some() {
+            slot.pending = Some(payload);
+            Throttled::Coalesced
+        } else if since_last >= interval {
+            slot.last_sent = now;
+            Throttled::Send(payload)
+        } else {
+            slot.pending = Some(payload);
+            Throttled::Hold(interval - since_last)
+        }
+    }
+
+    /// Take the pending payload of `event` once its flush is due
+    pub fn flush(&mut self, event: &str, now: Instant) -> Option<serde_json::Value> {
+        let slot = self.slots.get_mut(event)?;
+        let pending = slot.pending.take()?;
+        slot.last_sent = now;
+        if !self.intervals.contains_key(event) {
+            self.slots.remove(event);
+        }
+        Some(pending)
+    }
+}
+
+impl Default for EventThrottle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static THROTTLE: Mutex<EventThrottle> = Mutex::new(EventThrottle::new());
+
+/// Apply the configured per-event minimum intervals (`advanced.event_intervals_ms`)
+pub fn configure_throttle(intervals_ms: &BTreeMap<String, u64>) {
+    if let Ok(mut throttle) = THROTTLE.lock() {
+        throttle.configure(intervals_ms);
+    }
+}
+
+/// Emit an event to all windows and keep a copy for replay
+///
+/// Events with a configured minimum interval go out at most once per interval;
+/// the latest payload offered in between is sent when the interval ends, so
+/// the final value always arrives.
+pub fn emit<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) -> tauri::Result<()> {
+    let throttled = THROTTLE.lock().map(|throttle| throttle.is_throttled(event)).unwrap_or(false);
+    if !throttled {
+        return send(app, event, payload);
+    }
+
+    let value = serde_json::to_value(&payload)?;
+    let decision = match THROTTLE.lock() {
+        Ok(mut throttle) => throttle.offer(event, value, Instant::now()),
+        Err(_) => Throttled::Send(value),
+    };
+
+    match decision {
+        Throttled::Send(value) => send(app, event, value),
+        Throttled::Hold(delay) => {
+            let app = app.clone();
+            let event = event.to_string();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(delay).await;
+                let pending = THROTTLE.lock().ok().and_then(|mut throttle| throttle.flush(&event, Instant::now()));
+                if let Some(value) = pending {
+                    if let Err(e) = send(&app, &event, value) {
+                        eprintln!("Failed to emit {}: {}", event, e);
+                    }
+                }
+            });
+            Ok(())
+        }
+        Throttled::Coalesced => Ok(()),
+    }
+}
+
+/// Send an event now and record it for replay
+fn send<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) -> tauri::Result<()> {
+    if let Some(log) = app.try_state::<EventLogState>() {
+        if let (Ok(value), Ok(mut log)) = (serde_json::to_value(&payload), log.lock()) {
+            log.push(event, value);
+        }
+    }
+
+    app.emit(event, payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_since_returns_newer_events_in_order() {
+        let mut log = EventLog::new(10);
+        let first = log.push("ocr:level-update", json!({ "level": 120 }));
+        log.push("ocr:exp-update", json!({ "exp": 1000, "percentage": 10.5 }));
+
+        let all = log.since(None);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].event, "ocr:level-update");
+
+        let newer = log.since(Some(first));
+        assert_eq!(newer.len(), 1);
+        assert_eq!(newer[0].event, "ocr:exp-update");
+        assert!(log.since(Some(newer[0].seq)).is_empty());
+    }
+
+    #[test]
+    fn test_throttle_coalesces_to_latest() {
+        let mut throttle = EventThrottle::new();
+        throttle.configure(&BTreeMap::from([("ocr:exp-update".to_string(), 250)]));
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert_eq!(throttle.offer("ocr:exp-update", json!(1), at(0)), Throttled::Send(json!(1)));
+        assert_eq!(throttle.offer("ocr:exp-update", json!(2), at(100)), Throttled::Hold(Duration::from_millis(150)));
+        assert_eq!(throttle.offer("ocr:exp-update", json!(3), at(200)), Throttled::Coalesced);
+        assert_eq!(throttle.flush("ocr:exp-update", at(250)), Some(json!(3)));
+        assert_eq!(throttle.flush("ocr:exp-update", at(260)), None);
+
+        // Interval restarts at the flush
+        assert_eq!(throttle.offer("ocr:exp-update", json!(4), at(400)), Throttled::Hold(Duration::from_millis(100)));
+        assert_eq!(throttle.offer("ocr:exp-update", json!(5), at(600)), Throttled::Coalesced);
+
+        // Other events pass straight through
+        assert_eq!(throttle.offer("ocr:level-update", json!(6), at(600)), Throttled::Send(json!(6)));
+        assert!(!throttle.is_throttled("ocr:level-update"));
+    }
+
+    #[test]
+    fn test_reconfigure_keeps_pending_payload() {
+        let mut throttle = EventThrottle::new();
+        let intervals = BTreeMap::from([("ocr:exp-update".to_string(), 250)]);
+        throttle.configure(&intervals);
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        // Throttling turned off while a payload waits: it still flushes, once
+        throttle.offer("ocr:exp-update", json!(1), at(0));
+        assert_eq!(throttle.offer("ocr:exp-update", json!(2), at(100)), Throttled::Hold(Duration::from_millis(150)));
+        throttle.configure(&BTreeMap::new());
+        assert!(throttle.is_throttled("ocr:exp-update"));
+        assert_eq!(throttle.flush("ocr:exp-update", at(250)), Some(json!(2)));
+        assert!(!throttle.is_throttled("ocr:exp-update"));
+
+        // A newer payload sent meanwhile wins over the pending one
+        throttle.configure(&intervals);
+        throttle.offer("ocr:exp-update", json!(3), at(300));
+        throttle.offer("ocr:exp-update", json!(4), at(400));
+        throttle.configure(&BTreeMap::new());
+        assert_eq!(throttle.offer("ocr:exp-update", json!(5), at(450)), Throttled::Send(json!(5)));
+        assert_eq!(throttle.flush("ocr:exp-update", at(550)), None);
+    }
+
+    #[test]
+    fn test_buffer_drops_oldest_beyond_capacity() {
+        let mut log = EventLog::new(3);
+        for level in 0..5 {
+            log.push("ocr:level-update", json!({ "level": level }));
+        }
+
+        let events = log.since(None);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].payload, json!({ "level": 2 }));
+        assert_eq!(events[2].seq, 5);
+    }
+}
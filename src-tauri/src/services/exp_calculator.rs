@@ -41,7 +41,7 @@ impl ExpCalculator {
     }
 
     /// Update with new data and calculate statistics
-    pub fn update(&mut self, data: ExpData) -> Result<ExpStats, String> {
+    pub fn update(&mut self, mut data: ExpData) -> Result<ExpStats, String> {
         let initial = self
             .initial_data
             .as_ref()
@@ -104,11 +104,11 @@ impl ExpCalculator {
             // This ensures if we go 129 (99%) -> 130 (1%), we gain that 1% + the missing 1% of 129.
             // Note: We rely on data.exp being "fresh" (starting from 0 or low value).
             // If user connects late (130 | 50%), we treat that 50% as gained this session if we just leveled up.
-            let total_transition_gain = exp_gained_from_prev_level + data.exp;
+            let total_transition_gain = exp_gained_from_prev_level.saturating_add(data.exp);
 
-            self.completed_levels_exp += total_transition_gain;
+            self.completed_levels_exp = self.completed_levels_exp.saturating_add(total_transition_gain);
 
-            let percentage_gained = 100.0 - initial.percentage;
+            let percentage_gained = (100.0 - initial.percentage).max(0.0);
             self.completed_levels_percentage += percentage_gained;
 
             // Reset initial data for new level -> It effectively starts "now" with the current data
@@ -134,9 +134,15 @@ impl ExpCalculator {
         // If we just leveled up, initial.exp == data.exp, so exp_diff is 0.
         // The gain was already added to `completed_levels_exp`.
         let exp_diff = data.exp.saturating_sub(initial.exp);
-        let total_exp = exp_diff + self.completed_levels_exp;
+        let total_exp = exp_diff.saturating_add(self.completed_levels_exp);
+        // Percentage can't go down while EXP doesn't (OCR wobble); the held value
+        // is kept in `last_data` so the next reading compares against it
+        if data.level == last.level && data.exp == last.exp {
+            data.percentage = data.percentage.max(last.percentage);
+        }
+        // Rounded to the 2 decimals the client shows so repeated f64 sums don't drift
         let percentage_diff = data.percentage - initial.percentage;
-        let total_percentage = percentage_diff + self.completed_levels_percentage;
+        let total_percentage = round_percentage(percentage_diff + self.completed_levels_percentage).max(0.0);
 
         let total_meso = data
             .meso
@@ -144,31 +150,13 @@ impl ExpCalculator {
             .saturating_sub(initial.meso.unwrap_or(0));
 
         // Calculate elapsed time
-        let elapsed = self
-            .start_time
-            .ok_or("Start time not set")?
-            .elapsed()
-            .saturating_sub(self.paused_duration);
+        let elapsed = self.elapsed().ok_or("Start time not set")?;
         let elapsed_seconds = elapsed.as_secs();
 
         // Calculate hourly averages
-        let exp_per_hour = if elapsed_seconds > 0 {
-            (total_exp * 3600) / elapsed_seconds
-        } else {
-            0
-        };
-
-        let percentage_per_hour = if elapsed_seconds > 0 {
-            (total_percentage * 3600.0) / elapsed_seconds as f64
-        } else {
-            0.0
-        };
+        let (exp_per_hour, percentage_per_hour) = hourly_rates(total_exp, total_percentage, elapsed_seconds);
 
-        let meso_per_hour = if elapsed_seconds > 0 {
-            (total_meso * 3600) / elapsed_seconds
-        } else {
-            0
-        };
+        let meso_per_hour = per_period(total_meso, 3600, elapsed_seconds);
 
         // Get current and start levels (before moving data)
         let current_level = data.level;
@@ -176,11 +164,7 @@ impl ExpCalculator {
         let levels_gained = current_level.saturating_sub(start_level);
 
         // Calculate per-minute average
-        let exp_per_minute = if elapsed_seconds > 0 {
-            (total_exp * 60) / elapsed_seconds
-        } else {
-            0
-        };
+        let exp_per_minute = per_period(total_exp, 60, elapsed_seconds);
 
         self.last_data = Some(data);
 
@@ -204,6 +188,16 @@ impl ExpCalculator {
         })
     }
 
+    /// Tracked time since `start`, without paused time; None before `start`
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.start_time.map(|start| start.elapsed().saturating_sub(self.paused_duration))
+    }
+
+    /// Exclude time during which nothing could be tracked (e.g. system sleep)
+    pub fn add_paused_time(&mut self, duration: Duration) {
+        self.paused_duration += duration;
+    }
+
     /// Reset calculator state
     pub fn reset(&mut self) {
         self.initial_data = None;
@@ -222,6 +216,31 @@ impl ExpCalculator {
     }
 }
 
+/// Round a percentage to the 2 decimals the game client displays
+pub fn round_percentage(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// EXP and percentage per hour over `elapsed_seconds`
+pub fn hourly_rates(total_exp: u64, total_percentage: f64, elapsed_seconds: u64) -> (u64, f64) {
+    let percentage_per_hour = if elapsed_seconds > 0 {
+        (total_percentage * 3600.0) / elapsed_seconds as f64
+    } else {
+        0.0
+    };
+    (per_period(total_exp, 3600, elapsed_seconds), percentage_per_hour)
+}
+
+/// Average of `total` per `period_secs` over `elapsed_secs` without overflowing
+fn per_period(total: u64, period_secs: u64, elapsed_secs: u64) -> u64 {
+    if elapsed_secs == 0 {
+        return 0;
+    }
+
+    let average = total as u128 * period_secs as u128 / elapsed_secs as u128;
+    average.min(u64::MAX as u128) as u64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -360,6 +379,24 @@ mod tests {
         assert_eq!(stats.exp_per_minute, 100);
     }
 
+    #[test]
+    fn test_paused_time_excluded_from_rates() {
+        let mut calculator = ExpCalculator::new().unwrap();
+
+        calculator.start(ExpData { level: 50, exp: 0, percentage: 0.0, meso: None });
+
+        // 20 minutes wall time, 10 of them asleep
+        calculator.start_time = Some(Instant::now() - Duration::from_secs(1200));
+        calculator.add_paused_time(Duration::from_secs(600));
+
+        let stats = calculator
+            .update(ExpData { level: 50, exp: 1000, percentage: 10.0, meso: None })
+            .unwrap();
+
+        assert_eq!(stats.elapsed_seconds, 600);
+        assert_eq!(stats.exp_per_hour, 6000);
+    }
+
     #[test]
     fn test_exp_per_minute_calculation() {
         let mut calculator = ExpCalculator::new().unwrap();
@@ -425,4 +462,48 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Calculator not started");
     }
+
+    #[test]
+    fn test_large_exp_does_not_overflow() {
+        let level_table = LevelExpTable::load()
+            .unwrap()
+            .with_levels(vec![(250, u64::MAX - 10)]);
+        let mut calculator = ExpCalculator::new().unwrap().with_level_table(level_table);
+
+        calculator.start(ExpData { level: 250, exp: 0, percentage: 0.0, meso: None });
+        calculator.start_time = Some(Instant::now() - Duration::from_secs(1));
+
+        let stats = calculator
+            .update(ExpData { level: 251, exp: u64::MAX / 2, percentage: 50.0, meso: None })
+            .unwrap();
+
+        assert_eq!(stats.total_exp, u64::MAX);
+        assert_eq!(stats.exp_per_hour, u64::MAX);
+    }
+
+    #[test]
+    fn test_percentage_wobble_never_goes_negative() {
+        let mut calculator = ExpCalculator::new().unwrap();
+
+        calculator.start(ExpData { level: 50, exp: 1000, percentage: 10.0, meso: None });
+
+        // Same EXP, percentage misread slightly lower
+        let stats = calculator
+            .update(ExpData { level: 50, exp: 1000, percentage: 9.99, meso: None })
+            .unwrap();
+        assert_eq!(stats.total_percentage, 0.0);
+
+        // A gain is kept while EXP stays put
+        calculator.update(ExpData { level: 50, exp: 1050, percentage: 10.2, meso: None }).unwrap();
+        let stats = calculator
+            .update(ExpData { level: 50, exp: 1050, percentage: 10.1, meso: None })
+            .unwrap();
+        assert_eq!(stats.total_percentage, 0.2);
+
+        // Repeated f64 sums stay on the 2-decimal grid
+        let stats = calculator
+            .update(ExpData { level: 50, exp: 1100, percentage: 10.3, meso: None })
+            .unwrap();
+        assert_eq!(stats.total_percentage, 0.3);
+    }
 }
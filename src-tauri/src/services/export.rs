@@ -0,0 +1,441 @@
+use crate::commands::session::SessionRecord;
+use crate::models::timeline::Timeline;
+use crate::services::sample_log::RawSample;
+use std::path::Path;
+
+/// Serializes session records into one file format
+///
+/// Adding a format means implementing this trait and listing it in `exporter_for_path`.
+pub trait Exporter {
+    /// File extension (lowercase, without the dot) this exporter is picked for
+    fn extension(&self) -> &'static str;
+
+    fn export(&self, records: &[SessionRecord]) -> Result<Vec<u8>, String>;
+
+    /// Serialize raw tracking samples (services/sample_log.rs)
+    fn export_samples(&self, _samples: &[RawSample]) -> Result<Vec<u8>, String> {
+        Err(format!("Raw samples can't be exported as .{}", self.extension()))
+    }
+
+    /// Serialize session timelines (models/timeline.rs)
+    fn export_timelines(&self, _timelines: &[Timeline]) -> Result<Vec<u8>, String> {
+        Err(format!("Timelines can't be exported as .{}", self.extension()))
+    }
+}
+
+/// Column names shared by the tabular formats
+const COLUMNS: [&str; 10] = [
+    "id",
+    "title",
+    "timestamp",
+    "combat_time",
+    "exp_gained",
+    "current_level",
+    "avg_exp_per_second",
+    "hp_potions_used",
+    "mp_potions_used",
+    "map_name",
+];
+
+/// Column names of raw sample exports (nested raw/parsed values flattened)
+const SAMPLE_COLUMNS: [&str; 17] = [
+    "seq",
+    "timestamp",
+    "elapsed_seconds",
+    "tracking",
+    "accepted",
+    "ocr_healthy",
+    "raw_level",
+    "raw_exp",
+    "raw_percentage",
+    "raw_hp",
+    "raw_mp",
+    "level",
+    "exp",
+    "percentage",
+    "total_exp",
+    "hp_potion_count",
+    "mp_potion_count",
+];
+
+/// Column names of timeline exports, one row per point
+const TIMELINE_COLUMNS: [&str; 6] = [
+    "session_id",
+    "timestamp",
+    "map_name",
+    "elapsed_seconds",
+    "total_exp",
+    "latency_ms",
+];
+
+/// Comma-separated values with a header row (RFC 4180 quoting)
+pub struct CsvExporter;
+
+impl CsvExporter {
+    fn field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+impl Exporter for CsvExporter {
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+
+    fn export(&self, records: &[SessionRecord]) -> Result<Vec<u8>, String> {
+        let mut csv = COLUMNS.join(",");
+        csv.push_str("\r\n");
+
+        for record in records {
+            let fields = [
+                Self::field(&record.id),
+                Self::field(&record.title),
+                record.timestamp.to_string(),
+                record.combat_time.to_string(),
+                record.exp_gained.to_string(),
+                record.current_level.to_string(),
+                record.avg_exp_per_second.to_string(),
+                record.hp_potions_used.to_string(),
+                record.mp_potions_used.to_string(),
+                Self::field(record.map_name.as_deref().unwrap_or_default()),
+            ];
+            csv.push_str(&fields.join(","));
+            csv.push_str("\r\n");
+        }
+
+        Ok(csv.into_bytes())
+    }
+
+    fn export_samples(&self, samples: &[RawSample]) -> Result<Vec<u8>, String> {
+        fn optional<T: ToString>(value: Option<T>) -> String {
+            value.map(|v| v.to_string()).unwrap_or_default()
+        }
+
+        let mut csv = SAMPLE_COLUMNS.join(",");
+        csv.push_str("\r\n");
+
+        for sample in samples {
+            let fields = [
+                sample.seq.to_string(),
+                sample.timestamp.to_string(),
+                sample.elapsed_seconds.to_string(),
+                sample.tracking.to_string(),
+                sample.accepted.to_string(),
+                sample.ocr_healthy.to_string(),
+                optional(sample.raw.level),
+                optional(sample.raw.exp),
+                optional(sample.raw.percentage),
+                optional(sample.raw.hp),
+                optional(sample.raw.mp),
+                optional(sample.parsed.level),
+                optional(sample.parsed.exp),
+                optional(sample.parsed.percentage),
+                sample.parsed.total_exp.to_string(),
+                optional(sample.parsed.hp_potion_count),
+                optional(sample.parsed.mp_potion_count),
+            ];
+            csv.push_str(&fields.join(","));
+            csv.push_str("\r\n");
+        }
+
+        Ok(csv.into_bytes())
+    }
+
+    fn export_timelines(&self, timelines: &[Timeline]) -> Result<Vec<u8>, String> {
+        let mut csv = TIMELINE_COLUMNS.join(",");
+        csv.push_str("\r\n");
+
+        for timeline in timelines {
+            for point in &timeline.points {
+                let fields = [
+                    Self::field(&timeline.session_id),
+                    timeline.timestamp.to_string(),
+                    Self::field(timeline.map_name.as_deref().unwrap_or_default()),
+                    point.elapsed_seconds.to_string(),
+                    point.total_exp.to_string(),
+                    point.latency_ms.map(|ms| ms.to_string()).unwrap_or_default(),
+                ];
+                csv.push_str(&fields.join(","));
+                csv.push_str("\r\n");
+            }
+        }
+
+        Ok(csv.into_bytes())
+    }
+}
+
+/// One JSON object per line, same fields as the stored records
+pub struct JsonLinesExporter;
+
+impl Exporter for JsonLinesExporter {
+    fn extension(&self) -> &'static str {
+        "jsonl"
+    }
+
+    fn export(&self, records: &[SessionRecord]) -> Result<Vec<u8>, String> {
+        let mut output = Vec::new();
+        for record in records {
+            serde_json::to_writer(&mut output, record)
+                .map_err(|e| format!("Failed to serialize session: {}", e))?;
+            output.push(b'\n');
+        }
+        Ok(output)
+    }
+
+    fn export_samples(&self, samples: &[RawSample]) -> Result<Vec<u8>, String> {
+        let mut output = Vec::new();
+        for sample in samples {
+            serde_json::to_writer(&mut output, sample)
+                .map_err(|e| format!("Failed to serialize sample: {}", e))?;
+            output.push(b'\n');
+        }
+        Ok(output)
+    }
+
+    fn export_timelines(&self, timelines: &[Timeline]) -> Result<Vec<u8>, String> {
+        let mut output = Vec::new();
+        for timeline in timelines {
+            serde_json::to_writer(&mut output, timeline)
+                .map_err(|e| format!("Failed to serialize timeline: {}", e))?;
+            output.push(b'\n');
+        }
+        Ok(output)
+    }
+}
+
+/// Apache Parquet, one row group, for loading into pandas/Spark/DuckDB
+#[cfg(feature = "parquet")]
+pub struct ParquetExporter;
+
+#[cfg(feature = "parquet")]
+impl ParquetExporter {
+    const SCHEMA: &'static str = "
+        message session_record {
+            REQUIRED BYTE_ARRAY id (UTF8);
+            REQUIRED BYTE_ARRAY title (UTF8);
+            REQUIRED INT64 timestamp (TIMESTAMP(MILLIS,true));
+            REQUIRED INT32 combat_time;
+            REQUIRED INT64 exp_gained;
+            REQUIRED INT32 current_level;
+            REQUIRED DOUBLE avg_exp_per_second;
+            REQUIRED INT32 hp_potions_used;
+            REQUIRED INT32 mp_potions_used;
+            OPTIONAL BYTE_ARRAY map_name (UTF8);
+        }
+    ";
+}
+
+#[cfg(feature = "parquet")]
+impl Exporter for ParquetExporter {
+    fn extension(&self) -> &'static str {
+        "parquet"
+    }
+
+    fn export(&self, records: &[SessionRecord]) -> Result<Vec<u8>, String> {
+        use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int32Type, Int64Type};
+        use parquet::file::properties::WriterProperties;
+        use parquet::file::writer::SerializedFileWriter;
+        use parquet::schema::parser::parse_message_type;
+        use std::sync::Arc;
+
+        let parquet_error = |e: parquet::errors::ParquetError| format!("Failed to write Parquet: {}", e);
+
+        let schema = Arc::new(parse_message_type(Self::SCHEMA).map_err(parquet_error)?);
+        let properties = Arc::new(WriterProperties::builder().build());
+
+        let mut output = Vec::new();
+        let mut writer = SerializedFileWriter::new(&mut output, schema, properties).map_err(parquet_error)?;
+        let mut row_group = writer.next_row_group().map_err(parquet_error)?;
+
+        fn strings<'a>(values: impl Iterator<Item = &'a str>) -> Vec<ByteArray> {
+            values.map(ByteArray::from).collect()
+        }
+
+        // Columns come back in schema (= COLUMNS) order
+        let mut index = 0;
+        while let Some(mut column) = row_group.next_column().map_err(parquet_error)? {
+            let rows = records.iter();
+            let written = match COLUMNS[index] {
+                "id" => column.typed::<ByteArrayType>().write_batch(&strings(rows.map(|r| r.id.as_str())), None, None),
+                "title" => column.typed::<ByteArrayType>().write_batch(&strings(rows.map(|r| r.title.as_str())), None, None),
+                "timestamp" => {
+                    let values: Vec<i64> = rows.map(|r| r.timestamp).collect();
+                    column.typed::<Int64Type>().write_batch(&values, None, None)
+                }
+                "combat_time" => {
+                    let values: Vec<i32> = rows.map(|r| r.combat_time).collect();
+                    column.typed::<Int32Type>().write_batch(&values, None, None)
+                }
+                "exp_gained" => {
+                    let values: Vec<i64> = rows.map(|r| r.exp_gained).collect();
+                    column.typed::<Int64Type>().write_batch(&values, None, None)
+                }
+                "current_level" => {
+                    let values: Vec<i32> = rows.map(|r| r.current_level).collect();
+                    column.typed::<Int32Type>().write_batch(&values, None, None)
+                }
+                "avg_exp_per_second" => {
+                    let values: Vec<f64> = rows.map(|r| r.avg_exp_per_second).collect();
+                    column.typed::<DoubleType>().write_batch(&values, None, None)
+                }
+                "hp_potions_used" => {
+                    let values: Vec<i32> = rows.map(|r| r.hp_potions_used).collect();
+                    column.typed::<Int32Type>().write_batch(&values, None, None)
+                }
+                "mp_potions_used" => {
+                    let values: Vec<i32> = rows.map(|r| r.mp_potions_used).collect();
+                    column.typed::<Int32Type>().write_batch(&values, None, None)
+                }
+                _ => {
+                    // map_name: nulls are encoded as definition level 0 with no value
+                    let values = strings(records.iter().filter_map(|r| r.map_name.as_deref()));
+                    let definition_levels: Vec<i16> = rows.map(|r| r.map_name.is_some() as i16).collect();
+                    column.typed::<ByteArrayType>().write_batch(&values, Some(&definition_levels), None)
+                }
+            };
+            written.map_err(parquet_error)?;
+
+            column.close().map_err(parquet_error)?;
+            index += 1;
+        }
+
+        row_group.close().map_err(parquet_error)?;
+        writer.close().map_err(parquet_error)?;
+
+        Ok(output)
+    }
+}
+
+/// Exporters compiled into this build
+fn exporters() -> Vec<Box<dyn Exporter>> {
+    vec![
+        Box::new(CsvExporter),
+        Box::new(JsonLinesExporter),
+        #[cfg(feature = "parquet")]
+        Box::new(ParquetExporter),
+    ]
+}
+
+/// Exporter for a file path, chosen by its extension
+pub fn exporter_for_path(path: &Path) -> Result<Box<dyn Exporter>, String> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+
+    let exporters = exporters();
+    let supported: Vec<String> = exporters.iter().map(|exporter| format!(".{}", exporter.extension())).collect();
+
+    exporters
+        .into_iter()
+        .find(|exporter| exporter.extension() == extension)
+        .ok_or_else(|| format!("Unsupported export format '.{}' (use {})", extension, supported.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(title: &str, map_name: Option<&str>) -> SessionRecord {
+        SessionRecord {
+            title: title.to_string(),
+            hp_potions_used: 3,
+            mp_potions_used: 4,
+            map_name: map_name.map(str::to_string),
+            ..SessionRecord::sample(1_700_000_000_000)
+        }
+    }
+
+    #[test]
+    fn test_exporter_chosen_by_extension() {
+        assert_eq!(exporter_for_path(Path::new("out/sessions.CSV")).unwrap().extension(), "csv");
+        assert_eq!(exporter_for_path(Path::new("sessions.jsonl")).unwrap().extension(), "jsonl");
+        #[cfg(feature = "parquet")]
+        assert_eq!(exporter_for_path(Path::new("sessions.parquet")).unwrap().extension(), "parquet");
+        assert!(exporter_for_path(Path::new("sessions.xlsx")).is_err());
+        assert!(exporter_for_path(Path::new("sessions")).is_err());
+    }
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        let csv = CsvExporter.export(&[record("보스, \"트라이\"", Some("헤네시스"))]).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], COLUMNS.join(","));
+        assert_eq!(lines[1], "1700000000000,\"보스, \"\"트라이\"\"\",1700000000000,600,1000,100,1.5,3,4,헤네시스");
+    }
+
+    #[test]
+    fn test_csv_flattens_samples() {
+        use crate::services::sample_log::{ParsedValues, RawValues};
+
+        let sample = RawSample {
+            seq: 3,
+            timestamp: 1_700_000_000_000,
+            elapsed_seconds: 60,
+            tracking: true,
+            accepted: false,
+            ocr_healthy: true,
+            raw: RawValues { hp: Some(930), ..RawValues::default() },
+            parsed: ParsedValues { level: Some(126), hp_potion_count: Some(930), ..ParsedValues::default() },
+        };
+        let csv = String::from_utf8(CsvExporter.export_samples(&[sample]).unwrap()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], SAMPLE_COLUMNS.join(","));
+        assert_eq!(lines[1], "3,1700000000000,60,true,false,true,,,,930,,126,,,0,930,");
+        #[cfg(feature = "parquet")]
+        assert!(ParquetExporter.export_samples(&[sample]).is_err());
+    }
+
+    #[test]
+    fn test_timelines_export_one_row_per_point() {
+        use crate::models::timeline::TimelinePoint;
+
+        let timeline = Timeline {
+            session_id: "1700000000000".to_string(),
+            timestamp: 1_700_000_000_000,
+            map_name: Some("헤네시스".to_string()),
+            points: vec![
+                TimelinePoint { elapsed_seconds: 0, total_exp: 0, latency_ms: None, recorded_at: None },
+                TimelinePoint { elapsed_seconds: 30, total_exp: 300, latency_ms: Some(420), recorded_at: None },
+            ],
+        };
+
+        let csv = String::from_utf8(CsvExporter.export_timelines(&[timeline.clone()]).unwrap()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], TIMELINE_COLUMNS.join(","));
+        assert_eq!(lines[1], "1700000000000,1700000000000,헤네시스,0,0,");
+        assert_eq!(lines[2], "1700000000000,1700000000000,헤네시스,30,300,420");
+
+        let jsonl = String::from_utf8(JsonLinesExporter.export_timelines(&[timeline.clone()]).unwrap()).unwrap();
+        assert_eq!(serde_json::from_str::<Timeline>(jsonl.trim_end()).unwrap(), timeline);
+    }
+
+    #[test]
+    fn test_json_lines_round_trip() {
+        let output = JsonLinesExporter.export(&[record("a", None), record("b", Some("헤네시스"))]).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let titles: Vec<String> = output
+            .lines()
+            .map(|line| serde_json::from_str::<SessionRecord>(line).unwrap().title)
+            .collect();
+        assert_eq!(titles, vec!["a", "b"]);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_file_is_written() {
+        let output = ParquetExporter.export(&[record("a", None), record("b", Some("헤네시스"))]).unwrap();
+
+        assert_eq!(&output[..4], b"PAR1");
+        assert_eq!(&output[output.len() - 4..], b"PAR1");
+    }
+}
@@ -0,0 +1,218 @@
+/// Arithmetic expression for user-defined derived stats
+///
+/// Supports numbers, variables, `+ - * /`, unary minus and parentheses,
+/// e.g. `(meso - potions_cost) / hours`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Variable(String),
+    Negate(Box<Expr>),
+    Binary(Box<Expr>, Op, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(Op),
+    Open,
+    Close,
+}
+
+impl Expr {
+    /// Parse an expression, rejecting trailing input
+    pub fn parse(source: &str) -> Result<Expr, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens: &tokens, pos: 0 };
+
+        let expr = parser.expr()?;
+        if parser.pos < tokens.len() {
+            return Err(format!("Unexpected {:?} in '{}'", tokens[parser.pos], source));
+        }
+
+        Ok(expr)
+    }
+
+    /// Evaluate with variables from `lookup`
+    /// None if a variable has no value yet or the result isn't finite (e.g. / 0).
+    pub fn eval(&self, lookup: &dyn Fn(&str) -> Option<f64>) -> Option<f64> {
+        let value = match self {
+            Expr::Number(n) => *n,
+            Expr::Variable(name) => lookup(name)?,
+            Expr::Negate(inner) => -inner.eval(lookup)?,
+            Expr::Binary(left, op, right) => {
+                let (left, right) = (left.eval(lookup)?, right.eval(lookup)?);
+                match op {
+                    Op::Add => left + right,
+                    Op::Sub => left - right,
+                    Op::Mul => left * right,
+                    Op::Div => left / right,
+                }
+            }
+        };
+
+        value.is_finite().then_some(value)
+    }
+
+    /// Every variable name the expression refers to
+    pub fn variables(&self) -> Vec<&str> {
+        match self {
+            Expr::Number(_) => Vec::new(),
+            Expr::Variable(name) => vec![name.as_str()],
+            Expr::Negate(inner) => inner.variables(),
+            Expr::Binary(left, _, right) => {
+                let mut names = left.variables();
+                names.extend(right.variables());
+                names
+            }
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.') {
+                    number.push(c);
+                    chars.next();
+                }
+                let value = number.parse().map_err(|_| format!("Invalid number '{}'", number))?;
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek().filter(|c| c.is_alphanumeric() || **c == '_') {
+                    ident.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            _ => {
+                let token = match c {
+                    '+' => Token::Op(Op::Add),
+                    '-' => Token::Op(Op::Sub),
+                    '*' => Token::Op(Op::Mul),
+                    '/' => Token::Op(Op::Div),
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    other => return Err(format!("Unexpected character '{}'", other)),
+                };
+                tokens.push(token);
+                chars.next();
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent: expr = term (+|- term)*, term = factor (*|/ factor)*
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn peek_op(&self, ops: [Op; 2]) -> Option<Op> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) if ops.contains(op) => Some(*op),
+            _ => None,
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.term()?;
+        while let Some(op) = self.peek_op([Op::Add, Op::Sub]) {
+            self.pos += 1;
+            left = Expr::Binary(Box::new(left), op, Box::new(self.term()?));
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut left = self.factor()?;
+        while let Some(op) = self.peek_op([Op::Mul, Op::Div]) {
+            self.pos += 1;
+            left = Expr::Binary(Box::new(left), op, Box::new(self.factor()?));
+        }
+        Ok(left)
+    }
+
+    fn factor(&mut self) -> Result<Expr, String> {
+        match self.next().cloned() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Ident(name)) => Ok(Expr::Variable(name)),
+            Some(Token::Op(Op::Sub)) => Ok(Expr::Negate(Box::new(self.factor()?))),
+            Some(Token::Open) => {
+                let inner = self.expr()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err("Missing closing parenthesis".to_string()),
+                }
+            }
+            Some(token) => Err(format!("Unexpected {:?}", token)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(name: &str) -> Option<f64> {
+        match name {
+            "meso" => Some(1000.0),
+            "potions_cost" => Some(200.0),
+            "hours" => Some(2.0),
+            "zero" => Some(0.0),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_eval_with_precedence_and_parentheses() {
+        let expr = Expr::parse("(meso - potions_cost) / hours").unwrap();
+        assert_eq!(expr.eval(&vars), Some(400.0));
+        assert_eq!(expr.variables(), vec!["meso", "potions_cost", "hours"]);
+
+        assert_eq!(Expr::parse("1 + 2 * 3").unwrap().eval(&vars), Some(7.0));
+        assert_eq!(Expr::parse("-(1 + 2) * 2").unwrap().eval(&vars), Some(-6.0));
+    }
+
+    #[test]
+    fn test_eval_missing_values() {
+        assert_eq!(Expr::parse("meso / zero").unwrap().eval(&vars), None);
+        assert_eq!(Expr::parse("unknown + 1").unwrap().eval(&vars), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Expr::parse("(1 + 2").is_err());
+        assert!(Expr::parse("1 +").is_err());
+        assert!(Expr::parse("1 2").is_err());
+        assert!(Expr::parse("meso % 2").is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageEncoder};
+use serde::Deserialize;
+
+/// Largest image sent over IPC unless the caller raises it
+/// Full-screen PNGs of 4K screens are 10+ MB and stall the webview.
+pub const DEFAULT_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+/// JPEG quality when the caller doesn't give one
+pub const DEFAULT_JPEG_QUALITY: u8 = 80;
+
+/// Images are never shrunk below this width to fit the size cap
+const MIN_WIDTH: u32 = 64;
+
+/// Each attempt to fit the size cap shrinks the image to this fraction
+const SHRINK_STEP: f64 = 0.75;
+
+/// Encoding of image-returning commands
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadFormat {
+    #[default]
+    Png,
+    Jpeg,
+    /// Lossless WebP (`quality` doesn't apply)
+    Webp,
+}
+
+impl PayloadFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            PayloadFormat::Png => "image/png",
+            PayloadFormat::Jpeg => "image/jpeg",
+            PayloadFormat::Webp => "image/webp",
+        }
+    }
+}
+
+/// Downscaling and compression of an image sent to the frontend
+/// Every field is optional; the defaults keep full-size PNGs up to `DEFAULT_MAX_BYTES`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ImageOptions {
+    pub max_width: Option<u32>,
+    pub format: PayloadFormat,
+    /// JPEG quality, 1-100
+    pub quality: Option<u8>,
+    /// Size cap in bytes; larger images are shrunk until they fit
+    pub max_bytes: Option<usize>,
+}
+
+/// Encoded image and the size it was encoded at
+#[derive(Debug, Clone)]
+pub struct EncodedImage {
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub format: PayloadFormat,
+}
+
+impl EncodedImage {
+    /// `data:` URL for use as an `<img>` source
+    pub fn data_url(&self) -> String {
+        use base64::Engine as _;
+        format!(
+            "data:{};base64,{}",
+            self.format.mime_type(),
+            base64::engine::general_purpose::STANDARD.encode(&self.bytes)
+        )
+    }
+
+    /// Raw IPC body: width (u32 LE), height (u32 LE), then the encoded bytes
+    /// Lets binary responses say what size the image was shrunk to.
+    pub fn into_framed(self) -> Vec<u8> {
+        let mut body = Vec::with_capacity(8 + self.bytes.len());
+        body.extend_from_slice(&self.width.to_le_bytes());
+        body.extend_from_slice(&self.height.to_le_bytes());
+        body.extend_from_slice(&self.bytes);
+        body
+    }
+}
+
+fn encode_as(image: &DynamicImage, format: PayloadFormat, quality: u8) -> Result<Vec<u8>, String> {
+    let mut buf = Vec::new();
+    let result = match format {
+        PayloadFormat::Png => {
+            let image = image.to_rgba8();
+            PngEncoder::new(&mut buf).write_image(&image, image.width(), image.height(), image::ExtendedColorType::Rgba8)
+        }
+        // JPEG has no alpha channel
+        PayloadFormat::Jpeg => JpegEncoder::new_with_quality(&mut buf, quality).encode_image(&image.to_rgb8()),
+        PayloadFormat::Webp => {
+            let image = image.to_rgba8();
+            WebPEncoder::new_lossless(&mut buf).write_image(&image, image.width(), image.height(), image::ExtendedColorType::Rgba8)
+        }
+    };
+    result.map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(buf)
+}
+
+/// Encode `image` for the frontend, shrinking it to `max_width` and then
+/// further until it fits the size cap
+pub fn encode(image: &DynamicImage, options: &ImageOptions) -> Result<EncodedImage, String> {
+    let quality = options.quality.unwrap_or(DEFAULT_JPEG_QUALITY).clamp(1, 100);
+    let max_bytes = options.max_bytes.unwrap_or(DEFAULT_MAX_BYTES);
+
+    let mut width = options.max_width.map_or(image.width(), |max| image.width().min(max.max(1)));
+    loop {
+        let resized;
+        let scaled = if width < image.width() {
+            resized = image.resize(width, u32::MAX, FilterType::Triangle);
+            &resized
+        } else {
+            image
+        };
+
+        let bytes = encode_as(scaled, options.format, quality)?;
+        if bytes.len() <= max_bytes {
+            return Ok(EncodedImage {
+                bytes,
+                width: scaled.width(),
+                height: scaled.height(),
+                format: options.format,
+            });
+        }
+
+        if width <= MIN_WIDTH {
+            return Err(format!(
+                "Image doesn't fit in {} bytes even at {}px wide; raise max_bytes or use JPEG",
+                max_bytes, width
+            ));
+        }
+        width = ((width as f64 * SHRINK_STEP) as u32).max(MIN_WIDTH);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise(width: u32, height: u32) -> DynamicImage {
+        let mut seed = 12345u32;
+        DynamicImage::ImageRgb8(image::RgbImage::from_fn(width, height, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let [r, g, b, _] = seed.to_le_bytes();
+            image::Rgb([r, g, b])
+        }))
+    }
+
+    #[test]
+    fn test_max_width_keeps_aspect() {
+        let options = ImageOptions { max_width: Some(50), format: PayloadFormat::Jpeg, ..Default::default() };
+        let encoded = encode(&DynamicImage::new_rgba8(200, 100), &options).unwrap();
+
+        assert_eq!((encoded.width, encoded.height), (50, 25));
+        let decoded = image::load_from_memory(&encoded.bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (50, 25));
+        assert!(encoded.data_url().starts_with("data:image/jpeg;base64,"));
+
+        let bytes = encoded.bytes.clone();
+        let framed = encoded.into_framed();
+        assert_eq!(framed[0..4], 50u32.to_le_bytes());
+        assert_eq!(framed[4..8], 25u32.to_le_bytes());
+        assert_eq!(framed[8..], bytes[..]);
+    }
+
+    #[test]
+    fn test_shrinks_to_fit_cap() {
+        let image = noise(400, 200);
+        let full = encode(&image, &ImageOptions { max_bytes: Some(usize::MAX), ..Default::default() }).unwrap();
+        assert_eq!(full.width, 400);
+
+        let capped = encode(&image, &ImageOptions { max_bytes: Some(full.bytes.len() / 2), ..Default::default() }).unwrap();
+        assert!(capped.width < 400);
+        assert!(capped.bytes.len() <= full.bytes.len() / 2);
+
+        assert!(encode(&image, &ImageOptions { max_bytes: Some(10), ..Default::default() }).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Most recent samples kept for the percentiles
+const MAX_SAMPLES: usize = 512;
+
+/// When one reading passed each stage on its way to the frontend
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleTiming {
+    pub capture_started: Instant,
+    pub captured: Instant,
+    pub recognized: Instant,
+}
+
+impl SampleTiming {
+    /// Stage durations of a reading whose event went out at `emitted`
+    pub fn sample(&self, emitted: Instant) -> LatencySample {
+        LatencySample {
+            capture: self.captured.saturating_duration_since(self.capture_started),
+            ocr: self.recognized.saturating_duration_since(self.captured),
+            delivery: emitted.saturating_duration_since(self.recognized),
+        }
+    }
+}
+
+/// Capture start to event emit, split by stage
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencySample {
+    /// Screen capture
+    pub capture: Duration,
+    /// OCR request and parsing
+    pub ocr: Duration,
+    /// Tracker mailbox and event emit
+    pub delivery: Duration,
+}
+
+impl LatencySample {
+    pub fn total(&self) -> Duration {
+        self.capture + self.ocr + self.delivery
+    }
+}
+
+/// Median and 95th percentile of one stage
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct Percentiles {
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+}
+
+impl Percentiles {
+    fn of(mut durations: Vec<Duration>) -> Self {
+        durations.sort_unstable();
+        Self {
+            p50_ms: percentile_ms(&durations, 50.0),
+            p95_ms: percentile_ms(&durations, 95.0),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty durations
+fn percentile_ms(sorted: &[Duration], p: f64) -> f64 {
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1].as_micros() as f64 / 1000.0
+}
+
+/// Latency summary for the diagnostics panel
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub total: Percentiles,
+    pub capture: Percentiles,
+    pub ocr: Percentiles,
+    pub delivery: Percentiles,
+}
+
+/// Rolling window of recent samples
+pub struct LatencyRecorder {
+    samples: Mutex<VecDeque<LatencySample>>,
+}
+
+impl LatencyRecorder {
+    pub fn new() -> Self {
+        Self {
+            samples: Mutex::new(VecDeque::with_capacity(MAX_SAMPLES)),
+        }
+    }
+
+    pub fn record(&self, sample: LatencySample) {
+        if let Ok(mut samples) = self.samples.lock() {
+            if samples.len() == MAX_SAMPLES {
+                samples.pop_front();
+            }
+            samples.push_back(sample);
+        }
+    }
+
+    /// Percentiles over the window; None before the first sample
+    pub fn stats(&self) -> Option<LatencyStats> {
+        let samples = self.samples.lock().ok()?;
+        if samples.is_empty() {
+            return None;
+        }
+
+        let stage = |f: fn(&LatencySample) -> Duration| Percentiles::of(samples.iter().map(f).collect());
+        Some(LatencyStats {
+            samples: samples.len(),
+            total: stage(LatencySample::total),
+            capture: stage(|s| s.capture),
+            ocr: stage(|s| s.ocr),
+            delivery: stage(|s| s.delivery),
+        })
+    }
+}
+
+impl Default for LatencyRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Process-wide recorder fed by the tracker actor
+pub fn global() -> &'static LatencyRecorder {
+    static RECORDER: OnceLock<LatencyRecorder> = OnceLock::new();
+    RECORDER.get_or_init(LatencyRecorder::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(capture_ms: u64, ocr_ms: u64, delivery_ms: u64) -> LatencySample {
+        LatencySample {
+            capture: Duration::from_millis(capture_ms),
+            ocr: Duration::from_millis(ocr_ms),
+            delivery: Duration::from_millis(delivery_ms),
+        }
+    }
+
+    #[test]
+    fn test_timing_splits_stages() {
+        let start = Instant::now();
+        let timing = SampleTiming {
+            capture_started: start,
+            captured: start + Duration::from_millis(20),
+            recognized: start + Duration::from_millis(320),
+        };
+
+        let sample = timing.sample(start + Duration::from_millis(325));
+        assert_eq!(sample, self::sample(20, 300, 5));
+        assert_eq!(sample.total(), Duration::from_millis(325));
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let recorder = LatencyRecorder::new();
+        assert_eq!(recorder.stats(), None);
+
+        for ms in 1..=100 {
+            recorder.record(sample(ms, 0, 0));
+        }
+
+        let stats = recorder.stats().unwrap();
+        assert_eq!(stats.samples, 100);
+        assert_eq!(stats.capture.p50_ms, 50.0);
+        assert_eq!(stats.capture.p95_ms, 95.0);
+        assert_eq!(stats.total, stats.capture);
+        assert_eq!(stats.ocr.p95_ms, 0.0);
+    }
+
+    #[test]
+    fn test_window_drops_oldest() {
+        let recorder = LatencyRecorder::new();
+        for _ in 0..MAX_SAMPLES {
+            recorder.record(sample(1000, 0, 0));
+        }
+        for _ in 0..MAX_SAMPLES {
+            recorder.record(sample(10, 0, 0));
+        }
+
+        let stats = recorder.stats().unwrap();
+        assert_eq!(stats.samples, MAX_SAMPLES);
+        assert_eq!(stats.capture.p95_ms, 10.0);
+    }
+}
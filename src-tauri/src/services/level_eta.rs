@@ -0,0 +1,150 @@
+use crate::models::exp_data::LevelExpTable;
+use serde::Serialize;
+
+/// Levels projected when the caller doesn't ask for a count
+pub const DEFAULT_ETA_LEVELS: u32 = 5;
+
+/// Most levels projected in one call
+pub const MAX_ETA_LEVELS: u32 = 50;
+
+/// Projection for reaching one level at the current rate
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LevelEta {
+    pub level: u32,
+    /// EXP still needed; None if the level table doesn't cover the way there
+    pub remaining_exp: Option<u64>,
+    /// Seconds until the level at the current rate
+    pub seconds: Option<u64>,
+    /// Projected UTC Unix timestamp in milliseconds
+    pub eta: Option<i64>,
+}
+
+/// Where the character is now and how fast it's moving
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    pub level: u32,
+    pub exp: u64,
+    pub percentage: f64,
+    pub exp_per_hour: u64,
+    pub percentage_per_hour: f64,
+}
+
+impl Progress {
+    /// EXP to finish the current level: from the table, or estimated from the
+    /// EXP/percentage reading when the level isn't in it
+    fn current_level_exp(&self, table: &LevelExpTable) -> Option<u64> {
+        table.get_exp_for_level(self.level).or_else(|| {
+            (self.percentage > 0.0).then(|| (self.exp as f64 * 100.0 / self.percentage).round() as u64)
+        })
+    }
+}
+
+/// Projected times for `levels` (sorted, each above the current level)
+pub fn level_etas(table: &LevelExpTable, progress: Progress, levels: &[u32], now_millis: i64) -> Vec<LevelEta> {
+    let seconds_for = |exp: u64| {
+        (progress.exp_per_hour > 0).then(|| (exp as u128 * 3600 / progress.exp_per_hour as u128) as u64)
+    };
+
+    // EXP needed to reach `level`, summed over every level on the way
+    let exp_to_reach = |level: u32| -> Option<u64> {
+        let current = progress.current_level_exp(table)?.saturating_sub(progress.exp);
+        (progress.level + 1..level).try_fold(current, |total, l| Some(total.saturating_add(table.get_exp_for_level(l)?)))
+    };
+
+    levels.iter()
+        .filter(|&&level| level > progress.level)
+        .map(|&level| {
+            let remaining_exp = exp_to_reach(level);
+            let mut seconds = remaining_exp.and_then(seconds_for);
+
+            // The next level can still be projected from the percentage rate
+            if seconds.is_none() && level == progress.level + 1 && progress.percentage_per_hour > 0.0 {
+                let remaining = (100.0 - progress.percentage).max(0.0);
+                seconds = Some((remaining * 3600.0 / progress.percentage_per_hour).round() as u64);
+            }
+
+            LevelEta {
+                level,
+                remaining_exp,
+                seconds,
+                eta: seconds.map(|s| now_millis.saturating_add((s as i64).saturating_mul(1000))),
+            }
+        })
+        .collect()
+}
+
+/// First level on the way to `levels` whose EXP requirement the table lacks
+///
+/// Only the next level can be projected without the table (from the current
+/// reading), so anything further needs every level in between.
+pub fn missing_table_level(table: &LevelExpTable, current: u32, levels: &[u32]) -> Option<u32> {
+    let furthest = levels.iter().copied().max()?;
+    (current.saturating_add(1)..furthest).find(|&level| table.get_exp_for_level(level).is_none())
+}
+
+/// The next `count` levels (at most `MAX_ETA_LEVELS`), plus `target` if it's further out
+pub fn milestone_levels(current: u32, count: u32, target: Option<u32>) -> Vec<u32> {
+    let mut levels: Vec<u32> = (1..=count.min(MAX_ETA_LEVELS)).map(|i| current.saturating_add(i)).collect();
+    if let Some(target) = target.filter(|&t| t > current && !levels.contains(&t)) {
+        levels.push(target);
+        levels.sort_unstable();
+    }
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(exp_per_hour: u64, percentage_per_hour: f64) -> Progress {
+        Progress { level: 100, exp: 500, percentage: 50.0, exp_per_hour, percentage_per_hour }
+    }
+
+    #[test]
+    fn test_etas_sum_levels_from_table() {
+        let table = LevelExpTable::load().unwrap().with_levels(vec![(100, 1000), (101, 2000), (102, 4000)]);
+        let now = 1_700_000_000_000;
+
+        let etas = level_etas(&table, progress(1000, 10.0), &[101, 102, 103], now);
+        assert_eq!(etas[0].remaining_exp, Some(500));
+        assert_eq!(etas[0].seconds, Some(1800));
+        assert_eq!(etas[0].eta, Some(now + 1_800_000));
+        assert_eq!(etas[1].remaining_exp, Some(2500));
+        assert_eq!(etas[2].remaining_exp, Some(6500));
+    }
+
+    #[test]
+    fn test_etas_without_table() {
+        let table = LevelExpTable::load().unwrap();
+
+        // Current level is estimated from the reading; later levels are unknown
+        let etas = level_etas(&table, progress(1000, 10.0), &[101, 102], 0);
+        assert_eq!(etas[0].remaining_exp, Some(500));
+        assert_eq!(etas[1].remaining_exp, None);
+        assert_eq!(etas[1].seconds, None);
+
+        // No EXP rate yet: fall back to the percentage rate for the next level
+        let etas = level_etas(&table, progress(0, 10.0), &[101], 0);
+        assert_eq!(etas[0].seconds, Some(18_000));
+    }
+
+    #[test]
+    fn test_milestone_levels() {
+        assert_eq!(milestone_levels(100, 3, None), vec![101, 102, 103]);
+        assert_eq!(milestone_levels(100, 3, Some(130)), vec![101, 102, 103, 130]);
+        assert_eq!(milestone_levels(100, 3, Some(102)), vec![101, 102, 103]);
+        assert_eq!(milestone_levels(100, 3, Some(90)), vec![101, 102, 103]);
+        assert_eq!(milestone_levels(100, u32::MAX, None).len(), MAX_ETA_LEVELS as usize);
+    }
+
+    #[test]
+    fn test_missing_table_level() {
+        let empty = LevelExpTable::load().unwrap();
+        assert_eq!(missing_table_level(&empty, 100, &[101]), None);
+        assert_eq!(missing_table_level(&empty, 100, &[101, 102]), Some(101));
+
+        let table = empty.with_levels(vec![(101, 2000), (102, 4000)]);
+        assert_eq!(missing_table_level(&table, 100, &[101, 102, 103]), None);
+        assert_eq!(missing_table_level(&table, 100, &[101, 110]), Some(103));
+    }
+}
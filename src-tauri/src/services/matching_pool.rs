@@ -0,0 +1,86 @@
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::{Arc, RwLock};
+
+/// Dedicated pool for template matching, with the thread count it was built for
+static POOL: RwLock<Option<(usize, Arc<ThreadPool>)>> = RwLock::new(None);
+
+/// Threads for a configured cap; 0 leaves half the cores to the game
+pub fn thread_count(configured: u32) -> usize {
+    if configured > 0 {
+        return configured as usize;
+    }
+    std::thread::available_parallelism()
+        .map(|cores| cores.get() / 2)
+        .unwrap_or(1)
+        .max(1)
+}
+
+/// Size the matching pool from `AdvancedConfig::matching_threads`
+/// The pool is only rebuilt when the thread count changes.
+pub fn configure(configured: u32) {
+    let threads = thread_count(configured);
+    if matches!(POOL.read().as_deref(), Ok(Some((current, _))) if *current == threads) {
+        return;
+    }
+
+    match build(threads) {
+        Ok(pool) => {
+            if let Ok(mut current) = POOL.write() {
+                *current = Some((threads, Arc::new(pool)));
+            }
+        }
+        Err(e) => eprintln!("⚠️ Failed to build template matching pool: {}", e),
+    }
+}
+
+fn build(threads: usize) -> Result<ThreadPool, rayon::ThreadPoolBuildError> {
+    ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("template-matching-{}", i))
+        .build()
+}
+
+fn pool() -> Option<Arc<ThreadPool>> {
+    if let Ok(current) = POOL.read() {
+        if let Some((_, pool)) = current.as_ref() {
+            return Some(Arc::clone(pool));
+        }
+    }
+
+    configure(0);
+    POOL.read().ok()?.as_ref().map(|(_, pool)| Arc::clone(pool))
+}
+
+/// Run `f` with its rayon work confined to the matching pool
+///
+/// Matching calls from concurrent `spawn_blocking` tasks share the pool's
+/// threads, so together they never occupy more cores than the cap.
+pub fn install<R: Send>(f: impl FnOnce() -> R + Send) -> R {
+    match pool() {
+        Some(pool) => pool.install(f),
+        None => f(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rayon::prelude::*;
+
+    #[test]
+    fn test_thread_count() {
+        assert_eq!(thread_count(3), 3);
+        assert!(thread_count(0) >= 1);
+    }
+
+    #[test]
+    fn test_install_runs_in_capped_pool() {
+        configure(2);
+
+        let threads = install(rayon::current_num_threads);
+        assert_eq!(threads, 2);
+
+        let sum: u32 = install(|| (1..=100u32).into_par_iter().sum());
+        assert_eq!(sum, 5050);
+    }
+}
@@ -0,0 +1,271 @@
+//! In-process stand-in for the Python OCR server (`mock-ocr` feature)
+//!
+//! Serves `/health`, `/ocr` and `/shutdown` on the OCR server's port so the
+//! app runs without building `python_ocr_server`. Every `/ocr` request gets the
+//! next canned text; words become separate boxes spread over the image.
+//!
+//! Canned texts are read from the JSON array of strings at `$EXP_TRACKER_MOCK_OCR`
+//! (cycled). Without it, EXP readings that grow on every request are served.
+
+use crate::services::ocr::api::ImageRequest;
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+#[cfg(not(debug_assertions))]
+compile_error!("The mock-ocr feature is for development builds only");
+
+/// Same address the Python server listens on
+const ADDRESS: &str = "127.0.0.1:39835";
+
+/// Environment variable pointing at a JSON array of canned texts
+const RESPONSES_ENV: &str = "EXP_TRACKER_MOCK_OCR";
+
+/// Largest request accepted (headers + base64 image)
+const MAX_REQUEST_BYTES: usize = 32 * 1024 * 1024;
+
+/// Starting point and step of the default EXP readings
+const DEFAULT_EXP: u64 = 5_000_000;
+const DEFAULT_EXP_STEP: u64 = 1_234;
+const DEFAULT_LEVEL_EXP: u64 = 40_000_000;
+
+/// Where canned texts come from
+enum Responses {
+    Canned(Vec<String>),
+    GrowingExp,
+}
+
+impl Responses {
+    fn load() -> Self {
+        let Ok(path) = std::env::var(RESPONSES_ENV) else {
+            return Responses::GrowingExp;
+        };
+
+        let texts = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<Vec<String>>(&content).map_err(|e| e.to_string()));
+
+        match texts {
+            Ok(texts) if !texts.is_empty() => Responses::Canned(texts),
+            Ok(_) => {
+                eprintln!("⚠️  {} has no responses, serving EXP readings", path);
+                Responses::GrowingExp
+            }
+            Err(e) => {
+                eprintln!("⚠️  Failed to load mock OCR responses from {}: {}", path, e);
+                Responses::GrowingExp
+            }
+        }
+    }
+
+    /// Text for the `n`th `/ocr` request
+    fn text(&self, n: u64) -> String {
+        match self {
+            Responses::Canned(texts) => texts[(n % texts.len() as u64) as usize].clone(),
+            Responses::GrowingExp => {
+                let exp = DEFAULT_EXP + n * DEFAULT_EXP_STEP;
+                format!("{}[{:.2}%]", exp, exp as f64 * 100.0 / DEFAULT_LEVEL_EXP as f64)
+            }
+        }
+    }
+}
+
+/// Width and height of a PNG from its IHDR chunk
+fn png_size(png: &[u8]) -> Option<(u32, u32)> {
+    if png.len() < 24 || &png[..8] != b"\x89PNG\r\n\x1a\n" {
+        return None;
+    }
+    let width = u32::from_be_bytes(png[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(png[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// OCR response body: one box per word, laid out left to right inside the image
+fn ocr_response(text: &str, width: u32, height: u32) -> serde_json::Value {
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    // Keep clear of the image edges so the client doesn't treat text as clipped
+    let (left, right) = (width as f64 * 0.1, width as f64 * 0.9);
+    let (top, bottom) = (height as f64 * 0.1, height as f64 * 0.9);
+    let slot = (right - left) / words.len().max(1) as f64;
+
+    let boxes: Vec<_> = words.iter().enumerate().map(|(i, word)| {
+        let x1 = left + slot * i as f64;
+        let x2 = x1 + slot;
+        json!({
+            "box": [[x1, top], [x2, top], [x2, bottom], [x1, bottom]],
+            "text": word,
+            "score": 0.99,
+        })
+    }).collect();
+
+    json!({ "boxes": boxes, "raw_text": words.join(" ") })
+}
+
+/// Request line and body of one HTTP request
+async fn read_request(stream: &mut TcpStream) -> Result<(String, Vec<u8>), String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 16 * 1024];
+
+    let header_end = loop {
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buffer.len() > MAX_REQUEST_BYTES {
+            return Err("Request headers too large".to_string());
+        }
+        let n = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if n == 0 {
+            return Err("Connection closed".to_string());
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    };
+
+    let headers = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let request_line = headers.lines().next().unwrap_or_default().to_string();
+    let content_length = headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    if content_length > MAX_REQUEST_BYTES {
+        return Err("Request body too large".to_string());
+    }
+
+    let mut body = buffer.split_off(header_end);
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if n == 0 {
+            return Err("Connection closed".to_string());
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    Ok((request_line, body))
+}
+
+async fn write_response(stream: &mut TcpStream, status: &str, body: &serde_json::Value) {
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Answer one request; returns true when the client asked the server to shut down
+async fn handle(mut stream: TcpStream, responses: &Responses, requests: &AtomicU64) -> bool {
+    let (request_line, body) = match read_request(&mut stream).await {
+        Ok(request) => request,
+        Err(e) => {
+            write_response(&mut stream, "400 Bad Request", &json!({ "detail": e })).await;
+            return false;
+        }
+    };
+
+    let mut parts = request_line.split_whitespace();
+    match (parts.next().unwrap_or_default(), parts.next().unwrap_or_default()) {
+        ("GET", "/health") => {
+            write_response(&mut stream, "200 OK", &json!({ "status": "ok", "engine": "mock" })).await;
+        }
+        ("POST", "/ocr") => {
+            let image = serde_json::from_slice::<ImageRequest>(&body)
+                .map_err(|e| e.to_string())
+                .and_then(|request| general_purpose::STANDARD.decode(request.image_base64).map_err(|e| e.to_string()));
+
+            match image {
+                Ok(image) => {
+                    let (width, height) = png_size(&image).unwrap_or((100, 20));
+                    let text = responses.text(requests.fetch_add(1, Ordering::Relaxed));
+                    write_response(&mut stream, "200 OK", &ocr_response(&text, width, height)).await;
+                }
+                Err(e) => {
+                    write_response(&mut stream, "500 Internal Server Error", &json!({ "detail": format!("OCR failed: {}", e) })).await;
+                }
+            }
+        }
+        ("POST", "/shutdown") => {
+            write_response(&mut stream, "200 OK", &json!({ "status": "shutting down" })).await;
+            return true;
+        }
+        _ => {
+            write_response(&mut stream, "404 Not Found", &json!({ "detail": "Not Found" })).await;
+        }
+    }
+    false
+}
+
+/// Bind the OCR server's port and serve canned responses in the background
+pub async fn start() -> Result<(), String> {
+    let listener = TcpListener::bind(ADDRESS)
+        .await
+        .map_err(|e| format!("Failed to bind mock OCR server to {}: {}", ADDRESS, e))?;
+    let responses = Responses::load();
+
+    println!("🧪 Mock OCR server listening on {}", ADDRESS);
+
+    tokio::spawn(async move {
+        let requests = AtomicU64::new(0);
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+            if handle(stream, &responses, &requests).await {
+                println!("⏹️  Mock OCR server stopped");
+                return;
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_growing_exp_readings() {
+        let responses = Responses::GrowingExp;
+        assert_eq!(responses.text(0), "5000000[12.50%]");
+        assert_eq!(responses.text(2), "5002468[12.51%]");
+    }
+
+    #[test]
+    fn test_canned_responses_cycle() {
+        let responses = Responses::Canned(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(responses.text(0), "a");
+        assert_eq!(responses.text(3), "b");
+    }
+
+    #[test]
+    fn test_boxes_stay_inside_image() {
+        let response = ocr_response("EXP 5509611[12.76%]", 200, 20);
+        let boxes = response["boxes"].as_array().unwrap();
+
+        assert_eq!(boxes.len(), 2);
+        assert_eq!(boxes[1]["text"], "5509611[12.76%]");
+        assert_eq!(boxes[0]["box"][0][0], 20.0);
+        assert_eq!(boxes[1]["box"][1][0], 180.0);
+        assert_eq!(response["raw_text"], "EXP 5509611[12.76%]");
+    }
+
+    #[test]
+    fn test_png_size() {
+        let mut png = Vec::new();
+        image::DynamicImage::new_rgb8(7, 3)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        assert_eq!(png_size(&png), Some((7, 3)));
+        assert_eq!(png_size(b"not a png"), None);
+    }
+}
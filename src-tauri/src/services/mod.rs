@@ -1,8 +1,39 @@
+pub mod alert_engine;
+pub mod buffer_pool;
 pub mod config;
+pub mod event_log;
 pub mod exp_calculator;
+pub mod export;
+pub mod expression;
 pub mod hp_potion_calculator;
+pub mod image_payload;
+pub mod latency;
+pub mod level_eta;
+pub mod matching_pool;
+#[cfg(feature = "mock-ocr")]
+pub mod mock_ocr_server;
 pub mod mp_potion_calculator;
+pub mod ocr_failures;
 pub mod screen_capture;
+pub mod session_configs;
+pub mod session_store;
+pub mod shortcuts;
+pub mod shutdown;
+pub mod startup;
 pub mod ocr;
 pub mod ocr_tracker;
+pub mod preview_store;
+pub mod preview_tracking;
+pub mod privacy;
+pub mod progress_ledger;
 pub mod python_server;
+pub mod report;
+pub mod sample_log;
+pub mod self_test;
+pub mod storage;
+pub mod timeline_store;
+pub mod tracker_actor;
+pub mod tray;
+pub mod tracking_control;
+pub mod watchdog;
+pub mod window_state;
@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// MP Potion consumption tracker - completely independent
 pub struct MpPotionCalculator {
@@ -27,6 +27,14 @@ impl MpPotionCalculator {
         self.pending_increase = None;
     }
 
+    /// Exclude time during which nothing could be tracked (e.g. system sleep)
+    pub fn add_paused_time(&mut self, duration: Duration) {
+        // Shifting the start keeps the per-minute rate based on tracked time only
+        if let Some(start) = self.start_time {
+            self.start_time = Some(start + duration);
+        }
+    }
+
     /// Reset tracking
     pub fn reset(&mut self) {
         self.start_time = None;
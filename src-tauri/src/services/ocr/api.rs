@@ -0,0 +1,136 @@
+//! Request and response types of the Python OCR server
+//!
+//! Generated by build.rs from `python_ocr_server/openapi.json`, the API
+//! contract exported from the server (`python_ocr_server/export_openapi.py`).
+//! Change the server's models, re-export the schema, and these follow.
+//! Types the client doesn't use are marked in build.rs (`CLIENT_TYPES`).
+
+include!(concat!(env!("OUT_DIR"), "/ocr_api.rs"));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use serde_json::Value;
+
+    const SCHEMA: &str = include_str!("../../../../python_ocr_server/openapi.json");
+
+    fn spec() -> Value {
+        serde_json::from_str(SCHEMA).unwrap()
+    }
+
+    fn fixture(name: &str) -> Value {
+        let path = format!("{}/tests/fixtures/ocr_api/{}", env!("CARGO_MANIFEST_DIR"), name);
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap()
+    }
+
+    /// Schema of a documented response
+    fn response_schema<'a>(spec: &'a Value, method: &str, path: &str, status: &str) -> &'a Value {
+        let schema = &spec["paths"][path][method]["responses"][status]["content"]["application/json"]["schema"];
+        assert!(!schema.is_null(), "{} {} has no {} response in the contract", method, path, status);
+        schema
+    }
+
+    /// Check `value` against the subset of JSON Schema FastAPI emits
+    fn validate(spec: &Value, schema: &Value, value: &Value, at: &str) -> Result<(), String> {
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name = reference.rsplit('/').next().unwrap_or(reference);
+            return validate(spec, &spec["components"]["schemas"][name], value, at);
+        }
+        if let Some(options) = schema["anyOf"].as_array() {
+            return if options.iter().any(|option| validate(spec, option, value, at).is_ok()) {
+                Ok(())
+            } else {
+                Err(format!("{}: matches none of anyOf", at))
+            };
+        }
+
+        let type_ok = match schema["type"].as_str() {
+            Some("object") => value.is_object(),
+            Some("array") => value.is_array(),
+            Some("string") => value.is_string(),
+            Some("number") => value.is_number(),
+            Some("integer") => value.is_i64() || value.is_u64(),
+            Some("boolean") => value.is_boolean(),
+            _ => true,
+        };
+        if !type_ok {
+            return Err(format!("{}: expected {}, got {}", at, schema["type"], value));
+        }
+
+        if let Some(required) = schema["required"].as_array() {
+            for field in required.iter().filter_map(Value::as_str) {
+                if value.get(field).is_none() {
+                    return Err(format!("{}: missing required field '{}'", at, field));
+                }
+            }
+        }
+        if let Some(properties) = schema["properties"].as_object() {
+            for (field, property) in properties {
+                if let Some(field_value) = value.get(field) {
+                    validate(spec, property, field_value, &format!("{}.{}", at, field))?;
+                }
+            }
+        }
+        if let Some(items) = value.as_array() {
+            for (i, item) in items.iter().enumerate() {
+                validate(spec, &schema["items"], item, &format!("{}[{}]", at, i))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// A recorded response must match the contract and parse into the generated type
+    fn check_contract<T: DeserializeOwned>(method: &str, path: &str, status: &str, fixture_name: &str) -> T {
+        let spec = spec();
+        let value = fixture(fixture_name);
+        validate(&spec, response_schema(&spec, method, path, status), &value, fixture_name)
+            .unwrap_or_else(|e| panic!("{} breaks the contract: {}", fixture_name, e));
+        serde_json::from_value(value).unwrap_or_else(|e| panic!("{} doesn't parse: {}", fixture_name, e))
+    }
+
+    #[test]
+    fn test_recorded_responses_match_contract() {
+        let response: OcrResponse = check_contract("post", "/ocr", "200", "ocr_exp.json");
+        assert_eq!(response.boxes.len(), 2);
+        assert_eq!(response.boxes[1].box_[2], vec![167.0, 18.0]);
+
+        let response: OcrResponse = check_contract("post", "/ocr", "200", "ocr_empty.json");
+        assert!(response.boxes.is_empty());
+
+        let error: ErrorResponse = check_contract("post", "/ocr", "500", "ocr_error.json");
+        assert!(error.detail.starts_with("OCR failed"));
+
+        let error: HTTPValidationError = check_contract("post", "/ocr", "422", "ocr_validation_error.json");
+        assert_eq!(error.detail.unwrap()[0].type_, "missing");
+
+        let health: HealthResponse = check_contract("get", "/health", "200", "health.json");
+        assert_eq!(health.status, "ok");
+
+        let _: ShutdownResponse = check_contract("post", "/shutdown", "200", "shutdown.json");
+    }
+
+    #[test]
+    fn test_contract_rejects_drift() {
+        let spec = spec();
+        let schema = response_schema(&spec, "post", "/ocr", "200");
+
+        let mut renamed = fixture("ocr_exp.json");
+        let text_box = renamed["boxes"][0].as_object_mut().unwrap();
+        let corners = text_box.remove("box").unwrap();
+        text_box.insert("bbox".to_string(), corners);
+        assert!(validate(&spec, schema, &renamed, "response").is_err());
+
+        let mut retyped = fixture("ocr_exp.json");
+        retyped["boxes"][0]["score"] = Value::from("0.99");
+        assert!(validate(&spec, schema, &retyped, "response").is_err());
+    }
+
+    #[test]
+    fn test_request_matches_contract() {
+        let spec = spec();
+        let schema = &spec["paths"]["/ocr"]["post"]["requestBody"]["content"]["application/json"]["schema"];
+        let request = serde_json::to_value(ImageRequest { image_base64: "iVBORw0KGgo=".to_string() }).unwrap();
+        validate(&spec, schema, &request, "request").unwrap();
+    }
+}
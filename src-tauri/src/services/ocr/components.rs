@@ -0,0 +1,193 @@
+use image::GrayImage;
+use std::cell::RefCell;
+
+/// Bounding box of a 4-connected foreground component (inclusive bounds)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentBox {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+    pub pixel_count: u32,
+}
+
+impl ComponentBox {
+    pub fn width(&self) -> u32 {
+        self.right - self.left + 1
+    }
+
+    pub fn height(&self) -> u32 {
+        self.bottom - self.top + 1
+    }
+}
+
+/// Two-pass connected component labeling with union-find
+///
+/// Buffers are kept between calls, so labeling same-sized frames every
+/// tracking cycle does not reallocate.
+#[derive(Default)]
+pub struct ComponentLabeler {
+    labels: Vec<u32>,
+    parent: Vec<u32>,
+    box_index: Vec<u32>,
+}
+
+impl ComponentLabeler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Label 4-connected components of pixels matching `is_foreground`
+    /// Boxes are returned in raster order of each component's first pixel.
+    pub fn label(&mut self, image: &GrayImage, is_foreground: impl Fn(u8) -> bool) -> Vec<ComponentBox> {
+        let (width, height) = image.dimensions();
+        let (w, h) = (width as usize, height as usize);
+        let pixels = image.as_raw();
+
+        self.labels.clear();
+        self.labels.resize(w * h, 0);
+        self.parent.clear();
+        self.parent.push(0); // Label 0 = background
+
+        // Pass 1: provisional labels, recording equivalences
+        for y in 0..h {
+            for x in 0..w {
+                let i = y * w + x;
+                if !is_foreground(pixels[i]) {
+                    continue;
+                }
+
+                let left = if x > 0 { self.labels[i - 1] } else { 0 };
+                let up = if y > 0 { self.labels[i - w] } else { 0 };
+
+                self.labels[i] = match (left, up) {
+                    (0, 0) => {
+                        let label = self.parent.len() as u32;
+                        self.parent.push(label);
+                        label
+                    }
+                    (label, 0) | (0, label) => label,
+                    (a, b) => self.union(a, b),
+                };
+            }
+        }
+
+        // Pass 2: resolve roots and accumulate bounding boxes
+        self.box_index.clear();
+        self.box_index.resize(self.parent.len(), u32::MAX);
+        let mut boxes: Vec<ComponentBox> = Vec::new();
+
+        for y in 0..h {
+            for x in 0..w {
+                let label = self.labels[y * w + x];
+                if label == 0 {
+                    continue;
+                }
+
+                let root = self.find(label) as usize;
+                let (x, y) = (x as u32, y as u32);
+
+                if self.box_index[root] == u32::MAX {
+                    self.box_index[root] = boxes.len() as u32;
+                    boxes.push(ComponentBox { left: x, top: y, right: x, bottom: y, pixel_count: 0 });
+                }
+
+                let component = &mut boxes[self.box_index[root] as usize];
+                component.left = component.left.min(x);
+                component.right = component.right.max(x);
+                component.bottom = y; // Raster order: y never decreases
+                component.pixel_count += 1;
+            }
+        }
+
+        boxes
+    }
+
+    /// Find root label with path halving
+    fn find(&mut self, mut label: u32) -> u32 {
+        while self.parent[label as usize] != label {
+            let grandparent = self.parent[self.parent[label as usize] as usize];
+            self.parent[label as usize] = grandparent;
+            label = grandparent;
+        }
+        label
+    }
+
+    /// Merge two label sets, keeping the smaller root; returns that root
+    fn union(&mut self, a: u32, b: u32) -> u32 {
+        let root_a = self.find(a);
+        let root_b = self.find(b);
+        let (root, child) = if root_a <= root_b { (root_a, root_b) } else { (root_b, root_a) };
+        self.parent[child as usize] = root;
+        root
+    }
+}
+
+thread_local! {
+    // One labeler per worker thread; rayon/tokio blocking threads are reused across cycles
+    static LABELER: RefCell<ComponentLabeler> = RefCell::new(ComponentLabeler::new());
+}
+
+/// Label connected components using this thread's reusable buffers
+pub fn connected_components(image: &GrayImage, is_foreground: impl Fn(u8) -> bool) -> Vec<ComponentBox> {
+    LABELER.with(|labeler| labeler.borrow_mut().label(image, is_foreground))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    fn fill(image: &mut GrayImage, x0: u32, y0: u32, x1: u32, y1: u32) {
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                image.put_pixel(x, y, Luma([255]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_separate_rectangles() {
+        let mut image = GrayImage::new(40, 20);
+        fill(&mut image, 2, 3, 10, 8);
+        fill(&mut image, 20, 1, 30, 15);
+
+        let boxes = connected_components(&image, |v| v == 255);
+        assert_eq!(boxes.len(), 2);
+        assert_eq!(boxes[0], ComponentBox { left: 2, top: 3, right: 10, bottom: 8, pixel_count: 54 });
+        assert_eq!((boxes[1].width(), boxes[1].height()), (11, 15));
+    }
+
+    #[test]
+    fn test_u_shape_merges_into_one_component() {
+        // Two arms only connected at the bottom: needs the union step
+        let mut image = GrayImage::new(20, 20);
+        fill(&mut image, 2, 2, 4, 15);
+        fill(&mut image, 12, 2, 14, 15);
+        fill(&mut image, 2, 15, 14, 17);
+
+        let boxes = connected_components(&image, |v| v == 255);
+        assert_eq!(boxes.len(), 1);
+        assert_eq!((boxes[0].left, boxes[0].top, boxes[0].right, boxes[0].bottom), (2, 2, 14, 17));
+    }
+
+    #[test]
+    fn test_diagonal_pixels_are_not_connected() {
+        let mut image = GrayImage::new(4, 4);
+        image.put_pixel(0, 0, Luma([255]));
+        image.put_pixel(1, 1, Luma([255]));
+
+        assert_eq!(connected_components(&image, |v| v == 255).len(), 2);
+    }
+
+    #[test]
+    fn test_labeler_reuses_buffers_across_sizes() {
+        let mut labeler = ComponentLabeler::new();
+        let mut large = GrayImage::new(50, 50);
+        fill(&mut large, 0, 0, 49, 49);
+        assert_eq!(labeler.label(&large, |v| v == 255).len(), 1);
+
+        let small = GrayImage::new(5, 5);
+        assert!(labeler.label(&small, |v| v == 255).is_empty());
+    }
+}
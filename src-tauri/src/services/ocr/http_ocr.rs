@@ -1,11 +1,17 @@
-use crate::models::ocr_result::{ExpResult, LevelResult};
+use crate::models::ocr_result::{ExpResult, LevelResult, MapResult};
+use crate::models::roi::RoiEdge;
+use crate::services::matching_pool;
+use super::api::{ErrorResponse, HealthResponse, ImageRequest, OcrResponse, TextBox};
+use super::parser;
 use super::template_matcher::TemplateMatcher;
 use image::DynamicImage;
-use serde::{Deserialize, Serialize};
 use base64::{Engine as _, engine::general_purpose};
 use regex::Regex;
 use std::sync::Arc;
 
+/// Requests to the OCR server fail after this long
+pub const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// HTTP OCR client that communicates with Python FastAPI server
 #[derive(Clone)]
 pub struct HttpOcrClient {
@@ -14,32 +20,35 @@ pub struct HttpOcrClient {
     template_matcher: Option<Arc<TemplateMatcher>>,
 }
 
-#[derive(Serialize)]
-struct ImageRequest {
-    image_base64: String,
-}
-
-/// Single text box with bounding box coordinates
-#[derive(Deserialize, Clone, Debug)]
-struct TextBox {
-    #[serde(rename = "box")]
-    bbox: Vec<Vec<f64>>,  // 4 corner points [[x1,y1], [x2,y2], [x3,y3], [x4,y4]]
-    text: String,
-    score: f64,
+/// Text boxes within this many pixels of the left/right image edge are clipped
+const EDGE_MARGIN: f64 = 2.0;
+
+/// EXP reading along with the ROI edges its text runs into
+#[derive(Debug, Clone)]
+pub struct ExpReading {
+    pub result: Result<ExpResult, String>,
+    pub clipped_edges: Vec<RoiEdge>,
+    /// Lowest OCR score among the text boxes; None if no text was found
+    pub confidence: Option<f64>,
+    /// Text as recognized, kept when it couldn't be parsed
+    pub raw_text: String,
 }
 
-/// Unified OCR response from Python server
-#[derive(Deserialize)]
-struct OcrResponse {
-    boxes: Vec<TextBox>,
-    raw_text: String,  // Legacy concatenated text
+/// Level reading and how sure the OCR server was of it
+#[derive(Debug, Clone)]
+pub struct LevelReading {
+    pub result: Result<LevelResult, String>,
+    /// Lowest OCR score among the text boxes; None when read by template matching
+    pub confidence: Option<f64>,
+    /// Text as recognized, kept when it couldn't be parsed
+    pub raw_text: String,
 }
 
 impl TextBox {
     /// Get bounding box as (x_min, y_min, x_max, y_max)
     fn get_bbox_rect(&self) -> (f64, f64, f64, f64) {
-        let xs: Vec<f64> = self.bbox.iter().map(|p| p[0]).collect();
-        let ys: Vec<f64> = self.bbox.iter().map(|p| p[1]).collect();
+        let xs: Vec<f64> = self.box_.iter().map(|p| p[0]).collect();
+        let ys: Vec<f64> = self.box_.iter().map(|p| p[1]).collect();
 
         let x_min = xs.iter().cloned().fold(f64::INFINITY, f64::min);
         let x_max = xs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
@@ -80,7 +89,7 @@ impl TextBox {
 
     /// Get leftmost x-coordinate (for left-to-right sorting)
     fn left_x(&self) -> f64 {
-        self.bbox.iter().map(|p| p[0]).fold(f64::INFINITY, f64::min)
+        self.box_.iter().map(|p| p[0]).fold(f64::INFINITY, f64::min)
     }
 
     /// Get box area
@@ -94,7 +103,7 @@ impl HttpOcrClient {
     /// Create a new HTTP OCR client
     pub fn new() -> Result<Self, String> {
         let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(5))
+            .timeout(REQUEST_TIMEOUT)
             .build()
             .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
@@ -193,11 +202,18 @@ impl HttpOcrClient {
     /// Check if server is healthy
     pub async fn health_check(&self) -> Result<(), String> {
         let url = format!("{}/health", self.base_url);
-        self.client
+        let health: HealthResponse = self.client
             .get(&url)
             .send()
             .await
+            .map_err(|e| format!("Health check failed: {}", e))?
+            .json()
+            .await
             .map_err(|e| format!("Health check failed: {}", e))?;
+
+        if health.status != "ok" {
+            return Err(format!("OCR server ({}) reports status '{}'", health.engine, health.status));
+        }
         Ok(())
     }
 
@@ -213,6 +229,15 @@ impl HttpOcrClient {
     /// Call unified OCR endpoint and get processed text
     /// Returns text after NMS filtering and left-to-right sorting
     async fn recognize_text(&self, image: &DynamicImage) -> Result<String, String> {
+        let boxes = self.request_boxes(image).await?;
+
+        // Process boxes: filter overlapping, sort left-to-right, concatenate
+        let processed_text = Self::process_ocr_boxes(boxes);
+        Ok(processed_text)
+    }
+
+    /// Call unified OCR endpoint and get the raw text boxes
+    async fn request_boxes(&self, image: &DynamicImage) -> Result<Vec<TextBox>, String> {
         let image_base64 = Self::encode_image(image)?;
         let url = format!("{}/ocr", self.base_url);
 
@@ -226,7 +251,11 @@ impl HttpOcrClient {
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("OCR server error: {}", error_text));
+            // Server errors carry {"detail": ...}; anything else is shown as is
+            let detail = serde_json::from_str::<ErrorResponse>(&error_text)
+                .map(|error| error.detail)
+                .unwrap_or(error_text);
+            return Err(format!("OCR server error: {}", detail));
         }
 
         let data: OcrResponse = response
@@ -234,9 +263,19 @@ impl HttpOcrClient {
             .await
             .map_err(|e| format!("Failed to parse response: {}", e))?;
 
-        // Process boxes: filter overlapping, sort left-to-right, concatenate
-        let processed_text = Self::process_ocr_boxes(data.boxes);
-        Ok(processed_text)
+        Ok(data.boxes)
+    }
+
+    /// Join words of a text label (e.g. map name) left-to-right, keeping word spacing
+    fn join_words(boxes: Vec<TextBox>) -> String {
+        let mut filtered = Self::filter_overlapping_boxes(boxes, 0.3);
+        filtered.sort_by(|a, b| a.left_x().partial_cmp(&b.left_x()).unwrap_or(std::cmp::Ordering::Equal));
+
+        filtered.iter()
+            .map(|b| b.text.trim())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
     }
 
     /// Parse level from OCR text
@@ -260,8 +299,8 @@ impl HttpOcrClient {
 
     /// Parse EXP from OCR text
     fn parse_exp(text: &str) -> Result<(u64, f64), String> {
-        // Remove "EXP" prefix, spaces, and commas
-        let cleaned = text.replace("EXP", "").replace(" ", "").replace(",", "");
+        // Remove "EXP" prefix and thousands separators (client-language aware)
+        let cleaned = parser::normalize_numbers(&text.replace("EXP", ""), parser::decimal_separator());
 
         // Extract absolute value and percentage: "1234567[12.34%]" or "1234567[12.34]"
         let re = Regex::new(r"(\d+)\[?([\d.]+)%?\]?")
@@ -309,8 +348,18 @@ impl HttpOcrClient {
             .map_err(|e| format!("Failed to parse MP potion count '{}': {}", digits, e))
     }
 
+    /// Lowest score among `boxes` (the weakest part of the text)
+    fn confidence(boxes: &[TextBox]) -> Option<f64> {
+        boxes.iter().map(|b| b.score).reduce(f64::min)
+    }
+
     /// Recognize level from image using template matching (with RapidOCR fallback)
     pub async fn recognize_level(&self, image: &DynamicImage) -> Result<LevelResult, String> {
+        self.read_level(image).await?.result
+    }
+
+    /// Recognize level and report the OCR confidence; only a failed OCR request is an `Err`
+    pub async fn read_level(&self, image: &DynamicImage) -> Result<LevelReading, String> {
         // Try template matching first if available
         if let Some(matcher) = &self.template_matcher {
             let matcher = Arc::clone(matcher);
@@ -318,13 +367,17 @@ impl HttpOcrClient {
 
             // Run blocking template matching in dedicated thread pool
             let result = tokio::task::spawn_blocking(move || {
-                matcher.recognize_level(&image)
+                matching_pool::install(|| matcher.recognize_level(&image))
             }).await.map_err(|e| format!("Template matching task failed: {}", e))?;
 
             match result {
                 Ok(level) => {
-                    return Ok(LevelResult {
-                        level,
+                    return Ok(LevelReading {
+                        result: Ok(LevelResult {
+                            level,
+                            raw_text: format!("LV. {}", level),
+                        }),
+                        confidence: None,
                         raw_text: format!("LV. {}", level),
                     });
                 }
@@ -335,25 +388,68 @@ impl HttpOcrClient {
         }
 
         // Fall back to RapidOCR
-        let text = self.recognize_text(image).await?;
-        let level = Self::parse_level(&text)?;
-
-        Ok(LevelResult {
+        let boxes = self.request_boxes(image).await?;
+        let confidence = Self::confidence(&boxes);
+        let text = Self::process_ocr_boxes(boxes);
+        let result = Self::parse_level(&text).map(|level| LevelResult {
             level,
             raw_text: format!("LV. {}", level),
-        })
+        });
+
+        Ok(LevelReading { result, confidence, raw_text: text })
     }
 
     /// Recognize EXP from image
     pub async fn recognize_exp(&self, image: &DynamicImage) -> Result<ExpResult, String> {
-        let text = self.recognize_text(image).await?;
-        let (absolute, percentage) = Self::parse_exp(&text)?;
+        self.read_exp(image).await?.result
+    }
+
+    /// Recognize EXP and report text touching the image's left/right edge
+    /// (a clipped number); only a failed OCR request is an `Err`
+    pub async fn read_exp(&self, image: &DynamicImage) -> Result<ExpReading, String> {
+        let boxes = self.request_boxes(image).await?;
+        let clipped_edges = Self::clipped_edges(&boxes, image.width() as f64);
+        let confidence = Self::confidence(&boxes);
 
-        Ok(ExpResult {
+        let text = Self::process_ocr_boxes(boxes);
+        let result = Self::parse_exp(&text).map(|(absolute, percentage)| ExpResult {
             absolute,
             percentage,
-            raw_text: text,
-        })
+            raw_text: text.clone(),
+        });
+
+        Ok(ExpReading { result, clipped_edges, confidence, raw_text: text })
+    }
+
+    /// Image edges that some text box touches
+    fn clipped_edges(boxes: &[TextBox], image_width: f64) -> Vec<RoiEdge> {
+        let mut edges = Vec::new();
+        if boxes.iter().any(|b| b.get_bbox_rect().0 <= EDGE_MARGIN) {
+            edges.push(RoiEdge::Left);
+        }
+        if boxes.iter().any(|b| b.get_bbox_rect().2 >= image_width - EDGE_MARGIN) {
+            edges.push(RoiEdge::Right);
+        }
+        edges
+    }
+
+    /// Recognize map name from a map name ROI image
+    pub async fn recognize_map_name(&self, image: &DynamicImage) -> Result<MapResult, String> {
+        let boxes = self.request_boxes(image).await?;
+        let raw_text = boxes.iter().map(|b| b.text.as_str()).collect::<Vec<_>>().join(" ");
+        let map_name = Self::join_words(boxes);
+
+        if map_name.is_empty() {
+            return Err("No text found in map name region".to_string());
+        }
+
+        Ok(MapResult { map_name, raw_text })
+    }
+
+    /// Recognize the words of an arbitrary label, joined left-to-right
+    pub async fn recognize_words(&self, image: &DynamicImage) -> Result<String, String> {
+        let boxes = self.request_boxes(image).await?;
+        Ok(Self::join_words(boxes))
     }
 
     /// Recognize HP potion count from image
@@ -368,3 +464,34 @@ impl HttpOcrClient {
         Self::parse_mp_potion_count(&text)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_box(x_min: f64, x_max: f64) -> TextBox {
+        TextBox {
+            box_: vec![vec![x_min, 2.0], vec![x_max, 2.0], vec![x_max, 18.0], vec![x_min, 18.0]],
+            text: "1234".to_string(),
+            score: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_confidence_is_lowest_score() {
+        let mut boxes = vec![text_box(10.0, 40.0), text_box(50.0, 90.0)];
+        boxes[1].score = 0.6;
+        assert_eq!(HttpOcrClient::confidence(&boxes), Some(0.6));
+        assert_eq!(HttpOcrClient::confidence(&[]), None);
+    }
+
+    #[test]
+    fn test_clipped_edges() {
+        assert!(HttpOcrClient::clipped_edges(&[text_box(10.0, 90.0)], 100.0).is_empty());
+        assert_eq!(HttpOcrClient::clipped_edges(&[text_box(1.0, 90.0)], 100.0), vec![RoiEdge::Left]);
+        assert_eq!(
+            HttpOcrClient::clipped_edges(&[text_box(0.0, 40.0), text_box(50.0, 99.5)], 100.0),
+            vec![RoiEdge::Left, RoiEdge::Right]
+        );
+    }
+}
@@ -1,4 +1,9 @@
-use image::{DynamicImage, GrayImage, ImageBuffer, Luma, imageops};
+use crate::models::config::MAX_GRID_SIZE;
+use crate::models::slot::SlotId;
+use super::components::connected_components;
+use crate::services::buffer_pool;
+use image::{DynamicImage, GrayImage, ImageBuffer, Luma, RgbImage, imageops};
+use serde::Serialize;
 use std::path::Path;
 use std::collections::HashMap;
 use rayon::prelude::*;
@@ -21,7 +26,7 @@ pub struct SlotRoi {
 }
 
 /// Detection result for a single digit
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DigitDetection {
     pub digit: u8,
     pub x: u32,
@@ -32,10 +37,108 @@ pub struct DigitDetection {
     pub scale: f32,
 }
 
+/// Fraction of non-background pixels above which a slot is considered to hold an item icon
+const ICON_PIXEL_RATIO: f32 = 0.08;
+
+/// Normalized width of one item grid column (matches a quickslot column)
+const GRID_CELL_WIDTH: u32 = 130;
+
+/// Minimum histogram similarity for a slot icon to count as a potion match
+const MIN_ICON_SIMILARITY: f32 = 0.5;
+
+/// Minimum channel spread for a pixel to be treated as icon color
+/// Slot backgrounds and count digits are grey/white/black and get ignored.
+const ICON_MIN_SATURATION: u8 = 40;
+
+/// Color quantization levels per channel for icon histograms
+const ICON_HISTOGRAM_LEVELS: usize = 4;
+
+/// Kind of potion recognized from a slot icon
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PotionKind {
+    Hp,
+    Mp,
+}
+
+/// Potion icon template stored as a normalized color histogram
+#[derive(Debug, Clone)]
+struct PotionIconTemplate {
+    kind: PotionKind,
+    histogram: Vec<f32>,
+}
+
+/// Quickslots identified as holding HP / MP potions
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct PotionSlotMatch {
+    pub hp_slot: Option<SlotId>,
+    pub mp_slot: Option<SlotId>,
+    pub hp_score: f32,
+    pub mp_score: f32,
+}
+
+impl PotionSlotMatch {
+    /// Both potions were found
+    pub fn is_complete(&self) -> bool {
+        self.hp_slot.is_some() && self.mp_slot.is_some()
+    }
+}
+
+/// Classification of a slot's contents
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SlotState {
+    /// No item icon and no digits
+    Empty,
+    /// Item present and count recognized
+    Occupied,
+    /// Item icon present (or detection broken) but count could not be read
+    Unreadable,
+}
+
+/// Result of reading the item count in a single slot
+///
+/// `failure` is set when the count could not be read, so callers can tell a
+/// broken detection apart from a genuinely empty slot (count 0, no failure).
+#[derive(Debug, Clone, Serialize)]
+pub struct SlotReading {
+    pub state: SlotState,
+    pub count: u32,
+    /// Weakest digit match score (0.0 when no digits were detected)
+    pub confidence: f32,
+    /// Detected digits, sorted left to right
+    pub digits: Vec<DigitDetection>,
+    pub failure: Option<String>,
+}
+
+impl SlotReading {
+    /// Reading for a slot whose count could not be determined
+    pub fn failed(reason: impl Into<String>) -> Self {
+        Self {
+            state: SlotState::Unreadable,
+            count: 0,
+            confidence: 0.0,
+            digits: Vec::new(),
+            failure: Some(reason.into()),
+        }
+    }
+
+    /// Whether the count is trustworthy
+    pub fn is_readable(&self) -> bool {
+        self.state != SlotState::Unreadable
+    }
+
+    /// Count if readable, None otherwise
+    pub fn count(&self) -> Option<u32> {
+        if self.is_readable() { Some(self.count) } else { None }
+    }
+}
+
 /// Inventory template matcher for potion counting
 pub struct InventoryTemplateMatcher {
     templates: Vec<InventoryTemplate>,
-    slot_rois: HashMap<String, SlotRoi>,
+    icon_templates: Vec<PotionIconTemplate>,
+    slot_rois: HashMap<SlotId, SlotRoi>,
 }
 
 impl InventoryTemplateMatcher {
@@ -43,26 +146,27 @@ impl InventoryTemplateMatcher {
     pub fn new() -> Self {
         Self {
             templates: Vec::new(),
+            icon_templates: Vec::new(),
             slot_rois: Self::init_slot_rois(),
         }
     }
 
     /// Initialize slot ROI mappings
     /// Based on 522x255px inventory image with 4x2 grid layout
-    fn init_slot_rois() -> HashMap<String, SlotRoi> {
+    fn init_slot_rois() -> HashMap<SlotId, SlotRoi> {
         let mut rois = HashMap::new();
 
         // Row 0 (top row): y=64-125 (height=61)
-        rois.insert("shift".to_string(), SlotRoi { x: 0,   y: 64,  width: 130, height: 61 });
-        rois.insert("ins".to_string(),   SlotRoi { x: 130, y: 64,  width: 131, height: 61 });
-        rois.insert("home".to_string(),  SlotRoi { x: 261, y: 64,  width: 130, height: 61 });
-        rois.insert("pup".to_string(),   SlotRoi { x: 391, y: 64,  width: 130, height: 61 });
+        rois.insert(SlotId::Shift, SlotRoi { x: 0,   y: 64,  width: 130, height: 61 });
+        rois.insert(SlotId::Ins,   SlotRoi { x: 130, y: 64,  width: 131, height: 61 });
+        rois.insert(SlotId::Home,  SlotRoi { x: 261, y: 64,  width: 130, height: 61 });
+        rois.insert(SlotId::Pup,   SlotRoi { x: 391, y: 64,  width: 130, height: 61 });
 
         // Row 1 (bottom row): y=196-254 (height=58)
-        rois.insert("ctrl".to_string(),  SlotRoi { x: 0,   y: 196, width: 130, height: 58 });
-        rois.insert("del".to_string(),   SlotRoi { x: 130, y: 196, width: 131, height: 58 });
-        rois.insert("end".to_string(),   SlotRoi { x: 261, y: 196, width: 130, height: 58 });
-        rois.insert("pdn".to_string(),   SlotRoi { x: 391, y: 196, width: 130, height: 58 });
+        rois.insert(SlotId::Ctrl,  SlotRoi { x: 0,   y: 196, width: 130, height: 58 });
+        rois.insert(SlotId::Del,   SlotRoi { x: 130, y: 196, width: 131, height: 58 });
+        rois.insert(SlotId::End,   SlotRoi { x: 261, y: 196, width: 130, height: 58 });
+        rois.insert(SlotId::Pdn,   SlotRoi { x: 391, y: 196, width: 130, height: 58 });
 
         rois
     }
@@ -121,6 +225,188 @@ impl InventoryTemplateMatcher {
         Ok(())
     }
 
+    /// Load potion icon templates from directory
+    /// Files must be named `hp_<name>.png` or `mp_<name>.png`
+    pub fn load_icon_templates<P: AsRef<Path>>(&mut self, icon_dir: P) -> Result<(), String> {
+        let icon_dir = icon_dir.as_ref();
+
+        if !icon_dir.exists() {
+            return Err(format!("Icon directory not found: {:?}", icon_dir));
+        }
+
+        let entries = std::fs::read_dir(icon_dir)
+            .map_err(|e| format!("Failed to read icon directory: {}", e))?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+            let path = entry.path();
+
+            if path.extension().and_then(|s| s.to_str()) != Some("png") {
+                continue;
+            }
+
+            let Some(filename) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+
+            let kind = if filename.starts_with("hp_") {
+                PotionKind::Hp
+            } else if filename.starts_with("mp_") {
+                PotionKind::Mp
+            } else {
+                continue;
+            };
+
+            let img = image::open(&path)
+                .map_err(|e| format!("Failed to load icon {:?}: {}", path, e))?;
+            self.add_icon_template(kind, &img.to_rgb8());
+        }
+
+        if self.icon_templates.is_empty() {
+            return Err("No potion icon templates loaded".to_string());
+        }
+
+        #[cfg(debug_assertions)]
+        println!("✅ Loaded {} potion icon templates", self.icon_templates.len());
+
+        Ok(())
+    }
+
+    /// Register a potion icon template
+    fn add_icon_template(&mut self, kind: PotionKind, icon: &RgbImage) {
+        if let Some(histogram) = Self::icon_histogram(icon) {
+            self.icon_templates.push(PotionIconTemplate {
+                kind,
+                histogram,
+            });
+        }
+    }
+
+    /// Whether potion icons can be identified
+    pub fn has_icon_templates(&self) -> bool {
+        !self.icon_templates.is_empty()
+    }
+
+    /// Identify which quickslots hold HP and MP potions by icon matching
+    /// `coords` is the inventory region returned by `detect_inventory_region_with_coords`
+    pub fn identify_potion_slots(&self, image: &DynamicImage, coords: (u32, u32, u32, u32)) -> Result<PotionSlotMatch, String> {
+        if self.icon_templates.is_empty() {
+            return Err("No potion icon templates loaded".to_string());
+        }
+
+        let inventory = Self::normalized_inventory_color(image, coords);
+
+        // Best (slot, score) per potion kind, sorted by score descending
+        let mut hp_candidates = Vec::new();
+        let mut mp_candidates = Vec::new();
+
+        for (slot, roi) in &self.slot_rois {
+            let icon = imageops::crop_imm(&inventory, roi.x, roi.y, roi.width, roi.height).to_image();
+            let Some(histogram) = Self::icon_histogram(&icon) else {
+                continue;
+            };
+
+            for template in &self.icon_templates {
+                let score = Self::histogram_similarity(&histogram, &template.histogram);
+                if score < MIN_ICON_SIMILARITY {
+                    continue;
+                }
+
+                #[cfg(debug_assertions)]
+                println!("🧪 Slot {} matches {:?} potion icon ({:.2})", slot, template.kind, score);
+
+                match template.kind {
+                    PotionKind::Hp => hp_candidates.push((*slot, score)),
+                    PotionKind::Mp => mp_candidates.push((*slot, score)),
+                }
+            }
+        }
+
+        hp_candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        mp_candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Assign the stronger match first so HP and MP never share a slot
+        let hp_best = hp_candidates.first().map(|c| c.1).unwrap_or(0.0);
+        let mp_best = mp_candidates.first().map(|c| c.1).unwrap_or(0.0);
+
+        let mut result = PotionSlotMatch::default();
+        if hp_best >= mp_best {
+            if let Some((slot, score)) = hp_candidates.first() {
+                result.hp_slot = Some(*slot);
+                result.hp_score = *score;
+            }
+            if let Some((slot, score)) = mp_candidates.iter().find(|c| Some(&c.0) != result.hp_slot.as_ref()) {
+                result.mp_slot = Some(*slot);
+                result.mp_score = *score;
+            }
+        } else {
+            if let Some((slot, score)) = mp_candidates.first() {
+                result.mp_slot = Some(*slot);
+                result.mp_score = *score;
+            }
+            if let Some((slot, score)) = hp_candidates.iter().find(|c| Some(&c.0) != result.mp_slot.as_ref()) {
+                result.hp_slot = Some(*slot);
+                result.hp_score = *score;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Crop a slot out of the original color screenshot
+    /// Used for UI thumbnails; coordinates match the binarized OCR image
+    pub fn crop_slot_image(&self, image: &DynamicImage, coords: (u32, u32, u32, u32), slot: SlotId) -> Result<RgbImage, String> {
+        let roi = self.slot_rois.get(&slot)
+            .ok_or_else(|| format!("Invalid slot: {}", slot))?;
+
+        let inventory = Self::normalized_inventory_color(image, coords);
+        Ok(imageops::crop_imm(&inventory, roi.x, roi.y, roi.width, roi.height).to_image())
+    }
+
+    /// Crop the inventory FROM THE ORIGINAL COLOR image and normalize to 522x255
+    /// so the slot ROIs line up with the binarized OCR image
+    fn normalized_inventory_color(image: &DynamicImage, coords: (u32, u32, u32, u32)) -> RgbImage {
+        let (left, top, right, bottom) = coords;
+        let cropped = image.crop_imm(left, top, right - left + 1, bottom - top + 1).to_rgb8();
+        imageops::resize(&cropped, 522, 255, imageops::FilterType::Nearest)
+    }
+
+    /// Normalized color histogram over saturated (icon) pixels
+    /// Returns None when the image has too few colored pixels to hold an icon
+    fn icon_histogram(image: &RgbImage) -> Option<Vec<f32>> {
+        let levels = ICON_HISTOGRAM_LEVELS;
+        let mut histogram = vec![0f32; levels * levels * levels];
+        let mut counted = 0u32;
+
+        for pixel in image.pixels() {
+            let [r, g, b] = pixel.0;
+            let max = r.max(g).max(b);
+            let min = r.min(g).min(b);
+            if max - min < ICON_MIN_SATURATION {
+                continue;
+            }
+
+            let bin = |v: u8| v as usize * levels / 256;
+            histogram[(bin(r) * levels + bin(g)) * levels + bin(b)] += 1.0;
+            counted += 1;
+        }
+
+        let total = image.pixels().len() as f32;
+        if total == 0.0 || (counted as f32 / total) < ICON_PIXEL_RATIO {
+            return None;
+        }
+
+        for value in histogram.iter_mut() {
+            *value /= counted as f32;
+        }
+        Some(histogram)
+    }
+
+    /// Histogram intersection (1.0 = identical color distribution)
+    fn histogram_similarity(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x.min(*y)).sum()
+    }
+
     /// Detect inventory region from full screenshot with debug info
     /// Returns (inventory_image, (left, top, right, bottom))
     pub fn detect_inventory_region_with_coords(&self, image: &DynamicImage) -> Result<(DynamicImage, (u32, u32, u32, u32)), String> {
@@ -128,24 +414,24 @@ impl InventoryTemplateMatcher {
         let gray = image.to_luma8();
         let (width, height) = gray.dimensions();
 
-        // Step 2: Binarization (threshold 70) - parallel processing
+        // Step 2: Binarization (threshold 70) - parallel processing into a pooled buffer
+        let pool = buffer_pool::global();
         let gray_data = gray.as_raw();
-        let binary_data: Vec<u8> = gray_data
-            .par_iter()
-            .map(|&pixel| {
-                if pixel < 70 {
-                    255u8
-                } else {
-                    0u8
-                }
-            })
-            .collect();
+        let mut binary_data = pool.take(gray_data.len());
+        binary_data
+            .par_iter_mut()
+            .zip(gray_data.par_iter())
+            .for_each(|(out, &pixel)| {
+                *out = if pixel < 70 { 255u8 } else { 0u8 };
+            });
 
         let binary = GrayImage::from_raw(width, height, binary_data)
             .ok_or("Failed to create binary image from parallel processing")?;
 
         // Step 3: Find candidate regions via connected components (morphology removed for speed)
-        let candidates = self.find_candidate_regions(&binary)?;
+        let candidates = self.find_candidate_regions(&binary);
+        pool.give(binary.into_raw());
+        let candidates = candidates?;
 
         if candidates.is_empty() {
             return Err("No inventory region candidates found".to_string());
@@ -172,17 +458,23 @@ impl InventoryTemplateMatcher {
         );
 
         // Step 7: Final threshold for OCR (threshold 1, same as Python line 186)
-        // Dark pixels (< 1) become white (255)
-        let final_binary = ImageBuffer::from_fn(522, 255, |x, y| {
-            let pixel = resized_gray.get_pixel(x, y);
+        let final_binary = Self::binarize_for_ocr(&resized_gray);
+
+        Ok((DynamicImage::ImageLuma8(final_binary), (*left, *top, *right, *bottom)))
+    }
+
+    /// Final OCR threshold shared by all layouts
+    /// Dark pixels (< 1) become white (255)
+    fn binarize_for_ocr(gray: &GrayImage) -> GrayImage {
+        let (width, height) = gray.dimensions();
+        ImageBuffer::from_fn(width, height, |x, y| {
+            let pixel = gray.get_pixel(x, y);
             if pixel[0] < 1 {
                 Luma([255u8])  // Dark pixels → white
             } else {
                 Luma([0u8])    // Bright pixels → black
             }
-        });
-
-        Ok((DynamicImage::ImageLuma8(final_binary), (*left, *top, *right, *bottom)))
+        })
     }
 
     /// Detect inventory region from full screenshot
@@ -261,106 +553,80 @@ impl InventoryTemplateMatcher {
 
     /// Find candidate regions using connected components
     fn find_candidate_regions(&self, binary: &GrayImage) -> Result<Vec<(u32, u32, u32, u32)>, String> {
-        let (width, height) = binary.dimensions();
-        let mut visited = vec![vec![false; width as usize]; height as usize];
         let mut candidates = Vec::new();
 
-        for y in 0..height {
-            for x in 0..width {
-                if binary.get_pixel(x, y)[0] == 255 && !visited[y as usize][x as usize] {
-                    let component = self.flood_fill(binary, &mut visited, x, y);
-
-                    if component.is_empty() {
-                        continue;
-                    }
+        for component in connected_components(binary, |pixel| pixel == 255) {
+            let comp_width = component.width();
+            let comp_height = component.height();
 
-                    // Calculate bounding box
-                    let left = component.iter().map(|(x, _)| *x).min().unwrap();
-                    let right = component.iter().map(|(x, _)| *x).max().unwrap();
-                    let top = component.iter().map(|(_, y)| *y).min().unwrap();
-                    let bottom = component.iter().map(|(_, y)| *y).max().unwrap();
-
-                    let comp_width = right - left + 1;
-                    let comp_height = bottom - top + 1;
-
-                    // Filter by size (150-600 width, 80-400 height)
-                    if comp_width < 150 || comp_width > 600 {
-                        continue;
-                    }
-                    if comp_height < 80 || comp_height > 400 {
-                        continue;
-                    }
-
-                    // Filter by aspect ratio (1.5-2.5)
-                    let ratio = comp_width as f32 / comp_height as f32;
-                    if ratio < 1.5 || ratio > 2.5 {
-                        continue;
-                    }
-
-                    candidates.push((left, top, right, bottom));
-                }
+            // Filter by size (150-600 width, 80-400 height)
+            if comp_width < 150 || comp_width > 600 {
+                continue;
             }
-        }
-
-        Ok(candidates)
-    }
-
-    /// Flood fill to find connected component
-    fn flood_fill(&self, binary: &GrayImage, visited: &mut Vec<Vec<bool>>, start_x: u32, start_y: u32) -> Vec<(u32, u32)> {
-        let (width, height) = binary.dimensions();
-        let mut stack = vec![(start_x, start_y)];
-        let mut component = Vec::new();
-
-        while let Some((x, y)) = stack.pop() {
-            if x >= width || y >= height || visited[y as usize][x as usize] {
+            if comp_height < 80 || comp_height > 400 {
                 continue;
             }
 
-            if binary.get_pixel(x, y)[0] != 255 {
+            // Filter by aspect ratio (1.5-2.5)
+            let ratio = comp_width as f32 / comp_height as f32;
+            if ratio < 1.5 || ratio > 2.5 {
                 continue;
             }
 
-            visited[y as usize][x as usize] = true;
-            component.push((x, y));
-
-            // Add 4-connected neighbors
-            if x > 0 { stack.push((x - 1, y)); }
-            if x < width - 1 { stack.push((x + 1, y)); }
-            if y > 0 { stack.push((x, y - 1)); }
-            if y < height - 1 { stack.push((x, y + 1)); }
+            candidates.push((component.left, component.top, component.right, component.bottom));
         }
 
-        component
+        Ok(candidates)
     }
 
     /// Recognize potion count in specific slot
-    pub fn recognize_count_in_slot(&self, inventory_image: &DynamicImage, slot: &str) -> Result<u32, String> {
-        #[cfg(debug_assertions)]
-        let _t_start = std::time::Instant::now();
+    pub fn recognize_count_in_slot(&self, inventory_image: &DynamicImage, slot: SlotId) -> Result<u32, String> {
+        let reading = self.read_slot(inventory_image, slot);
+        match reading.failure {
+            Some(reason) => Err(reason),
+            None => Ok(reading.count),
+        }
+    }
 
+    /// Read the item count in a specific slot with per-digit diagnostics
+    pub fn read_slot(&self, inventory_image: &DynamicImage, slot: SlotId) -> SlotReading {
         // Get ROI for slot
-        let roi = self.slot_rois.get(slot)
-            .ok_or(format!("Invalid slot: {}", slot))?;
+        let roi = match self.slot_rois.get(&slot) {
+            Some(roi) => roi,
+            None => return SlotReading::failed(format!("Invalid slot: {}", slot)),
+        };
 
         // Convert to grayscale
         let gray = inventory_image.to_luma8();
 
         // Verify inventory image size
         if gray.width() != 522 || gray.height() != 255 {
-            return Err(format!("Invalid inventory size: {}x{} (expected 522x255)", gray.width(), gray.height()));
+            return SlotReading::failed(format!("Invalid inventory size: {}x{} (expected 522x255)", gray.width(), gray.height()));
         }
 
-        #[cfg(debug_assertions)]
-        let _t_prep = std::time::Instant::now();
+        self.read_slot_roi(&gray, roi, slot.as_str())
+    }
 
+    /// Read the item count inside a slot ROI of a binarized inventory image
+    fn read_slot_roi(&self, gray: &GrayImage, roi: &SlotRoi, slot: &str) -> SlotReading {
         // Detect digits in ROI
-        let detections = self.detect_digits_in_roi(&gray, roi)?;
-
-        #[cfg(debug_assertions)]
-        let _t_detect = std::time::Instant::now();
+        let detections = match self.detect_digits_in_roi(gray, roi) {
+            Ok(detections) => detections,
+            Err(e) => return SlotReading::failed(e),
+        };
 
         if detections.is_empty() {
-            return Ok(0); // Empty slot
+            // No digits: either a genuinely empty slot, or an item whose count we missed
+            if self.has_item_icon(gray, roi) {
+                return SlotReading::failed("Item icon present but count unreadable");
+            }
+            return SlotReading {
+                state: SlotState::Empty,
+                count: 0,
+                confidence: 0.0,
+                digits: Vec::new(),
+                failure: None,
+            };
         }
 
         // Sort detections left to right
@@ -373,31 +639,62 @@ impl InventoryTemplateMatcher {
             println!("  [{}] digit={}, x={}, score={:.3}, scale={:.2}", i, d.digit, d.x, d.score, d.scale);
         }
 
+        let confidence = sorted.iter().map(|d| d.score).fold(f32::INFINITY, f32::min);
+
         // Concatenate digits to form number
         let number_str: String = sorted.iter().map(|d| d.digit.to_string()).collect();
-        let count = number_str.parse::<u32>()
-            .map_err(|e| format!("Failed to parse potion count: {}", e))?;
+        let count = match number_str.parse::<u32>() {
+            Ok(count) => count,
+            Err(e) => {
+                return SlotReading {
+                    state: SlotState::Unreadable,
+                    count: 0,
+                    confidence,
+                    digits: sorted,
+                    failure: Some(format!("Failed to parse potion count: {}", e)),
+                };
+            }
+        };
 
         println!("🔍 [{}] Final result: \"{}\" → {}", slot.to_uppercase(), number_str, count);
 
-        Ok(count)
+        SlotReading {
+            state: SlotState::Occupied,
+            count,
+            confidence,
+            digits: sorted,
+            failure: None,
+        }
+    }
+
+    /// Check whether a slot holds an item icon
+    /// Empty slots are nearly uniform, so count pixels that differ from the slot's
+    /// dominant (background) value - this works regardless of binarization polarity.
+    fn has_item_icon(&self, gray: &GrayImage, roi: &SlotRoi) -> bool {
+        let slot = imageops::crop_imm(gray, roi.x, roi.y, roi.width, roi.height).to_image();
+
+        let mut histogram = [0u32; 256];
+        for pixel in slot.pixels() {
+            histogram[pixel[0] as usize] += 1;
+        }
+
+        let total = slot.pixels().len() as f32;
+        if total == 0.0 {
+            return false;
+        }
+
+        let background = histogram.iter().copied().max().unwrap_or(0) as f32;
+        (total - background) / total > ICON_PIXEL_RATIO
     }
 
     /// Recognize counts in all 8 inventory slots
-    /// Returns HashMap with slot names as keys and item counts as values
-    pub fn recognize_all_slots(&self, inventory_image: &DynamicImage) -> Result<HashMap<String, u32>, String> {
-        let slots = vec![
-            "shift".to_string(), "ins".to_string(), "home".to_string(), "pup".to_string(),
-            "ctrl".to_string(), "del".to_string(), "end".to_string(), "pdn".to_string()
-        ];
-        self.recognize_specific_slots(inventory_image, &slots)
+    /// Returns HashMap with slot names as keys and slot readings as values
+    pub fn recognize_all_slots(&self, inventory_image: &DynamicImage) -> Result<HashMap<SlotId, SlotReading>, String> {
+        self.recognize_specific_slots(inventory_image, &SlotId::ALL)
     }
 
     /// Recognize counts in specific slots
-    pub fn recognize_specific_slots(&self, inventory_image: &DynamicImage, slots: &[String]) -> Result<HashMap<String, u32>, String> {
-        #[cfg(debug_assertions)]
-        let _t_start = std::time::Instant::now();
-
+    pub fn recognize_specific_slots(&self, inventory_image: &DynamicImage, slots: &[SlotId]) -> Result<HashMap<SlotId, SlotReading>, String> {
         // Verify inventory image size
         let gray = inventory_image.to_luma8();
         if gray.width() != 522 || gray.height() != 255 {
@@ -406,10 +703,56 @@ impl InventoryTemplateMatcher {
 
         let mut results = HashMap::new();
 
-        for slot in slots {
-            // Recognize count in this slot, default to 0 if recognition fails
-            let count = self.recognize_count_in_slot(inventory_image, slot).unwrap_or(0);
-            results.insert(slot.to_string(), count);
+        for &slot in slots {
+            results.insert(slot, self.read_slot(inventory_image, slot));
+        }
+
+        Ok(results)
+    }
+
+    /// Recognize counts in cells of the regular item inventory window
+    /// `grid_image` must cover exactly the slot grid; cells are row-major indices.
+    /// Returns HashMap with cell indices as keys and slot readings as values
+    pub fn recognize_grid_slots(&self, grid_image: &DynamicImage, rows: u32, columns: u32, cells: &[u32]) -> Result<HashMap<u32, SlotReading>, String> {
+        if rows == 0 || columns == 0 {
+            return Err("Item grid must have at least one row and column".to_string());
+        }
+        // Bounded like `PotionConfig::validate`, so the scaled image stays small
+        let too_large = || format!("Item grid too large: {}x{} cells", columns, rows);
+        if rows > MAX_GRID_SIZE || columns > MAX_GRID_SIZE {
+            return Err(too_large());
+        }
+        let cell_count = rows.checked_mul(columns).ok_or_else(too_large)?;
+        let target_width = columns.checked_mul(GRID_CELL_WIDTH).ok_or_else(too_large)?;
+
+        let gray = grid_image.to_luma8();
+        let (width, height) = gray.dimensions();
+        if width < columns || height < rows {
+            return Err(format!("Item grid image too small: {}x{} for {}x{} cells", width, height, columns, rows));
+        }
+
+        // Normalize so each column is as wide as a quickslot column (522 / 4),
+        // keeping digits at the scale the templates were cut from
+        let target_height = ((height as f32 * target_width as f32 / width as f32).round() as u32).max(rows);
+        let resized = imageops::resize(&gray, target_width, target_height, imageops::FilterType::Nearest);
+        let binary = Self::binarize_for_ocr(&resized);
+
+        let cell_height = target_height / rows;
+        let mut results = HashMap::new();
+
+        for &cell in cells {
+            if cell >= cell_count {
+                results.insert(cell, SlotReading::failed(format!("Invalid grid cell: {}", cell)));
+                continue;
+            }
+
+            let roi = SlotRoi {
+                x: (cell % columns) * GRID_CELL_WIDTH,
+                y: (cell / columns) * cell_height,
+                width: GRID_CELL_WIDTH,
+                height: cell_height,
+            };
+            results.insert(cell, self.read_slot_roi(&binary, &roi, &format!("grid {}", cell)));
         }
 
         Ok(results)
@@ -673,8 +1016,8 @@ impl InventoryTemplateMatcher {
     }
 
     /// Get available slot names
-    pub fn get_available_slots(&self) -> Vec<String> {
-        let mut slots: Vec<String> = self.slot_rois.keys().cloned().collect();
+    pub fn get_available_slots(&self) -> Vec<SlotId> {
+        let mut slots: Vec<SlotId> = self.slot_rois.keys().copied().collect();
         slots.sort();
         slots
     }
@@ -690,8 +1033,97 @@ mod tests {
         assert_eq!(matcher.slot_rois.len(), 8);
 
         // Test specific slots
-        assert!(matcher.slot_rois.contains_key("shift"));
-        assert!(matcher.slot_rois.contains_key("pdn"));
+        assert!(matcher.slot_rois.contains_key(&SlotId::Shift));
+        assert!(matcher.slot_rois.contains_key(&SlotId::Pdn));
+    }
+
+    #[test]
+    fn test_read_slot_invalid_size_reports_failure() {
+        let matcher = InventoryTemplateMatcher::new();
+        let image = DynamicImage::ImageLuma8(GrayImage::new(100, 100));
+
+        let reading = matcher.read_slot(&image, SlotId::Shift);
+        assert!(!reading.is_readable());
+        assert_eq!(reading.count(), None);
+    }
+
+    #[test]
+    fn test_read_slot_empty_slot_is_readable() {
+        let matcher = InventoryTemplateMatcher::new();
+        let image = DynamicImage::ImageLuma8(GrayImage::new(522, 255));
+
+        // No templates loaded -> no digits -> empty slot, not a failure
+        let reading = matcher.read_slot(&image, SlotId::Shift);
+        assert_eq!(reading.state, SlotState::Empty);
+        assert!(reading.is_readable());
+        assert_eq!(reading.count(), Some(0));
+        assert!(reading.digits.is_empty());
+    }
+
+    #[test]
+    fn test_read_slot_icon_without_digits_is_unreadable() {
+        let matcher = InventoryTemplateMatcher::new();
+        let mut gray = GrayImage::new(522, 255);
+
+        // Paint an "icon" covering part of the shift slot (x: 0-130, y: 64-125)
+        for y in 70..110 {
+            for x in 20..80 {
+                gray.put_pixel(x, y, Luma([255u8]));
+            }
+        }
+
+        let reading = matcher.read_slot(&DynamicImage::ImageLuma8(gray), SlotId::Shift);
+        assert_eq!(reading.state, SlotState::Unreadable);
+        assert_eq!(reading.count(), None);
+    }
+
+    #[test]
+    fn test_recognize_grid_slots_reads_requested_cells() {
+        let matcher = InventoryTemplateMatcher::new();
+        let image = DynamicImage::ImageLuma8(GrayImage::new(144, 216));
+
+        let results = matcher.recognize_grid_slots(&image, 6, 4, &[0, 23, 24]).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[&0].state, SlotState::Empty);
+        assert_eq!(results[&23].state, SlotState::Empty);
+        assert_eq!(results[&24].state, SlotState::Unreadable); // Out of range
+
+        assert!(matcher.recognize_grid_slots(&image, 6, u32::MAX, &[0]).is_err());
+    }
+
+    #[test]
+    fn test_identify_potion_slots_by_icon_color() {
+        let mut matcher = InventoryTemplateMatcher::new();
+        let red = RgbImage::from_pixel(32, 32, image::Rgb([200, 30, 30]));
+        let blue = RgbImage::from_pixel(32, 32, image::Rgb([30, 60, 210]));
+        matcher.add_icon_template(PotionKind::Hp, &red);
+        matcher.add_icon_template(PotionKind::Mp, &blue);
+
+        // Grey inventory with a red icon in "del" and a blue icon in "end"
+        let mut inventory = RgbImage::from_pixel(522, 255, image::Rgb([60, 60, 60]));
+        for y in 200..240 {
+            for x in 150..200 {
+                inventory.put_pixel(x, y, image::Rgb([200, 30, 30]));
+            }
+            for x in 280..330 {
+                inventory.put_pixel(x, y, image::Rgb([30, 60, 210]));
+            }
+        }
+
+        let result = matcher
+            .identify_potion_slots(&DynamicImage::ImageRgb8(inventory), (0, 0, 521, 254))
+            .unwrap();
+
+        assert_eq!(result.hp_slot, Some(SlotId::Del));
+        assert_eq!(result.mp_slot, Some(SlotId::End));
+    }
+
+    #[test]
+    fn test_identify_potion_slots_requires_templates() {
+        let matcher = InventoryTemplateMatcher::new();
+        let image = DynamicImage::ImageRgb8(RgbImage::new(522, 255));
+        assert!(matcher.identify_potion_slots(&image, (0, 0, 521, 254)).is_err());
     }
 
     #[test]
@@ -699,6 +1131,6 @@ mod tests {
         let matcher = InventoryTemplateMatcher::new();
         let slots = matcher.get_available_slots();
         assert_eq!(slots.len(), 8);
-        assert!(slots.contains(&"shift".to_string()));
+        assert!(slots.contains(&SlotId::Shift));
     }
 }
@@ -1,8 +1,10 @@
 pub mod parser;
+pub mod api;
 pub mod http_ocr;
 pub mod template_matcher;
 pub mod inventory_template_matcher;
+pub mod components;
 
 // Re-export main types
 pub use http_ocr::HttpOcrClient;
-pub use inventory_template_matcher::InventoryTemplateMatcher;
+pub use inventory_template_matcher::{InventoryTemplateMatcher, PotionKind, PotionSlotMatch, SlotReading, SlotState};
@@ -1,4 +1,34 @@
+use crate::models::custom_metric::{MetricParser, MetricValue};
 use regex::Regex;
+use std::sync::RwLock;
+
+/// Decimal separator of the game client (see ClientLanguage)
+static DECIMAL_SEPARATOR: RwLock<char> = RwLock::new('.');
+
+/// Set the decimal separator used when reading OCR'd numbers
+pub fn set_decimal_separator(separator: char) {
+    if let Ok(mut current) = DECIMAL_SEPARATOR.write() {
+        *current = separator;
+    }
+}
+
+pub fn decimal_separator() -> char {
+    DECIMAL_SEPARATOR.read().map(|separator| *separator).unwrap_or('.')
+}
+
+/// Rewrite client-formatted numbers with '.' as the only decimal point
+/// Separator-like characters other than `decimal_separator` are thousands
+/// separators and get dropped, so "1.234.567[12,34%]" with ',' becomes
+/// "1234567[12.34%]" instead of being misread as a decimal.
+pub fn normalize_numbers(text: &str, decimal_separator: char) -> String {
+    text.chars()
+        .filter_map(|c| match c {
+            c if c == decimal_separator => Some('.'),
+            '.' | ',' | '\'' | ' ' | '\u{a0}' | '\u{202f}' => None,
+            c => Some(c),
+        })
+        .collect()
+}
 
 /// Parsed EXP data containing both absolute and percentage values
 #[derive(Debug, Clone, PartialEq)]
@@ -65,6 +95,8 @@ pub fn parse_mp(text: &str) -> Result<u32, String> {
 /// Brackets are optional - matches legacy Python parser behavior
 /// Returns ExpData with absolute value and percentage
 pub fn parse_exp(text: &str) -> Result<ExpData, String> {
+    let text = &normalize_numbers(text, decimal_separator());
+
     // First, clean the text: remove all characters except digits, ., %, [, ]
     // Matches legacy: re.sub(r"[^0-9\.\%\[\]]+", "", raw)
     let clean = text.chars()
@@ -158,6 +190,45 @@ pub fn parse_exp(text: &str) -> Result<ExpData, String> {
     Err(format!("No valid percentage pattern found in: {} (cleaned: {})", text, clean))
 }
 
+/// Parse a custom metric reading according to its parser type
+pub fn parse_metric(text: &str, parser: MetricParser) -> Result<MetricValue, String> {
+    match parser {
+        MetricParser::Integer => {
+            let digits: String = normalize_numbers(text, decimal_separator())
+                .chars()
+                .take_while(|c| *c != '.')
+                .filter(|c| c.is_ascii_digit())
+                .collect();
+            if digits.is_empty() {
+                return Err(format!("No digits found in: {}", text));
+            }
+            digits
+                .parse()
+                .map(MetricValue::Integer)
+                .map_err(|e| format!("Failed to parse integer '{}': {}", digits, e))
+        }
+        MetricParser::Percentage => {
+            let clean = normalize_numbers(text, decimal_separator());
+            let number = Regex::new(r"(\d+(?:\.\d+)?)%").unwrap()
+                .captures(&clean)
+                .or_else(|| Regex::new(r"(\d+(?:\.\d+)?)").unwrap().captures(&clean))
+                .map(|caps| caps[1].to_string())
+                .ok_or_else(|| format!("No percentage found in: {}", text))?;
+            number
+                .parse()
+                .map(MetricValue::Percentage)
+                .map_err(|e| format!("Failed to parse percentage '{}': {}", number, e))
+        }
+        MetricParser::Text => {
+            let trimmed = text.trim();
+            if trimmed.is_empty() {
+                return Err("No text found".to_string());
+            }
+            Ok(MetricValue::Text(trimmed.to_string()))
+        }
+    }
+}
+
 /// Parse map name from OCR text
 /// Expected format: Korean text like "히든스트리트 작은 난파선"
 /// Returns the map name (trimmed, non-empty)
@@ -306,6 +377,24 @@ mod tests {
     // EXP Parser Tests (🔴 RED Phase)
     // ============================================================
 
+    #[test]
+    fn test_normalize_numbers_by_client_language() {
+        // ko/en clients: comma thousands, dot decimal
+        assert_eq!(normalize_numbers("1,234,567[12.34%]", '.'), "1234567[12.34%]");
+        // de clients: dot thousands, comma decimal
+        assert_eq!(normalize_numbers("1.234.567[12,34%]", ','), "1234567[12.34%]");
+        // fr clients: space thousands
+        assert_eq!(normalize_numbers("1\u{202f}234\u{202f}567[12,34%]", ','), "1234567[12.34%]");
+    }
+
+    #[test]
+    fn test_parse_metric_by_parser_type() {
+        assert_eq!(parse_metric("Fame 1,234", MetricParser::Integer), Ok(MetricValue::Integer(1234)));
+        assert_eq!(parse_metric("Rate 45.5% (x2)", MetricParser::Percentage), Ok(MetricValue::Percentage(45.5)));
+        assert_eq!(parse_metric(" Elite Boss ", MetricParser::Text), Ok(MetricValue::Text("Elite Boss".to_string())));
+        assert!(parse_metric("---", MetricParser::Integer).is_err());
+    }
+
     #[test]
     fn test_parse_exp_valid_decimal_percentage() {
         let result = parse_exp("5509611[12.76%]");
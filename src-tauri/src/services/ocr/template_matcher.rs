@@ -1,6 +1,7 @@
 use image::{DynamicImage, GrayImage, ImageBuffer, Luma};
 use std::path::Path;
 use rayon::prelude::*;
+use super::components::connected_components;
 
 /// Template for digit recognition
 #[derive(Debug, Clone)]
@@ -130,8 +131,8 @@ impl TemplateMatcher {
 
     /// Find digit boxes with aspect ratio filtering
     pub fn find_digit_boxes(&self, mask: &GrayImage) -> Result<Vec<BoundingBox>, String> {
-        // Find connected components (simple flood fill approach)
-        let contours = find_contours(mask);
+        // Find connected components (two-pass labeling)
+        let components = connected_components(mask, |pixel| pixel > 128);
 
         let mut digit_boxes = Vec::new();
 
@@ -139,18 +140,19 @@ impl TemplateMatcher {
         let min_ratio = 0.800;
         let max_ratio = 0.900;
 
-        for contour in contours.iter() {
-            let bbox = get_bounding_box(&contour);
-
-            if bbox.width == 0 || bbox.height == 0 {
-                continue;
-            }
+        for component in components {
+            let bbox = BoundingBox {
+                x: component.left,
+                y: component.top,
+                width: component.width(),
+                height: component.height(),
+            };
 
             let ratio = bbox.width as f32 / bbox.height as f32;
 
             // Check aspect ratio only (no position filter)
             if ratio >= min_ratio && ratio <= max_ratio {
-                digit_boxes.push(bbox.clone());
+                digit_boxes.push(bbox);
             }
         }
 
@@ -363,78 +365,6 @@ fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
     (h, s, v)
 }
 
-/// Find connected components in binary mask (simple approach)
-fn find_contours(mask: &GrayImage) -> Vec<Vec<(u32, u32)>> {
-    let (width, height) = mask.dimensions();
-    let mut visited = vec![vec![false; width as usize]; height as usize];
-    let mut contours = Vec::new();
-    
-    for y in 0..height {
-        for x in 0..width {
-            if mask.get_pixel(x, y)[0] > 128 && !visited[y as usize][x as usize] {
-                let contour = flood_fill(mask, &mut visited, x, y);
-                if !contour.is_empty() {
-                    contours.push(contour);
-                }
-            }
-        }
-    }
-    
-    contours
-}
-
-/// Flood fill to find connected component
-fn flood_fill(
-    mask: &GrayImage,
-    visited: &mut Vec<Vec<bool>>,
-    start_x: u32,
-    start_y: u32,
-) -> Vec<(u32, u32)> {
-    let (width, height) = mask.dimensions();
-    let mut stack = vec![(start_x, start_y)];
-    let mut contour = Vec::new();
-    
-    while let Some((x, y)) = stack.pop() {
-        if x >= width || y >= height || visited[y as usize][x as usize] {
-            continue;
-        }
-        
-        if mask.get_pixel(x, y)[0] <= 128 {
-            continue;
-        }
-        
-        visited[y as usize][x as usize] = true;
-        contour.push((x, y));
-        
-        // Add neighbors
-        if x > 0 { stack.push((x - 1, y)); }
-        if x < width - 1 { stack.push((x + 1, y)); }
-        if y > 0 { stack.push((x, y - 1)); }
-        if y < height - 1 { stack.push((x, y + 1)); }
-    }
-    
-    contour
-}
-
-/// Get bounding box from contour points
-fn get_bounding_box(contour: &[(u32, u32)]) -> BoundingBox {
-    if contour.is_empty() {
-        return BoundingBox { x: 0, y: 0, width: 0, height: 0 };
-    }
-    
-    let min_x = contour.iter().map(|(x, _)| *x).min().unwrap();
-    let max_x = contour.iter().map(|(x, _)| *x).max().unwrap();
-    let min_y = contour.iter().map(|(_, y)| *y).min().unwrap();
-    let max_y = contour.iter().map(|(_, y)| *y).max().unwrap();
-    
-    BoundingBox {
-        x: min_x,
-        y: min_y,
-        width: max_x - min_x + 1,
-        height: max_y - min_y + 1,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,94 @@
+use crate::models::ocr_failure::FailureHistogram;
+use crate::services::session_store::{SessionFile, SessionStore};
+use crate::services::storage;
+use chrono::{Local, Timelike};
+use std::sync::{Arc, Mutex};
+
+/// OCR failures of the running session, shared by the OCR loops
+#[derive(Clone, Default)]
+pub struct FailureRecorder(Arc<Mutex<FailureHistogram>>);
+
+impl FailureRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn success(&self, roi: &str) {
+        if let Ok(mut histogram) = self.0.lock() {
+            histogram.record_success(roi);
+        }
+    }
+
+    /// Count a failed read of `roi`, `elapsed_seconds` into the session
+    pub fn failure(&self, roi: &str, elapsed_seconds: u64, error: &str) {
+        if let Ok(mut histogram) = self.0.lock() {
+            histogram.record_failure(roi, elapsed_seconds, Local::now().hour(), error);
+        }
+    }
+
+    pub fn snapshot(&self) -> FailureHistogram {
+        self.0.lock().map(|histogram| histogram.clone()).unwrap_or_default()
+    }
+
+    /// Snapshot and clear in one step
+    pub fn take(&self) -> FailureHistogram {
+        self.0.lock().map(|mut histogram| std::mem::take(&mut *histogram)).unwrap_or_default()
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut histogram) = self.0.lock() {
+            *histogram = FailureHistogram::default();
+        }
+    }
+}
+
+impl SessionFile for FailureHistogram {
+    const DIR: &'static str = storage::OCR_FAILURES_DIR;
+    const KIND: &'static str = "OCR failure";
+
+    fn session_id(&self) -> &str {
+        &self.session_id
+    }
+}
+
+/// Failure histograms of saved sessions, stored as `<root>/<session_id>.json`
+pub type FailureStore = SessionStore<FailureHistogram>;
+
+impl FailureStore {
+    /// The `limit` most recent histograms, oldest first
+    /// Unreadable files are skipped.
+    pub fn recent(&self, limit: usize) -> Vec<FailureHistogram> {
+        let mut histograms = self.all();
+        histograms.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        histograms.truncate(limit);
+        histograms.reverse();
+        histograms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_recent_keeps_latest_oldest_first() {
+        let root = std::env::temp_dir().join(format!("exp-tracker-ocr-failures-{}", std::process::id()));
+        let store = FailureStore::new(root.clone());
+
+        for (id, timestamp) in [("1", 1), ("2", 2), ("3", 3)] {
+            let mut histogram = FailureHistogram { session_id: id.to_string(), timestamp, ..Default::default() };
+            histogram.record_failure("exp", 0, 0, "no digits");
+            store.save(&histogram).unwrap();
+        }
+
+        let recent: Vec<String> = store.recent(2).into_iter().map(|h| h.session_id).collect();
+        assert_eq!(recent, vec!["2", "3"]);
+
+        store.delete("3").unwrap();
+        store.delete("3").unwrap();
+        assert_eq!(store.recent(5).len(), 2);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
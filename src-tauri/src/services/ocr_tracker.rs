@@ -1,260 +1,311 @@
-use crate::commands::ocr::OcrServiceState;
-use crate::models::exp_data::ExpData;
-use crate::models::roi::Roi;
-use crate::models::config::PotionConfig;
-use crate::services::exp_calculator::ExpCalculator;
-use crate::services::hp_potion_calculator::HpPotionCalculator;
-use crate::services::mp_potion_calculator::MpPotionCalculator;
-use crate::services::screen_capture::ScreenCapture;
+use crate::commands::ocr::{OcrService, OcrServiceState};
+use crate::commands::config::auto_switch_layout;
+use crate::commands::tracking::TrackerState;
+use crate::models::roi::{Roi, RoiEdge};
+use crate::models::checkpoint::{Checkpoint, CheckpointDelta};
+use crate::models::config::{AppConfig, PotionConfig, RoiConfig, TrackingMode};
+use crate::models::custom_metric::{CustomMetric, MetricValue};
+use crate::models::slot::{SlotId, SlotKey};
+use crate::models::timeline::TimelinePoint;
+use crate::models::ocr_failure::FailureHistogram;
+use crate::services::latency::SampleTiming;
+use crate::services::screen_capture::{DisplayInfo, ScreenCapture};
+use crate::services::session_configs::SessionConfig;
+use crate::services::alert_engine;
+use crate::services::buffer_pool;
 use crate::services::config::ConfigManager;
-use serde::Serialize;
+use crate::services::event_log;
+use crate::services::expression::Expr;
+use crate::services::matching_pool;
+use crate::services::ocr_failures::FailureRecorder;
+use crate::services::storage;
+use crate::services::ocr::{PotionSlotMatch, SlotReading, SlotState};
+use crate::services::tracker_actor::{FinishedSession, TrackerActor, TrackerHandle, TrackerMsg};
+use crate::services::watchdog::{Heartbeats, StalledLoop};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
-use tauri::{AppHandle, Emitter, Manager};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
 use tokio::sync::Mutex;
 use tokio::time::sleep;
 use image::DynamicImage;
-use std::fs;
+
+/// Interval between OCR server health checks
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The EXP ROI may be widened up to this factor when its number outgrows it
+const MAX_ROI_EXPANSION: f64 = 1.5;
+
+/// Potion slot identifications missing a potion are retried after this long
+/// (e.g. the potion was dragged onto the quickslots after tracking started)
+const IDENTIFY_RETRY: Duration = Duration::from_secs(60);
+
+/// Health loop iterations longer than this mean the system was asleep
+/// (well above the 5s health check timeout plus the interval)
+const SLEEP_GAP_THRESHOLD: Duration = Duration::from_secs(30);
 
 /// Current tracking statistics
-#[derive(Debug, Clone, Serialize)]
+///
+/// Counts and EXP are unsigned end to end (OCR → calculators → here), so a
+/// reset or resume can never surface as a negative value.
+/// Saved session records keep a snapshot (`SessionRecord::final_stats`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TrackingStats {
-    pub level: Option<i32>,
-    pub exp: Option<i64>,
+    pub level: Option<u32>,
+    pub exp: Option<u64>,
     pub percentage: Option<f64>,
-    pub hp_potion_count: Option<i32>,
-    pub mp_potion_count: Option<i32>,
-    pub total_exp: i64,
+    pub hp_potion_count: Option<u32>,
+    pub mp_potion_count: Option<u32>,
+    pub total_exp: u64,
     pub total_percentage: f64,
-    pub elapsed_seconds: i64,
-    pub exp_per_hour: i64,
+    pub elapsed_seconds: u64,
+    pub exp_per_hour: u64,
     pub percentage_per_hour: f64,
+    /// The session is still in its warm-up (see TrackingConfig::rate_warmup_secs);
+    /// the hourly rates are extrapolated from too little data to trust
+    #[serde(default)]
+    pub rates_estimating: bool,
     pub is_tracking: bool,
+    /// The running session was continued after a stop rather than started fresh
+    pub resumed: bool,
     pub error: Option<String>,
-    pub hp_potions_used: i32,
-    pub mp_potions_used: i32,
+    pub hp_potions_used: u32,
+    pub mp_potions_used: u32,
     pub hp_potions_per_minute: f64,
     pub mp_potions_per_minute: f64,
     pub ocr_server_healthy: bool,
+    /// Latest reading of each custom metric, by name
+    pub custom_metrics: BTreeMap<String, MetricValue>,
+    /// Derived metric values; metrics whose inputs aren't known yet are left out
+    pub derived_metrics: BTreeMap<String, f64>,
+    /// Active ROI layout (see AppConfig::layouts)
+    #[serde(default)]
+    pub layout: Option<String>,
 }
 
-/// OCR Tracker state
-struct TrackerState {
-    level: Option<u32>,
-    exp: Option<u64>,
-    percentage: Option<f64>,
-    hp_potion_count: Option<u32>,
-    mp_potion_count: Option<u32>,
-    // Independent calculators - each tracks its own data
-    exp_calculator: ExpCalculator,
-    hp_calculator: HpPotionCalculator,
-    mp_calculator: MpPotionCalculator,
-    is_tracking: bool,
-    error: Option<String>,
-    // Level stability tracking
-    prev_level: Option<u32>,
-    level_match_count: u32,
-    // Session started flag
-    session_started: bool,
-    // OCR server health status
-    ocr_server_healthy: bool,
-    // Latest stats cache - each calculator updates its own fields
-    latest_stats: TrackingStats,
+impl TrackingStats {
+    /// Stat names usable as variables in derived metric expressions
+    pub const VARIABLES: &'static [&'static str] = &[
+        "level", "exp", "percentage", "hp_potion_count", "mp_potion_count",
+        "total_exp", "total_percentage", "elapsed_seconds", "minutes", "hours",
+        "exp_per_hour", "percentage_per_hour", "hp_potions_used", "mp_potions_used",
+        "potions_used", "hp_potions_per_minute", "mp_potions_per_minute",
+    ];
+
+    /// Value of a stat or numeric custom metric for expression evaluation
+    pub fn variable(&self, name: &str) -> Option<f64> {
+        let value = match name {
+            "level" => self.level? as f64,
+            "exp" => self.exp? as f64,
+            "percentage" => self.percentage?,
+            "hp_potion_count" => self.hp_potion_count? as f64,
+            "mp_potion_count" => self.mp_potion_count? as f64,
+            "total_exp" => self.total_exp as f64,
+            "total_percentage" => self.total_percentage,
+            "elapsed_seconds" => self.elapsed_seconds as f64,
+            "minutes" => self.elapsed_seconds as f64 / 60.0,
+            "hours" => self.elapsed_seconds as f64 / 3600.0,
+            "exp_per_hour" => self.exp_per_hour as f64,
+            "percentage_per_hour" => self.percentage_per_hour,
+            "hp_potions_used" => self.hp_potions_used as f64,
+            "mp_potions_used" => self.mp_potions_used as f64,
+            "potions_used" => self.hp_potions_used as f64 + self.mp_potions_used as f64,
+            "hp_potions_per_minute" => self.hp_potions_per_minute,
+            "mp_potions_per_minute" => self.mp_potions_per_minute,
+            custom => match self.custom_metrics.get(custom)? {
+                MetricValue::Integer(value) => *value as f64,
+                MetricValue::Percentage(value) => *value,
+                MetricValue::Text(_) => return None,
+            },
+        };
+        Some(value)
+    }
 }
 
-impl TrackerState {
-    fn new() -> Result<Self, String> {
-        Ok(Self {
+/// Payload for "tracking:checkpoint"
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointEvent {
+    pub checkpoint: Checkpoint,
+    /// None for the first checkpoint of a session
+    pub since_previous: Option<CheckpointDelta>,
+}
+
+/// What a checkpoint reads with, cloned out of the `OcrTracker`
+pub struct CheckpointReader {
+    run: u64,
+    screen_capture: Arc<ScreenCapture>,
+    app: AppHandle,
+    ocr_service: OcrServiceState,
+    unreadable_slots: UnreadableSlots,
+}
+
+impl CheckpointReader {
+    /// Tracking run the reader was taken in, for `OcrTracker::record_checkpoint`
+    pub fn run(&self) -> u64 {
+        self.run
+    }
+
+    /// One-shot capture + OCR of every enabled metric (checkpoint mode)
+    /// Nothing reaches the actor until `OcrTracker::record_checkpoint`
+    pub async fn read(self) -> Result<Checkpoint, String> {
+        let config = {
+            if let Some(config_state) = self.app.try_state::<std::sync::Mutex<ConfigManager>>() {
+                match config_state.lock() {
+                    Ok(manager) => manager.load()?,
+                    Err(e) => return Err(format!("Failed to lock config: {}", e)),
+                }
+            } else {
+                return Err("Config manager not available".to_string());
+            }
+        };
+        let tracking_config = config.tracking;
+
+        let image = Arc::new(self.screen_capture.capture_full()?);
+        let http_client = self.ocr_service.http_client.clone();
+
+        let mut checkpoint = Checkpoint {
+            timestamp: chrono::Utc::now().timestamp_millis(),
             level: None,
             exp: None,
             percentage: None,
             hp_potion_count: None,
             mp_potion_count: None,
-            exp_calculator: ExpCalculator::new()?,
-            hp_calculator: HpPotionCalculator::new(),
-            mp_calculator: MpPotionCalculator::new(),
-            is_tracking: false,
-            error: None,
-            prev_level: None,
-            level_match_count: 0,
-            session_started: false,
-            ocr_server_healthy: true,
-            latest_stats: TrackingStats {
-                level: None,
-                exp: None,
-                percentage: None,
-                hp_potion_count: None,
-                mp_potion_count: None,
-                total_exp: 0,
-                total_percentage: 0.0,
-                elapsed_seconds: 0,
-                exp_per_hour: 0,
-                percentage_per_hour: 0.0,
-                is_tracking: false,
-                error: None,
-                hp_potions_used: 0,
-                mp_potions_used: 0,
-                hp_potions_per_minute: 0.0,
-                mp_potions_per_minute: 0.0,
-                ocr_server_healthy: true,
-            },
-        })
-    }
+        };
 
-    /// Update level - emit immediately for UI responsiveness
-    fn update_level(&mut self, new_level: u32) -> bool {
-        let should_emit = match self.prev_level {
-            Some(prev) if prev == new_level => {
-                // Same as before - already displayed in UI, no need to re-emit
-                self.level_match_count += 1;
-                false
-            }
-            _ => {
-                // New value - emit immediately to UI
-                self.prev_level = Some(new_level);
-                self.level_match_count = 1;
-                self.level = Some(new_level);
-                true
+        if tracking_config.track_level {
+            match http_client.recognize_level(&*image).await {
+                Ok(result) => checkpoint.level = Some(result.level),
+                Err(e) => eprintln!("❌ Checkpoint LEVEL OCR failed: {}", e),
             }
-        };
-        should_emit
-    }
-
-    /// Update EXP and trigger calculator update - returns true if changed
-    fn update_exp_data(&mut self, exp: u64, percentage: f64) -> bool {
-        let changed = self.exp != Some(exp) || self.percentage != Some(percentage);
-        self.exp = Some(exp);
-        self.percentage = Some(percentage);
-
-        // Update ExpCalculator if level is stable
-        if let Some(level) = self.level {
-            let data = ExpData {
-                level,
-                exp,
-                percentage,
-                meso: None,
-            };
-
-            if !self.session_started {
-                self.exp_calculator.start(data);
-                self.session_started = true;
-            } else {
-                // Update session with EXP tracking - ORIGINAL WORKING MECHANISM
-                let result = self.exp_calculator.update(data);
-
-                match result {
-                    Ok(stats) => {
-                        // Cache ONLY EXP stats - HP/MP have their own calculators now
-                        self.latest_stats.total_exp = stats.total_exp as i64;
-                        self.latest_stats.total_percentage = stats.total_percentage;
-                        self.latest_stats.elapsed_seconds = stats.elapsed_seconds as i64;
-                        self.latest_stats.exp_per_hour = stats.exp_per_hour as i64;
-                        self.latest_stats.percentage_per_hour = stats.percentage_per_hour;
-                        self.error = None;
-                    }
-                    Err(e) => {
-                        self.error = Some(e);
+        }
+
+        if tracking_config.track_exp {
+            match config.roi.exp {
+                Some(exp_roi) if !roi_readable(&self.app, "exp", &exp_roi) => {}
+                Some(exp_roi) => {
+                    let exp_image = self.screen_capture.capture_region(&exp_roi)?;
+                    match http_client.recognize_exp(&exp_image).await {
+                        Ok(result) => {
+                            checkpoint.exp = Some(result.absolute);
+                            checkpoint.percentage = Some(result.percentage);
+                        }
+                        Err(e) => eprintln!("❌ Checkpoint EXP OCR failed: {}", e),
                     }
                 }
+                None => eprintln!("⚠️ Checkpoint skipped EXP: ROI not configured"),
             }
         }
-        changed
-    }
-
-    fn to_stats(&self) -> TrackingStats {
-        // ORIGINAL EXP MECHANISM: Read from cached latest_stats
-        // All trackers use the same mechanism now
-        TrackingStats {
-            level: self.level.map(|l| l as i32),
-            exp: self.exp.map(|e| e as i64),
-            percentage: self.percentage,
-            hp_potion_count: self.hp_potion_count.map(|h| h as i32),
-            mp_potion_count: self.mp_potion_count.map(|m| m as i32),
-            // Read from cache (same as original EXP mechanism)
-            total_exp: self.latest_stats.total_exp,
-            total_percentage: self.latest_stats.total_percentage,
-            elapsed_seconds: self.latest_stats.elapsed_seconds,
-            exp_per_hour: self.latest_stats.exp_per_hour,
-            percentage_per_hour: self.latest_stats.percentage_per_hour,
-            is_tracking: self.is_tracking,
-            error: self.error.clone(),
-            hp_potions_used: self.latest_stats.hp_potions_used,
-            mp_potions_used: self.latest_stats.mp_potions_used,
-            hp_potions_per_minute: self.latest_stats.hp_potions_per_minute,
-            mp_potions_per_minute: self.latest_stats.mp_potions_per_minute,
-            ocr_server_healthy: self.ocr_server_healthy,
-        }
-    }
-}
-
-/// Event payloads for Frontend updates
-#[derive(Clone, Serialize)]
-struct LevelUpdate {
-    level: u32,
-}
-
-#[derive(Clone, Serialize)]
-struct ExpUpdate {
-    exp: u64,
-    percentage: f64,
-}
 
-#[derive(Clone, Serialize)]
-struct HpPotionUpdate {
-    hp_potion_count: u32,
-}
+        if tracking_config.track_potions {
+            let service = Arc::clone(&self.ocr_service);
+            let image = Arc::clone(&image);
+            let mut potion_config = config.potion;
+            let scale_factor = self.screen_capture.get_scale_factor();
+
+            let inventory_result = tokio::task::spawn_blocking(move || matching_pool::install(|| {
+                let (results, _, _) = read_potion_inventory(&service, &image, &mut potion_config, None, None, scale_factor)?;
+                Ok::<_, String>((results, potion_config))
+            })).await;
+
+            match inventory_result {
+                Ok(Ok((inventory, potion_config))) => {
+                    let hp = read_potion_slot(&self.app, &self.unreadable_slots, &inventory, potion_config.hp_slot_key());
+                    let mp = read_potion_slot(&self.app, &self.unreadable_slots, &inventory, potion_config.mp_slot_key());
+                    checkpoint.hp_potion_count = hp;
+                    checkpoint.mp_potion_count = mp;
+                }
+                Ok(Err(e)) => eprintln!("❌ Checkpoint inventory OCR failed: {}", e),
+                Err(e) => eprintln!("❌ Checkpoint inventory task failed: {}", e),
+            }
+        }
 
-#[derive(Clone, Serialize)]
-struct MpPotionUpdate {
-    mp_potion_count: u32,
+        Ok(checkpoint)
+    }
 }
 
-    /// Global OCR Tracker instance
+/// Global OCR Tracker instance
+///
+/// Owns the OCR loops; all tracking state lives in the `TrackerActor`.
 pub struct OcrTracker {
-    state: Arc<Mutex<TrackerState>>,
+    tracker: TrackerHandle,
     stop_signal: Arc<Mutex<bool>>,
     screen_capture: Arc<ScreenCapture>,
     app: AppHandle,
     ocr_service: OcrServiceState,  // Shared OCR service instance
-    background_tasks: Vec<tokio::task::JoinHandle<()>>, // Store task handles for cleanup
+    background_tasks: Vec<(OcrLoop, tokio::task::JoinHandle<()>)>, // Store task handles for cleanup/restart
+    heartbeats: Heartbeats, // Last iteration of each loop, watched by the health loop
+    checkpoints: Vec<Checkpoint>, // Checkpoint mode log for the current session
+    failures: FailureRecorder, // OCR failures of the current session, by ROI
+    unreadable_slots: UnreadableSlots, // Potion slots currently alerted as unreadable
+    session_config: Option<SessionConfig>, // Settings the loops last started with
+    runs: u64, // Times tracking started; checkpoints read during an earlier run are stale
 }
 
 impl OcrTracker {
     pub fn new(app: AppHandle, ocr_service: OcrServiceState) -> Result<Self, String> {
+        // Opened first so a failure doesn't leave the actor and alert loop running
+        let screen_capture = Arc::new(ScreenCapture::new()?);
+        let tracker = TrackerActor::new()?.spawn(app.clone());
+        alert_engine::spawn_alert_loop(app.clone(), tracker.clone());
+
         Ok(Self {
-            state: Arc::new(Mutex::new(TrackerState::new()?)),
+            tracker,
             stop_signal: Arc::new(Mutex::new(false)),
-            screen_capture: Arc::new(ScreenCapture::new()?),
+            screen_capture,
             app,
             ocr_service,  // Store shared OCR service
             background_tasks: Vec::new(),
+            heartbeats: Heartbeats::new(),
+            checkpoints: Vec::new(),
+            failures: FailureRecorder::new(),
+            unreadable_slots: UnreadableSlots::default(),
+            session_config: None,
+            runs: 0,
         })
     }
 
-    /// Start OCR tracking with 3 independent parallel tasks (Level, EXP, Inventory)
+    /// Start OCR tracking with independent parallel tasks (Level, EXP, Inventory)
+    /// Metrics disabled in TrackingConfig don't get a loop
     /// Inventory recognition uses automatic ROI detection
+    /// `resume` continues the stopped session; otherwise a fresh session starts.
     pub async fn start_tracking(
         &mut self,
         level_roi: Roi,
         exp_roi: Roi,
+        resume: bool,
     ) -> Result<(), String> {
-        // Check if already tracking - prevent reinitialization
-        let mut state = self.state.lock().await;
-        if state.is_tracking {
+        // Actor resets for a new session or resumes an existing one;
+        // returns false if already tracking - prevent reinitialization
+        let started = self.tracker.request(|reply| TrackerMsg::Start { resume, reply }).await?;
+        if !started {
             return Ok(());
         }
+        self.runs += 1;
 
-        // Check if this is a resume (session_started = true) or new session
-        let is_resume = state.session_started;
+        // Checkpoints and failure counts belong to the session they were taken in
+        if !resume {
+            self.checkpoints.clear();
+            self.failures.clear();
+        }
 
-        if !is_resume {
-            // New session - reset state completely
-            *state = TrackerState::new()?;
+        // Don't leave the actor tracking without loops feeding it
+        if let Err(e) = self.start_loops(level_roi, exp_roi).await {
+            self.stop_tracking().await;
+            return Err(e);
         }
+        Ok(())
+    }
 
-        // Set tracking flag
-        state.is_tracking = true;
-        drop(state);
+    /// Spawn the OCR loops of the configured metrics
+    async fn start_loops(&mut self, level_roi: Roi, exp_roi: Roi) -> Result<(), String> {
+        // Re-open the monitor if its scale changed since it was opened
+        // (e.g. resuming after a "display:resolution-changed" pause)
+        let mut display = self.screen_capture.current_display()?;
+        if (display.scale_factor - self.screen_capture.get_scale_factor()).abs() > f64::EPSILON {
+            self.screen_capture = Arc::new(ScreenCapture::new()?);
+            display = self.screen_capture.current_display()?;
+        }
 
         // Reset stop signal
         *self.stop_signal.lock().await = false;
@@ -262,19 +313,102 @@ impl OcrTracker {
         // Clear any existing tasks (safety check)
         self.abort_background_tasks().await;
 
+        // Only pay for the loops of metrics this profile tracks
+        let config = {
+            if let Some(config_state) = self.app.try_state::<std::sync::Mutex<ConfigManager>>() {
+                match config_state.lock() {
+                    Ok(manager) => manager.load().unwrap_or_default(),
+                    Err(_) => AppConfig::default()
+                }
+            } else {
+                AppConfig::default()
+            }
+        };
+        self.session_config = Some(SessionConfig {
+            session_id: String::new(),
+            timestamp: 0,
+            started_at: chrono::Utc::now().timestamp_millis(),
+            level_roi,
+            exp_roi,
+            display,
+            config: config.clone(),
+        });
+        let tracking_config = config.tracking;
+
+        // Derived metrics are re-evaluated by the actor on every stats update
+        let derived_metrics = config.derived_metrics.iter()
+            .filter_map(|metric| match Expr::parse(&metric.expression) {
+                Ok(expr) => Some((metric.name.clone(), expr)),
+                Err(e) => {
+                    eprintln!("⚠️ Skipping derived metric '{}': {}", metric.name, e);
+                    None
+                }
+            })
+            .collect();
+        self.tracker.send(TrackerMsg::SetDerivedMetrics(derived_metrics)).await;
+        self.tracker.send(TrackerMsg::SetLayout(config.active_layout.clone())).await;
+        self.tracker.send(TrackerMsg::SetRateWarmup(tracking_config.rate_warmup_secs)).await;
+
+        // Checkpoint mode: readings only come from `capture_checkpoint`
+        if tracking_config.mode == TrackingMode::Checkpoint {
+            println!("📍 Checkpoint mode: press {} to record a checkpoint", tracking_config.checkpoint_shortcut);
+            self.start_loop(OcrLoop::HealthCheck);
+            return Ok(());
+        }
+
         // Spawn OCR tasks: combined Level+Inventory (shared capture), separate EXP, health check
         // Store handles to allow proper cancellation
-        let task1 = self.spawn_combined_level_inventory_loop(level_roi, self.app.clone());
-        let task2 = self.spawn_exp_loop(exp_roi, self.app.clone());
-        let task3 = self.spawn_health_check_loop(self.app.clone());
-
-        self.background_tasks.push(task1);
-        self.background_tasks.push(task2);
-        self.background_tasks.push(task3);
+        if tracking_config.track_level || tracking_config.track_potions {
+            self.start_loop(OcrLoop::LevelInventory {
+                level_roi,
+                display,
+                track_level: tracking_config.track_level,
+                track_potions: tracking_config.track_potions,
+            });
+        }
+        if tracking_config.track_exp && roi_readable(&self.app, "exp", &exp_roi) {
+            self.start_loop(OcrLoop::Exp(exp_roi));
+        }
+        for metric in config.custom_metrics.into_iter().filter(|m| m.enabled) {
+            if !roi_readable(&self.app, &metric.name, &metric.roi) {
+                continue;
+            }
+            self.start_loop(OcrLoop::CustomMetric(metric));
+        }
+        self.start_loop(OcrLoop::HealthCheck);
 
         Ok(())
     }
 
+    fn start_loop(&mut self, ocr_loop: OcrLoop) {
+        let task = self.spawn_loop(&ocr_loop);
+        self.background_tasks.push((ocr_loop, task));
+    }
+
+    fn spawn_loop(&self, ocr_loop: &OcrLoop) -> tokio::task::JoinHandle<()> {
+        match ocr_loop.clone() {
+            OcrLoop::LevelInventory { level_roi, display, track_level, track_potions } => {
+                self.spawn_combined_level_inventory_loop(level_roi, self.app.clone(), display, track_level, track_potions)
+            }
+            OcrLoop::Exp(roi) => self.spawn_exp_loop(roi, self.app.clone()),
+            OcrLoop::CustomMetric(metric) => self.spawn_custom_metric_loop(metric),
+            OcrLoop::HealthCheck => self.spawn_health_check_loop(self.app.clone()),
+        }
+    }
+
+    /// Abort a stalled loop and start it again; false if no such loop is running
+    pub fn restart_loop(&mut self, name: &str) -> bool {
+        let Some(index) = self.background_tasks.iter().position(|(ocr_loop, _)| ocr_loop.name() == name) else {
+            self.heartbeats.remove(name);
+            return false;
+        };
+
+        self.background_tasks[index].1.abort();
+        let task = self.spawn_loop(&self.background_tasks[index].0);
+        self.background_tasks[index].1 = task;
+        true
+    }
+
     /// Stop all OCR loops
     pub async fn stop_tracking(&mut self) {
         *self.stop_signal.lock().await = true;
@@ -282,63 +416,183 @@ impl OcrTracker {
         // Abort all background tasks immediately
         self.abort_background_tasks().await;
 
-        let mut state = self.state.lock().await;
-        state.is_tracking = false;
+        self.tracker.send(TrackerMsg::Stop).await;
     }
 
     /// Helper to abort all background tasks
     async fn abort_background_tasks(&mut self) {
-        for task in &self.background_tasks {
+        for (_, task) in &self.background_tasks {
             task.abort();
         }
         self.background_tasks.clear();
+        self.heartbeats.clear();
     }
 
     /// Get current tracking statistics
     pub async fn get_stats(&self) -> TrackingStats {
-        let state = self.state.lock().await;
-        state.to_stats()
+        self.tracker.stats()
+    }
+
+    /// EXP timeline of the current session
+    pub async fn timeline(&self) -> Result<Vec<TimelinePoint>, String> {
+        self.tracker.request(TrackerMsg::Timeline).await
+    }
+
+    /// Enter the level by hand (OCR unavailable, e.g. unsupported resolution)
+    /// Goes through the same path as OCR readings, so timer and rate math still work
+    pub async fn set_manual_level(&self, level: u32) -> Result<(), String> {
+        self.tracker.request(|reply| TrackerMsg::ManualLevel { level, reply }).await
+    }
+
+    /// Enter EXP by hand; requires a level (manual or recognized) to be known
+    pub async fn set_manual_exp(&self, exp: u64, percentage: f64) -> Result<(), String> {
+        self.tracker.request(|reply| TrackerMsg::ManualExp { exp, percentage, reply }).await
+    }
+
+    /// Stop the loops and take the session's final stats and timeline
+    /// Errors (with tracking stopped) if no EXP was tracked.
+    pub async fn finish(&mut self) -> Result<FinishedSession, String> {
+        *self.stop_signal.lock().await = true;
+        self.abort_background_tasks().await;
+
+        self.tracker.request(TrackerMsg::Finish).await
     }
 
     /// Reset tracking session
     pub async fn reset(&mut self) -> Result<(), String> {
         self.stop_tracking().await;
+        self.checkpoints.clear();
+        self.failures.clear();
+        self.session_config = None;
         
-        let mut state = self.state.lock().await;
-        *state = TrackerState::new()?;
-        Ok(())
+        self.tracker.request(TrackerMsg::Reset).await
+    }
+
+    /// OCR failures of the current session, by ROI
+    pub fn failure_histogram(&self) -> FailureHistogram {
+        self.failures.snapshot()
+    }
+
+    /// Hand the failures counted so far to a saved record and start counting afresh
+    pub fn take_failure_histogram(&self) -> FailureHistogram {
+        self.failures.take()
+    }
+
+    /// Settings the OCR loops last started with; None before the first start
+    pub fn session_config(&self) -> Option<SessionConfig> {
+        self.session_config.clone()
+    }
+
+    /// Report `layout` as the active layout in the stats
+    pub async fn set_layout(&self, layout: Option<String>) {
+        self.tracker.send(TrackerMsg::SetLayout(layout)).await;
+    }
+
+    /// Checkpoints recorded in the current session
+    pub fn get_checkpoints(&self) -> Vec<Checkpoint> {
+        self.checkpoints.clone()
+    }
+
+    /// Handles a checkpoint reads with, so the OCR runs without the tracker lock
+    pub fn checkpoint_reader(&self) -> Result<CheckpointReader, String> {
+        if !self.tracker.stats().is_tracking {
+            return Err("Start tracking before taking a checkpoint".to_string());
+        }
+
+        Ok(CheckpointReader {
+            run: self.runs,
+            screen_capture: Arc::clone(&self.screen_capture),
+            app: self.app.clone(),
+            ocr_service: Arc::clone(&self.ocr_service),
+            unreadable_slots: self.unreadable_slots.clone(),
+        })
+    }
+
+    /// Add a checkpoint read by `checkpoint_reader` during tracking run `run` to the session log
+    /// Readings go through the actor like loop readings, so rates are derived from checkpoints
+    pub async fn record_checkpoint(&mut self, run: u64, checkpoint: Checkpoint) -> Result<CheckpointEvent, String> {
+        // Tracking may have stopped (or restarted) while the OCR ran
+        if run != self.runs || !self.tracker.stats().is_tracking {
+            return Err("Tracking stopped before the checkpoint was recorded".to_string());
+        }
+
+        if let Some(level) = checkpoint.level {
+            self.tracker.send(TrackerMsg::LevelRead(level)).await;
+        }
+        if let (Some(exp), Some(percentage)) = (checkpoint.exp, checkpoint.percentage) {
+            self.tracker.send(TrackerMsg::ExpRead { exp, percentage, timing: None }).await;
+        }
+        self.tracker.send(TrackerMsg::PotionRead {
+            hp: checkpoint.hp_potion_count,
+            mp: checkpoint.mp_potion_count,
+        }).await;
+
+        let event = CheckpointEvent {
+            since_previous: self.checkpoints.last().map(|previous| checkpoint.delta_since(previous)),
+            checkpoint: checkpoint.clone(),
+        };
+        self.checkpoints.push(checkpoint);
+
+        println!("📍 Checkpoint #{} recorded", self.checkpoints.len());
+        if let Err(e) = event_log::emit(&self.app, "tracking:checkpoint", &event) {
+            eprintln!("Failed to emit checkpoint: {}", e);
+        }
+
+        Ok(event)
     }
 
     /// Combined Level + Inventory OCR loop (shares full screen capture for efficiency)
-    fn spawn_combined_level_inventory_loop(&self, _roi: Roi, app: AppHandle) -> tokio::task::JoinHandle<()> {
-        let state = Arc::clone(&self.state);
+    fn spawn_combined_level_inventory_loop(
+        &self,
+        _roi: Roi,
+        app: AppHandle,
+        display: DisplayInfo,
+        track_level: bool,
+        track_potions: bool,
+    ) -> tokio::task::JoinHandle<()> {
+        let tracker = self.tracker.clone();
         let stop_signal = Arc::clone(&self.stop_signal);
         let screen_capture = Arc::clone(&self.screen_capture);
         let ocr_service = Arc::clone(&self.ocr_service);
+        let heartbeats = self.heartbeats.clone();
+        let failures = self.failures.clone();
+        let unreadable_slots = self.unreadable_slots.clone();
 
         tokio::spawn(async move {
             // Image cache for duplicate detection
-            let mut last_image_bytes: Option<Vec<u8>> = None;
+            let mut last_image_bytes: Vec<u8> = Vec::new(); // Reused every cycle, empty until first capture
 
             // ROI memoization for performance (caches detected regions)
             let mut memoized_level_roi: Option<(u32, u32, u32, u32)> = None;
             let mut memoized_inventory_roi: Option<(u32, u32, u32, u32)> = None;
 
+            // Potion slots identified by icon and when (cleared when the inventory moves)
+            let mut identified_slots: Option<(PotionSlotMatch, Instant)> = None;
+
+            heartbeats.beat(LEVEL_INVENTORY_LOOP, update_interval(&app));
             while !*stop_signal.lock().await {
                 let _start = std::time::Instant::now();
 
+                // Dynamic interval based on config
+                let interval = update_interval(&app);
+
+                // ROIs silently break when the resolution or scale changes
+                // (e.g. game switched to fullscreen), so pause and let the user rescale
+                if let Ok(current) = screen_capture.current_display() {
+                    if current != display {
+                        pause_for_display_change(&app, &tracker, &stop_signal, display, current).await;
+                        break;
+                    }
+                }
+
                 // Single full screen capture for both Level and Inventory
                 match screen_capture.capture_full() {
                     Ok(image) => {
-                        // Convert image to raw bytes for comparison
-                        let current_bytes = image.as_bytes().to_vec();
-
-                        // Check if image is identical to last capture
-                        if let Some(ref last_bytes) = last_image_bytes {
-                            if current_bytes == *last_bytes {
-                                sleep(Duration::from_millis(500)).await;
-                                continue;
-                            }
+                        // Check if image is identical to last capture (compared in place, no per-cycle copy)
+                        if !last_image_bytes.is_empty() && last_image_bytes.as_slice() == image.as_bytes() {
+                            sleep(Duration::from_millis(500)).await;
+                            heartbeats.beat(LEVEL_INVENTORY_LOOP, interval);
+                            continue;
                         }
 
                         // Process Level and Inventory independently (not waiting for each other)
@@ -346,14 +600,10 @@ impl OcrTracker {
                         let image = Arc::new(image);
 
                         // Spawn Level OCR as independent task with ROI memoization
-                        {
-                            let http_client = {
-                                let service = ocr_service.lock();
-                                service.http_client.clone()
-                            };
+                        if track_level {
+                            let http_client = ocr_service.http_client.clone();
                             let image = Arc::clone(&image);
-                            let app = app.clone();
-                            let state = Arc::clone(&state);
+                            let tracker = tracker.clone();
                             let memoized_roi = memoized_level_roi.clone();
 
                             let updated_roi = tokio::spawn(async move {
@@ -393,36 +643,32 @@ impl OcrTracker {
                                 Ok(result) => {
                                     println!("📊 [LEVEL] {} (text: '{}')", result.level, result.raw_text);
                                     
-                                    let should_emit = {
-                                        let mut state = state.lock().await;
-                                        state.update_level(result.level)
-                                    };
-
-                                    if should_emit {
-                                        if let Err(e) = app.emit("ocr:level-update", LevelUpdate { level: result.level }) {
-                                            eprintln!("Failed to emit level update: {}", e);
-                                        }
-                                    }
+                                    failures.success(LEVEL_ROI);
+                                    tracker.send(TrackerMsg::LevelRead(result.level)).await;
                                 }
-                                Err(_e) => {
+                                Err(e) => {
                                     // Level OCR failed, will retry on next cycle
+                                    record_failure(&failures, &tracker, LEVEL_ROI, &e);
                                 }
                             }
                         }
 
                         // Spawn Inventory OCR as independent task with ROI memoization
-                        {
+                        if track_potions {
                             let ocr_service_clone = Arc::clone(&ocr_service);
                             let image = Arc::clone(&image);
-                            let app = app.clone();
-                            let state = Arc::clone(&state);
+                            let tracker = tracker.clone();
                             let memoized_roi = memoized_inventory_roi.clone();
+                            let cached_identification = identified_slots.clone()
+                                .filter(|(found, at)| found.is_complete() || at.elapsed() < IDENTIFY_RETRY)
+                                .map(|(found, _)| found);
+                            let scale_factor = screen_capture.get_scale_factor();
 
                             let app_handle = app.clone();
                             let updated_roi = tokio::spawn(async move {
                                 let inventory_result = tokio::task::spawn_blocking(move || {
                                     // Load config to get active potion slots
-                                    let potion_config = {
+                                    let mut potion_config = {
                                         if let Some(config_state) = app_handle.try_state::<std::sync::Mutex<ConfigManager>>() {
                                             match config_state.lock() {
                                                 Ok(manager) => match manager.load() {
@@ -435,53 +681,20 @@ impl OcrTracker {
                                             PotionConfig::default()
                                         }
                                     };
-                                    let slots = vec![potion_config.hp_potion_slot.clone(), potion_config.mp_potion_slot.clone()];
-
-                                    let service = ocr_service_clone.lock();
-
-                                    // Try memoized ROI first (fast path)
-                                    if let Some((left, top, right, bottom)) = memoized_roi {
-                                        let padding = 100;
-                                        let img_width = image.width();
-                                        let img_height = image.height();
-                                        let padded_left = left.saturating_sub(padding);
-                                        let padded_top = top.saturating_sub(padding);
-                                        let padded_right = (right + padding).min(img_width - 1);
-                                        let padded_bottom = (bottom + padding).min(img_height - 1);
-
-                                        let crop_width = padded_right - padded_left + 1;
-                                        let crop_height = padded_bottom - padded_top + 1;
-                                        let cropped = image.crop_imm(padded_left, padded_top, crop_width, crop_height);
-
-                                        if let Ok(results) = service.recognize_specific_inventory(&cropped, &slots) {
-                                            return Ok((results, Some((left, top, right, bottom)), potion_config));
-                                        }
-                                    }
-
-                                    // Fallback: Full detection
-                                    match service.recognize_specific_inventory(&*image, &slots) {
-                                        Ok(results) => {
-                                            // Try to get ROI coordinates for memoization
-                                            if let Some(matcher) = &service.inventory_matcher {
-                                                if let Ok((_, coords)) = matcher.detect_inventory_region_with_coords(&*image) {
-                                                    let (left, top, right, bottom) = coords;
-                                                    let width = right - left + 1;
-                                                    let height = bottom - top + 1;
-                                                    let cropped_original = image::imageops::crop_imm(&*image, left, top, width, height);
-                                                    let dynamic_img = DynamicImage::ImageRgba8(cropped_original.to_image());
-                                                    save_inventory_preview(&dynamic_img);
-                                                    
-                                                    return Ok((results, Some(coords), potion_config));
-                                                }
-                                            }
-                                            Ok((results, None, potion_config))
-                                        }
-                                        Err(e) => Err(e)
-                                    }
+                                    let (results, roi, identification) = matching_pool::install(|| read_potion_inventory(
+                                        &ocr_service_clone,
+                                        &image,
+                                        &mut potion_config,
+                                        cached_identification,
+                                        memoized_roi,
+                                        scale_factor,
+                                    ))?;
+
+                                    Ok::<_, String>((results, roi, potion_config, identification))
                                 }).await;
 
                                 match inventory_result {
-                                    Ok(Ok((results, roi, config))) => (Ok((results, config)), roi),
+                                    Ok(Ok((results, roi, config, identification))) => (Ok((results, config, identification)), roi),
                                     Ok(Err(e)) => (Err(e), None),
                                     Err(e) => (Err(format!("Task failed: {}", e)), None)
                                 }
@@ -493,75 +706,58 @@ impl OcrTracker {
                             };
 
                             // Update memoized ROI if we got a new one
+                            let inventory_moved = memoized_inventory_roi.is_some() && new_roi.is_some() && new_roi != memoized_inventory_roi;
                             if new_roi.is_some() {
                                 memoized_inventory_roi = new_roi;
                             }
 
                             match inventory_result {
-                                Ok((inventory, potion_config)) => {
-                                    let hp_potion_count = *inventory.get(&potion_config.hp_potion_slot).unwrap_or(&0);
-                                    let mp_potion_count = *inventory.get(&potion_config.mp_potion_slot).unwrap_or(&0);
-
-                                    let mut state = state.lock().await;
-                                    state.hp_potion_count = Some(hp_potion_count);
-                                    state.mp_potion_count = Some(mp_potion_count);
-
-                                    let (hp_used, hp_per_min) = state.hp_calculator.update(hp_potion_count);
-                                    state.latest_stats.hp_potions_used = hp_used as i32;
-                                    state.latest_stats.hp_potions_per_minute = hp_per_min;
-
-                                    let (mp_used, mp_per_min) = state.mp_calculator.update(mp_potion_count);
-                                    state.latest_stats.mp_potions_used = mp_used as i32;
-                                    state.latest_stats.mp_potions_per_minute = mp_per_min;
-
-                                    drop(state);
+                                Ok((inventory, potion_config, identification)) => {
+                                    // Re-identify potion icons after the inventory moved
+                                    identified_slots = if inventory_moved {
+                                        None
+                                    } else {
+                                        identification.map(|found| match identified_slots.take() {
+                                            Some((cached, at)) if cached == found => (found, at),
+                                            _ => (found, Instant::now()),
+                                        })
+                                    };
 
-                                    // Emit events to Frontend
-                                    if let Err(e) = app.emit("ocr:hp-potion-update", HpPotionUpdate { hp_potion_count }) {
-                                        eprintln!("Failed to emit HP potion update: {}", e);
-                                    }
+                                    let hp_potion_count = read_potion_slot(&app, &unreadable_slots, &inventory, potion_config.hp_slot_key());
+                                    let mp_potion_count = read_potion_slot(&app, &unreadable_slots, &inventory, potion_config.mp_slot_key());
 
-                                    if let Err(e) = app.emit("ocr:mp-potion-update", MpPotionUpdate { mp_potion_count }) {
-                                        eprintln!("Failed to emit MP potion update: {}", e);
-                                    }
+                                    failures.success(INVENTORY_ROI);
+                                    tracker.send(TrackerMsg::PotionRead { hp: hp_potion_count, mp: mp_potion_count }).await;
                                 }
-                                Err(_e) => {
+                                Err(e) => {
                                     // Inventory OCR failed, will retry on next cycle
+                                    record_failure(&failures, &tracker, INVENTORY_ROI, &e);
                                 }
                             }
                         }
 
                         // Update cache
-                        last_image_bytes = Some(current_bytes);
+                        buffer_pool::global().copy_into(&mut last_image_bytes, image.as_bytes());
                     }
-                    Err(_e) => {
+                    Err(e) => {
                         // Full screen capture failed, will retry on next cycle
+                        let tracked = [(track_level, LEVEL_ROI), (track_potions, INVENTORY_ROI)];
+                        for (_, roi) in tracked.into_iter().filter(|(tracked, _)| *tracked) {
+                            record_failure(&failures, &tracker, roi, &e);
+                        }
                     }
                 }
 
-                // Dynamic sleep based on config
-                let interval_ms = {
-                    if let Some(config_state) = app.try_state::<std::sync::Mutex<ConfigManager>>() {
-                        match config_state.lock() {
-                            Ok(manager) => match manager.load() {
-                                Ok(config) => (config.tracking.update_interval.max(1) as f64 * 1000.0) as u64,
-                                Err(_) => 1000
-                            },
-                            Err(_) => 1000
-                        }
-                    } else {
-                        1000
-                    }
-                };
-                sleep(Duration::from_millis(interval_ms)).await;
+                heartbeats.beat(LEVEL_INVENTORY_LOOP, interval);
+                sleep(interval).await;
             }
         })
     }
 
     // Independent Level OCR loop with shared OCR service + image caching
     // NOTE: Template matching uses FULL SCREEN, not ROI (roi param unused)
-    fn spawn_level_loop(&self, _roi: Roi, app: AppHandle) -> tokio::task::JoinHandle<()> {
-        let state = Arc::clone(&self.state);
+    fn spawn_level_loop(&self, _roi: Roi, _app: AppHandle) -> tokio::task::JoinHandle<()> {
+        let tracker = self.tracker.clone();
         let stop_signal = Arc::clone(&self.stop_signal);
         let screen_capture = Arc::clone(&self.screen_capture);
         let ocr_service = Arc::clone(&self.ocr_service);  // Use shared service
@@ -571,7 +767,7 @@ impl OcrTracker {
             println!("🚀 LEVEL OCR task started - using shared OCR service (FULL SCREEN capture for template matching)");
 
             // Image cache for duplicate detection
-            let mut last_image_bytes: Option<Vec<u8>> = None;
+            let mut last_image_bytes: Vec<u8> = Vec::new(); // Reused every cycle, empty until first capture
 
             while !*stop_signal.lock().await {
                 let start = std::time::Instant::now();
@@ -580,33 +776,19 @@ impl OcrTracker {
                 // Template matching needs full screen to find orange boxes
                 match screen_capture.capture_full() {
                     Ok(image) => {
-                        // Convert image to raw bytes for comparison
-                        let current_bytes = image.as_bytes().to_vec();
-
-                        // Check if image is identical to last capture
-                        if let Some(ref last_bytes) = last_image_bytes {
-                            if current_bytes == *last_bytes {
-                                #[cfg(debug_assertions)]
-                                println!("⏭️  LEVEL: Skipped (identical image)");
-                                sleep(Duration::from_millis(500)).await;
-                                continue;
-                            }
+                        // Check if image is identical to last capture (compared in place, no per-cycle copy)
+                        if !last_image_bytes.is_empty() && last_image_bytes.as_slice() == image.as_bytes() {
+                            #[cfg(debug_assertions)]
+                            println!("⏭️  LEVEL: Skipped (identical image)");
+                            sleep(Duration::from_millis(500)).await;
+                            continue;
                         }
 
                         // Image changed - run OCR with FULL SCREEN
-                        let http_client = {
-                            let service = ocr_service.lock();
-                            service.http_client.clone()
-                        };
+                        let http_client = ocr_service.http_client.clone();
                         match http_client.recognize_level(&image).await {
                             Ok(result) => {
-                                let mut state = state.lock().await;
-                                state.update_level(result.level);
-
-                                // Emit event to Frontend if level is confirmed (stable)
-                                if let Some(level) = state.level {
-                                    app.emit("ocr:level-update", LevelUpdate { level }).ok();
-                                }
+                                tracker.send(TrackerMsg::LevelRead(result.level)).await;
 
                                 #[cfg(debug_assertions)]
                                 {
@@ -621,7 +803,7 @@ impl OcrTracker {
                         }
 
                         // Update cache
-                        last_image_bytes = Some(current_bytes);
+                        buffer_pool::global().copy_into(&mut last_image_bytes, image.as_bytes());
                     }
                     Err(e) => {
                         #[cfg(debug_assertions)]
@@ -639,118 +821,175 @@ impl OcrTracker {
 
     // Independent EXP OCR loop with shared OCR service + image caching
     fn spawn_exp_loop(&self, roi: Roi, app: AppHandle) -> tokio::task::JoinHandle<()> {
-        let state = Arc::clone(&self.state);
+        let tracker = self.tracker.clone();
         let stop_signal = Arc::clone(&self.stop_signal);
         let screen_capture = Arc::clone(&self.screen_capture);
         let ocr_service = Arc::clone(&self.ocr_service);  // Use shared service
+        let heartbeats = self.heartbeats.clone();
+        let failures = self.failures.clone();
 
         tokio::spawn(async move {
             // Image cache for duplicate detection
-            let mut last_image_bytes: Option<Vec<u8>> = None;
+            let mut last_image_bytes: Vec<u8> = Vec::new(); // Reused every cycle, empty until first capture
+
+            // Widened when the number outgrows the saved ROI (e.g. 9,999,999 -> 10,000,000)
+            let mut effective_roi = roi;
+            let max_width = (roi.width as f64 * MAX_ROI_EXPANSION).round() as u32;
+            let screen_width = screen_capture.current_display().map(|d| d.width).unwrap_or(u32::MAX);
 
+            heartbeats.beat(EXP_LOOP, update_interval(&app));
             while !*stop_signal.lock().await {
-                match screen_capture.capture_region(&roi) {
+                // Dynamic interval based on config
+                let interval = update_interval(&app);
+
+                let capture_started = Instant::now();
+                match screen_capture.capture_region(&effective_roi) {
                     Ok(image) => {
-                        let current_bytes = image.as_bytes().to_vec();
+                        let captured = Instant::now();
 
-                        // Check if image is identical to last capture
-                        if let Some(ref last_bytes) = last_image_bytes {
-                            if current_bytes == *last_bytes {
-                                sleep(Duration::from_millis(500)).await;
-                                continue;
-                            }
+                        // Check if image is identical to last capture (compared in place, no per-cycle copy)
+                        if !last_image_bytes.is_empty() && last_image_bytes.as_slice() == image.as_bytes() {
+                            sleep(Duration::from_millis(500)).await;
+                            heartbeats.beat(EXP_LOOP, interval);
+                            continue;
                         }
 
                         // Image changed - run OCR
-                        let http_client = {
-                            let service = ocr_service.lock();
-                            service.http_client.clone()
-                        };
+                        let http_client = ocr_service.http_client.clone();
                         
-                        match http_client.recognize_exp(&image).await {
-                            Ok(result) => {
-                                println!("📊 [EXP] {} [{:.2}%] (text: '{}')", 
-                                    result.absolute, result.percentage, result.raw_text);
-                                
-                                let should_emit = {
-                                    let mut state_guard = state.lock().await;
-                                    state_guard.update_exp_data(result.absolute, result.percentage)
-                                };
-
-                                // Emit event to Frontend if EXP changed
-                                if should_emit {
-                                    if let Err(e) = app.emit("ocr:exp-update", ExpUpdate {
-                                        exp: result.absolute,
-                                        percentage: result.percentage
-                                    }) {
-                                        eprintln!("Failed to emit EXP update: {}", e);
+                        match http_client.read_exp(&image).await {
+                            Ok(reading) => {
+                                match &reading.result {
+                                    Ok(result) => {
+                                        println!("📊 [EXP] {} [{:.2}%] (text: '{}')", 
+                                            result.absolute, result.percentage, result.raw_text);
+
+                                        failures.success(EXP_LOOP);
+                                        tracker.send(TrackerMsg::ExpRead {
+                                            exp: result.absolute,
+                                            percentage: result.percentage,
+                                            timing: Some(SampleTiming { capture_started, captured, recognized: Instant::now() }),
+                                        }).await;
                                     }
+                                    Err(e) => record_failure(&failures, &tracker, EXP_LOOP, e),
+                                }
+
+                                // About one digit per step (digits are roughly half as wide as tall)
+                                let step = (effective_roi.height / 2).max(4);
+                                let expanded = effective_roi.expanded(&reading.clipped_edges, step, max_width, screen_width);
+                                if expanded != effective_roi {
+                                    effective_roi = expanded;
+                                    notify_roi_expanded(&app, roi, effective_roi, &reading.clipped_edges);
+                                    // Re-read the wider region right away
+                                    last_image_bytes.clear();
+                                    continue;
                                 }
                             }
-                            Err(_e) => {
+                            Err(e) => {
                                 // EXP OCR failed, will retry on next cycle
+                                record_failure(&failures, &tracker, EXP_LOOP, &e);
                             }
                         }
 
                         // Update cache
-                        last_image_bytes = Some(current_bytes);
+                        buffer_pool::global().copy_into(&mut last_image_bytes, image.as_bytes());
                     }
-                    Err(_e) => {
+                    Err(e) => {
                         // EXP capture failed, will retry on next cycle
+                        record_failure(&failures, &tracker, EXP_LOOP, &e);
                     }
                 }
 
-                // Dynamic sleep based on config
-                let interval_ms = {
-                    if let Some(config_state) = app.try_state::<std::sync::Mutex<ConfigManager>>() {
-                        match config_state.lock() {
-                            Ok(manager) => match manager.load() {
-                                Ok(config) => (config.tracking.update_interval.max(1) as f64 * 1000.0) as u64,
-                                Err(_) => 1000
-                            },
-                            Err(_) => 1000
+                heartbeats.beat(EXP_LOOP, interval);
+                sleep(interval).await;
+            }
+        })
+    }
+
+    // Custom metric OCR loop - one per user-defined metric, on its own interval
+    fn spawn_custom_metric_loop(&self, metric: CustomMetric) -> tokio::task::JoinHandle<()> {
+        let tracker = self.tracker.clone();
+        let stop_signal = Arc::clone(&self.stop_signal);
+        let screen_capture = Arc::clone(&self.screen_capture);
+        let ocr_service = Arc::clone(&self.ocr_service);
+        let interval = Duration::from_secs(metric.interval_secs.max(1));
+        let heartbeats = self.heartbeats.clone();
+        let failures = self.failures.clone();
+        let loop_name = OcrLoop::CustomMetric(metric.clone()).name();
+
+        tokio::spawn(async move {
+            // Image cache for duplicate detection
+            let mut last_image_bytes: Vec<u8> = Vec::new();
+
+            heartbeats.beat(&loop_name, interval);
+            while !*stop_signal.lock().await {
+                match screen_capture.capture_region(&metric.roi) {
+                    Ok(image) => {
+                        // Unchanged region - keep the previous reading
+                        if last_image_bytes.as_slice() != image.as_bytes() {
+                            match ocr_service.recognize_custom_metric(&image, metric.parser).await {
+                                Ok(value) => {
+                                    #[cfg(debug_assertions)]
+                                    println!("📊 [{}] {:?}", metric.name, value);
+
+                                    failures.success(&loop_name);
+                                    tracker.send(TrackerMsg::CustomMetricRead {
+                                        name: metric.name.clone(),
+                                        value,
+                                    }).await;
+                                }
+                                Err(e) => {
+                                    // Custom metric OCR failed, will retry on next cycle
+                                    record_failure(&failures, &tracker, &loop_name, &e);
+                                }
+                            }
+
+                            buffer_pool::global().copy_into(&mut last_image_bytes, image.as_bytes());
                         }
-                    } else {
-                        1000
                     }
-                };
-                sleep(Duration::from_millis(interval_ms)).await;
+                    Err(e) => {
+                        // Custom metric capture failed, will retry on next cycle
+                        record_failure(&failures, &tracker, &loop_name, &e);
+                    }
+                }
+
+                heartbeats.beat(&loop_name, interval);
+                sleep(interval).await;
             }
+
+            #[cfg(debug_assertions)]
+            println!("⏹️  Custom metric '{}' task stopped", metric.name);
         })
     }
 
     // Unified Inventory OCR loop - Rust native with automatic ROI detection
     fn spawn_inventory_loop(&self, app: AppHandle) -> tokio::task::JoinHandle<()> {
-        let state = Arc::clone(&self.state);
+        let tracker = self.tracker.clone();
         let stop_signal = Arc::clone(&self.stop_signal);
         let screen_capture = Arc::clone(&self.screen_capture);
         let ocr_service = Arc::clone(&self.ocr_service);
+        let unreadable_slots = self.unreadable_slots.clone();
 
         tokio::spawn(async move {
             // Image cache for duplicate detection
-            let mut last_image_bytes: Option<Vec<u8>> = None;
+            let mut last_image_bytes: Vec<u8> = Vec::new(); // Reused every cycle, empty until first capture
 
             while !*stop_signal.lock().await {
                 // Capture full screen for automatic inventory detection
                 match screen_capture.capture_full() {
                     Ok(image) => {
-                        // Convert image to raw bytes for comparison
-                        let current_bytes = image.as_bytes().to_vec();
-
-                        // Check if image is identical to last capture
-                        if let Some(ref last_bytes) = last_image_bytes {
-                            if current_bytes == *last_bytes {
-                                sleep(Duration::from_millis(500)).await;
-                                continue;
-                            }
+                        // Check if image is identical to last capture (compared in place, no per-cycle copy)
+                        if !last_image_bytes.is_empty() && last_image_bytes.as_slice() == image.as_bytes() {
+                            sleep(Duration::from_millis(500)).await;
+                            continue;
                         }
 
                         // Run Rust native inventory recognition (async, non-blocking)
                         let ocr_service_clone = Arc::clone(&ocr_service);
                         let image_clone = image.clone();
                         let inventory_results = match tokio::task::spawn_blocking(move || {
-                            let service = ocr_service_clone.lock();
-                            service.recognize_inventory(&image_clone)
+                            let service = &*ocr_service_clone;
+                            matching_pool::install(|| service.recognize_inventory(&image_clone))
                         }).await {
                             Ok(result) => result,
                             Err(e) => Err(format!("Inventory recognition task failed: {}", e))
@@ -774,29 +1013,11 @@ impl OcrTracker {
                     };
 
                                 // Extract HP and MP counts from inventory
-                                let hp_potion_count = *inventory.get(&potion_config.hp_potion_slot).unwrap_or(&0);
-                                let mp_potion_count = *inventory.get(&potion_config.mp_potion_slot).unwrap_or(&0);
+                                let hp_potion_count = read_potion_slot(&app, &unreadable_slots, &inventory, potion_config.hp_slot_key());
+                                let mp_potion_count = read_potion_slot(&app, &unreadable_slots, &inventory, potion_config.mp_slot_key());
 
-                                // Update state and calculators
-                                let mut state = state.lock().await;
-                                state.hp_potion_count = Some(hp_potion_count);
-                                state.mp_potion_count = Some(mp_potion_count);
-
-                                // Update HP calculator
-                                let (hp_used, hp_per_min) = state.hp_calculator.update(hp_potion_count);
-                                state.latest_stats.hp_potions_used = hp_used as i32;
-                                state.latest_stats.hp_potions_per_minute = hp_per_min;
-
-                                // Update MP calculator
-                                let (mp_used, mp_per_min) = state.mp_calculator.update(mp_potion_count);
-                                state.latest_stats.mp_potions_used = mp_used as i32;
-                                state.latest_stats.mp_potions_per_minute = mp_per_min;
-
-                                drop(state);
-
-                                // Emit events to Frontend
-                                app.emit("ocr:hp-potion-update", HpPotionUpdate { hp_potion_count }).ok();
-                                app.emit("ocr:mp-potion-update", MpPotionUpdate { mp_potion_count }).ok();
+                                // Actor updates calculators and emits events to Frontend
+                                tracker.send(TrackerMsg::PotionRead { hp: hp_potion_count, mp: mp_potion_count }).await;
                             }
                             Err(_e) => {
                                 // Inventory OCR failed, will retry on next cycle
@@ -804,7 +1025,7 @@ impl OcrTracker {
                         }
 
                         // Update cache
-                        last_image_bytes = Some(current_bytes);
+                        buffer_pool::global().copy_into(&mut last_image_bytes, image.as_bytes());
                     }
                     Err(_e) => {
                         // Full screen capture failed, will retry on next cycle
@@ -818,45 +1039,379 @@ impl OcrTracker {
 
 
     /// Spawn health check loop - monitors OCR server health
-    fn spawn_health_check_loop(&self, _app: AppHandle) -> tokio::task::JoinHandle<()> {
-        let state = Arc::clone(&self.state);
+    /// Also detects system sleep: a loop iteration far longer than its interval
+    /// means the machine was suspended, so the gap is excluded from elapsed time
+    fn spawn_health_check_loop(&self, app: AppHandle) -> tokio::task::JoinHandle<()> {
+        let tracker = self.tracker.clone();
         let stop_signal = Arc::clone(&self.stop_signal);
         let ocr_service = Arc::clone(&self.ocr_service);  // Use shared service
+        let heartbeats = self.heartbeats.clone();
 
         tokio::spawn(async move {
+            let mut last_tick = Instant::now();
+
             while !*stop_signal.lock().await {
                 // Use shared OCR service for health check
-                let http_client = {
-                    let service = ocr_service.lock();
-                    service.http_client.clone()
-                };
+                let http_client = ocr_service.http_client.clone();
                 match http_client.health_check().await {
-                    Ok(_) => {
-                        let mut state = state.lock().await;
-                        state.ocr_server_healthy = true;
-                        state.latest_stats.ocr_server_healthy = true;
-                    }
-                    Err(_e) => {
-                        let mut state = state.lock().await;
-                        state.ocr_server_healthy = false;
-                        state.latest_stats.ocr_server_healthy = false;
-                    }
+                    Ok(_) => tracker.send(TrackerMsg::HealthChanged(true)).await,
+                    Err(_e) => tracker.send(TrackerMsg::HealthChanged(false)).await,
                 }
 
                 // Check every 2 seconds
-                sleep(Duration::from_secs(2)).await;
+                sleep(HEALTH_CHECK_INTERVAL).await;
+
+                let iteration = last_tick.elapsed();
+                last_tick = Instant::now();
+                if iteration > SLEEP_GAP_THRESHOLD {
+                    let gap = iteration.saturating_sub(HEALTH_CHECK_INTERVAL);
+                    println!("💤 Resumed from sleep after {}s, excluding gap from tracking time", gap.as_secs());
+
+                    tracker.send(TrackerMsg::SleepGap(gap)).await;
+                    if let Err(e) = event_log::emit(&app, "system:resumed-from-sleep", SleepGap { gap_seconds: gap.as_secs() }) {
+                        eprintln!("Failed to emit resume event: {}", e);
+                    }
+
+                    // Every loop was frozen along with this one
+                    heartbeats.refresh();
+                }
+
+                for stalled in heartbeats.stalled() {
+                    restart_stalled_loop(&app, stalled).await;
+                }
             }
         })
     }
 }
 
+/// Name the level/inventory loop beats under
+const LEVEL_INVENTORY_LOOP: &str = "level_inventory";
+/// Name the EXP loop beats under
+const EXP_LOOP: &str = "exp";
+
+/// Failure histogram names of the ROIs read by the level + inventory loop
+/// (the EXP and custom metric ROIs go by their loop names)
+const LEVEL_ROI: &str = "level";
+const INVENTORY_ROI: &str = "inventory";
+
+/// OCR loops of a tracking session, kept so a stalled one can be restarted
+#[derive(Debug, Clone)]
+enum OcrLoop {
+    LevelInventory {
+        level_roi: Roi,
+        display: DisplayInfo,
+        track_level: bool,
+        track_potions: bool,
+    },
+    Exp(Roi),
+    CustomMetric(CustomMetric),
+    HealthCheck,
+}
+
+impl OcrLoop {
+    /// Heartbeat name, also reported in "tracking:loop-stalled"
+    fn name(&self) -> String {
+        match self {
+            OcrLoop::LevelInventory { .. } => LEVEL_INVENTORY_LOOP.to_string(),
+            OcrLoop::Exp(_) => EXP_LOOP.to_string(),
+            OcrLoop::CustomMetric(metric) => format!("custom:{}", metric.name),
+            OcrLoop::HealthCheck => "health_check".to_string(),
+        }
+    }
+}
+
+/// Count a failed read of `roi` at the session's current elapsed time
+fn record_failure(failures: &FailureRecorder, tracker: &TrackerHandle, roi: &str, error: &str) {
+    failures.failure(roi, tracker.stats().elapsed_seconds, error);
+}
+
+/// OCR update interval from the config (1s if it can't be read)
+pub(crate) fn update_interval(app: &AppHandle) -> Duration {
+    let interval_secs = match app.try_state::<std::sync::Mutex<ConfigManager>>() {
+        Some(config_state) => match config_state.lock() {
+            Ok(manager) => manager.load().map(|config| config.tracking.update_interval).unwrap_or(1),
+            Err(_) => 1,
+        },
+        None => 1,
+    };
+    Duration::from_secs(interval_secs.max(1))
+}
+
+/// Report a loop that stopped iterating and start it again
+async fn restart_stalled_loop(app: &AppHandle, stalled: StalledLoop) {
+    eprintln!("🐕 OCR loop '{}' hasn't iterated for {}s, restarting it", stalled.name, stalled.stalled_seconds);
+
+    let name = stalled.name.clone();
+    if let Err(e) = event_log::emit(app, "tracking:loop-stalled", stalled) {
+        eprintln!("Failed to emit loop stall event: {}", e);
+    }
+
+    if let Some(tracker_state) = app.try_state::<TrackerState>() {
+        tracker_state.0.lock().await.restart_loop(&name);
+    }
+}
+
+/// Payload for "ocr:roi-expanded"
+#[derive(Clone, Serialize)]
+struct RoiExpanded {
+    roi_type: &'static str,
+    /// ROI as saved in the config
+    saved: Roi,
+    /// ROI currently captured; the user should re-save the ROI to cover this
+    effective: Roi,
+    edges: Vec<RoiEdge>,
+}
+
+/// Tell the user the EXP ROI was widened so they can re-save it
+fn notify_roi_expanded(app: &AppHandle, saved: Roi, effective: Roi, edges: &[RoiEdge]) {
+    println!("↔️ EXP text touches the ROI edge ({:?}); capturing {}x{} at ({}, {}) - re-save the EXP ROI",
+        edges, effective.width, effective.height, effective.x, effective.y);
+
+    let payload = RoiExpanded { roi_type: "exp", saved, effective, edges: edges.to_vec() };
+    if let Err(e) = event_log::emit(app, "ocr:roi-expanded", payload) {
+        eprintln!("Failed to emit ROI expansion event: {}", e);
+    }
+}
+
+/// Payload for "ocr:roi-too-small"
+#[derive(Clone, Serialize)]
+struct RoiTooSmall {
+    /// "exp" or the custom metric's name
+    roi_type: String,
+    roi: Roi,
+    error: String,
+}
+
+/// Whether `roi` is large enough to OCR; if not, say so and skip its readings
+fn roi_readable(app: &AppHandle, roi_type: &str, roi: &Roi) -> bool {
+    let Err(error) = roi.check_size() else {
+        return true;
+    };
+
+    eprintln!("⚠️ Skipping {} OCR: {}", roi_type, error);
+    let payload = RoiTooSmall { roi_type: roi_type.to_string(), roi: *roi, error };
+    if let Err(e) = event_log::emit(app, "ocr:roi-too-small", payload) {
+        eprintln!("Failed to emit ROI size event: {}", e);
+    }
+    false
+}
+
+/// Payload for "system:resumed-from-sleep"
+#[derive(Clone, Serialize)]
+struct SleepGap {
+    gap_seconds: u64,
+}
+
+/// Payload for "display:resolution-changed"
+#[derive(Clone, Serialize)]
+struct DisplayChange {
+    previous: DisplayInfo,
+    current: DisplayInfo,
+    /// Saved ROIs mapped onto the new display (applied via `apply_rescaled_rois`)
+    rescaled_rois: Option<RoiConfig>,
+}
+
+/// Pause tracking after a display change and offer rescaled ROIs
+async fn pause_for_display_change(
+    app: &AppHandle,
+    tracker: &TrackerHandle,
+    stop_signal: &Mutex<bool>,
+    previous: DisplayInfo,
+    current: DisplayInfo,
+) {
+    println!(
+        "🖥️ Display changed: {}x{} @{} → {}x{} @{}, pausing tracking",
+        previous.width, previous.height, previous.scale_factor,
+        current.width, current.height, current.scale_factor
+    );
+
+    *stop_signal.lock().await = true;
+
+    // Runs apart from the calling loop, which stopping tracking aborts
+    let app = app.clone();
+    let tracker = tracker.clone();
+    tauri::async_runtime::spawn(async move {
+        // Through the control channel, so "tracking:lifecycle" reports the pause
+        let stopped = match app.try_state::<TrackerState>() {
+            Some(state) => state.control().stop().await,
+            None => Err("Tracker not available".to_string()),
+        };
+        if let Err(e) = stopped {
+            eprintln!("⚠️ Failed to pause tracking through its control: {}", e);
+            tracker.send(TrackerMsg::Stop).await;
+        }
+
+        // A saved layout for the new display (e.g. "fullscreen") beats rescaled ROIs
+        match auto_switch_layout(&app, previous, current).await {
+            Ok(true) => return,
+            Ok(false) => {}
+            Err(e) => eprintln!("⚠️ Layout auto-switch failed: {}", e),
+        }
+
+        let rescaled_rois = {
+            if let Some(config_state) = app.try_state::<std::sync::Mutex<ConfigManager>>() {
+                match config_state.lock() {
+                    Ok(manager) => manager.load().ok().map(|config| {
+                        let (scale_x, scale_y) = previous.roi_scale_to(&current);
+                        config.roi.scaled(scale_x, scale_y)
+                    }),
+                    Err(_) => None
+                }
+            } else {
+                None
+            }
+        };
+
+        if let Err(e) = event_log::emit(&app, "display:resolution-changed", DisplayChange { previous, current, rescaled_rois }) {
+            eprintln!("Failed to emit display change: {}", e);
+        }
+    });
+}
+
+/// Alert payload for an occupied slot whose count could not be read
+#[derive(Clone, Serialize)]
+struct PotionSlotAlert {
+    slot: String,
+    reason: String,
+}
+
+/// Potion slots that are currently unreadable, shared by the loops reading them
+/// so the alert fires once when a slot becomes unreadable, not on every cycle
+#[derive(Clone, Default)]
+struct UnreadableSlots(Arc<std::sync::Mutex<HashSet<SlotKey>>>);
+
+impl UnreadableSlots {
+    /// Record a slot's latest state; returns true if it just became unreadable
+    fn update(&self, slot: SlotKey, unreadable: bool) -> bool {
+        let Ok(mut slots) = self.0.lock() else {
+            return false;
+        };
+        if unreadable {
+            slots.insert(slot)
+        } else {
+            slots.remove(&slot);
+            false
+        }
+    }
+}
+
+/// Extract a trustworthy count for a slot
+/// Empty slots read as 0; unreadable slots yield None and raise an alert instead
+fn read_potion_slot(
+    app: &AppHandle,
+    unreadable_slots: &UnreadableSlots,
+    inventory: &HashMap<SlotKey, SlotReading>,
+    slot: SlotKey,
+) -> Option<u32> {
+    let reading = inventory.get(&slot)?;
+
+    let unreadable = reading.state == SlotState::Unreadable;
+    if unreadable_slots.update(slot, unreadable) {
+        let reason = reading.failure.clone().unwrap_or_default();
+        eprintln!("⚠️ [{}] Slot unreadable: {}", slot.to_string().to_uppercase(), reason);
+
+        if let Err(e) = event_log::emit(app, "ocr:potion-slot-unreadable", PotionSlotAlert { slot: slot.to_string(), reason }) {
+            eprintln!("Failed to emit potion slot alert: {}", e);
+        }
+    }
+
+    reading.count()
+}
+
+/// Read every inventory slot the potion config needs from a full screen capture
+/// Applies icon-identified potion slots to `potion_config` (identifying them if not cached)
+/// Returns the readings, the quickslot region to memoize, and the identification to cache
+fn read_potion_inventory(
+    service: &OcrService,
+    image: &DynamicImage,
+    potion_config: &mut PotionConfig,
+    cached_identification: Option<PotionSlotMatch>,
+    memoized_roi: Option<(u32, u32, u32, u32)>,
+    scale_factor: f64,
+) -> Result<(HashMap<SlotKey, SlotReading>, Option<(u32, u32, u32, u32)>, Option<PotionSlotMatch>), String> {
+    // Identify potion slots by icon once per inventory position
+    let mut identification = cached_identification;
+    if identification.is_none() && potion_config.auto_identify_slots && service.can_identify_potion_slots() {
+        identification = service.identify_potion_slots(image).ok();
+    }
+    if let Some(found) = &identification {
+        potion_config.apply_identified_slots(found.hp_slot, found.mp_slot);
+    }
+
+    let mut results = HashMap::new();
+    let mut roi = None;
+
+    // Hotkey quickslot layout (auto-detected region)
+    let slots = potion_config.quickslot_slots();
+    if !slots.is_empty() {
+        let (readings, detected_roi) = read_quickslot_inventory(service, image, memoized_roi, &slots)?;
+        results.extend(readings);
+        roi = detected_roi;
+    }
+
+    // Regular inventory window grid (user-defined region)
+    let cells = potion_config.grid_slots();
+    if !cells.is_empty() {
+        let grid_roi = potion_config.grid.roi.as_ref()
+            .ok_or_else(|| "Item grid ROI not configured".to_string())?;
+        let grid_image = ScreenCapture::crop_logical(image, grid_roi, scale_factor)?;
+        results.extend(service.recognize_grid_inventory(&grid_image, &potion_config.grid, &cells)?);
+    }
+
+    Ok((results, roi, identification))
+}
+
+/// Read quickslot counts, trying the memoized inventory region before full detection
+/// Returns the readings and the inventory region to memoize (if known)
+fn read_quickslot_inventory(
+    service: &OcrService,
+    image: &DynamicImage,
+    memoized_roi: Option<(u32, u32, u32, u32)>,
+    slots: &[SlotId],
+) -> Result<(HashMap<SlotKey, SlotReading>, Option<(u32, u32, u32, u32)>), String> {
+    // Try memoized ROI first (fast path)
+    if let Some((left, top, right, bottom)) = memoized_roi {
+        let padding = 100;
+        let img_width = image.width();
+        let img_height = image.height();
+        let padded_left = left.saturating_sub(padding);
+        let padded_top = top.saturating_sub(padding);
+        let padded_right = (right + padding).min(img_width - 1);
+        let padded_bottom = (bottom + padding).min(img_height - 1);
+
+        let crop_width = padded_right - padded_left + 1;
+        let crop_height = padded_bottom - padded_top + 1;
+        let cropped = image.crop_imm(padded_left, padded_top, crop_width, crop_height);
+
+        if let Ok(results) = service.recognize_specific_inventory(&cropped, slots) {
+            return Ok((results, Some((left, top, right, bottom))));
+        }
+    }
+
+    // Fallback: Full detection
+    let results = service.recognize_specific_inventory(image, slots)?;
+
+    // Try to get ROI coordinates for memoization
+    if let Some(matcher) = &service.inventory_matcher {
+        if let Ok((_, coords)) = matcher.detect_inventory_region_with_coords(image) {
+            let (left, top, right, bottom) = coords;
+            let width = right - left + 1;
+            let height = bottom - top + 1;
+            save_inventory_preview(&image.crop_imm(left, top, width, height));
+
+            return Ok((results, Some(coords)));
+        }
+    }
+    Ok((results, None))
+}
+
 /// Helper function to save inventory preview image
 fn save_inventory_preview(image: &DynamicImage) {
-    let temp_dir = std::env::temp_dir().join("exp-tracker-previews");
-    if fs::create_dir_all(&temp_dir).is_err() {
-        return;
-    }
+    let debug_dir = match storage::data_subdir(storage::DEBUG_DIR) {
+        Ok(dir) => dir,
+        Err(_) => return,
+    };
 
-    let file_path = temp_dir.join("inventory_preview.png");
+    let file_path = debug_dir.join("inventory_preview.png");
     let _ = image.save(&file_path);
 }
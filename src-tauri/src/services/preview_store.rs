@@ -0,0 +1,171 @@
+use crate::services::storage;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+/// Previews kept per profile and ROI type
+pub const PREVIEW_HISTORY: usize = 5;
+
+/// Profile used when the caller doesn't name one
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// One stored ROI preview image
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PreviewEntry {
+    /// Unique within its profile and ROI type (creation time in ms)
+    pub id: String,
+    pub profile: String,
+    pub roi_type: String,
+    pub path: String,
+    pub created_at: i64,
+}
+
+/// ROI preview images, stored as `<root>/<profile>/<roi_type>/<created_at>.png`
+pub struct PreviewStore {
+    root: PathBuf,
+}
+
+impl PreviewStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Store in the current data directory
+    pub fn open() -> Self {
+        Self::new(storage::previews_dir())
+    }
+
+    /// Save a preview and drop the oldest beyond `PREVIEW_HISTORY`
+    pub fn save(&self, profile: &str, roi_type: &str, png: &[u8]) -> Result<PreviewEntry, String> {
+        let dir = self.dir(profile, roi_type);
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create preview directory: {}", e))?;
+
+        // Saving twice within a millisecond must not overwrite
+        let mut created_at = chrono::Utc::now().timestamp_millis();
+        while dir.join(format!("{}.png", created_at)).exists() {
+            created_at += 1;
+        }
+
+        let path = dir.join(format!("{}.png", created_at));
+        fs::write(&path, png)
+            .map_err(|e| format!("Failed to write preview file: {}", e))?;
+
+        for old in self.list(profile, roi_type).iter().skip(PREVIEW_HISTORY) {
+            let _ = fs::remove_file(&old.path);
+        }
+
+        Ok(self.entry(profile, roi_type, created_at))
+    }
+
+    /// Previews newest first
+    pub fn list(&self, profile: &str, roi_type: &str) -> Vec<PreviewEntry> {
+        let entries = match fs::read_dir(self.dir(profile, roi_type)) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut created: Vec<i64> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension()? != "png" {
+                    return None;
+                }
+                path.file_stem()?.to_str()?.parse().ok()
+            })
+            .collect();
+        created.sort_unstable_by(|a, b| b.cmp(a));
+
+        created.into_iter()
+            .map(|created_at| self.entry(profile, roi_type, created_at))
+            .collect()
+    }
+
+    /// A specific preview, or the newest one
+    pub fn get(&self, profile: &str, roi_type: &str, id: Option<&str>) -> Option<PreviewEntry> {
+        let previews = self.list(profile, roi_type);
+        match id {
+            Some(id) => previews.into_iter().find(|preview| preview.id == id),
+            None => previews.into_iter().next(),
+        }
+    }
+
+    pub fn delete(&self, profile: &str, roi_type: &str, id: &str) -> Result<(), String> {
+        let preview = self.get(profile, roi_type, Some(id))
+            .ok_or_else(|| format!("Preview '{}' not found", id))?;
+        fs::remove_file(&preview.path)
+            .map_err(|e| format!("Failed to delete preview: {}", e))
+    }
+
+    fn dir(&self, profile: &str, roi_type: &str) -> PathBuf {
+        self.root.join(sanitize(profile)).join(roi_type)
+    }
+
+    fn entry(&self, profile: &str, roi_type: &str, created_at: i64) -> PreviewEntry {
+        let path = self.dir(profile, roi_type).join(format!("{}.png", created_at));
+        PreviewEntry {
+            id: created_at.to_string(),
+            profile: profile.to_string(),
+            roi_type: roi_type.to_string(),
+            path: path.to_string_lossy().to_string(),
+            created_at,
+        }
+    }
+}
+
+/// Profile name as a safe folder name
+fn sanitize(profile: &str) -> String {
+    let name: String = profile.trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+
+    if name.is_empty() { DEFAULT_PROFILE.to_string() } else { name }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_store(name: &str) -> PreviewStore {
+        let root = std::env::temp_dir().join(format!("exp-tracker-previews-test-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&root);
+        PreviewStore::new(root)
+    }
+
+    #[test]
+    fn test_history_keeps_newest_previews() {
+        let store = test_store("history");
+
+        let saved: Vec<PreviewEntry> = (0..PREVIEW_HISTORY + 2)
+            .map(|i| store.save("main", "level", &[i as u8]).unwrap())
+            .collect();
+
+        let previews = store.list("main", "level");
+        assert_eq!(previews.len(), PREVIEW_HISTORY);
+        assert_eq!(previews[0], saved[saved.len() - 1]);
+        assert_eq!(store.get("main", "level", None), Some(saved[saved.len() - 1].clone()));
+        assert!(store.get("main", "level", Some(&saved[0].id)).is_none());
+
+        let _ = fs::remove_dir_all(&store.root);
+    }
+
+    #[test]
+    fn test_profiles_do_not_clobber_each_other() {
+        let store = test_store("profiles");
+
+        store.save("main", "exp", &[1]).unwrap();
+        let other = store.save("../alt", "exp", &[2]).unwrap();
+
+        assert_eq!(store.list("main", "exp").len(), 1);
+        assert_eq!(fs::read(&other.path).unwrap(), vec![2]);
+        assert!(other.path.contains("___alt"));
+
+        store.delete("../alt", "exp", &other.id).unwrap();
+        assert!(store.list("../alt", "exp").is_empty());
+        assert_eq!(store.list("main", "exp").len(), 1);
+
+        let _ = fs::remove_dir_all(&store.root);
+    }
+}
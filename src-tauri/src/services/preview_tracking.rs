@@ -0,0 +1,170 @@
+use crate::commands::ocr::OcrServiceState;
+use crate::commands::tracking::TrackerState;
+use crate::models::roi::Roi;
+use crate::services::ocr_tracker::update_interval;
+use crate::services::screen_capture::ScreenCapture;
+use image::DynamicImage;
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// One ROI's reading in preview tracking
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PreviewReading {
+    /// "level" or "exp"
+    pub roi: String,
+    pub level: Option<u32>,
+    pub exp: Option<u64>,
+    pub percentage: Option<f64>,
+    /// Text as recognized, also when it couldn't be parsed; None if nothing was read
+    pub raw_text: Option<String>,
+    /// Lowest OCR score among the text boxes; None when nothing was read
+    /// or the level was read by template matching
+    pub confidence: Option<f64>,
+    pub error: Option<String>,
+}
+
+impl PreviewReading {
+    fn failed(roi: &str, error: String) -> Self {
+        Self {
+            roi: roi.to_string(),
+            level: None,
+            exp: None,
+            percentage: None,
+            raw_text: None,
+            confidence: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Payload for "preview:readings"
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewCycle {
+    pub seq: u64,
+    pub timestamp: i64, // Unix timestamp in milliseconds (UTC)
+    pub readings: Vec<PreviewReading>,
+}
+
+/// Run capture + OCR on the level and EXP ROIs every update interval and
+/// emit what was recognized as "preview:readings"
+///
+/// Nothing goes to the tracker actor, so the timer, rates and calculators
+/// are untouched. Stops on its own once real tracking starts.
+pub fn spawn(
+    app: AppHandle,
+    ocr_service: OcrServiceState,
+    level_roi: Option<Roi>,
+    exp_roi: Option<Roi>,
+) -> Result<tauri::async_runtime::JoinHandle<()>, String> {
+    if level_roi.is_none() && exp_roi.is_none() {
+        return Err("Set the level or EXP ROI before previewing".to_string());
+    }
+    let capture = Arc::new(ScreenCapture::new()?);
+
+    Ok(tauri::async_runtime::spawn(async move {
+        let mut seq = 0;
+
+        loop {
+            if let Some(tracker) = app.try_state::<TrackerState>() {
+                if tracker.0.lock().await.get_stats().await.is_tracking {
+                    println!("🔍 Tracking started, preview stopped");
+                    return;
+                }
+            }
+
+            let screen = {
+                let capture = capture.clone();
+                tokio::task::spawn_blocking(move || capture.capture_full())
+                    .await
+                    .map_err(|e| format!("Capture task failed: {}", e))
+                    .and_then(|result| result)
+            };
+
+            let mut readings = Vec::new();
+            match screen {
+                Ok(screen) => {
+                    let scale_factor = capture.get_scale_factor();
+                    if let Some(roi) = &level_roi {
+                        readings.push(read_level(&ocr_service, &screen, roi, scale_factor).await);
+                    }
+                    if let Some(roi) = &exp_roi {
+                        readings.push(read_exp(&ocr_service, &screen, roi, scale_factor).await);
+                    }
+                }
+                Err(e) => {
+                    if level_roi.is_some() {
+                        readings.push(PreviewReading::failed("level", e.clone()));
+                    }
+                    if exp_roi.is_some() {
+                        readings.push(PreviewReading::failed("exp", e));
+                    }
+                }
+            }
+
+            seq += 1;
+            let cycle = PreviewCycle {
+                seq,
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                readings,
+            };
+            if let Err(e) = app.emit("preview:readings", cycle) {
+                eprintln!("Failed to emit preview readings: {}", e);
+            }
+
+            tokio::time::sleep(update_interval(&app)).await;
+        }
+    }))
+}
+
+async fn read_level(ocr_service: &OcrServiceState, screen: &DynamicImage, roi: &Roi, scale_factor: f64) -> PreviewReading {
+    let image = match ScreenCapture::crop_logical(screen, roi, scale_factor) {
+        Ok(image) => image,
+        Err(e) => return PreviewReading::failed("level", e),
+    };
+
+    match ocr_service.http_client.read_level(&image).await {
+        Ok(reading) => {
+            let (level, error) = match reading.result {
+                Ok(result) => (Some(result.level), None),
+                Err(e) => (None, Some(e)),
+            };
+            PreviewReading {
+                roi: "level".to_string(),
+                level,
+                exp: None,
+                percentage: None,
+                raw_text: Some(reading.raw_text),
+                confidence: reading.confidence,
+                error,
+            }
+        }
+        Err(e) => PreviewReading::failed("level", e),
+    }
+}
+
+async fn read_exp(ocr_service: &OcrServiceState, screen: &DynamicImage, roi: &Roi, scale_factor: f64) -> PreviewReading {
+    let image = match ScreenCapture::crop_logical(screen, roi, scale_factor) {
+        Ok(image) => image,
+        Err(e) => return PreviewReading::failed("exp", e),
+    };
+
+    match ocr_service.http_client.read_exp(&image).await {
+        Ok(reading) => {
+            let (exp, percentage, error) = match reading.result {
+                Ok(result) => (Some(result.absolute), Some(result.percentage), None),
+                Err(e) => (None, None, Some(e)),
+            };
+            PreviewReading {
+                roi: "exp".to_string(),
+                level: None,
+                exp,
+                percentage,
+                raw_text: Some(reading.raw_text),
+                confidence: reading.confidence,
+                error,
+            }
+        }
+        Err(e) => PreviewReading::failed("exp", e),
+    }
+}
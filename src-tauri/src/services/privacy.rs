@@ -0,0 +1,41 @@
+use crate::commands::session::SessionRecord;
+
+/// Shown in place of a hidden map name
+pub const REDACTED: &str = "***";
+
+/// Map name as it may be shown or shared in privacy mode
+pub fn redact_map_name(map_name: Option<String>) -> Option<String> {
+    map_name.map(|_| REDACTED.to_string())
+}
+
+/// Record with its map name starred out, in the title too
+/// Stored records keep the real name; this is only for what leaves the backend.
+pub fn redact_record(mut record: SessionRecord) -> SessionRecord {
+    if let Some(map_name) = record.map_name.as_deref().filter(|name| !name.is_empty()) {
+        record.title = record.title.replace(map_name, REDACTED);
+    }
+    record.map_name = redact_map_name(record.map_name);
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_record_hides_map_in_title() {
+        let record = SessionRecord {
+            title: "헤네시스 · 2024년 01월 01일 12:00 전투".to_string(),
+            map_name: Some("헤네시스".to_string()),
+            ..SessionRecord::sample(0)
+        };
+
+        let redacted = redact_record(record.clone());
+        assert_eq!(redacted.title, "*** · 2024년 01월 01일 12:00 전투");
+        assert_eq!(redacted.map_name.as_deref(), Some(REDACTED));
+
+        let no_map = redact_record(SessionRecord { map_name: None, ..record });
+        assert_eq!(no_map.map_name, None);
+        assert!(no_map.title.starts_with("헤네시스"));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::commands::session::{self, SessionRecord};
+use crate::services::exp_calculator::round_percentage;
+use serde::Serialize;
+
+/// Progress between two sessions smaller than this (in levels, i.e. 0.1%) is OCR noise, not a gap
+const GAP_TOLERANCE_LEVELS: f64 = 0.001;
+
+/// Where a session started and ended
+///
+/// End values come from the record's final stats; the start is worked back
+/// from the percentage gained. Records saved before final stats were kept only
+/// know their end level, so their percentages are None. Split and merged
+/// records keep the snapshot of a different stretch of time: its end is still
+/// theirs, but their start is only known to the level.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LedgerEntry {
+    pub session_id: String,
+    /// Wall-clock session start, UTC Unix timestamp in milliseconds
+    /// (estimated from the combat time for older records)
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub start_level: u32,
+    pub start_percentage: Option<f64>,
+    pub end_level: u32,
+    pub end_percentage: Option<f64>,
+    /// EXP into `end_level` at the end
+    pub end_exp: Option<u64>,
+    pub exp_gained: u64,
+    pub levels_gained: u32,
+}
+
+impl LedgerEntry {
+    /// None for records without a level reading
+    pub fn of(record: &SessionRecord) -> Option<Self> {
+        let stats = record.final_stats.as_ref();
+        let end_level = stats
+            .and_then(|s| s.level)
+            .or_else(|| u32::try_from(record.current_level).ok())
+            .filter(|&level| level > 0)?;
+        let end_percentage = stats.and_then(|s| s.percentage);
+
+        // The percentage gained covers the snapshot's time, not necessarily the record's
+        let gained = stats.filter(|s| s.elapsed_seconds == record.combat_time.max(0) as u64);
+
+        let (start_level, start_percentage) = match (end_percentage, gained) {
+            (Some(end_percentage), Some(stats)) => {
+                let start = position(end_level, end_percentage) - stats.total_percentage / 100.0;
+                let start_level = (start.floor() as u32).clamp(1, end_level);
+                (start_level, Some(round_percentage((start - start_level as f64) * 100.0).max(0.0)))
+            }
+            _ => (end_level, None),
+        };
+
+        Some(Self {
+            session_id: record.id.clone(),
+            started_at: session::session_start(record),
+            ended_at: record.timestamp,
+            start_level,
+            start_percentage,
+            end_level,
+            end_percentage,
+            end_exp: stats.and_then(|s| s.exp),
+            exp_gained: record.exp_gained.max(0) as u64,
+            levels_gained: end_level - start_level,
+        })
+    }
+
+    fn start(&self) -> Option<f64> {
+        self.start_percentage.map(|p| position(self.start_level, p))
+    }
+
+    fn end(&self) -> Option<f64> {
+        self.end_percentage.map(|p| position(self.end_level, p))
+    }
+}
+
+/// Progress made between two sessions without tracking
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ProgressGap {
+    /// Session before and after the gap
+    pub after_session: String,
+    pub before_session: String,
+    pub from_level: u32,
+    pub from_percentage: Option<f64>,
+    pub to_level: u32,
+    pub to_percentage: Option<f64>,
+    /// Levels (fractional when percentages are known) gained in between;
+    /// negative when EXP was lost (e.g. death penalty) or another character was tracked
+    pub levels: f64,
+    /// Time between the sessions in milliseconds
+    pub duration_ms: i64,
+}
+
+/// Long-term level progression across saved sessions
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ProgressHistory {
+    /// Oldest session first
+    pub entries: Vec<LedgerEntry>,
+    pub gaps: Vec<ProgressGap>,
+    pub first_level: Option<u32>,
+    pub current_level: Option<u32>,
+    /// Levels gained inside tracked sessions
+    pub tracked_levels: f64,
+    /// Levels gained (or lost) between them
+    pub untracked_levels: f64,
+}
+
+/// Fractional level, e.g. 126 at 12.76% is 126.1276
+fn position(level: u32, percentage: f64) -> f64 {
+    level as f64 + percentage / 100.0
+}
+
+/// Build the ledger from saved sessions (any order)
+pub fn progress_history(records: &[SessionRecord]) -> ProgressHistory {
+    let mut entries: Vec<LedgerEntry> = records.iter().filter_map(LedgerEntry::of).collect();
+    entries.sort_by_key(|entry| entry.ended_at);
+    ledger(entries)
+}
+
+fn ledger(entries: Vec<LedgerEntry>) -> ProgressHistory {
+    let mut gaps = Vec::new();
+    let mut tracked_levels = 0.0;
+
+    for (i, entry) in entries.iter().enumerate() {
+        tracked_levels += match (entry.start(), entry.end()) {
+            (Some(start), Some(end)) => end - start,
+            _ => entry.levels_gained as f64,
+        };
+
+        let Some(previous) = i.checked_sub(1).map(|p| &entries[p]) else {
+            continue;
+        };
+        // Compare percentages when both sides have them, otherwise whole levels
+        let levels = match (previous.end(), entry.start()) {
+            (Some(end), Some(start)) => start - end,
+            _ => entry.start_level as f64 - previous.end_level as f64,
+        };
+        if levels.abs() >= GAP_TOLERANCE_LEVELS {
+            gaps.push(ProgressGap {
+                after_session: previous.session_id.clone(),
+                before_session: entry.session_id.clone(),
+                from_level: previous.end_level,
+                from_percentage: previous.end_percentage,
+                to_level: entry.start_level,
+                to_percentage: entry.start_percentage,
+                levels: round_levels(levels),
+                duration_ms: (entry.started_at - previous.ended_at).max(0),
+            });
+        }
+    }
+
+    ProgressHistory {
+        first_level: entries.first().map(|e| e.start_level),
+        current_level: entries.last().map(|e| e.end_level),
+        untracked_levels: round_levels(gaps.iter().map(|gap| gap.levels).sum()),
+        tracked_levels: round_levels(tracked_levels),
+        gaps,
+        entries,
+    }
+}
+
+/// Levels to the 0.01% the client shows
+fn round_levels(levels: f64) -> f64 {
+    (levels * 10_000.0).round() / 10_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, ended_at: i64, start: (u32, f64), end: (u32, f64)) -> LedgerEntry {
+        LedgerEntry {
+            session_id: id.to_string(),
+            started_at: ended_at - 3_600_000,
+            ended_at,
+            start_level: start.0,
+            start_percentage: Some(start.1),
+            end_level: end.0,
+            end_percentage: Some(end.1),
+            end_exp: None,
+            exp_gained: 0,
+            levels_gained: end.0 - start.0,
+        }
+    }
+
+    #[test]
+    fn test_gaps_between_sessions() {
+        let hour = 3_600_000;
+        let history = ledger(vec![
+            entry("a", hour, (100, 10.0), (100, 90.0)),
+            // Continues where "a" ended: no gap
+            entry("b", 3 * hour, (100, 90.0), (101, 20.0)),
+            // Played 1.5 levels without tracking
+            entry("c", 6 * hour, (102, 70.0), (102, 80.0)),
+            // Lost 5% (death penalty)
+            entry("d", 8 * hour, (102, 75.0), (103, 0.0)),
+        ]);
+
+        let levels: Vec<f64> = history.gaps.iter().map(|gap| gap.levels).collect();
+        assert_eq!(levels, vec![1.5, -0.05]);
+        assert_eq!(history.gaps[0].after_session, "b");
+        assert_eq!(history.gaps[0].duration_ms, 2 * hour);
+        assert_eq!(history.tracked_levels, 1.45);
+        assert_eq!(history.untracked_levels, 1.45);
+        assert_eq!((history.first_level, history.current_level), (Some(100), Some(103)));
+    }
+
+    #[test]
+    fn test_records_without_final_stats_compare_levels() {
+        let record = |id: &str, timestamp: i64, level: i32| SessionRecord {
+            id: id.to_string(),
+            current_level: level,
+            ..SessionRecord::sample(timestamp)
+        };
+
+        // Given out of order; the record without a level is left out
+        let history = progress_history(&[record("b", 2_000_000, 52), record("a", 1_000_000, 50), record("x", 1_500_000, 0)]);
+        let ids: Vec<&str> = history.entries.iter().map(|e| e.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(history.entries[0].started_at, 400_000);
+        assert_eq!(history.entries[0].start_percentage, None);
+        assert_eq!(history.gaps.len(), 1);
+        assert_eq!(history.gaps[0].levels, 2.0);
+        assert_eq!(history.gaps[0].duration_ms, 400_000);
+
+        // "b" was resumed: it started long before its end minus its combat time
+        let resumed = SessionRecord { started_at: Some(1_100_000), ..record("b", 2_000_000, 52) };
+        let history = progress_history(&[resumed, record("a", 1_000_000, 50)]);
+        assert_eq!(history.entries[1].started_at, 1_100_000);
+        assert_eq!(history.gaps[0].duration_ms, 100_000);
+    }
+
+    #[test]
+    fn test_start_ignores_snapshot_of_other_time() {
+        use crate::services::ocr_tracker::TrackingStats;
+
+        let stats = TrackingStats {
+            level: Some(101),
+            percentage: Some(20.0),
+            total_percentage: 60.0,
+            elapsed_seconds: 600,
+            ..Default::default()
+        };
+        let whole = SessionRecord { final_stats: Some(stats), ..SessionRecord::sample(1_000_000) };
+        let entry = LedgerEntry::of(&whole).unwrap();
+        assert_eq!((entry.start_level, entry.start_percentage), (100, Some(60.0)));
+
+        // The later half of a split keeps the whole session's snapshot
+        let second_half = SessionRecord { combat_time: 300, ..whole };
+        let entry = LedgerEntry::of(&second_half).unwrap();
+        assert_eq!((entry.start_level, entry.start_percentage), (101, None));
+        assert_eq!((entry.end_level, entry.end_percentage), (101, Some(20.0)));
+    }
+}
@@ -1,12 +1,143 @@
-use std::process::{Child, Command};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Child;
 use std::time::Duration;
 use tokio::time::sleep;
 
+/// Server folder inside the bundled resources (see `bundle.resources` in tauri.conf.json)
+const SERVER_RESOURCE_DIR: &str = "resources/ocr_server";
+
+/// Platform whose install layout is probed
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Platform {
+    Windows,
+    MacOs,
+    Linux,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        if cfg!(target_os = "windows") {
+            Platform::Windows
+        } else if cfg!(target_os = "macos") {
+            Platform::MacOs
+        } else {
+            Platform::Linux
+        }
+    }
+
+    /// File name PyInstaller gives the server binary
+    pub fn server_exe_name(&self) -> &'static str {
+        match self {
+            Platform::Windows => "ocr_server.exe",
+            Platform::MacOs | Platform::Linux => "ocr_server",
+        }
+    }
+
+    /// Script that builds the server binary on this platform
+    pub fn build_script(&self) -> &'static str {
+        match self {
+            Platform::Windows => "scripts\\build_python_server.bat",
+            Platform::MacOs | Platform::Linux => "./scripts/build_python_server.sh",
+        }
+    }
+
+    /// Folders that may hold the server, most likely first
+    ///
+    /// `resource_dir` is Tauri's resolved resource folder when known; the
+    /// rest are the bundle layouts relative to the app binary, then dev paths.
+    pub fn server_dirs(&self, exe_dir: &Path, resource_dir: Option<&Path>) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        if let Some(resource_dir) = resource_dir {
+            dirs.push(resource_dir.join(SERVER_RESOURCE_DIR));
+        }
+
+        match self {
+            // NSIS/MSI install resources next to the .exe
+            Platform::Windows => dirs.push(exe_dir.join(SERVER_RESOURCE_DIR)),
+            // Contents/MacOS/<app> -> Contents/Resources
+            Platform::MacOs => dirs.push(exe_dir.join("../Resources").join(SERVER_RESOURCE_DIR)),
+            // deb/rpm/AppImage: usr/bin/<app> -> usr/lib/<productName>
+            Platform::Linux => {
+                dirs.push(exe_dir.join("../lib/exp-tracker").join(SERVER_RESOURCE_DIR));
+                dirs.push(exe_dir.join(SERVER_RESOURCE_DIR));
+            }
+        }
+
+        // Development: target/<profile>/ -> src-tauri/resources
+        dirs.push(exe_dir.join("../..").join(SERVER_RESOURCE_DIR));
+        dirs.push(PathBuf::from(SERVER_RESOURCE_DIR));
+
+        let mut unique: Vec<PathBuf> = Vec::new();
+        for dir in dirs {
+            if !unique.contains(&dir) {
+                unique.push(dir);
+            }
+        }
+        unique
+    }
+}
+
+/// One place the server binary was looked for
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ProbedPath {
+    pub path: String,
+    pub exists: bool,
+}
+
+/// Where the OCR server binary was looked for and which one would be started
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ServerLocation {
+    pub platform: Platform,
+    pub exe_name: String,
+    pub probed: Vec<ProbedPath>,
+    /// First existing binary; None if none was found
+    pub found: Option<String>,
+}
+
+/// Probe every candidate folder for the server binary
+pub fn locate_server(resource_dir: Option<&Path>) -> Result<ServerLocation, String> {
+    let exe_dir = std::env::current_exe()
+        .map_err(|e| format!("Failed to get exe path: {}", e))?
+        .parent()
+        .ok_or("Failed to get exe parent dir")?
+        .to_path_buf();
+
+    Ok(locate_in(Platform::current(), &exe_dir, resource_dir, |path| path.is_file()))
+}
+
+fn locate_in(
+    platform: Platform,
+    exe_dir: &Path,
+    resource_dir: Option<&Path>,
+    exists: impl Fn(&Path) -> bool,
+) -> ServerLocation {
+    let exe_name = platform.server_exe_name();
+    let probed: Vec<ProbedPath> = platform
+        .server_dirs(exe_dir, resource_dir)
+        .iter()
+        .map(|dir| {
+            let bin = dir.join(exe_name);
+            ProbedPath { exists: exists(&bin), path: bin.to_string_lossy().to_string() }
+        })
+        .collect();
+
+    ServerLocation {
+        platform,
+        exe_name: exe_name.to_string(),
+        found: probed.iter().find(|p| p.exists).map(|p| p.path.clone()),
+        probed,
+    }
+}
+
 /// Python OCR Server Manager
 /// Handles automatic start/stop of the Python FastAPI server
 pub struct PythonServerManager {
     process: Option<Child>,
     base_url: String,
+    /// Tauri's resource folder, probed first for the bundled server
+    resource_dir: Option<PathBuf>,
 }
 
 impl PythonServerManager {
@@ -15,9 +146,19 @@ impl PythonServerManager {
         Self {
             process: None,
             base_url: "http://127.0.0.1:39835".to_string(),
+            resource_dir: None,
         }
     }
 
+    /// Where the app's bundled resources live (from `app.path().resource_dir()`)
+    pub fn set_resource_dir(&mut self, resource_dir: Option<PathBuf>) {
+        self.resource_dir = resource_dir;
+    }
+
+    pub fn resource_dir(&self) -> Option<&Path> {
+        self.resource_dir.as_deref()
+    }
+
     /// Start the Python OCR server using bundled binary
     pub async fn start(&mut self) -> Result<(), String> {
         #[cfg(debug_assertions)]
@@ -30,62 +171,59 @@ impl PythonServerManager {
             return Ok(());
         }
 
-        // Start bundled server
-        let child = self.start_server()?;
-        self.process = Some(child);
+        // Development builds can serve canned responses instead of the real server
+        #[cfg(feature = "mock-ocr")]
+        {
+            super::mock_ocr_server::start().await
+        }
 
-        // Wait for server to be ready
-        self.wait_for_ready().await?;
+        #[cfg(all(feature = "python-server", not(feature = "mock-ocr")))]
+        {
+            // Start bundled server
+            let child = self.start_server()?;
+            self.process = Some(child);
 
-        #[cfg(debug_assertions)]
-        println!("✅ Python OCR server started successfully");
+            // Wait for server to be ready
+            self.wait_for_ready().await?;
+
+            #[cfg(debug_assertions)]
+            println!("✅ Python OCR server started successfully");
+
+            Ok(())
+        }
 
-        Ok(())
+        #[cfg(not(any(feature = "python-server", feature = "mock-ocr")))]
+        {
+            Err(format!(
+                "No OCR server at {} (built without the python-server feature).\n\
+                Start python_ocr_server yourself or enable the mock-ocr feature.",
+                self.base_url
+            ))
+        }
     }
 
     /// Start server using bundled binary (onedir mode)
+    #[cfg(all(feature = "python-server", not(feature = "mock-ocr")))]
     fn start_server(&self) -> Result<Child, String> {
-        // Get the directory where the executable is located
-        let exe_dir = std::env::current_exe()
-            .map_err(|e| format!("Failed to get exe path: {}", e))?
-            .parent()
-            .ok_or("Failed to get exe parent dir")?
-            .to_path_buf();
-
-        // Platform-specific executable name
-        #[cfg(target_os = "windows")]
-        let exe_name = "ocr_server.exe";
-        #[cfg(not(target_os = "windows"))]
-        let exe_name = "ocr_server";
-
-        // Look for ocr_server directory in resources (for bundled app)
-        let server_dir = exe_dir.join("../Resources/resources/ocr_server");
-        let server_bin = server_dir.join(exe_name);
-
-        // Fallback: look in src-tauri/resources (for development)
-        let (server_dir, server_bin) = if server_bin.exists() {
-            (server_dir, server_bin)
-        } else {
-            let dev_dir = exe_dir.join("resources/ocr_server");
-            let dev_bin = dev_dir.join(exe_name);
-            (dev_dir, dev_bin)
-        };
-
-        if !server_bin.exists() {
+        let location = locate_server(self.resource_dir())?;
+        let Some(server_bin) = location.found.map(PathBuf::from) else {
+            let probed: Vec<&str> = location.probed.iter().map(|p| p.path.as_str()).collect();
             return Err(format!(
-                "OCR server binary not found at: {:?}\n\n\
-                Please build it first:\n\
-                  ./scripts/build_python_server.sh",
-                server_bin
+                "OCR server binary ({}) not found. Looked in:\n  {}\n\n\
+                Please build it first:\n  {}",
+                location.exe_name,
+                probed.join("\n  "),
+                location.platform.build_script()
             ));
-        }
+        };
+        let server_dir = server_bin.parent().map(Path::to_path_buf).unwrap_or_default();
 
         #[cfg(debug_assertions)]
         println!("📍 Server directory: {:?}", server_dir);
         #[cfg(debug_assertions)]
         println!("📍 Server binary: {:?}", server_bin);
 
-        Command::new(server_bin)
+        std::process::Command::new(server_bin)
             .current_dir(server_dir)
             .spawn()
             .map_err(|e| format!("Failed to start server: {}", e))
@@ -102,6 +240,7 @@ impl PythonServerManager {
     }
 
     /// Wait for server to be ready (max 30 seconds)
+    #[cfg(all(feature = "python-server", not(feature = "mock-ocr")))]
     async fn wait_for_ready(&self) -> Result<(), String> {
         // Initial wait for server process to boot (PyInstaller takes ~2-3 seconds)
         sleep(Duration::from_millis(2000)).await;
@@ -231,3 +370,33 @@ impl Drop for PythonServerManager {
         self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_install_finds_exe_next_to_app() {
+        let exe_dir = Path::new("C:/Program Files/exp-tracker");
+        let expected = exe_dir.join("resources/ocr_server/ocr_server.exe");
+
+        let location = locate_in(Platform::Windows, exe_dir, None, |path| path == expected);
+        assert_eq!(location.exe_name, "ocr_server.exe");
+        assert_eq!(location.found, Some(expected.to_string_lossy().to_string()));
+        assert!(location.probed.iter().all(|p| p.path.ends_with("ocr_server.exe")));
+    }
+
+    #[test]
+    fn test_resource_dir_is_probed_first_and_missing_binary_lists_every_path() {
+        let exe_dir = Path::new("/Applications/exp-tracker.app/Contents/MacOS");
+        let resource_dir = Path::new("/Applications/exp-tracker.app/Contents/Resources");
+
+        let location = locate_in(Platform::MacOs, exe_dir, Some(resource_dir), |_| false);
+        assert_eq!(location.found, None);
+        assert_eq!(
+            location.probed[0].path,
+            resource_dir.join("resources/ocr_server/ocr_server").to_string_lossy()
+        );
+        assert!(location.probed.len() >= 3);
+    }
+}
@@ -0,0 +1,420 @@
+use crate::commands::session::SessionRecord;
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Output format of a generated report
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+}
+
+impl ReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "html",
+            ReportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// Which sessions a report covers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReportRange {
+    /// The last `days` days up to now (7 for a weekly report)
+    LastDays { days: u32 },
+    /// UTC Unix timestamps in milliseconds, `to` exclusive
+    Custom { from: i64, to: i64 },
+}
+
+impl ReportRange {
+    /// (from, to) in UTC milliseconds
+    pub fn bounds(&self, now_millis: i64) -> (i64, i64) {
+        match self {
+            ReportRange::LastDays { days } => (now_millis - *days as i64 * 24 * 60 * 60 * 1000, now_millis),
+            ReportRange::Custom { from, to } => (*from, *to),
+        }
+    }
+}
+
+/// Totals over the sessions of a report
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportTotals {
+    pub sessions: usize,
+    pub combat_seconds: i64,
+    pub exp_gained: i64,
+    pub hp_potions_used: i64,
+    pub mp_potions_used: i64,
+}
+
+impl ReportTotals {
+    pub fn from_records(records: &[SessionRecord]) -> Self {
+        Self {
+            sessions: records.len(),
+            combat_seconds: records.iter().map(|r| r.combat_time as i64).sum(),
+            exp_gained: records.iter().map(|r| r.exp_gained).sum(),
+            hp_potions_used: records.iter().map(|r| r.hp_potions_used as i64).sum(),
+            mp_potions_used: records.iter().map(|r| r.mp_potions_used as i64).sum(),
+        }
+    }
+
+    pub fn exp_per_hour(&self) -> i64 {
+        exp_per_hour(self.exp_gained, self.combat_seconds)
+    }
+}
+
+fn exp_per_hour(exp: i64, seconds: i64) -> i64 {
+    if seconds > 0 {
+        (exp as i128 * 3600 / seconds as i128) as i64
+    } else {
+        0
+    }
+}
+
+/// Map with the highest EXP per hour, and that rate
+/// Sessions that ended within the rate warm-up (`warmup_secs`) are left out:
+/// a lucky first minute would otherwise outrank hours of real grinding.
+pub fn best_map(records: &[SessionRecord], warmup_secs: u64) -> Option<(String, i64)> {
+    let mut maps: BTreeMap<&str, (i64, i64)> = BTreeMap::new();
+    for record in records {
+        if (record.combat_time.max(0) as u64) < warmup_secs {
+            continue;
+        }
+        if let Some(map_name) = record.map_name.as_deref() {
+            let (exp, seconds) = maps.entry(map_name).or_default();
+            *exp += record.exp_gained;
+            *seconds += record.combat_time as i64;
+        }
+    }
+
+    maps.into_iter()
+        .filter(|(_, (_, seconds))| *seconds > 0)
+        .map(|(map_name, (exp, seconds))| (map_name.to_string(), exp_per_hour(exp, seconds)))
+        .max_by_key(|(_, rate)| *rate)
+}
+
+/// EXP gained per local day, every day of the range included
+fn exp_per_day(records: &[SessionRecord], from: i64, to: i64) -> Vec<(NaiveDate, i64)> {
+    let local_date = |millis: i64| Local.timestamp_millis_opt(millis).single().map(|d| d.date_naive());
+    let (Some(first), Some(last)) = (local_date(from), local_date(to - 1)) else {
+        return Vec::new();
+    };
+
+    let mut days: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+    let mut day = first;
+    while day <= last {
+        days.insert(day, 0);
+        day += Duration::days(1);
+    }
+    for record in records {
+        if let Some(exp) = local_date(record.timestamp).and_then(|date| days.get_mut(&date)) {
+            *exp += record.exp_gained;
+        }
+    }
+
+    days.into_iter().collect()
+}
+
+/// Bar chart of daily EXP as a standalone SVG
+/// Long ranges are drawn with one bar per several days so bars stay visible.
+pub fn daily_exp_chart(days: &[(NaiveDate, i64)]) -> String {
+    const WIDTH: usize = 640;
+    const HEIGHT: usize = 240;
+    const LABEL_HEIGHT: usize = 24;
+    /// Narrowest slot a bar gets, and the least room a date label needs
+    const MIN_SLOT: usize = 8;
+    const LABEL_SPACING: usize = 40;
+
+    let days_per_bar = days.len().div_ceil(WIDTH / MIN_SLOT).max(1);
+    let bars: Vec<(NaiveDate, NaiveDate, i64)> = days.chunks(days_per_bar)
+        .map(|chunk| (chunk[0].0, chunk[chunk.len() - 1].0, chunk.iter().map(|(_, exp)| *exp).sum()))
+        .collect();
+
+    let max = bars.iter().map(|(_, _, exp)| *exp).max().unwrap_or(0).max(1);
+    let slot = WIDTH / bars.len().max(1);
+    let bar_width = (slot * 3 / 4).max(1);
+    let label_every = LABEL_SPACING.div_ceil(slot.max(1));
+    let plot_height = HEIGHT - LABEL_HEIGHT;
+
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="11">"##
+    );
+    for (i, (first, last, exp)) in bars.iter().enumerate() {
+        let bar_height = (*exp as f64 / max as f64 * (plot_height - 12) as f64).round() as usize;
+        let x = i * slot + slot.saturating_sub(bar_width) / 2;
+        let center = i * slot + slot / 2;
+        let period = if first == last { first.to_string() } else { format!("{} – {}", first, last) };
+        svg.push_str(&format!(
+            r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#4f8cff"><title>{}: {}</title></rect>"##,
+            x, plot_height - bar_height, bar_width, bar_height, period, format_number(*exp)
+        ));
+        if i % label_every == 0 {
+            svg.push_str(&format!(
+                r##"<text x="{}" y="{}" text-anchor="middle" fill="#666">{}</text>"##,
+                center, HEIGHT - 8, first.format("%m/%d")
+            ));
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// 1234567 -> "1,234,567"
+pub fn format_number(value: i64) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    if value < 0 {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+fn format_duration(seconds: i64) -> String {
+    format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn local_datetime(millis: i64) -> String {
+    Local.timestamp_millis_opt(millis)
+        .single()
+        .map(|datetime| datetime.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// Render a report over `records` (display titles, already filtered to the range)
+pub fn render(records: &[SessionRecord], from: i64, to: i64, format: ReportFormat, warmup_secs: u64) -> String {
+    let mut records = records.to_vec();
+    records.sort_by_key(|record| record.timestamp);
+
+    let totals = ReportTotals::from_records(&records);
+    let best = best_map(&records, warmup_secs);
+    let chart = daily_exp_chart(&exp_per_day(&records, from, to));
+    let period = format!("{} – {}", local_datetime(from), local_datetime(to));
+
+    match format {
+        ReportFormat::Html => render_html(&records, &totals, best, &chart, &period),
+        ReportFormat::Markdown => render_markdown(&records, &totals, best, &chart, &period),
+    }
+}
+
+fn render_html(
+    records: &[SessionRecord],
+    totals: &ReportTotals,
+    best: Option<(String, i64)>,
+    chart: &str,
+    period: &str,
+) -> String {
+    let mut rows = String::new();
+    for record in records {
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            local_datetime(record.timestamp),
+            escape_html(&record.title),
+            escape_html(record.map_name.as_deref().unwrap_or("-")),
+            record.current_level,
+            format_duration(record.combat_time as i64),
+            format_number(record.exp_gained),
+            format_number(exp_per_hour(record.exp_gained, record.combat_time as i64)),
+            record.hp_potions_used + record.mp_potions_used,
+        ));
+    }
+    let best = match best {
+        Some((map_name, rate)) => format!("{} ({} EXP/h)", escape_html(&map_name), format_number(rate)),
+        None => "-".to_string(),
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="ko">
+<head>
+<meta charset="utf-8">
+<title>EXP Tracker Report</title>
+<style>
+body {{ font-family: sans-serif; margin: 2rem; color: #222; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border: 1px solid #ddd; padding: 4px 8px; text-align: left; }}
+th {{ background: #f4f4f4; }}
+dl {{ display: grid; grid-template-columns: max-content auto; gap: 4px 16px; }}
+dt {{ font-weight: bold; }}
+</style>
+</head>
+<body>
+<h1>EXP Tracker Report</h1>
+<p>{period}</p>
+<h2>Totals</h2>
+<dl>
+<dt>Sessions</dt><dd>{sessions}</dd>
+<dt>Combat time</dt><dd>{combat_time}</dd>
+<dt>EXP gained</dt><dd>{exp}</dd>
+<dt>EXP per hour</dt><dd>{exp_per_hour}</dd>
+<dt>Potions used</dt><dd>HP {hp} / MP {mp}</dd>
+<dt>Best map</dt><dd>{best}</dd>
+</dl>
+<h2>EXP per day</h2>
+{chart}
+<h2>Sessions</h2>
+<table>
+<tr><th>Date</th><th>Title</th><th>Map</th><th>Level</th><th>Time</th><th>EXP</th><th>EXP/h</th><th>Potions</th></tr>
+{rows}</table>
+</body>
+</html>
+"#,
+        period = escape_html(period),
+        sessions = totals.sessions,
+        combat_time = format_duration(totals.combat_seconds),
+        exp = format_number(totals.exp_gained),
+        exp_per_hour = format_number(totals.exp_per_hour()),
+        hp = format_number(totals.hp_potions_used),
+        mp = format_number(totals.mp_potions_used),
+        best = best,
+        chart = chart,
+        rows = rows,
+    )
+}
+
+fn render_markdown(
+    records: &[SessionRecord],
+    totals: &ReportTotals,
+    best: Option<(String, i64)>,
+    chart: &str,
+    period: &str,
+) -> String {
+    use base64::Engine;
+
+    let mut report = format!("# EXP Tracker Report\n\n{}\n\n## Totals\n\n", period);
+    report.push_str(&format!("- Sessions: {}\n", totals.sessions));
+    report.push_str(&format!("- Combat time: {}\n", format_duration(totals.combat_seconds)));
+    report.push_str(&format!("- EXP gained: {}\n", format_number(totals.exp_gained)));
+    report.push_str(&format!("- EXP per hour: {}\n", format_number(totals.exp_per_hour())));
+    report.push_str(&format!(
+        "- Potions used: HP {} / MP {}\n",
+        format_number(totals.hp_potions_used),
+        format_number(totals.mp_potions_used)
+    ));
+    match best {
+        Some((map_name, rate)) => report.push_str(&format!("- Best map: {} ({} EXP/h)\n", map_name, format_number(rate))),
+        None => report.push_str("- Best map: -\n"),
+    }
+
+    // Markdown has no inline SVG; a data URI keeps the report a single file
+    let chart = base64::engine::general_purpose::STANDARD.encode(chart);
+    report.push_str(&format!("\n## EXP per day\n\n![EXP per day](data:image/svg+xml;base64,{})\n", chart));
+
+    report.push_str("\n## Sessions\n\n| Date | Title | Map | Level | Time | EXP | EXP/h | Potions |\n");
+    report.push_str("|---|---|---|---:|---:|---:|---:|---:|\n");
+    for record in records {
+        report.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            local_datetime(record.timestamp),
+            escape_markdown_cell(&record.title),
+            escape_markdown_cell(record.map_name.as_deref().unwrap_or("-")),
+            record.current_level,
+            format_duration(record.combat_time as i64),
+            format_number(record.exp_gained),
+            format_number(exp_per_hour(record.exp_gained, record.combat_time as i64)),
+            record.hp_potions_used + record.mp_potions_used,
+        ));
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(map_name: Option<&str>, combat_time: i32, exp_gained: i64) -> SessionRecord {
+        SessionRecord {
+            title: "보스 <트라이>".to_string(),
+            combat_time,
+            exp_gained,
+            hp_potions_used: 10,
+            mp_potions_used: 5,
+            map_name: map_name.map(str::to_string),
+            ..SessionRecord::sample(1_700_000_000_000)
+        }
+    }
+
+    #[test]
+    fn test_totals_and_best_map() {
+        let records = vec![
+            record(Some("헤네시스"), 3600, 1_000_000),
+            record(Some("커닝시티"), 1800, 800_000),
+            record(Some("헤네시스"), 3600, 2_000_000),
+            record(None, 3600, 9_000_000),
+        ];
+
+        let totals = ReportTotals::from_records(&records);
+        assert_eq!(totals.sessions, 4);
+        assert_eq!(totals.exp_gained, 12_800_000);
+        assert_eq!(totals.hp_potions_used, 40);
+
+        assert_eq!(best_map(&records, 120), Some(("커닝시티".to_string(), 1_600_000)));
+        assert_eq!(best_map(&[], 120), None);
+    }
+
+    #[test]
+    fn test_best_map_skips_warmup_sessions() {
+        let records = vec![
+            record(Some("헤네시스"), 3600, 1_000_000),
+            // 60 seconds at 6M/h is still an estimate
+            record(Some("커닝시티"), 60, 100_000),
+        ];
+
+        assert_eq!(best_map(&records, 120), Some(("헤네시스".to_string(), 1_000_000)));
+        assert_eq!(best_map(&records, 0), Some(("커닝시티".to_string(), 6_000_000)));
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(0), "0");
+        assert_eq!(format_number(1234567), "1,234,567");
+        assert_eq!(format_number(-1000), "-1,000");
+    }
+
+    #[test]
+    fn test_html_report_escapes_titles() {
+        let records = vec![record(Some("헤네시스"), 3600, 1_000_000)];
+        let html = render(&records, 1_699_900_000_000, 1_700_100_000_000, ReportFormat::Html, 120);
+
+        assert!(html.contains("보스 &lt;트라이&gt;"));
+        assert!(html.contains("<svg"));
+        assert!(html.contains("1,000,000"));
+    }
+
+    #[test]
+    fn test_last_days_range() {
+        let now = 1_700_000_000_000;
+        assert_eq!(ReportRange::LastDays { days: 7 }.bounds(now), (now - 604_800_000, now));
+    }
+
+    #[test]
+    fn test_chart_groups_days_of_long_ranges() {
+        let first = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let days: Vec<(NaiveDate, i64)> = (0..800).map(|i| (first + Duration::days(i), 1000)).collect();
+
+        let svg = daily_exp_chart(&days);
+        // 800 days at 10 days per bar
+        assert_eq!(svg.matches("<rect").count(), 80);
+        assert!(svg.contains("2023-01-01 – 2023-01-10: 10,000"));
+    }
+}
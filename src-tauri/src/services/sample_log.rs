@@ -0,0 +1,505 @@
+use crate::services::ocr_tracker::TrackingStats;
+use crate::services::storage;
+use crate::services::tracker_actor::{TrackerActor, TrackerMsg};
+use memmap2::MmapMut;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+/// Identifies a sample log file (and its layout version)
+const MAGIC: &[u8; 8] = b"EXPRING1";
+
+/// magic, record size (u32), reserved (u32), capacity (u64), next sequence number (u64)
+const HEADER_SIZE: usize = 32;
+
+/// Bytes per record (layout in `RawSample::encode`)
+pub const RECORD_SIZE: usize = 96;
+
+/// Largest ring `configure` opens (~384 MB)
+pub const MAX_CAPACITY: u64 = 4_000_000;
+
+// Record flags
+const TRACKING: u16 = 1 << 0;
+/// The reading changed what the frontend shows
+const ACCEPTED: u16 = 1 << 1;
+const OCR_HEALTHY: u16 = 1 << 2;
+const LEVEL_READ: u16 = 1 << 3;
+const EXP_READ: u16 = 1 << 4;
+const HP_READ: u16 = 1 << 5;
+const MP_READ: u16 = 1 << 6;
+
+/// Values as OCR'd, before the tracker looked at them (None = not part of this reading)
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+pub struct RawValues {
+    pub level: Option<u32>,
+    pub exp: Option<u64>,
+    pub percentage: Option<f64>,
+    pub hp: Option<u32>,
+    pub mp: Option<u32>,
+}
+
+impl RawValues {
+    /// Readings carried by a tracker message; None for control messages
+    pub fn of(msg: &TrackerMsg) -> Option<Self> {
+        match msg {
+            TrackerMsg::LevelRead(level) | TrackerMsg::ManualLevel { level, .. } => {
+                Some(Self { level: Some(*level), ..Self::default() })
+            }
+            TrackerMsg::ExpRead { exp, percentage, .. } | TrackerMsg::ManualExp { exp, percentage, .. } => {
+                Some(Self { exp: Some(*exp), percentage: Some(*percentage), ..Self::default() })
+            }
+            TrackerMsg::PotionRead { hp, mp } => Some(Self { hp: *hp, mp: *mp, ..Self::default() }),
+            _ => None,
+        }
+    }
+}
+
+/// Tracker state after the reading was handled
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+pub struct ParsedValues {
+    pub level: Option<u32>,
+    pub exp: Option<u64>,
+    pub percentage: Option<f64>,
+    pub total_exp: u64,
+    pub hp_potion_count: Option<u32>,
+    pub mp_potion_count: Option<u32>,
+}
+
+impl ParsedValues {
+    pub fn of(stats: &TrackingStats) -> Self {
+        Self {
+            level: stats.level,
+            exp: stats.exp,
+            percentage: stats.percentage,
+            total_exp: stats.total_exp,
+            hp_potion_count: stats.hp_potion_count,
+            mp_potion_count: stats.mp_potion_count,
+        }
+    }
+}
+
+/// One reading as it went through the tracker
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct RawSample {
+    /// Increasing from 1 across the whole log
+    pub seq: u64,
+    pub timestamp: i64, // Unix timestamp in milliseconds (UTC)
+    /// Session elapsed time after the reading
+    pub elapsed_seconds: u64,
+    pub tracking: bool,
+    pub accepted: bool,
+    pub ocr_healthy: bool,
+    pub raw: RawValues,
+    pub parsed: ParsedValues,
+}
+
+impl RawSample {
+    pub fn new(raw: RawValues, stats: &TrackingStats, accepted: bool) -> Self {
+        Self {
+            seq: 0,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            elapsed_seconds: stats.elapsed_seconds,
+            tracking: stats.is_tracking,
+            accepted,
+            ocr_healthy: stats.ocr_server_healthy,
+            raw,
+            parsed: ParsedValues::of(stats),
+        }
+    }
+
+    /// Little-endian record; a missing value is stored as 0 with its flag unset
+    ///
+    /// | offset | field | offset | field |
+    /// |---|---|---|---|
+    /// | 0 | seq u64 | 48 | raw hp u32, raw mp u32 |
+    /// | 8 | timestamp i64 | 56 | level u32, hp count u32 |
+    /// | 16 | elapsed seconds u64 | 64 | exp u64 |
+    /// | 24 | flags u16, parsed flags u16 | 72 | percentage f64 |
+    /// | 28 | raw level u32 | 80 | total exp u64 |
+    /// | 32 | raw exp u64 | 88 | mp count u32, reserved u32 |
+    /// | 40 | raw percentage f64 | | |
+    fn encode(&self, out: &mut [u8]) {
+        let mut flags = 0;
+        for (set, flag) in [
+            (self.tracking, TRACKING),
+            (self.accepted, ACCEPTED),
+            (self.ocr_healthy, OCR_HEALTHY),
+            (self.raw.level.is_some(), LEVEL_READ),
+            (self.raw.exp.is_some(), EXP_READ),
+            (self.raw.hp.is_some(), HP_READ),
+            (self.raw.mp.is_some(), MP_READ),
+        ] {
+            if set {
+                flags |= flag;
+            }
+        }
+        let parsed = &self.parsed;
+        let mut parsed_flags = 0;
+        for (set, flag) in [
+            (parsed.level.is_some(), LEVEL_READ),
+            (parsed.exp.is_some(), EXP_READ),
+            (parsed.hp_potion_count.is_some(), HP_READ),
+            (parsed.mp_potion_count.is_some(), MP_READ),
+        ] {
+            if set {
+                parsed_flags |= flag;
+            }
+        }
+
+        out.fill(0);
+        out[0..8].copy_from_slice(&self.seq.to_le_bytes());
+        out[8..16].copy_from_slice(&self.timestamp.to_le_bytes());
+        out[16..24].copy_from_slice(&self.elapsed_seconds.to_le_bytes());
+        out[24..26].copy_from_slice(&flags.to_le_bytes());
+        out[26..28].copy_from_slice(&parsed_flags.to_le_bytes());
+        out[28..32].copy_from_slice(&self.raw.level.unwrap_or_default().to_le_bytes());
+        out[32..40].copy_from_slice(&self.raw.exp.unwrap_or_default().to_le_bytes());
+        out[40..48].copy_from_slice(&self.raw.percentage.unwrap_or_default().to_le_bytes());
+        out[48..52].copy_from_slice(&self.raw.hp.unwrap_or_default().to_le_bytes());
+        out[52..56].copy_from_slice(&self.raw.mp.unwrap_or_default().to_le_bytes());
+        out[56..60].copy_from_slice(&parsed.level.unwrap_or_default().to_le_bytes());
+        out[60..64].copy_from_slice(&parsed.hp_potion_count.unwrap_or_default().to_le_bytes());
+        out[64..72].copy_from_slice(&parsed.exp.unwrap_or_default().to_le_bytes());
+        out[72..80].copy_from_slice(&parsed.percentage.unwrap_or_default().to_le_bytes());
+        out[80..88].copy_from_slice(&parsed.total_exp.to_le_bytes());
+        out[88..92].copy_from_slice(&parsed.mp_potion_count.unwrap_or_default().to_le_bytes());
+    }
+
+    /// Record at `bytes`; None for an empty slot
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let u32_at = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+        let u64_at = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+        let f64_at = |at: usize| f64::from_bits(u64_at(at));
+
+        let seq = u64_at(0);
+        if seq == 0 {
+            return None;
+        }
+        let flags = u16::from_le_bytes([bytes[24], bytes[25]]);
+        let parsed_flags = u16::from_le_bytes([bytes[26], bytes[27]]);
+        let raw = |flag: u16| flags & flag != 0;
+        let parsed = |flag: u16| parsed_flags & flag != 0;
+
+        Some(Self {
+            seq,
+            timestamp: u64_at(8) as i64,
+            elapsed_seconds: u64_at(16),
+            tracking: raw(TRACKING),
+            accepted: raw(ACCEPTED),
+            ocr_healthy: raw(OCR_HEALTHY),
+            raw: RawValues {
+                level: raw(LEVEL_READ).then(|| u32_at(28)),
+                exp: raw(EXP_READ).then(|| u64_at(32)),
+                percentage: raw(EXP_READ).then(|| f64_at(40)),
+                hp: raw(HP_READ).then(|| u32_at(48)),
+                mp: raw(MP_READ).then(|| u32_at(52)),
+            },
+            parsed: ParsedValues {
+                level: parsed(LEVEL_READ).then(|| u32_at(56)),
+                exp: parsed(EXP_READ).then(|| u64_at(64)),
+                percentage: parsed(EXP_READ).then(|| f64_at(72)),
+                total_exp: u64_at(80),
+                hp_potion_count: parsed(HP_READ).then(|| u32_at(60)),
+                mp_potion_count: parsed(MP_READ).then(|| u32_at(88)),
+            },
+        })
+    }
+}
+
+/// Fixed-size, memory-mapped ring of raw samples (oldest overwritten first)
+///
+/// Records land in the page cache as they are written, so the log survives
+/// the app crashing; the OS writes it back to disk on its own.
+pub struct SampleLog {
+    path: PathBuf,
+    mmap: MmapMut,
+    capacity: u64,
+    next_seq: u64,
+}
+
+impl SampleLog {
+    /// Open the log at `path`, starting over if it was written with another capacity or layout
+    pub fn open(path: &Path, capacity: u64) -> Result<Self, String> {
+        if capacity == 0 {
+            return Err("Sample log capacity must be at least 1".to_string());
+        }
+        if capacity > MAX_CAPACITY {
+            return Err(format!("Sample log capacity must be at most {}", MAX_CAPACITY));
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create sample log directory: {}", e))?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .map_err(|e| format!("Failed to open sample log: {}", e))?;
+
+        let len = HEADER_SIZE as u64 + capacity * RECORD_SIZE as u64;
+
+        let mut header = [0u8; HEADER_SIZE];
+        let reuse = file.metadata().map(|m| m.len() == len).unwrap_or(false)
+            && (&file).read_exact(&mut header).is_ok()
+            && header[0..8] == MAGIC[..]
+            && u32::from_le_bytes(header[8..12].try_into().unwrap()) == RECORD_SIZE as u32
+            && u64::from_le_bytes(header[16..24].try_into().unwrap()) == capacity;
+        if !reuse {
+            // Truncating zeroes the records without touching every page
+            file.set_len(0).and_then(|_| file.set_len(len))
+                .map_err(|e| format!("Failed to size sample log: {}", e))?;
+        }
+
+        // SAFETY: the file is only modified through this mapping while it is open
+        let mut mmap = unsafe { MmapMut::map_mut(&file) }
+            .map_err(|e| format!("Failed to map sample log: {}", e))?;
+
+        let next_seq = if reuse {
+            u64::from_le_bytes(mmap[24..32].try_into().unwrap()).max(1)
+        } else {
+            mmap[0..8].copy_from_slice(MAGIC);
+            mmap[8..12].copy_from_slice(&(RECORD_SIZE as u32).to_le_bytes());
+            mmap[16..24].copy_from_slice(&capacity.to_le_bytes());
+            1
+        };
+
+        let mut log = Self { path: path.to_path_buf(), mmap, capacity, next_seq };
+        log.write_next_seq();
+        Ok(log)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Write a sample (its `seq` is assigned here) and return its sequence number
+    pub fn append(&mut self, mut sample: RawSample) -> u64 {
+        sample.seq = self.next_seq;
+        let slot = HEADER_SIZE + ((sample.seq - 1) % self.capacity) as usize * RECORD_SIZE;
+        sample.encode(&mut self.mmap[slot..slot + RECORD_SIZE]);
+
+        self.next_seq += 1;
+        self.write_next_seq();
+        sample.seq
+    }
+
+    /// Samples still in the ring, oldest first
+    pub fn samples(&self) -> Vec<RawSample> {
+        decode_records(&self.mmap[HEADER_SIZE..])
+    }
+
+    fn write_next_seq(&mut self) {
+        self.mmap[24..32].copy_from_slice(&self.next_seq.to_le_bytes());
+    }
+}
+
+impl Drop for SampleLog {
+    fn drop(&mut self) {
+        let _ = self.mmap.flush();
+    }
+}
+
+/// Every record in a ring's data area, sorted by sequence number
+fn decode_records(data: &[u8]) -> Vec<RawSample> {
+    let mut samples: Vec<RawSample> = data.chunks_exact(RECORD_SIZE).filter_map(RawSample::decode).collect();
+    samples.sort_by_key(|sample| sample.seq);
+    samples
+}
+
+/// Samples in a sample log file that isn't open (e.g. after logging was turned off)
+pub fn read_file(path: &Path) -> Result<Vec<RawSample>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read sample log: {}", e))?;
+    if bytes.len() < HEADER_SIZE || bytes[0..8] != MAGIC[..] {
+        return Err(format!("{} is not a sample log", path.display()));
+    }
+    Ok(decode_records(&bytes[HEADER_SIZE..]))
+}
+
+static LOG: Mutex<Option<SampleLog>> = Mutex::new(None);
+
+/// Sample log in the current data directory
+pub fn log_path() -> PathBuf {
+    storage::data_dir().join(storage::SAMPLE_LOG_FILE)
+}
+
+/// Start or stop logging (called at startup and when settings change)
+/// `capacity` is clamped to 1..=`MAX_CAPACITY`.
+pub fn configure(enabled: bool, capacity: u64) {
+    let Ok(mut log) = LOG.lock() else {
+        return;
+    };
+    if !enabled {
+        *log = None;
+        return;
+    }
+
+    let capacity = capacity.clamp(1, MAX_CAPACITY);
+    let path = log_path();
+    if log.as_ref().is_some_and(|log| log.path() == path && log.capacity() == capacity) {
+        return;
+    }
+    // Unmap before reopening, which may resize the file
+    *log = None;
+    match SampleLog::open(&path, capacity) {
+        Ok(opened) => {
+            println!("🧾 Logging raw samples to {} ({} records)", path.display(), capacity);
+            *log = Some(opened);
+        }
+        Err(e) => eprintln!("⚠️ Sample log disabled: {}", e),
+    }
+}
+
+/// Unmap the log so its file can be moved; `configure` reopens it
+pub fn close() {
+    if let Ok(mut log) = LOG.lock() {
+        *log = None;
+    }
+}
+
+/// Append a sample if logging is on
+pub fn record(sample: RawSample) {
+    if let Ok(mut log) = LOG.lock() {
+        if let Some(log) = log.as_mut() {
+            log.append(sample);
+        }
+    }
+}
+
+/// Logged samples, oldest first (read from disk when logging is off)
+pub fn samples() -> Result<Vec<RawSample>, String> {
+    if let Some(log) = LOG.lock().map_err(|e| format!("Failed to lock sample log: {}", e))?.as_ref() {
+        return Ok(log.samples());
+    }
+    let path = log_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    read_file(&path)
+}
+
+/// Result of feeding logged readings through a fresh tracker
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayResult {
+    pub samples: usize,
+    /// Tracker state after the last sample
+    pub stats: TrackingStats,
+    /// Samples whose replayed values differ from the logged ones
+    pub mismatches: Vec<u64>,
+}
+
+/// Rebuild what the tracker did from the raw readings
+///
+/// A new session starts wherever the elapsed time went backwards; stops and
+/// resumes follow the logged tracking flag. Elapsed time and rates are not
+/// compared since they depend on when the readings arrived.
+pub fn replay(samples: &[RawSample]) -> Result<ReplayResult, String> {
+    let mut actor = TrackerActor::new()?;
+    let mut mismatches = Vec::new();
+    let mut previous: Option<&RawSample> = None;
+
+    for sample in samples {
+        let new_session = previous.is_none_or(|p| sample.elapsed_seconds < p.elapsed_seconds);
+        let was_tracking = previous.is_some_and(|p| p.tracking) && !new_session;
+        if sample.tracking && !was_tracking {
+            let (reply, _) = oneshot::channel();
+            actor.handle(TrackerMsg::Start { resume: !new_session, reply });
+        } else if !sample.tracking && was_tracking {
+            actor.handle(TrackerMsg::Stop);
+        }
+
+        let raw = sample.raw;
+        let msg = if let Some(level) = raw.level {
+            TrackerMsg::LevelRead(level)
+        } else if let (Some(exp), Some(percentage)) = (raw.exp, raw.percentage) {
+            TrackerMsg::ExpRead { exp, percentage, timing: None }
+        } else {
+            TrackerMsg::PotionRead { hp: raw.hp, mp: raw.mp }
+        };
+        actor.handle(TrackerMsg::HealthChanged(sample.ocr_healthy));
+        actor.handle(msg);
+
+        if ParsedValues::of(&actor.stats()) != sample.parsed {
+            mismatches.push(sample.seq);
+        }
+        previous = Some(sample);
+    }
+
+    Ok(ReplayResult { samples: samples.len(), stats: actor.stats(), mismatches })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(exp: u64, total_exp: u64) -> RawSample {
+        RawSample {
+            seq: 0,
+            timestamp: 1_700_000_000_000,
+            elapsed_seconds: 60,
+            tracking: true,
+            accepted: true,
+            ocr_healthy: true,
+            raw: RawValues { exp: Some(exp), percentage: Some(12.76), ..RawValues::default() },
+            parsed: ParsedValues {
+                level: Some(126),
+                exp: Some(exp),
+                percentage: Some(12.76),
+                total_exp,
+                hp_potion_count: Some(930),
+                mp_potion_count: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_record_roundtrip() {
+        let mut original = sample(5_509_611, 1200);
+        original.seq = 7;
+        let mut bytes = [0xff; RECORD_SIZE];
+        original.encode(&mut bytes);
+        assert_eq!(RawSample::decode(&bytes), Some(original));
+        assert_eq!(RawSample::decode(&[0; RECORD_SIZE]), None);
+    }
+
+    #[test]
+    fn test_ring_wraps_and_survives_reopen() {
+        let path = std::env::temp_dir().join(format!("exp-tracker-samples-{}.ring", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut log = SampleLog::open(&path, 3).unwrap();
+        for exp in 1..=5 {
+            log.append(sample(exp, exp * 10));
+        }
+        let seqs: Vec<u64> = log.samples().iter().map(|s| s.seq).collect();
+        assert_eq!(seqs, vec![3, 4, 5]);
+        drop(log);
+
+        let mut log = SampleLog::open(&path, 3).unwrap();
+        assert_eq!(log.append(sample(6, 60)), 6);
+        assert_eq!(read_file(&path).unwrap().last().unwrap().raw.exp, Some(6));
+        drop(log);
+
+        // A different capacity starts a fresh log
+        let log = SampleLog::open(&path, 4).unwrap();
+        assert!(log.samples().is_empty());
+        drop(log);
+
+        // So does a file of the right size that isn't a sample log
+        fs::write(&path, vec![0xff; HEADER_SIZE + 4 * RECORD_SIZE]).unwrap();
+        let log = SampleLog::open(&path, 4).unwrap();
+        assert!(log.samples().is_empty());
+        drop(log);
+        let _ = fs::remove_file(&path);
+
+        assert!(SampleLog::open(&path, MAX_CAPACITY + 1).is_err());
+        assert!(SampleLog::open(&path, u64::MAX).is_err());
+    }
+}
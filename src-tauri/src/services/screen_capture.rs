@@ -1,5 +1,6 @@
 use crate::models::roi::Roi;
 use image::DynamicImage;
+use serde::{Deserialize, Serialize};
 use xcap::Monitor;
 
 /// Thread-safe wrapper for xcap::Monitor
@@ -21,6 +22,26 @@ unsafe impl Send for SendSyncMonitor {}
 // and the OS display resources are inherently shareable across threads.
 unsafe impl Sync for SendSyncMonitor {}
 
+/// Monitor geometry snapshot used to detect resolution / scale changes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct DisplayInfo {
+    /// Logical width (same units as ROIs)
+    pub width: u32,
+    /// Logical height (same units as ROIs)
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+impl DisplayInfo {
+    /// Factors that map ROIs captured on `self` onto `other`
+    pub fn roi_scale_to(&self, other: &DisplayInfo) -> (f64, f64) {
+        (
+            other.width as f64 / self.width.max(1) as f64,
+            other.height as f64 / self.height.max(1) as f64,
+        )
+    }
+}
+
 /// Screen capture service using xcap
 pub struct ScreenCapture {
     monitor: SendSyncMonitor,
@@ -74,24 +95,25 @@ impl ScreenCapture {
         // Convert RgbaImage to DynamicImage
         let image = DynamicImage::ImageRgba8(rgba_image);
 
+        Self::crop_logical(&image, roi, self.scale_factor)
+    }
+
+    /// Crop a logical-pixel ROI out of an already captured full screen image
+    /// Lets one capture feed several ROIs without grabbing the screen again
+    pub fn crop_logical(image: &DynamicImage, roi: &Roi, scale_factor: f64) -> Result<DynamicImage, String> {
         // ROI coordinates are in logical pixels (from frontend)
         // xcap.capture_image() returns physical pixels on all platforms
         // Therefore, we need to scale logical → physical on all platforms including macOS
-        let (physical_x, physical_y, physical_width, physical_height) = {
-            (
-                (roi.x as f64 * self.scale_factor) as u32,
-                (roi.y as f64 * self.scale_factor) as u32,
-                (roi.width as f64 * self.scale_factor) as u32,
-                (roi.height as f64 * self.scale_factor) as u32,
-            )
-        };
+        let physical = roi.to_physical(scale_factor);
+        let (physical_x, physical_y, physical_width, physical_height) =
+            (physical.x, physical.y, physical.width, physical.height);
 
         // Validate dimensions
         if physical_width == 0 {
-            return Err(format!("Invalid ROI: width is 0 (roi.width={}, scale={})", roi.width, self.scale_factor));
+            return Err(format!("Invalid ROI: width is 0 (roi.width={}, scale={})", roi.width, scale_factor));
         }
         if physical_height == 0 {
-            return Err(format!("Invalid ROI: height is 0 (roi.height={}, scale={})", roi.height, self.scale_factor));
+            return Err(format!("Invalid ROI: height is 0 (roi.height={}, scale={})", roi.height, scale_factor));
         }
 
         // Calculate available space
@@ -169,6 +191,32 @@ impl ScreenCapture {
         }
     }
 
+    /// Query the monitor's current geometry
+    /// Cheap enough to call every tracking cycle; unlike `get_scale_factor`
+    /// this reflects resolution or scale changes made after startup.
+    pub fn current_display(&self) -> Result<DisplayInfo, String> {
+        let scale_factor = self.monitor.0.scale_factor().unwrap_or(1.0) as f64;
+        let physical_width = self
+            .monitor.0
+            .width()
+            .map_err(|e| format!("Failed to get width: {}", e))?;
+        let physical_height = self
+            .monitor.0
+            .height()
+            .map_err(|e| format!("Failed to get height: {}", e))?;
+
+        // Same logical conversion as get_dimensions, using the live scale factor
+        #[cfg(target_os = "macos")]
+        let (width, height) = (physical_width, physical_height);
+        #[cfg(not(target_os = "macos"))]
+        let (width, height) = (
+            (physical_width as f64 / scale_factor) as u32,
+            (physical_height as f64 / scale_factor) as u32,
+        );
+
+        Ok(DisplayInfo { width, height, scale_factor })
+    }
+
     /// Convert image to PNG bytes for transmission
     pub fn image_to_png_bytes(image: &DynamicImage) -> Result<Vec<u8>, String> {
         let mut buf = Vec::new();
@@ -0,0 +1,321 @@
+use crate::commands::config::ConfigManagerState;
+use crate::commands::ocr::{OcrService, OcrServiceState};
+use crate::commands::tracking::{PreviewTrackingState, TrackerState};
+use crate::services::alert_engine::AlertFired;
+use crate::services::event_log;
+use crate::services::storage;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// How often the scheduler checks whether a self-test is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Scheduled self-tests run at most this often
+const RUN_EVERY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Self-test results kept in the history
+pub const HISTORY_LIMIT: usize = 30;
+
+/// Percentage values match within this (OCR'd to two decimals)
+const PERCENTAGE_TOLERANCE: f64 = 0.005;
+
+/// Value a fixture image must be read as, encoded in its file name:
+/// `level_126.png`, `exp_5509611_1276.png` (12.76%), `hp_930.png`, `mp_460.png`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Expected {
+    Level(u32),
+    Exp { absolute: u64, percentage: f64 },
+    HpPotions(u32),
+    MpPotions(u32),
+}
+
+impl Expected {
+    pub fn from_file_stem(stem: &str) -> Option<Self> {
+        let mut parts = stem.split('_');
+        let kind = parts.next()?;
+        let value = parts.next()?.parse::<u64>().ok()?;
+
+        let expected = match kind {
+            "level" => Expected::Level(u32::try_from(value).ok()?),
+            "exp" => Expected::Exp {
+                absolute: value,
+                percentage: parts.next()?.parse::<u32>().ok()? as f64 / 100.0,
+            },
+            "hp" => Expected::HpPotions(u32::try_from(value).ok()?),
+            "mp" => Expected::MpPotions(u32::try_from(value).ok()?),
+            _ => return None,
+        };
+        parts.next().is_none().then_some(expected)
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Expected::Level(level) => format!("LV. {}", level),
+            Expected::Exp { absolute, percentage } => format!("{} [{:.2}%]", absolute, percentage),
+            Expected::HpPotions(count) => format!("HP {}", count),
+            Expected::MpPotions(count) => format!("MP {}", count),
+        }
+    }
+}
+
+/// Outcome of one fixture
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FixtureResult {
+    pub fixture: String,
+    pub expected: String,
+    /// What was read (None if recognition failed)
+    pub actual: Option<String>,
+    pub passed: bool,
+    pub error: Option<String>,
+}
+
+/// One run over every bundled fixture
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SelfTestResult {
+    pub timestamp: i64, // Unix timestamp in milliseconds (UTC)
+    pub passed: usize,
+    pub total: usize,
+    /// Passed fixtures out of all, 0.0-1.0
+    pub accuracy: f64,
+    pub fixtures: Vec<FixtureResult>,
+}
+
+impl SelfTestResult {
+    fn new(timestamp: i64, fixtures: Vec<FixtureResult>) -> Self {
+        let passed = fixtures.iter().filter(|f| f.passed).count();
+        let total = fixtures.len();
+        let accuracy = if total == 0 { 0.0 } else { passed as f64 / total as f64 };
+        Self { timestamp, passed, total, accuracy, fixtures }
+    }
+}
+
+/// Bundled fixture folder (same lookup as the level templates)
+pub fn fixture_dir() -> Option<PathBuf> {
+    [
+        "src-tauri/resources/self_test", // Development (from project root)
+        "resources/self_test",           // Development (from src-tauri)
+        "../Resources/self_test",        // macOS bundled
+        "./resources/self_test",         // Windows/Linux bundled
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .find(|path| path.exists())
+}
+
+/// Read every fixture in `dir` the way tracking would and compare with its expected value
+pub async fn run(ocr_service: &OcrService, dir: &Path) -> Result<SelfTestResult, String> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read self-test fixtures: {}", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "png"))
+        .collect();
+    paths.sort();
+
+    let mut fixtures = Vec::new();
+    for path in paths {
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let Some(expected) = Expected::from_file_stem(&stem) else {
+            continue;
+        };
+        fixtures.push(check_fixture(ocr_service, &path, &stem, expected).await);
+    }
+
+    if fixtures.is_empty() {
+        return Err(format!("No self-test fixtures in {}", dir.display()));
+    }
+    Ok(SelfTestResult::new(chrono::Utc::now().timestamp_millis(), fixtures))
+}
+
+async fn check_fixture(ocr_service: &OcrService, path: &Path, name: &str, expected: Expected) -> FixtureResult {
+    let read = match image::open(path) {
+        Ok(image) => match expected {
+            Expected::Level(_) => ocr_service.recognize_level(&image).await
+                .map(|result| Expected::Level(result.level)),
+            Expected::Exp { .. } => ocr_service.recognize_exp(&image).await
+                .map(|result| Expected::Exp { absolute: result.absolute, percentage: result.percentage }),
+            Expected::HpPotions(_) => ocr_service.recognize_hp_potion_count(&image).await.map(Expected::HpPotions),
+            Expected::MpPotions(_) => ocr_service.recognize_mp_potion_count(&image).await.map(Expected::MpPotions),
+        },
+        Err(e) => Err(format!("Failed to load fixture: {}", e)),
+    };
+
+    let (actual, error) = match read {
+        Ok(actual) => (Some(actual), None),
+        Err(e) => (None, Some(e)),
+    };
+    FixtureResult {
+        fixture: name.to_string(),
+        expected: expected.describe(),
+        passed: actual.is_some_and(|actual| matches(expected, actual)),
+        actual: actual.map(|actual| actual.describe()),
+        error,
+    }
+}
+
+fn matches(expected: Expected, actual: Expected) -> bool {
+    match (expected, actual) {
+        (Expected::Exp { absolute, percentage }, Expected::Exp { absolute: read, percentage: read_percentage }) => {
+            absolute == read && (percentage - read_percentage).abs() < PERCENTAGE_TOLERANCE
+        }
+        _ => expected == actual,
+    }
+}
+
+fn history_path() -> PathBuf {
+    storage::data_dir().join(storage::SELF_TESTS_FILE)
+}
+
+/// Past self-test results, oldest first
+pub fn load_history() -> Vec<SelfTestResult> {
+    fs::read_to_string(history_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Append a result, keeping the last `HISTORY_LIMIT`
+pub fn record(result: &SelfTestResult) -> Result<(), String> {
+    let mut history = load_history();
+    history.push(result.clone());
+    if history.len() > HISTORY_LIMIT {
+        history.drain(..history.len() - HISTORY_LIMIT);
+    }
+
+    let content = serde_json::to_string_pretty(&history)
+        .map_err(|e| format!("Failed to serialize self-test history: {}", e))?;
+    fs::write(history_path(), content)
+        .map_err(|e| format!("Failed to write self-test history: {}", e))
+}
+
+/// Whether `latest` reads fewer fixtures correctly than the run before it
+pub fn accuracy_dropped(previous: Option<&SelfTestResult>, latest: &SelfTestResult) -> bool {
+    previous.is_some_and(|previous| latest.accuracy < previous.accuracy)
+}
+
+/// Run the self-test, record it, and alert if accuracy dropped since the last run
+/// Nothing is recorded while the OCR server is down, so an outage isn't taken for an accuracy drop.
+pub async fn run_and_record(app: &AppHandle, ocr_service: &OcrService) -> Result<SelfTestResult, String> {
+    let dir = fixture_dir().ok_or("Self-test fixtures not found")?;
+    ocr_service.health_check().await
+        .map_err(|e| format!("OCR server unavailable, self-test skipped: {}", e))?;
+    let result = run(ocr_service, &dir).await?;
+
+    let previous = load_history().pop();
+    record(&result)?;
+    println!("🧪 OCR self-test: {}/{} fixtures read correctly", result.passed, result.total);
+
+    if accuracy_dropped(previous.as_ref(), &result) {
+        let previous_accuracy = previous.map(|p| p.accuracy).unwrap_or_default();
+        let failed: Vec<&str> = result.fixtures.iter().filter(|f| !f.passed).map(|f| f.fixture.as_str()).collect();
+        let alert = AlertFired {
+            rule_id: "ocr-self-test".to_string(),
+            name: "OCR self-test".to_string(),
+            stat: "self_test_accuracy".to_string(),
+            value: result.accuracy,
+            message: format!(
+                "OCR accuracy dropped from {:.0}% to {:.0}% (failing: {}). Font rendering or display settings may have changed.",
+                previous_accuracy * 100.0,
+                result.accuracy * 100.0,
+                failed.join(", ")
+            ),
+            timestamp: result.timestamp,
+        };
+        eprintln!("⚠️ {}", alert.message);
+        if let Err(e) = event_log::emit(app, "alert:notification", &alert) {
+            eprintln!("Failed to emit self-test alert: {}", e);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Whether a scheduled run is due: enabled, idle, and a day since the last run
+fn is_due(app: &AppHandle, now: i64) -> bool {
+    let enabled = app
+        .try_state::<ConfigManagerState>()
+        .and_then(|state| state.lock().ok().and_then(|manager| manager.load().ok()))
+        .is_some_and(|config| config.advanced.nightly_self_test);
+    if !enabled {
+        return false;
+    }
+
+    load_history().last().is_none_or(|last| now - last.timestamp >= RUN_EVERY_MS)
+}
+
+/// Whether tracking or preview tracking is using the OCR server
+async fn is_busy(app: &AppHandle) -> bool {
+    let previewing = app
+        .try_state::<PreviewTrackingState>()
+        .and_then(|preview| preview.lock().ok()
+            .map(|task| task.as_ref().is_some_and(|task| !task.inner().is_finished())))
+        .unwrap_or(false);
+    if previewing {
+        return true;
+    }
+
+    match app.try_state::<TrackerState>() {
+        Some(tracker) => tracker.0.lock().await.get_stats().await.is_tracking,
+        None => false,
+    }
+}
+
+/// Run the self-test once a day while nothing is being tracked (if enabled)
+pub fn spawn_self_test_loop(app: AppHandle, ocr_service: OcrServiceState) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            if !is_due(&app, chrono::Utc::now().timestamp_millis()) {
+                continue;
+            }
+            // Never compete with a running session or preview for the OCR server
+            if is_busy(&app).await {
+                continue;
+            }
+
+            if let Err(e) = run_and_record(&app, &ocr_service).await {
+                eprintln!("⚠️ OCR self-test failed to run: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_from_file_name() {
+        assert_eq!(Expected::from_file_stem("level_126"), Some(Expected::Level(126)));
+        assert_eq!(
+            Expected::from_file_stem("exp_5509611_1276"),
+            Some(Expected::Exp { absolute: 5_509_611, percentage: 12.76 })
+        );
+        assert_eq!(Expected::from_file_stem("mp_460"), Some(Expected::MpPotions(460)));
+        assert_eq!(Expected::from_file_stem("map_korean"), None);
+        assert_eq!(Expected::from_file_stem("exp_5509611"), None);
+        assert_eq!(Expected::from_file_stem("level_126_extra"), None);
+    }
+
+    #[test]
+    fn test_accuracy_drop_compares_with_previous_run() {
+        let fixture = |passed| FixtureResult {
+            fixture: "level_126".to_string(),
+            expected: "LV. 126".to_string(),
+            actual: None,
+            passed,
+            error: None,
+        };
+        let all_pass = SelfTestResult::new(1, vec![fixture(true), fixture(true)]);
+        let one_fails = SelfTestResult::new(2, vec![fixture(true), fixture(false)]);
+        assert_eq!(one_fails.accuracy, 0.5);
+
+        assert!(accuracy_dropped(Some(&all_pass), &one_fails));
+        assert!(!accuracy_dropped(Some(&one_fails), &all_pass));
+        assert!(!accuracy_dropped(None, &one_fails));
+    }
+}
@@ -0,0 +1,39 @@
+use crate::models::config::AppConfig;
+use crate::models::roi::Roi;
+use crate::services::screen_capture::DisplayInfo;
+use crate::services::session_store::{SessionFile, SessionStore};
+use crate::services::storage;
+use serde::{Deserialize, Serialize};
+
+/// Effective settings of a session, stored next to its record
+///
+/// Taken whenever the OCR loops (re)start, so a resumed session or a layout
+/// switch leaves the settings the loops last ran with.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionConfig {
+    /// Id of the session record; empty while the session is running
+    pub session_id: String,
+    /// Session end, UTC Unix timestamp in milliseconds (same as the record)
+    pub timestamp: i64,
+    /// When the loops started with these settings, UTC Unix timestamp in milliseconds
+    pub started_at: i64,
+    /// ROIs the loops read (given by the frontend, may differ from `config.roi`)
+    pub level_roi: Roi,
+    pub exp_roi: Roi,
+    pub display: DisplayInfo,
+    pub config: AppConfig,
+}
+
+impl SessionFile for SessionConfig {
+    const DIR: &'static str = storage::SESSION_CONFIGS_DIR;
+    const KIND: &'static str = "session config";
+    const PRETTY: bool = true;
+
+    fn session_id(&self) -> &str {
+        &self.session_id
+    }
+}
+
+/// Session config snapshots, stored as `<root>/<session_id>.json`
+pub type SessionConfigStore = SessionStore<SessionConfig>;
+
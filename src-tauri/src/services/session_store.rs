@@ -0,0 +1,139 @@
+use crate::services::storage;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+/// Data kept next to a session record, one JSON file per session
+pub trait SessionFile: Serialize + DeserializeOwned {
+    /// Folder in the data directory
+    const DIR: &'static str;
+    /// What the files hold, for error messages
+    const KIND: &'static str;
+    /// Indent the JSON (for files users may read)
+    const PRETTY: bool = false;
+
+    fn session_id(&self) -> &str;
+}
+
+/// Per-session files, stored as `<root>/<session_id>.json`
+pub struct SessionStore<T> {
+    root: PathBuf,
+    _file: PhantomData<T>,
+}
+
+/// Whether `id` can name a file in the store
+/// Ids come from the webview, so anything that could leave the folder is refused.
+pub fn is_valid_session_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl<T: SessionFile> SessionStore<T> {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root, _file: PhantomData }
+    }
+
+    /// Store in the current data directory
+    pub fn open() -> Self {
+        Self::new(storage::data_dir().join(T::DIR))
+    }
+
+    fn path(&self, session_id: &str) -> Result<PathBuf, String> {
+        if !is_valid_session_id(session_id) {
+            return Err(format!("Invalid session id '{}'", session_id));
+        }
+        Ok(self.root.join(format!("{}.json", session_id)))
+    }
+
+    pub fn save(&self, value: &T) -> Result<(), String> {
+        let path = self.path(value.session_id())?;
+        fs::create_dir_all(&self.root)
+            .map_err(|e| format!("Failed to create {} directory: {}", T::KIND, e))?;
+
+        let content = if T::PRETTY { serde_json::to_string_pretty(value) } else { serde_json::to_string(value) }
+            .map_err(|e| format!("Failed to serialize {}: {}", T::KIND, e))?;
+        fs::write(path, content)
+            .map_err(|e| format!("Failed to write {} file: {}", T::KIND, e))
+    }
+
+    pub fn load(&self, session_id: &str) -> Result<T, String> {
+        let content = fs::read_to_string(self.path(session_id)?)
+            .map_err(|_| format!("No {} saved for session '{}'", T::KIND, session_id))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", T::KIND, e))
+    }
+
+    /// Remove a session's file (missing files are fine)
+    pub fn delete(&self, session_id: &str) -> Result<(), String> {
+        match fs::remove_file(self.path(session_id)?) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to delete {} file: {}", T::KIND, e)),
+        }
+    }
+
+    /// Every stored file; unreadable ones are skipped
+    pub fn all(&self) -> Vec<T> {
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return Vec::new();
+        };
+
+        entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+            .filter_map(|content| serde_json::from_str::<T>(&content).ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Note {
+        session_id: String,
+        text: String,
+    }
+
+    impl SessionFile for Note {
+        const DIR: &'static str = "notes";
+        const KIND: &'static str = "note";
+
+        fn session_id(&self) -> &str {
+            &self.session_id
+        }
+    }
+
+    #[test]
+    fn test_save_load_delete() {
+        let root = std::env::temp_dir().join(format!("exp-tracker-session-store-{}", std::process::id()));
+        let store = SessionStore::<Note>::new(root.clone());
+
+        let note = Note { session_id: "1700000000000".to_string(), text: "hi".to_string() };
+        store.save(&note).unwrap();
+        assert_eq!(store.load("1700000000000").unwrap(), note);
+        assert_eq!(store.all(), vec![note]);
+
+        store.delete("1700000000000").unwrap();
+        store.delete("1700000000000").unwrap();
+        assert!(store.load("1700000000000").is_err());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_ids_cannot_leave_the_store() {
+        let store = SessionStore::<Note>::new(std::env::temp_dir().join("exp-tracker-session-store-ids"));
+
+        assert!(is_valid_session_id("1700000000000-ab12cd_3"));
+        for id in ["", "../config", "..", "a/b", "a\\b", "a.json"] {
+            assert!(!is_valid_session_id(id), "{}", id);
+            assert!(store.load(id).unwrap_err().starts_with("Invalid session id"));
+            assert!(store.delete(id).is_err());
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::commands::config::ConfigManagerState;
+use crate::commands::tracking::TrackerState;
+use crate::models::config::{TrackingConfig, TrackingMode};
+use crate::services::event_log;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+/// Key that toggles the timer (not configurable)
+const TOGGLE_TIMER_SHORTCUT: &str = "`";
+
+/// What a global shortcut does
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    ToggleTimer,
+    Checkpoint,
+    NextLayout,
+}
+
+/// A shortcut that could not be registered (the others still work)
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ShortcutFailure {
+    pub action: ShortcutAction,
+    pub shortcut: String,
+    pub error: String,
+}
+
+/// Shortcuts that failed at startup (also emitted as "shortcuts:registration-failed")
+pub type ShortcutFailuresState = std::sync::Mutex<Vec<ShortcutFailure>>;
+
+/// Every binding, in registration order
+pub fn bindings(tracking: &TrackingConfig) -> Vec<(ShortcutAction, String)> {
+    vec![
+        (ShortcutAction::ToggleTimer, TOGGLE_TIMER_SHORTCUT.to_string()),
+        (ShortcutAction::Checkpoint, tracking.checkpoint_shortcut.clone()),
+        (ShortcutAction::NextLayout, tracking.layout_shortcut.clone()),
+    ]
+}
+
+/// Split bindings into ones to register and ones that reuse an earlier binding's key
+/// (keys compare case-insensitively, like the plugin's parser)
+fn dedupe(bindings: Vec<(ShortcutAction, String)>) -> (Vec<(ShortcutAction, String)>, Vec<ShortcutFailure>) {
+    let mut unique: Vec<(ShortcutAction, String)> = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for (action, shortcut) in bindings {
+        let taken_by = unique.iter().find(|(_, s)| s.trim().eq_ignore_ascii_case(shortcut.trim()));
+        match taken_by {
+            Some((other, _)) => conflicts.push(ShortcutFailure {
+                error: format!("Already used for {:?}", other),
+                action,
+                shortcut,
+            }),
+            None => unique.push((action, shortcut)),
+        }
+    }
+    (unique, conflicts)
+}
+
+/// Register every configured shortcut, one at a time
+///
+/// A key taken by another app (or a typo in the config) only disables that
+/// binding; failures are returned and emitted so the UI can point at them.
+pub fn register_all(app: &AppHandle) -> Vec<ShortcutFailure> {
+    let tracking = app
+        .try_state::<ConfigManagerState>()
+        .and_then(|state| state.lock().ok().and_then(|manager| manager.load().ok()))
+        .map(|config| config.tracking)
+        .unwrap_or_default();
+
+    let (bindings, mut failures) = dedupe(bindings(&tracking));
+    for (action, shortcut) in bindings {
+        let handle = app.clone();
+        let registered = app.global_shortcut().on_shortcut(shortcut.as_str(), move |_app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                run(&handle, action);
+            }
+        });
+
+        match registered {
+            Ok(()) => {
+                #[cfg(debug_assertions)]
+                println!("✅ {:?} shortcut registered: {}", action, shortcut);
+            }
+            Err(e) => failures.push(ShortcutFailure { action, shortcut, error: e.to_string() }),
+        }
+    }
+
+    for failure in &failures {
+        eprintln!("⚠️ {:?} shortcut '{}' not registered: {}", failure.action, failure.shortcut, failure.error);
+    }
+    if !failures.is_empty() {
+        // Sent before the webview listens; kept in the event log for get_recent_events
+        if let Err(e) = event_log::emit(app, "shortcuts:registration-failed", failures.clone()) {
+            eprintln!("Failed to emit shortcut failures: {}", e);
+        }
+    }
+    failures
+}
+
+fn run(handle: &AppHandle, action: ShortcutAction) {
+    match action {
+        ShortcutAction::ToggleTimer => {
+            #[cfg(debug_assertions)]
+            println!("🎹 Global shortcut triggered: `");
+
+            // Emit event to frontend
+            let _ = handle.emit("global-shortcut-toggle-timer", ());
+        }
+        // Only acts in checkpoint mode
+        ShortcutAction::Checkpoint => {
+            let handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                let in_checkpoint_mode = {
+                    let config_state = handle.state::<ConfigManagerState>();
+                    let mode = match config_state.lock() {
+                        Ok(manager) => manager.load().map(|config| config.tracking.mode).ok(),
+                        Err(_) => None,
+                    };
+                    mode == Some(TrackingMode::Checkpoint)
+                };
+                if !in_checkpoint_mode {
+                    return;
+                }
+
+                let Some(tracker_state) = handle.try_state::<TrackerState>() else {
+                    return;
+                };
+                if let Err(e) = tracker_state.capture_checkpoint().await {
+                    eprintln!("❌ Checkpoint failed: {}", e);
+                }
+            });
+        }
+        // Switches to the next saved ROI layout
+        ShortcutAction::NextLayout => {
+            let handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::commands::config::apply_layout(&handle, None).await {
+                    eprintln!("❌ Layout switch failed: {}", e);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_keys_fail_only_the_later_binding() {
+        let mut tracking = TrackingConfig::default();
+        tracking.layout_shortcut = "f9".to_string();
+
+        let (unique, conflicts) = dedupe(bindings(&tracking));
+        let actions: Vec<ShortcutAction> = unique.iter().map(|(action, _)| *action).collect();
+        assert_eq!(actions, vec![ShortcutAction::ToggleTimer, ShortcutAction::Checkpoint]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].action, ShortcutAction::NextLayout);
+        assert_eq!(conflicts[0].shortcut, "f9");
+    }
+}
@@ -0,0 +1,80 @@
+use crate::commands::config::ConfigManagerState;
+use crate::commands::session::{self, SessionMapState, SessionRecordsState};
+use crate::commands::tracking::TrackerState;
+use crate::models::config::TrackingConfig;
+use crate::services::python_server::PythonServerManager;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex as AsyncMutex;
+
+/// Set by the first shutdown; Quit and closing the window can both arrive
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Save the running session under the default title before the app exits
+async fn save_session_on_exit(app: &AppHandle) {
+    let (Some(tracker), Some(records), Some(session_map)) = (
+        app.try_state::<TrackerState>(),
+        app.try_state::<SessionRecordsState>(),
+        app.try_state::<SessionMapState>(),
+    ) else {
+        return;
+    };
+
+    // A stopped session may already have been saved by hand
+    if !tracker.0.lock().await.get_stats().await.is_tracking {
+        return;
+    }
+
+    match session::finish_and_save(&tracker, &records, &session_map, String::new()).await {
+        Ok(record) => println!("💾 Session {} saved on exit", record.id),
+        Err(e) => eprintln!("❌ Failed to save session on exit: {}", e),
+    }
+}
+
+/// Save, stop tracking and the OCR server, then exit
+/// (closing the main window, or Quit in the tray menu)
+///
+/// Only the first call does anything, so the session is saved once.
+pub async fn run(app: AppHandle) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    // Save the running session before it's stopped
+    let save_on_exit = {
+        let config_state = app.state::<ConfigManagerState>();
+        let save_on_exit = match config_state.lock() {
+            Ok(manager) => manager.load().map(|config| config.tracking.save_session_on_exit).ok(),
+            Err(_) => None,
+        };
+        save_on_exit.unwrap_or_else(|| TrackingConfig::default().save_session_on_exit)
+    };
+    if save_on_exit {
+        save_session_on_exit(&app).await;
+    }
+
+    // Stop OCR tracking
+    if let Some(tracker_state) = app.try_state::<TrackerState>() {
+        let mut tracker = tracker_state.inner().0.lock().await;
+        tracker.stop_tracking().await;
+
+        #[cfg(debug_assertions)]
+        println!("🛑 OCR tracking stopped");
+    }
+
+    // Shutdown Python OCR server
+    let server_state = app.state::<AsyncMutex<PythonServerManager>>();
+    {
+        let mut server = server_state.lock().await;
+        server.stop_async().await;
+
+        #[cfg(debug_assertions)]
+        println!("🛑 Python server shutdown signal sent");
+    }
+
+    #[cfg(debug_assertions)]
+    println!("👋 Application closing");
+
+    // Now that cleanup is complete, exit the app
+    app.exit(0);
+}
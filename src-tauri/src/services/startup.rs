@@ -0,0 +1,83 @@
+use serde::Serialize;
+
+/// Startup stage that can fail without stopping the app
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InitStage {
+    Config,
+    DataDirectory,
+    OcrService,
+    ExpCalculator,
+    Tracker,
+}
+
+impl InitStage {
+    /// What the user can do about a failure in this stage
+    pub fn hint(&self) -> &'static str {
+        match self {
+            InitStage::Config => "Settings are kept in a temporary folder and may not persist. Check that the config folder is writable.",
+            InitStage::DataDirectory => "Session records may not be saved. Choose another data directory in settings.",
+            InitStage::OcrService => "OCR is unavailable. Restart the app; if it keeps failing, reinstall it.",
+            InitStage::ExpCalculator => "EXP rates are unavailable because the level EXP table could not be loaded. Reinstall the app.",
+            InitStage::Tracker => "Tracking is unavailable. Check that a monitor is connected and screen capture is permitted.",
+        }
+    }
+}
+
+/// A stage that failed during startup
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct InitFailure {
+    pub stage: InitStage,
+    pub error: String,
+    pub hint: String,
+}
+
+/// Failures collected while starting up (emitted as "startup:init-failed")
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartupReport {
+    pub failures: Vec<InitFailure>,
+}
+
+impl StartupReport {
+    /// Unwrap a stage result, recording the error instead of panicking
+    pub fn stage<T>(&mut self, stage: InitStage, result: Result<T, String>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                eprintln!("❌ Startup stage {:?} failed: {}", stage, error);
+                self.failures.push(InitFailure {
+                    stage,
+                    error,
+                    hint: stage.hint().to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Startup report kept for the diagnostics command
+pub type StartupReportState = std::sync::Mutex<StartupReport>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_records_failures_and_passes_values() {
+        let mut report = StartupReport::default();
+
+        assert_eq!(report.stage(InitStage::Config, Ok(1)), Some(1));
+        assert!(report.is_ok());
+
+        let failed: Option<u32> = report.stage(InitStage::Tracker, Err("no monitor".to_string()));
+        assert!(failed.is_none());
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].stage, InitStage::Tracker);
+        assert_eq!(report.failures[0].hint, InitStage::Tracker.hint());
+    }
+}
@@ -0,0 +1,424 @@
+use crate::models::config::StorageConfig;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, SystemTime};
+
+/// Session records file (in the data directory)
+pub const SESSION_RECORDS_FILE: &str = "session_records.json";
+
+/// Debug images folder (in the data directory)
+pub const DEBUG_DIR: &str = "debug";
+
+/// Timeline folder (in the data directory)
+pub const TIMELINES_DIR: &str = "timelines";
+
+/// Per-session OCR failure histograms (in the data directory)
+pub const OCR_FAILURES_DIR: &str = "ocr_failures";
+
+/// Per-session config snapshots (in the data directory)
+pub const SESSION_CONFIGS_DIR: &str = "session_configs";
+
+/// OCR self-test history (in the data directory)
+pub const SELF_TESTS_FILE: &str = "self_tests.json";
+
+/// Raw tracking sample ring log (in the data directory)
+pub const SAMPLE_LOG_FILE: &str = "samples.ring";
+
+/// ROI preview history folder (in the data directory)
+pub const PREVIEWS_DIR: &str = "previews";
+
+/// Generated reports folder (in the data directory)
+pub const REPORTS_DIR: &str = "reports";
+
+/// Everything the app owns inside the data directory (moved on migration)
+const MANAGED_ENTRIES: [&str; 9] = [
+    SESSION_RECORDS_FILE, DEBUG_DIR, TIMELINES_DIR, OCR_FAILURES_DIR, SESSION_CONFIGS_DIR, SELF_TESTS_FILE,
+    SAMPLE_LOG_FILE, PREVIEWS_DIR, REPORTS_DIR,
+];
+
+/// Temp files older than this are removed at startup
+const STALE_TEMP_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Group of files shown in storage usage
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageCategory {
+    SessionRecords,
+    DebugImages,
+    Timelines,
+    OcrFailures,
+    SessionConfigs,
+    /// ROI preview history
+    Previews,
+    /// Scratch files in the system temp directory
+    TempFiles,
+}
+
+impl StorageCategory {
+    pub const ALL: [StorageCategory; 7] = [
+        StorageCategory::SessionRecords,
+        StorageCategory::DebugImages,
+        StorageCategory::Timelines,
+        StorageCategory::OcrFailures,
+        StorageCategory::SessionConfigs,
+        StorageCategory::Previews,
+        StorageCategory::TempFiles,
+    ];
+
+    /// File or folder holding this category
+    pub fn path(&self) -> PathBuf {
+        match self {
+            StorageCategory::SessionRecords => data_dir().join(SESSION_RECORDS_FILE),
+            StorageCategory::DebugImages => data_dir().join(DEBUG_DIR),
+            StorageCategory::Timelines => data_dir().join(TIMELINES_DIR),
+            StorageCategory::OcrFailures => data_dir().join(OCR_FAILURES_DIR),
+            StorageCategory::SessionConfigs => data_dir().join(SESSION_CONFIGS_DIR),
+            StorageCategory::Previews => previews_dir(),
+            StorageCategory::TempFiles => temp_files_dir(),
+        }
+    }
+
+    /// Session records are user data; they're removed per session, not in bulk
+    pub fn is_cleanable(&self) -> bool {
+        !matches!(self, StorageCategory::SessionRecords)
+    }
+}
+
+/// Disk usage of one category
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CategoryUsage {
+    pub category: StorageCategory,
+    pub path: String,
+    pub bytes: u64,
+    pub files: u64,
+}
+
+/// What a cleanup removed
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CleanupResult {
+    pub category: StorageCategory,
+    pub files_removed: u64,
+    pub bytes_freed: u64,
+}
+
+/// Platform data directory, e.g. `%APPDATA%\exp-tracker` or `~/.local/share/exp-tracker`
+pub fn default_data_dir() -> Result<PathBuf, String> {
+    Ok(dirs::data_dir()
+        .ok_or("Failed to determine data directory")?
+        .join("exp-tracker"))
+}
+
+/// Data directory chosen by the storage config
+pub fn resolve_data_dir(config: &StorageConfig) -> Result<PathBuf, String> {
+    match config.data_directory.as_deref().map(str::trim) {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => default_data_dir(),
+    }
+}
+
+fn current() -> &'static RwLock<Option<PathBuf>> {
+    static DATA_DIR: OnceLock<RwLock<Option<PathBuf>>> = OnceLock::new();
+    DATA_DIR.get_or_init(|| RwLock::new(None))
+}
+
+/// Resolve and create the data directory at startup
+/// Session records saved by older versions in the config directory are moved over.
+pub fn init(config: &StorageConfig) -> Result<PathBuf, String> {
+    let data_dir = resolve_data_dir(config)?;
+    fs::create_dir_all(&data_dir)
+        .map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+    if let Some(config_dir) = dirs::config_dir().map(|dir| dir.join("exp-tracker")) {
+        let legacy = config_dir.join(SESSION_RECORDS_FILE);
+        let target = data_dir.join(SESSION_RECORDS_FILE);
+        if config_dir != data_dir && legacy.exists() && !target.exists() {
+            move_entry(&legacy, &target)?;
+            println!("🗂️ Moved session records to data directory: {}", data_dir.display());
+        }
+    }
+
+    set_data_dir(data_dir.clone());
+    Ok(data_dir)
+}
+
+/// Current data directory (platform default until `init` runs)
+pub fn data_dir() -> PathBuf {
+    if let Ok(dir) = current().read() {
+        if let Some(dir) = dir.as_ref() {
+            return dir.clone();
+        }
+    }
+    default_data_dir().unwrap_or_else(|_| std::env::temp_dir().join("exp-tracker"))
+}
+
+pub fn set_data_dir(path: PathBuf) {
+    if let Ok(mut dir) = current().write() {
+        *dir = Some(path);
+    }
+}
+
+/// Session records file in the current data directory
+pub fn session_records_path() -> Result<PathBuf, String> {
+    let dir = data_dir();
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    Ok(dir.join(SESSION_RECORDS_FILE))
+}
+
+/// Subfolder of the current data directory (created if missing)
+pub fn data_subdir(name: &str) -> Result<PathBuf, String> {
+    let dir = data_dir().join(name);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {} directory: {}", name, e))?;
+    Ok(dir)
+}
+
+/// ROI preview history in the current data directory
+pub fn previews_dir() -> PathBuf {
+    data_dir().join(PREVIEWS_DIR)
+}
+
+/// Scratch folder in the system temp directory (also holds pre-history previews)
+pub fn temp_files_dir() -> PathBuf {
+    std::env::temp_dir().join("exp-tracker-previews")
+}
+
+/// Bytes and file count of a category
+pub fn usage(category: StorageCategory) -> CategoryUsage {
+    let path = category.path();
+    let (bytes, files) = dir_usage(&path);
+    CategoryUsage {
+        category,
+        path: path.to_string_lossy().to_string(),
+        bytes,
+        files,
+    }
+}
+
+/// Delete a category's files (only those older than `max_age`, if given)
+pub fn cleanup(category: StorageCategory, max_age: Option<Duration>) -> Result<CleanupResult, String> {
+    if !category.is_cleanable() {
+        return Err(format!("{:?} cannot be cleaned up in bulk", category));
+    }
+
+    let (files_removed, bytes_freed) = remove_files(&category.path(), max_age)?;
+    Ok(CleanupResult { category, files_removed, bytes_freed })
+}
+
+/// Startup housekeeping: drop temp files nobody has touched in a while
+pub fn cleanup_stale_temp_files() {
+    match cleanup(StorageCategory::TempFiles, Some(STALE_TEMP_AGE)) {
+        Ok(result) if result.files_removed > 0 => {
+            println!("🧹 Removed {} stale temp files ({} bytes)", result.files_removed, result.bytes_freed);
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Failed to clean temp files: {}", e),
+    }
+}
+
+/// Total size and file count under a path (a single file counts as one)
+fn dir_usage(path: &Path) -> (u64, u64) {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return (0, 0),
+    };
+    if metadata.is_file() {
+        return (metadata.len(), 1);
+    }
+
+    let mut total = (0, 0);
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            let (bytes, files) = dir_usage(&entry.path());
+            total.0 += bytes;
+            total.1 += files;
+        }
+    }
+    total
+}
+
+/// Remove files under a directory, keeping the directory itself
+fn remove_files(path: &Path, max_age: Option<Duration>) -> Result<(u64, u64), String> {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return Ok((0, 0)), // Nothing stored yet
+    };
+
+    let now = SystemTime::now();
+    let mut removed = (0, 0);
+
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+
+        if metadata.is_dir() {
+            let (files, bytes) = remove_files(&entry_path, max_age)?;
+            removed.0 += files;
+            removed.1 += bytes;
+            let _ = fs::remove_dir(&entry_path); // Only succeeds once empty
+            continue;
+        }
+
+        let is_stale = match max_age {
+            Some(max_age) => metadata.modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age > max_age),
+            None => true,
+        };
+        if !is_stale {
+            continue;
+        }
+
+        fs::remove_file(&entry_path)
+            .map_err(|e| format!("Failed to remove {}: {}", entry_path.display(), e))?;
+        removed.0 += 1;
+        removed.1 += metadata.len();
+    }
+
+    Ok(removed)
+}
+
+/// Move the app's files from one data directory to another
+/// Refuses to overwrite anything already in `to`; returns the moved entry names.
+pub fn migrate_data_dir(from: &Path, to: &Path) -> Result<Vec<String>, String> {
+    fs::create_dir_all(to)
+        .map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+    if from == to {
+        return Ok(Vec::new());
+    }
+
+    let entries: Vec<&str> = MANAGED_ENTRIES.iter()
+        .copied()
+        .filter(|name| from.join(name).exists())
+        .collect();
+
+    // Check every conflict up front so a failed migration moves nothing
+    if let Some(conflict) = entries.iter().find(|name| to.join(name).exists()) {
+        return Err(format!("'{}' already exists in {}", conflict, to.display()));
+    }
+
+    for name in &entries {
+        move_entry(&from.join(name), &to.join(name))?;
+    }
+
+    Ok(entries.iter().map(|name| name.to_string()).collect())
+}
+
+/// Rename, falling back to copy + delete across drives
+fn move_entry(from: &Path, to: &Path) -> Result<(), String> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    copy_recursive(from, to)?;
+    if from.is_dir() {
+        fs::remove_dir_all(from)
+    } else {
+        fs::remove_file(from)
+    }
+    .map_err(|e| format!("Failed to remove {}: {}", from.display(), e))
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> Result<(), String> {
+    if from.is_dir() {
+        fs::create_dir_all(to)
+            .map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+        let entries = fs::read_dir(from)
+            .map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+        for entry in entries.flatten() {
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("exp-tracker-storage-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_resolve_prefers_configured_directory() {
+        let config = StorageConfig { data_directory: Some("/data/exp".to_string()) };
+        assert_eq!(resolve_data_dir(&config).unwrap(), PathBuf::from("/data/exp"));
+
+        let blank = StorageConfig { data_directory: Some("  ".to_string()) };
+        assert_eq!(resolve_data_dir(&blank).unwrap(), default_data_dir().unwrap());
+    }
+
+    #[test]
+    fn test_migrate_moves_managed_entries_only() {
+        let from = temp_dir("migrate-from");
+        let to = temp_dir("migrate-to");
+        fs::write(from.join(SESSION_RECORDS_FILE), "[]").unwrap();
+        fs::create_dir_all(from.join(DEBUG_DIR)).unwrap();
+        fs::write(from.join(DEBUG_DIR).join("a.png"), [0u8; 4]).unwrap();
+        fs::write(from.join("unrelated.txt"), "keep").unwrap();
+
+        let moved = migrate_data_dir(&from, &to).unwrap();
+
+        assert_eq!(moved, vec![SESSION_RECORDS_FILE.to_string(), DEBUG_DIR.to_string()]);
+        assert!(to.join(DEBUG_DIR).join("a.png").exists());
+        assert!(!from.join(SESSION_RECORDS_FILE).exists());
+        assert!(from.join("unrelated.txt").exists());
+
+        let _ = fs::remove_dir_all(&from);
+        let _ = fs::remove_dir_all(&to);
+    }
+
+    #[test]
+    fn test_usage_and_cleanup_of_nested_files() {
+        let dir = temp_dir("cleanup");
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("a.png"), [0u8; 10]).unwrap();
+        fs::write(dir.join("nested").join("b.png"), [0u8; 5]).unwrap();
+
+        assert_eq!(dir_usage(&dir), (15, 2));
+
+        // Fresh files survive an age-limited cleanup
+        assert_eq!(remove_files(&dir, Some(STALE_TEMP_AGE)).unwrap(), (0, 0));
+
+        assert_eq!(remove_files(&dir, None).unwrap(), (2, 15));
+        assert!(dir.exists());
+        assert_eq!(dir_usage(&dir), (0, 0));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_session_records_are_not_cleanable() {
+        assert!(cleanup(StorageCategory::SessionRecords, None).is_err());
+    }
+
+    #[test]
+    fn test_migrate_refuses_to_overwrite() {
+        let from = temp_dir("conflict-from");
+        let to = temp_dir("conflict-to");
+        fs::write(from.join(SESSION_RECORDS_FILE), "[1]").unwrap();
+        fs::write(to.join(SESSION_RECORDS_FILE), "[2]").unwrap();
+
+        assert!(migrate_data_dir(&from, &to).is_err());
+        assert_eq!(fs::read_to_string(to.join(SESSION_RECORDS_FILE)).unwrap(), "[2]");
+        assert!(from.join(SESSION_RECORDS_FILE).exists());
+
+        let _ = fs::remove_dir_all(&from);
+        let _ = fs::remove_dir_all(&to);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::models::timeline::Timeline;
+use crate::services::session_store::{SessionFile, SessionStore};
+use crate::services::storage;
+
+impl SessionFile for Timeline {
+    const DIR: &'static str = storage::TIMELINES_DIR;
+    const KIND: &'static str = "timeline";
+
+    fn session_id(&self) -> &str {
+        &self.session_id
+    }
+}
+
+/// Session timelines, stored as `<root>/<session_id>.json`
+pub type TimelineStore = SessionStore<Timeline>;
+
+impl TimelineStore {
+    /// The `limit` most recent timelines recorded on `map_name`
+    /// Unreadable files are skipped.
+    pub fn recent_on_map(&self, map_name: Option<&str>, limit: usize) -> Vec<Timeline> {
+        let mut timelines: Vec<Timeline> = self.all()
+            .into_iter()
+            .filter(|timeline| timeline.map_name.as_deref() == map_name)
+            .collect();
+
+        timelines.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        timelines.truncate(limit);
+        timelines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::timeline::TimelinePoint;
+    use std::fs;
+
+    fn timeline(session_id: &str, timestamp: i64, map_name: Option<&str>) -> Timeline {
+        Timeline {
+            session_id: session_id.to_string(),
+            timestamp,
+            map_name: map_name.map(str::to_string),
+            points: vec![TimelinePoint { elapsed_seconds: 0, total_exp: 0, latency_ms: None, recorded_at: None }],
+        }
+    }
+
+    #[test]
+    fn test_recent_on_map() {
+        let root = std::env::temp_dir().join(format!("exp-tracker-timelines-{}", std::process::id()));
+        let store = TimelineStore::new(root.clone());
+
+        store.save(&timeline("1", 1, Some("헤네시스"))).unwrap();
+        store.save(&timeline("2", 2, Some("헤네시스"))).unwrap();
+        store.save(&timeline("3", 3, Some("커닝시티"))).unwrap();
+        store.save(&timeline("4", 4, Some("헤네시스"))).unwrap();
+
+        let recent: Vec<String> = store.recent_on_map(Some("헤네시스"), 2)
+            .into_iter()
+            .map(|t| t.session_id)
+            .collect();
+        assert_eq!(recent, vec!["4", "2"]);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
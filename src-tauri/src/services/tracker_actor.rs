@@ -0,0 +1,983 @@
+use crate::models::config::DEFAULT_RATE_WARMUP_SECS;
+use crate::models::custom_metric::MetricValue;
+use crate::models::exp_data::ExpData;
+use crate::models::timeline::{TimelinePoint, TIMELINE_INTERVAL_SECS};
+use crate::services::event_log;
+use crate::services::exp_calculator::{hourly_rates, ExpCalculator};
+use crate::services::expression::Expr;
+use crate::services::hp_potion_calculator::HpPotionCalculator;
+use crate::services::latency::{self, LatencySample, SampleTiming};
+use crate::services::mp_potion_calculator::MpPotionCalculator;
+use crate::services::ocr_tracker::TrackingStats;
+use crate::services::sample_log::{self, RawSample, RawValues};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tokio::sync::{mpsc, oneshot, watch};
+
+/// Capacity of the actor mailbox (loops block briefly if it fills up)
+const MAILBOX_CAPACITY: usize = 256;
+
+/// How often elapsed time advances without a new EXP reading
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Messages sent from OCR loops and commands to the tracker actor
+#[derive(Debug)]
+pub enum TrackerMsg {
+    LevelRead(u32),
+    /// `timing` is set for live readings so their latency can be measured
+    ExpRead { exp: u64, percentage: f64, timing: Option<SampleTiming> },
+    /// Level entered by hand; replies with an error if it is invalid
+    ManualLevel { level: u32, reply: oneshot::Sender<Result<(), String>> },
+    /// EXP entered by hand; refused (with a reply) until a level is known
+    ManualExp { exp: u64, percentage: f64, reply: oneshot::Sender<Result<(), String>> },
+    /// `None` means the slot could not be read (not that it is empty)
+    PotionRead { hp: Option<u32>, mp: Option<u32> },
+    /// A user-defined metric (see models::custom_metric) was read
+    CustomMetricRead { name: String, value: MetricValue },
+    /// Replace the derived metric definitions (name, parsed expression)
+    SetDerivedMetrics(Vec<(String, Expr)>),
+    /// Name of the active ROI layout
+    SetLayout(Option<String>),
+    /// Seconds at the start of a session whose rates are only estimates
+    SetRateWarmup(u64),
+    HealthChanged(bool),
+    /// The machine was asleep for this long; excluded from elapsed time
+    SleepGap(Duration),
+    /// Begin tracking - replies `false` if tracking was already running
+    /// `resume` continues the stopped session; otherwise a fresh one starts.
+    Start {
+        resume: bool,
+        reply: oneshot::Sender<Result<bool, String>>,
+    },
+    Stop,
+    Reset(oneshot::Sender<Result<(), String>>),
+    /// Reply with the current session's EXP timeline
+    Timeline(oneshot::Sender<Result<Vec<TimelinePoint>, String>>),
+    /// Stop tracking and reply with the session's final stats and timeline
+    Finish(oneshot::Sender<Result<FinishedSession, String>>),
+}
+
+/// Final state of a session ended with `TrackerMsg::Finish`
+#[derive(Debug, Clone)]
+pub struct FinishedSession {
+    pub stats: TrackingStats,
+    pub timeline: Vec<TimelinePoint>,
+}
+
+impl TrackerMsg {
+    fn timing(&self) -> Option<SampleTiming> {
+        match self {
+            TrackerMsg::ExpRead { timing, .. } => *timing,
+            _ => None,
+        }
+    }
+}
+
+/// Frontend events produced while handling a message
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrackerEvent {
+    Level(u32),
+    Exp { exp: u64, percentage: f64 },
+    HpPotion(u32),
+    MpPotion(u32),
+    CustomMetric { name: String, value: MetricValue },
+}
+
+/// Event payloads for Frontend updates
+#[derive(Clone, Serialize)]
+struct LevelUpdate {
+    level: u32,
+}
+
+#[derive(Clone, Serialize)]
+struct ExpUpdate {
+    exp: u64,
+    percentage: f64,
+}
+
+#[derive(Clone, Serialize)]
+struct HpPotionUpdate {
+    hp_potion_count: u32,
+}
+
+#[derive(Clone, Serialize)]
+struct MpPotionUpdate {
+    mp_potion_count: u32,
+}
+
+#[derive(Clone, Serialize)]
+struct CustomMetricUpdate {
+    name: String,
+    value: MetricValue,
+}
+
+/// Handle used by the tracker and its loops to talk to the actor
+#[derive(Clone)]
+pub struct TrackerHandle {
+    tx: mpsc::Sender<TrackerMsg>,
+    stats_rx: watch::Receiver<TrackingStats>,
+}
+
+impl TrackerHandle {
+    /// Send a message, ignoring a closed mailbox (app shutting down)
+    pub async fn send(&self, msg: TrackerMsg) {
+        let _ = self.tx.send(msg).await;
+    }
+
+    /// Send a message and wait for the actor's reply
+    pub async fn request<T>(
+        &self,
+        make_msg: impl FnOnce(oneshot::Sender<Result<T, String>>) -> TrackerMsg,
+    ) -> Result<T, String> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.tx
+            .send(make_msg(reply_tx))
+            .await
+            .map_err(|_| "Tracker actor is not running".to_string())?;
+        reply_rx
+            .await
+            .map_err(|_| "Tracker actor dropped the request".to_string())?
+    }
+
+    /// Latest published statistics (never waits on the actor)
+    pub fn stats(&self) -> TrackingStats {
+        self.stats_rx.borrow().clone()
+    }
+}
+
+/// Level reading and stability tracking
+struct LevelCell {
+    level: Option<u32>,
+    prev_level: Option<u32>,
+    level_match_count: u32,
+}
+
+impl LevelCell {
+    fn new() -> Self {
+        Self {
+            level: None,
+            prev_level: None,
+            level_match_count: 0,
+        }
+    }
+
+    /// Update level - emit immediately for UI responsiveness
+    fn update(&mut self, new_level: u32) -> bool {
+        let should_emit = match self.prev_level {
+            Some(prev) if prev == new_level => {
+                // Same as before - already displayed in UI, no need to re-emit
+                self.level_match_count += 1;
+                false
+            }
+            _ => {
+                // New value - emit immediately to UI
+                self.prev_level = Some(new_level);
+                self.level_match_count = 1;
+                self.level = Some(new_level);
+                true
+            }
+        };
+        should_emit
+    }
+}
+
+/// EXP reading, calculator and cached EXP stats
+struct ExpCell {
+    exp: Option<u64>,
+    percentage: Option<f64>,
+    exp_calculator: ExpCalculator,
+    // Session started flag
+    session_started: bool,
+    error: Option<String>,
+    // Latest EXP stats cache
+    total_exp: u64,
+    total_percentage: f64,
+    elapsed_seconds: u64,
+    exp_per_hour: u64,
+    percentage_per_hour: f64,
+    /// Total EXP every `TIMELINE_INTERVAL_SECS` of tracked time
+    timeline: Vec<TimelinePoint>,
+}
+
+impl ExpCell {
+    fn new() -> Result<Self, String> {
+        Ok(Self {
+            exp: None,
+            percentage: None,
+            exp_calculator: ExpCalculator::new()?,
+            session_started: false,
+            error: None,
+            total_exp: 0,
+            total_percentage: 0.0,
+            elapsed_seconds: 0,
+            exp_per_hour: 0,
+            percentage_per_hour: 0.0,
+            timeline: Vec::new(),
+        })
+    }
+
+    /// Update EXP and trigger calculator update - returns true if changed
+    fn update(&mut self, level: Option<u32>, exp: u64, percentage: f64) -> bool {
+        let changed = self.exp != Some(exp) || self.percentage != Some(percentage);
+        self.exp = Some(exp);
+        self.percentage = Some(percentage);
+
+        // Update ExpCalculator if level is stable
+        if let Some(level) = level {
+            let data = ExpData {
+                level,
+                exp,
+                percentage,
+                meso: None,
+            };
+
+            if !self.session_started {
+                self.exp_calculator.start(data);
+                self.session_started = true;
+                self.timeline.push(TimelinePoint {
+                    elapsed_seconds: 0,
+                    total_exp: 0,
+                    latency_ms: None,
+                    recorded_at: Some(chrono::Utc::now().timestamp_millis()),
+                });
+            } else {
+                // Update session with EXP tracking - ORIGINAL WORKING MECHANISM
+                let result = self.exp_calculator.update(data);
+
+                match result {
+                    Ok(stats) => {
+                        // Cache ONLY EXP stats - HP/MP have their own calculators now
+                        self.total_exp = stats.total_exp;
+                        self.total_percentage = stats.total_percentage;
+                        self.elapsed_seconds = stats.elapsed_seconds;
+                        self.exp_per_hour = stats.exp_per_hour;
+                        self.percentage_per_hour = stats.percentage_per_hour;
+                        self.error = None;
+
+                        self.push_timeline_if_due();
+                    }
+                    Err(e) => {
+                        self.error = Some(e);
+                    }
+                }
+            }
+        }
+        changed
+    }
+
+    /// Advance elapsed time and the hourly rates from the clock, so the timer
+    /// keeps moving while EXP stalls - returns true if a second passed
+    fn tick(&mut self) -> bool {
+        if !self.session_started {
+            return false;
+        }
+        let Some(elapsed) = self.exp_calculator.elapsed() else {
+            return false;
+        };
+        let elapsed_seconds = elapsed.as_secs();
+        if elapsed_seconds == self.elapsed_seconds {
+            return false;
+        }
+
+        self.elapsed_seconds = elapsed_seconds;
+        (self.exp_per_hour, self.percentage_per_hour) =
+            hourly_rates(self.total_exp, self.total_percentage, elapsed_seconds);
+        self.push_timeline_if_due();
+        true
+    }
+
+    /// Add a point for the latest stats once an interval passed since the last one
+    fn push_timeline_if_due(&mut self) {
+        let due = self.timeline.last()
+            .is_none_or(|p| self.elapsed_seconds >= p.elapsed_seconds + TIMELINE_INTERVAL_SECS);
+        if due {
+            self.timeline.push(TimelinePoint {
+                elapsed_seconds: self.elapsed_seconds,
+                total_exp: self.total_exp,
+                latency_ms: None,
+                recorded_at: Some(chrono::Utc::now().timestamp_millis()),
+            });
+        }
+    }
+
+    /// Add a timeline point for the latest stats if the last point is older
+    fn flush_timeline(&mut self) {
+        if !self.session_started {
+            return;
+        }
+        if self.timeline.last().is_none_or(|p| p.elapsed_seconds < self.elapsed_seconds) {
+            self.timeline.push(TimelinePoint {
+                elapsed_seconds: self.elapsed_seconds,
+                total_exp: self.total_exp,
+                latency_ms: None,
+                recorded_at: Some(chrono::Utc::now().timestamp_millis()),
+            });
+        }
+    }
+}
+
+/// Potion readings and their independent calculators
+struct PotionCell {
+    hp_potion_count: Option<u32>,
+    mp_potion_count: Option<u32>,
+    hp_calculator: HpPotionCalculator,
+    mp_calculator: MpPotionCalculator,
+    hp_potions_used: u32,
+    mp_potions_used: u32,
+    hp_potions_per_minute: f64,
+    mp_potions_per_minute: f64,
+}
+
+impl PotionCell {
+    fn new() -> Self {
+        Self {
+            hp_potion_count: None,
+            mp_potion_count: None,
+            hp_calculator: HpPotionCalculator::new(),
+            mp_calculator: MpPotionCalculator::new(),
+            hp_potions_used: 0,
+            mp_potions_used: 0,
+            hp_potions_per_minute: 0.0,
+            mp_potions_per_minute: 0.0,
+        }
+    }
+
+    /// Feed a new HP count into the HP calculator
+    fn update_hp(&mut self, hp_potion_count: u32) {
+        self.hp_potion_count = Some(hp_potion_count);
+
+        let (hp_used, hp_per_min) = self.hp_calculator.update(hp_potion_count);
+        self.hp_potions_used = hp_used;
+        self.hp_potions_per_minute = hp_per_min;
+    }
+
+    /// Feed a new MP count into the MP calculator
+    fn update_mp(&mut self, mp_potion_count: u32) {
+        self.mp_potion_count = Some(mp_potion_count);
+
+        let (mp_used, mp_per_min) = self.mp_calculator.update(mp_potion_count);
+        self.mp_potions_used = mp_used;
+        self.mp_potions_per_minute = mp_per_min;
+    }
+}
+
+/// Single owner of all tracking state
+///
+/// OCR loops never touch calculators directly - they send `TrackerMsg`s and
+/// the actor applies them one at a time, so update order is deterministic.
+pub struct TrackerActor {
+    level: LevelCell,
+    exp: ExpCell,
+    potions: PotionCell,
+    /// Latest reading of each custom metric, by name
+    custom_metrics: BTreeMap<String, MetricValue>,
+    /// Derived metric definitions, evaluated in `stats()`
+    derived_metrics: Vec<(String, Expr)>,
+    is_tracking: bool,
+    /// Whether the running session was resumed after a stop
+    resumed: bool,
+    /// When tracking was last stopped; the stopped time is excluded on resume
+    stopped_at: Option<Instant>,
+    // OCR server health status
+    ocr_server_healthy: bool,
+    /// Active ROI layout (kept across sessions)
+    layout: Option<String>,
+    /// Rates are flagged as estimating until this much time has elapsed
+    rate_warmup_secs: u64,
+}
+
+impl TrackerActor {
+    pub fn new() -> Result<Self, String> {
+        Ok(Self {
+            level: LevelCell::new(),
+            exp: ExpCell::new()?,
+            potions: PotionCell::new(),
+            custom_metrics: BTreeMap::new(),
+            derived_metrics: Vec::new(),
+            is_tracking: false,
+            resumed: false,
+            stopped_at: None,
+            ocr_server_healthy: true,
+            layout: None,
+            rate_warmup_secs: DEFAULT_RATE_WARMUP_SECS,
+        })
+    }
+
+    /// Spawn the actor task and return a handle to it
+    pub fn spawn(mut self, app: AppHandle) -> TrackerHandle {
+        let (tx, mut rx) = mpsc::channel(MAILBOX_CAPACITY);
+        let (stats_tx, stats_rx) = watch::channel(self.stats());
+
+        tauri::async_runtime::spawn(async move {
+            let mut ticker = tokio::time::interval(TICK_INTERVAL);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+            loop {
+                let msg = tokio::select! {
+                    msg = rx.recv() => match msg {
+                        Some(msg) => msg,
+                        None => break,
+                    },
+                    _ = ticker.tick() => {
+                        if self.tick() {
+                            stats_tx.send_replace(self.stats());
+                        }
+                        continue;
+                    }
+                };
+
+                let timing = msg.timing();
+                let raw = RawValues::of(&msg);
+                let timeline_len = self.exp.timeline.len();
+
+                let events = self.handle(msg);
+                let exp_emitted = events.iter().any(|event| matches!(event, TrackerEvent::Exp { .. }));
+                if let Some(raw) = raw {
+                    sample_log::record(RawSample::new(raw, &self.stats(), !events.is_empty()));
+                }
+                for event in events {
+                    emit_event(&app, event);
+                }
+
+                // Readings that didn't change the display have no latency to speak of
+                if let Some(timing) = timing.filter(|_| exp_emitted) {
+                    self.record_latency(timing.sample(Instant::now()), timeline_len);
+                }
+                stats_tx.send_replace(self.stats());
+            }
+        });
+
+        TrackerHandle { tx, stats_rx }
+    }
+
+    /// Apply a single message and return the frontend events it produced
+    pub fn handle(&mut self, msg: TrackerMsg) -> Vec<TrackerEvent> {
+        let mut events = Vec::new();
+
+        match msg {
+            TrackerMsg::LevelRead(level) => {
+                if self.level.update(level) {
+                    events.push(TrackerEvent::Level(level));
+                }
+            }
+            TrackerMsg::ExpRead { exp, percentage, .. } => {
+                if self.exp.update(self.level.level, exp, percentage) {
+                    events.push(TrackerEvent::Exp { exp, percentage });
+                }
+            }
+            TrackerMsg::ManualLevel { level, reply } => {
+                let result = if level == 0 { Err("Level must be at least 1".to_string()) } else { Ok(()) };
+                if result.is_ok() && self.level.update(level) {
+                    events.push(TrackerEvent::Level(level));
+                }
+                let _ = reply.send(result);
+            }
+            TrackerMsg::ManualExp { exp, percentage, reply } => {
+                // Checked here so a level can't change between the check and the update
+                let result = if !(0.0..=100.0).contains(&percentage) {
+                    Err(format!("Invalid EXP percentage: {}", percentage))
+                } else if self.level.level.is_none() {
+                    Err("Set the level before entering EXP".to_string())
+                } else {
+                    Ok(())
+                };
+                if result.is_ok() && self.exp.update(self.level.level, exp, percentage) {
+                    events.push(TrackerEvent::Exp { exp, percentage });
+                }
+                let _ = reply.send(result);
+            }
+            TrackerMsg::PotionRead { hp, mp } => {
+                // Unreadable slots are skipped so they never register as "0 potions"
+                if let Some(hp) = hp {
+                    self.potions.update_hp(hp);
+                    events.push(TrackerEvent::HpPotion(hp));
+                }
+                if let Some(mp) = mp {
+                    self.potions.update_mp(mp);
+                    events.push(TrackerEvent::MpPotion(mp));
+                }
+            }
+            TrackerMsg::CustomMetricRead { name, value } => {
+                // Only changed readings go to the frontend
+                if self.custom_metrics.get(&name) != Some(&value) {
+                    self.custom_metrics.insert(name.clone(), value.clone());
+                    events.push(TrackerEvent::CustomMetric { name, value });
+                }
+            }
+            TrackerMsg::SetDerivedMetrics(derived_metrics) => {
+                self.derived_metrics = derived_metrics;
+            }
+            TrackerMsg::SetLayout(layout) => {
+                self.layout = layout;
+            }
+            TrackerMsg::SetRateWarmup(secs) => {
+                self.rate_warmup_secs = secs;
+            }
+            TrackerMsg::HealthChanged(healthy) => {
+                self.ocr_server_healthy = healthy;
+            }
+            TrackerMsg::SleepGap(gap) => {
+                self.add_paused_time(gap);
+            }
+            TrackerMsg::Start { resume, reply } => {
+                let _ = reply.send(self.start(resume));
+            }
+            TrackerMsg::Stop => self.stop(),
+            TrackerMsg::Reset(reply) => {
+                let _ = reply.send(self.reset());
+            }
+            TrackerMsg::Timeline(reply) => {
+                let _ = reply.send(Ok(self.exp.timeline.clone()));
+            }
+            TrackerMsg::Finish(reply) => {
+                let _ = reply.send(self.finish());
+            }
+        }
+
+        events
+    }
+
+    /// Advance the session timer (nothing moves while stopped) - returns true if it changed
+    pub fn tick(&mut self) -> bool {
+        self.is_tracking && self.exp.tick()
+    }
+
+    /// Start or resume tracking - returns false if already tracking
+    fn start(&mut self, resume: bool) -> Result<bool, String> {
+        // Check if already tracking - prevent reinitialization
+        if self.is_tracking {
+            return Ok(false);
+        }
+
+        // Resuming needs a session to continue; otherwise start from scratch
+        if resume && self.exp.session_started {
+            if let Some(stopped_at) = self.stopped_at.take() {
+                self.add_paused_time(stopped_at.elapsed());
+            }
+            self.resumed = true;
+        } else {
+            self.reset()?;
+        }
+
+        self.is_tracking = true;
+        Ok(true)
+    }
+
+    fn stop(&mut self) {
+        if self.is_tracking {
+            self.is_tracking = false;
+            self.stopped_at = Some(Instant::now());
+            // Untracked time then starts at a point, which `Timeline::elapsed_at` relies on
+            self.exp.flush_timeline();
+        }
+    }
+
+    /// Stop and hand out the session with a final timeline point at its end
+    fn finish(&mut self) -> Result<FinishedSession, String> {
+        self.stop();
+        if !self.exp.session_started {
+            return Err("No EXP has been tracked in this session".to_string());
+        }
+
+        self.exp.flush_timeline();
+        Ok(FinishedSession {
+            stats: self.stats(),
+            timeline: self.exp.timeline.clone(),
+        })
+    }
+
+    /// Record an EXP reading's latency; a timeline point it added (the timeline
+    /// was `timeline_len` long before) is tagged with it
+    fn record_latency(&mut self, sample: LatencySample, timeline_len: usize) {
+        latency::global().record(sample);
+
+        if self.exp.timeline.len() > timeline_len {
+            if let Some(point) = self.exp.timeline.last_mut() {
+                point.latency_ms = Some(sample.total().as_millis().min(u32::MAX as u128) as u32);
+            }
+        }
+    }
+
+    /// Exclude time from the elapsed time of every calculator
+    fn add_paused_time(&mut self, duration: Duration) {
+        self.exp.exp_calculator.add_paused_time(duration);
+        self.potions.hp_calculator.add_paused_time(duration);
+        self.potions.mp_calculator.add_paused_time(duration);
+    }
+
+    /// Reset every cell to a fresh session
+    fn reset(&mut self) -> Result<(), String> {
+        self.exp = ExpCell::new()?;
+        self.level = LevelCell::new();
+        self.potions = PotionCell::new();
+        self.custom_metrics.clear();
+        self.is_tracking = false;
+        self.resumed = false;
+        self.stopped_at = None;
+        self.ocr_server_healthy = true;
+        Ok(())
+    }
+
+    /// Snapshot of the current statistics
+    pub fn stats(&self) -> TrackingStats {
+        let mut stats = TrackingStats {
+            level: self.level.level,
+            exp: self.exp.exp,
+            percentage: self.exp.percentage,
+            hp_potion_count: self.potions.hp_potion_count,
+            mp_potion_count: self.potions.mp_potion_count,
+            total_exp: self.exp.total_exp,
+            total_percentage: self.exp.total_percentage,
+            elapsed_seconds: self.exp.elapsed_seconds,
+            exp_per_hour: self.exp.exp_per_hour,
+            percentage_per_hour: self.exp.percentage_per_hour,
+            rates_estimating: self.exp.elapsed_seconds < self.rate_warmup_secs,
+            is_tracking: self.is_tracking,
+            resumed: self.resumed,
+            error: self.exp.error.clone(),
+            hp_potions_used: self.potions.hp_potions_used,
+            mp_potions_used: self.potions.mp_potions_used,
+            hp_potions_per_minute: self.potions.hp_potions_per_minute,
+            mp_potions_per_minute: self.potions.mp_potions_per_minute,
+            ocr_server_healthy: self.ocr_server_healthy,
+            custom_metrics: self.custom_metrics.clone(),
+            derived_metrics: BTreeMap::new(),
+            layout: self.layout.clone(),
+        };
+
+        let derived_metrics = self.derived_metrics.iter()
+            .filter_map(|(name, expr)| Some((name.clone(), expr.eval(&|var| stats.variable(var))?)))
+            .collect();
+        stats.derived_metrics = derived_metrics;
+        stats
+    }
+}
+
+/// Emit a tracker event to the frontend (recorded for replay)
+fn emit_event(app: &AppHandle, event: TrackerEvent) {
+    let result = match event {
+        TrackerEvent::Level(level) => event_log::emit(app, "ocr:level-update", LevelUpdate { level }),
+        TrackerEvent::Exp { exp, percentage } => {
+            event_log::emit(app, "ocr:exp-update", ExpUpdate { exp, percentage })
+        }
+        TrackerEvent::HpPotion(hp_potion_count) => {
+            event_log::emit(app, "ocr:hp-potion-update", HpPotionUpdate { hp_potion_count })
+        }
+        TrackerEvent::MpPotion(mp_potion_count) => {
+            event_log::emit(app, "ocr:mp-potion-update", MpPotionUpdate { mp_potion_count })
+        }
+        TrackerEvent::CustomMetric { name, value } => {
+            event_log::emit(app, "ocr:custom-metric-update", CustomMetricUpdate { name, value })
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("Failed to emit tracker event: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start(actor: &mut TrackerActor, resume: bool) -> bool {
+        let (reply, mut reply_rx) = oneshot::channel();
+        actor.handle(TrackerMsg::Start { resume, reply });
+        reply_rx.try_recv().unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_level_emits_only_on_change() {
+        let mut actor = TrackerActor::new().unwrap();
+
+        assert_eq!(actor.handle(TrackerMsg::LevelRead(50)), vec![TrackerEvent::Level(50)]);
+        assert!(actor.handle(TrackerMsg::LevelRead(50)).is_empty());
+        assert_eq!(actor.handle(TrackerMsg::LevelRead(51)), vec![TrackerEvent::Level(51)]);
+        assert_eq!(actor.stats().level, Some(51));
+    }
+
+    fn manual(
+        actor: &mut TrackerActor,
+        make_msg: impl FnOnce(oneshot::Sender<Result<(), String>>) -> TrackerMsg,
+    ) -> Result<(), String> {
+        let (reply, mut reply_rx) = oneshot::channel();
+        actor.handle(make_msg(reply));
+        reply_rx.try_recv().unwrap()
+    }
+
+    #[test]
+    fn test_manual_entries_are_validated_by_the_actor() {
+        let mut actor = TrackerActor::new().unwrap();
+
+        assert!(manual(&mut actor, |reply| TrackerMsg::ManualExp { exp: 1000, percentage: 10.0, reply }).is_err());
+        assert!(manual(&mut actor, |reply| TrackerMsg::ManualLevel { level: 0, reply }).is_err());
+        assert_eq!(actor.stats().level, None);
+
+        manual(&mut actor, |reply| TrackerMsg::ManualLevel { level: 50, reply }).unwrap();
+        assert!(manual(&mut actor, |reply| TrackerMsg::ManualExp { exp: 1000, percentage: 100.5, reply }).is_err());
+        assert_eq!(actor.stats().exp, None);
+
+        manual(&mut actor, |reply| TrackerMsg::ManualExp { exp: 1000, percentage: 10.0, reply }).unwrap();
+        assert_eq!(actor.stats().level, Some(50));
+        assert_eq!(actor.stats().exp, Some(1000));
+    }
+
+    #[test]
+    fn test_exp_accumulates_after_level_known() {
+        let mut actor = TrackerActor::new().unwrap();
+
+        // EXP before level is only displayed, not fed to the calculator
+        actor.handle(TrackerMsg::ExpRead { exp: 500, percentage: 5.0, timing: None });
+        assert!(!actor.exp.session_started);
+
+        actor.handle(TrackerMsg::LevelRead(50));
+        actor.handle(TrackerMsg::ExpRead { exp: 1000, percentage: 10.0, timing: None });
+        let events = actor.handle(TrackerMsg::ExpRead { exp: 1500, percentage: 15.0, timing: None });
+
+        assert_eq!(events, vec![TrackerEvent::Exp { exp: 1500, percentage: 15.0 }]);
+        assert_eq!(actor.stats().total_exp, 500);
+    }
+
+    #[test]
+    fn test_resume_continues_existing_session() {
+        let mut actor = TrackerActor::new().unwrap();
+
+        assert!(start(&mut actor, false));
+        assert!(!start(&mut actor, false)); // Already tracking
+
+        actor.handle(TrackerMsg::LevelRead(50));
+        actor.handle(TrackerMsg::ExpRead { exp: 1000, percentage: 10.0, timing: None });
+        actor.handle(TrackerMsg::Stop);
+
+        assert!(start(&mut actor, true));
+        let stats = actor.stats();
+        assert_eq!(stats.level, Some(50));
+        assert!(stats.is_tracking);
+        assert!(stats.resumed);
+    }
+
+    #[test]
+    fn test_start_after_stop_begins_fresh_session() {
+        let mut actor = TrackerActor::new().unwrap();
+
+        assert!(start(&mut actor, false));
+        actor.handle(TrackerMsg::LevelRead(50));
+        actor.handle(TrackerMsg::ExpRead { exp: 1000, percentage: 10.0, timing: None });
+        actor.handle(TrackerMsg::Stop);
+
+        assert!(start(&mut actor, false));
+        let stats = actor.stats();
+        assert_eq!(stats.level, None);
+        assert!(!stats.resumed);
+    }
+
+    #[test]
+    fn test_resume_without_session_starts_fresh() {
+        let mut actor = TrackerActor::new().unwrap();
+
+        assert!(start(&mut actor, true));
+        assert!(!actor.stats().resumed);
+    }
+
+    #[test]
+    fn test_unreadable_potion_slot_is_skipped() {
+        let mut actor = TrackerActor::new().unwrap();
+
+        let events = actor.handle(TrackerMsg::PotionRead { hp: Some(100), mp: None });
+
+        assert_eq!(events, vec![TrackerEvent::HpPotion(100)]);
+        assert_eq!(actor.stats().hp_potion_count, Some(100));
+        assert_eq!(actor.stats().mp_potion_count, None);
+    }
+
+    #[test]
+    fn test_custom_metric_emits_only_on_change() {
+        let mut actor = TrackerActor::new().unwrap();
+        let read = |value: i64| TrackerMsg::CustomMetricRead {
+            name: "fame".to_string(),
+            value: MetricValue::Integer(value),
+        };
+
+        assert_eq!(actor.handle(read(10)).len(), 1);
+        assert!(actor.handle(read(10)).is_empty());
+        assert_eq!(actor.handle(read(11)).len(), 1);
+        assert_eq!(actor.stats().custom_metrics.get("fame"), Some(&MetricValue::Integer(11)));
+    }
+
+    #[test]
+    fn test_derived_metrics_use_stats_and_custom_metrics() {
+        let mut actor = TrackerActor::new().unwrap();
+        actor.handle(TrackerMsg::SetDerivedMetrics(vec![
+            ("net_meso".to_string(), Expr::parse("meso - potions_used * 10").unwrap()),
+            ("per_level".to_string(), Expr::parse("meso / level").unwrap()),
+        ]));
+        actor.handle(TrackerMsg::CustomMetricRead {
+            name: "meso".to_string(),
+            value: MetricValue::Integer(1000),
+        });
+
+        // Level unknown yet, so only net_meso has a value
+        let derived = actor.stats().derived_metrics;
+        assert_eq!(derived.get("net_meso"), Some(&1000.0));
+        assert_eq!(derived.get("per_level"), None);
+    }
+
+    #[test]
+    fn test_reset_clears_state() {
+        let mut actor = TrackerActor::new().unwrap();
+
+        actor.handle(TrackerMsg::LevelRead(50));
+        actor.handle(TrackerMsg::PotionRead { hp: Some(100), mp: Some(50) });
+        actor.handle(TrackerMsg::HealthChanged(false));
+
+        let (reply_tx, _reply_rx) = oneshot::channel();
+        actor.handle(TrackerMsg::Reset(reply_tx));
+
+        let stats = actor.stats();
+        assert_eq!(stats.level, None);
+        assert_eq!(stats.hp_potion_count, None);
+        assert!(stats.ocr_server_healthy);
+        assert!(!stats.is_tracking);
+    }
+
+    #[test]
+    fn test_new_session_after_reset_starts_from_zero() {
+        let mut actor = TrackerActor::new().unwrap();
+
+        actor.handle(TrackerMsg::LevelRead(50));
+        actor.handle(TrackerMsg::ExpRead { exp: 9000, percentage: 90.0, timing: None });
+        actor.handle(TrackerMsg::ExpRead { exp: 9500, percentage: 95.0, timing: None });
+        actor.handle(TrackerMsg::PotionRead { hp: Some(100), mp: None });
+        actor.handle(TrackerMsg::PotionRead { hp: Some(90), mp: None });
+
+        let (reply_tx, _reply_rx) = oneshot::channel();
+        actor.handle(TrackerMsg::Reset(reply_tx));
+
+        // Lower readings than before the reset must not show up as negative gains
+        actor.handle(TrackerMsg::LevelRead(50));
+        actor.handle(TrackerMsg::ExpRead { exp: 1000, percentage: 10.0, timing: None });
+        actor.handle(TrackerMsg::PotionRead { hp: Some(200), mp: None });
+
+        let stats = actor.stats();
+        assert_eq!(stats.total_exp, 0);
+        assert_eq!(stats.total_percentage, 0.0);
+        assert_eq!(stats.hp_potions_used, 0);
+    }
+
+    #[test]
+    fn test_resume_keeps_totals() {
+        let mut actor = TrackerActor::new().unwrap();
+
+        assert!(start(&mut actor, false));
+        actor.handle(TrackerMsg::LevelRead(50));
+        actor.handle(TrackerMsg::ExpRead { exp: 1000, percentage: 10.0, timing: None });
+        actor.handle(TrackerMsg::ExpRead { exp: 1500, percentage: 15.0, timing: None });
+        actor.handle(TrackerMsg::Stop);
+
+        assert!(start(&mut actor, true));
+        actor.handle(TrackerMsg::ExpRead { exp: 1600, percentage: 16.0, timing: None });
+
+        let stats = actor.stats();
+        assert_eq!(stats.total_exp, 600);
+        assert_eq!(stats.total_percentage, 6.0);
+    }
+
+    #[test]
+    fn test_tick_advances_elapsed_without_exp_change() {
+        let mut actor = TrackerActor::new().unwrap();
+
+        assert!(start(&mut actor, false));
+        actor.handle(TrackerMsg::LevelRead(50));
+        actor.handle(TrackerMsg::ExpRead { exp: 1000, percentage: 10.0, timing: None });
+        actor.handle(TrackerMsg::ExpRead { exp: 1600, percentage: 16.0, timing: None });
+
+        // EXP stalls for two minutes: only the ticker moves the timer and rates
+        actor.exp.exp_calculator.start_time = Some(Instant::now() - Duration::from_secs(120));
+        assert!(actor.tick());
+        let stats = actor.stats();
+        assert_eq!(stats.elapsed_seconds, 120);
+        assert_eq!(stats.exp_per_hour, 18_000);
+        assert_eq!(stats.total_exp, 600);
+        assert!(!actor.tick());
+
+        // Stopped: the timer holds
+        actor.handle(TrackerMsg::Stop);
+        actor.exp.exp_calculator.start_time = Some(Instant::now() - Duration::from_secs(180));
+        assert!(!actor.tick());
+        assert_eq!(actor.stats().elapsed_seconds, 120);
+    }
+
+    #[test]
+    fn test_timeline_starts_with_session() {
+        let mut actor = TrackerActor::new().unwrap();
+
+        actor.handle(TrackerMsg::LevelRead(50));
+        actor.handle(TrackerMsg::ExpRead { exp: 1000, percentage: 10.0, timing: None });
+        actor.handle(TrackerMsg::ExpRead { exp: 1100, percentage: 11.0, timing: None });
+
+        // Less than one interval in: only the starting point
+        assert_eq!(actor.exp.timeline.len(), 1);
+        assert_eq!((actor.exp.timeline[0].elapsed_seconds, actor.exp.timeline[0].total_exp), (0, 0));
+        assert!(actor.exp.timeline[0].recorded_at.is_some());
+    }
+
+    #[test]
+    fn test_latency_tags_new_timeline_point() {
+        let mut actor = TrackerActor::new().unwrap();
+        let sample = LatencySample {
+            capture: Duration::from_millis(15),
+            ocr: Duration::from_millis(400),
+            delivery: Duration::from_millis(5),
+        };
+
+        actor.handle(TrackerMsg::LevelRead(50));
+        actor.handle(TrackerMsg::ExpRead { exp: 1000, percentage: 10.0, timing: None });
+        actor.record_latency(sample, 0);
+        assert_eq!(actor.exp.timeline[0].latency_ms, Some(420));
+
+        // No point added by this reading: the existing one keeps its latency
+        actor.handle(TrackerMsg::ExpRead { exp: 1100, percentage: 11.0, timing: None });
+        actor.record_latency(LatencySample { ocr: Duration::from_secs(3), ..sample }, 1);
+        assert_eq!(actor.exp.timeline[0].latency_ms, Some(420));
+    }
+
+    #[test]
+    fn test_finish_flushes_final_point() {
+        let mut actor = TrackerActor::new().unwrap();
+        let (reply, mut reply_rx) = oneshot::channel();
+        actor.handle(TrackerMsg::Finish(reply));
+        assert!(reply_rx.try_recv().unwrap().is_err());
+
+        assert!(start(&mut actor, false));
+        actor.handle(TrackerMsg::LevelRead(50));
+        actor.handle(TrackerMsg::ExpRead { exp: 1000, percentage: 10.0, timing: None });
+        actor.exp.elapsed_seconds = 45;
+        actor.exp.total_exp = 900;
+
+        let (reply, mut reply_rx) = oneshot::channel();
+        actor.handle(TrackerMsg::Finish(reply));
+        let finished = reply_rx.try_recv().unwrap().unwrap();
+
+        assert!(!finished.stats.is_tracking);
+        assert_eq!(finished.timeline.last().map(|p| (p.elapsed_seconds, p.total_exp)), Some((45, 900)));
+        assert_eq!(finished.timeline.len(), 2);
+    }
+
+    #[test]
+    fn test_rates_estimating_during_warmup() {
+        let mut actor = TrackerActor::new().unwrap();
+        actor.handle(TrackerMsg::SetRateWarmup(60));
+        assert!(start(&mut actor, false));
+
+        actor.exp.elapsed_seconds = 59;
+        assert!(actor.stats().rates_estimating);
+        actor.exp.elapsed_seconds = 60;
+        assert!(!actor.stats().rates_estimating);
+
+        actor.handle(TrackerMsg::SetRateWarmup(0));
+        actor.exp.elapsed_seconds = 0;
+        assert!(!actor.stats().rates_estimating);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::models::roi::Roi;
+use crate::services::event_log;
+use crate::services::ocr_tracker::OcrTracker;
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::AppHandle;
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+/// Capacity of the control mailbox (rapid clicks queue up here)
+const MAILBOX_CAPACITY: usize = 32;
+
+/// Where the OCR loops are in their lifecycle, emitted as "tracking:lifecycle"
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Lifecycle {
+    Stopped,
+    Starting,
+    Running,
+    Stopping,
+}
+
+impl Lifecycle {
+    /// Catch up with tracking stopped or started outside the control channel
+    /// (finish_session, app exit)
+    fn sync(self, is_tracking: bool) -> Self {
+        match (self, is_tracking) {
+            (Lifecycle::Running, false) => Lifecycle::Stopped,
+            (Lifecycle::Stopped, true) => Lifecycle::Running,
+            (state, _) => state,
+        }
+    }
+
+    /// Whether `command` changes anything in this state; others are no-ops
+    fn accepts(self, command: &Command) -> bool {
+        match command {
+            Command::Start { .. } => self == Lifecycle::Stopped,
+            Command::Stop => self == Lifecycle::Running,
+            Command::Reset => true,
+        }
+    }
+}
+
+/// Lifecycle command
+#[derive(Debug)]
+enum Command {
+    Start { level_roi: Roi, exp_roi: Roi, resume: bool },
+    Stop,
+    Reset,
+}
+
+struct ControlMsg {
+    command: Command,
+    reply: oneshot::Sender<Result<(), String>>,
+}
+
+/// Single entry point for starting and stopping tracking
+///
+/// Commands are applied one at a time in arrival order, so overlapping start
+/// and stop calls (rapid clicks, hotkeys) queue up instead of interleaving, and
+/// a start while running or a stop while stopped is ignored.
+#[derive(Clone)]
+pub struct TrackingControl {
+    tx: mpsc::Sender<ControlMsg>,
+}
+
+impl TrackingControl {
+    /// Spawn the control task for `tracker`
+    pub fn spawn(app: AppHandle, tracker: Arc<Mutex<OcrTracker>>) -> Self {
+        let (tx, mut rx) = mpsc::channel::<ControlMsg>(MAILBOX_CAPACITY);
+
+        tauri::async_runtime::spawn(async move {
+            let mut lifecycle = Lifecycle::Stopped;
+
+            while let Some(ControlMsg { command, reply }) = rx.recv().await {
+                let mut tracker = tracker.lock().await;
+                lifecycle = lifecycle.sync(tracker.get_stats().await.is_tracking);
+
+                if !lifecycle.accepts(&command) {
+                    #[cfg(debug_assertions)]
+                    println!("⏭️ Ignoring {:?} while {:?}", command, lifecycle);
+
+                    let _ = reply.send(Ok(()));
+                    continue;
+                }
+
+                let result = match command {
+                    Command::Start { level_roi, exp_roi, resume } => {
+                        set_lifecycle(&app, &mut lifecycle, Lifecycle::Starting);
+                        let result = tracker.start_tracking(level_roi, exp_roi, resume).await;
+                        let next = if result.is_ok() { Lifecycle::Running } else { Lifecycle::Stopped };
+                        set_lifecycle(&app, &mut lifecycle, next);
+                        result
+                    }
+                    Command::Stop => {
+                        set_lifecycle(&app, &mut lifecycle, Lifecycle::Stopping);
+                        tracker.stop_tracking().await;
+                        set_lifecycle(&app, &mut lifecycle, Lifecycle::Stopped);
+                        Ok(())
+                    }
+                    Command::Reset => {
+                        set_lifecycle(&app, &mut lifecycle, Lifecycle::Stopping);
+                        let result = tracker.reset().await;
+                        set_lifecycle(&app, &mut lifecycle, Lifecycle::Stopped);
+                        result
+                    }
+                };
+                let _ = reply.send(result);
+            }
+        });
+
+        Self { tx }
+    }
+
+    /// Start a fresh session, or continue the stopped one with `resume`
+    pub async fn start(&self, level_roi: Roi, exp_roi: Roi, resume: bool) -> Result<(), String> {
+        self.request(Command::Start { level_roi, exp_roi, resume }).await
+    }
+
+    pub async fn stop(&self) -> Result<(), String> {
+        self.request(Command::Stop).await
+    }
+
+    pub async fn reset(&self) -> Result<(), String> {
+        self.request(Command::Reset).await
+    }
+
+    async fn request(&self, command: Command) -> Result<(), String> {
+        let (reply, reply_rx) = oneshot::channel();
+        self.tx
+            .send(ControlMsg { command, reply })
+            .await
+            .map_err(|_| "Tracking control is not running".to_string())?;
+        reply_rx
+            .await
+            .map_err(|_| "Tracking control dropped the request".to_string())?
+    }
+}
+
+fn set_lifecycle(app: &AppHandle, lifecycle: &mut Lifecycle, next: Lifecycle) {
+    *lifecycle = next;
+    if let Err(e) = event_log::emit(app, "tracking:lifecycle", next) {
+        eprintln!("Failed to emit lifecycle event: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start() -> Command {
+        let roi = Roi { x: 0, y: 0, width: 100, height: 20 };
+        Command::Start { level_roi: roi, exp_roi: roi, resume: false }
+    }
+
+    #[test]
+    fn test_repeated_commands_are_ignored() {
+        assert!(Lifecycle::Stopped.accepts(&start()));
+        assert!(!Lifecycle::Running.accepts(&start()));
+        assert!(Lifecycle::Running.accepts(&Command::Stop));
+        assert!(!Lifecycle::Stopped.accepts(&Command::Stop));
+        assert!(Lifecycle::Stopped.accepts(&Command::Reset));
+    }
+
+    #[test]
+    fn test_sync_follows_tracker() {
+        assert_eq!(Lifecycle::Running.sync(false), Lifecycle::Stopped);
+        assert_eq!(Lifecycle::Stopped.sync(true), Lifecycle::Running);
+        assert_eq!(Lifecycle::Running.sync(true), Lifecycle::Running);
+        assert_eq!(Lifecycle::Stopped.sync(false), Lifecycle::Stopped);
+    }
+}
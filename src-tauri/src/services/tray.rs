@@ -0,0 +1,53 @@
+use crate::services::window_state::MAIN_WINDOW;
+use tauri::menu::{Menu, MenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager};
+
+/// Id of the app's tray icon
+pub const TRAY_ID: &str = "main";
+
+const SHOW_ITEM: &str = "show";
+const QUIT_ITEM: &str = "quit";
+
+/// Add the tray icon with "Show window" and "Quit"
+///
+/// Quit is the only way out while the main window is hidden to the tray,
+/// so it runs the full shutdown (save, stop tracking, stop the OCR server).
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    let tray_error = |e: tauri::Error| format!("Failed to create tray icon: {}", e);
+
+    let show = MenuItem::with_id(app, SHOW_ITEM, "Show window", true, None::<&str>).map_err(tray_error)?;
+    let quit = MenuItem::with_id(app, QUIT_ITEM, "Quit", true, None::<&str>).map_err(tray_error)?;
+    let menu = Menu::with_items(app, &[&show, &quit]).map_err(tray_error)?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("exp-tracker")
+        .menu(&menu)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            SHOW_ITEM => show_main_window(app),
+            QUIT_ITEM => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(crate::services::shutdown::run(app));
+            }
+            _ => {}
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app).map_err(tray_error)?;
+    Ok(())
+}
+
+/// Whether the tray icon exists (closing to the tray needs a way back)
+pub fn is_available(app: &AppHandle) -> bool {
+    app.tray_by_id(TRAY_ID).is_some()
+}
+
+/// Bring the main window back from the tray
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
@@ -0,0 +1,134 @@
+use crate::services::ocr::http_ocr::REQUEST_TIMEOUT;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A loop is stalled once it hasn't iterated for this many of its intervals
+/// on top of the OCR requests it may wait out (see `stall_threshold`)
+pub const STALL_FACTOR: u32 = 3;
+
+/// Longest a healthy iteration can take: one iteration may wait out the
+/// request timeout twice (level and inventory are read from the same capture)
+fn stall_threshold(interval: Duration) -> Duration {
+    interval * STALL_FACTOR + REQUEST_TIMEOUT * 2
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Heartbeat {
+    at: Instant,
+    interval: Duration,
+}
+
+/// Payload for "tracking:loop-stalled"
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StalledLoop {
+    pub name: String,
+    /// Time since the loop last iterated
+    pub stalled_seconds: u64,
+}
+
+/// Last iteration of every OCR loop, shared by the loops and the health loop
+///
+/// A loop whose await hangs (e.g. a wedged HTTP connection) stops beating,
+/// which is the only way to tell it apart from a loop with nothing to report.
+#[derive(Clone, Default)]
+pub struct Heartbeats(Arc<Mutex<HashMap<String, Heartbeat>>>);
+
+impl Heartbeats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an iteration of `name`, which runs every `interval`
+    pub fn beat(&self, name: &str, interval: Duration) {
+        self.beat_at(name, interval, Instant::now());
+    }
+
+    fn beat_at(&self, name: &str, interval: Duration, at: Instant) {
+        if let Ok(mut beats) = self.0.lock() {
+            beats.insert(name.to_string(), Heartbeat { at, interval });
+        }
+    }
+
+    /// Stop watching `name`
+    pub fn remove(&self, name: &str) {
+        if let Ok(mut beats) = self.0.lock() {
+            beats.remove(name);
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut beats) = self.0.lock() {
+            beats.clear();
+        }
+    }
+
+    /// Treat every loop as having just iterated (e.g. after the system slept)
+    pub fn refresh(&self) {
+        let now = Instant::now();
+        if let Ok(mut beats) = self.0.lock() {
+            for beat in beats.values_mut() {
+                beat.at = now;
+            }
+        }
+    }
+
+    /// Loops that haven't iterated within their stall threshold
+    pub fn stalled(&self) -> Vec<StalledLoop> {
+        self.stalled_at(Instant::now())
+    }
+
+    fn stalled_at(&self, now: Instant) -> Vec<StalledLoop> {
+        let Ok(beats) = self.0.lock() else {
+            return Vec::new();
+        };
+
+        let mut stalled: Vec<StalledLoop> = beats.iter()
+            .filter_map(|(name, beat)| {
+                let since = now.saturating_duration_since(beat.at);
+                (since > stall_threshold(beat.interval)).then(|| StalledLoop {
+                    name: name.clone(),
+                    stalled_seconds: since.as_secs(),
+                })
+            })
+            .collect();
+        stalled.sort_by(|a, b| a.name.cmp(&b.name));
+        stalled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loop_stalls_after_intervals_and_request_timeouts() {
+        let heartbeats = Heartbeats::new();
+        let start = Instant::now();
+
+        heartbeats.beat_at("exp", Duration::from_secs(1), start);
+        heartbeats.beat_at("custom:fame", Duration::from_secs(10), start);
+
+        // A slow but healthy iteration that waited out the request timeout
+        assert!(heartbeats.stalled_at(start + Duration::from_secs(1) + REQUEST_TIMEOUT).is_empty());
+        assert!(heartbeats.stalled_at(start + Duration::from_secs(13)).is_empty());
+
+        let stalled = heartbeats.stalled_at(start + Duration::from_secs(14));
+        assert_eq!(stalled, vec![StalledLoop { name: "exp".to_string(), stalled_seconds: 14 }]);
+
+        heartbeats.remove("exp");
+        assert!(heartbeats.stalled_at(start + Duration::from_secs(14)).is_empty());
+    }
+
+    #[test]
+    fn test_refresh_clears_stalls() {
+        let heartbeats = Heartbeats::new();
+        let start = Instant::now();
+        heartbeats.beat_at("exp", Duration::from_secs(1), start);
+        assert_eq!(heartbeats.stalled_at(start + Duration::from_secs(60)).len(), 1);
+
+        heartbeats.refresh();
+        assert!(heartbeats.stalled_at(Instant::now() + Duration::from_secs(1)).is_empty());
+    }
+}
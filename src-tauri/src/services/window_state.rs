@@ -0,0 +1,268 @@
+use crate::commands::config::ConfigManagerState;
+use crate::models::config::{WindowConfig, WindowDimensions};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, LogicalPosition, Manager, WebviewWindow, Window};
+
+/// Wait for moves/resizes to settle before writing the config file
+pub const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Pixels of the window that must stay on a monitor to count as reachable
+pub const MIN_VISIBLE: i32 = 50;
+
+/// Label of the main tracker window (tauri.conf.json default)
+pub const MAIN_WINDOW: &str = "main";
+
+/// Label of the frameless overlay window
+pub const OVERLAY_WINDOW: &str = "overlay";
+
+/// Label of the secondary live graph/stats window
+pub const STATS_WINDOW: &str = "stats";
+
+/// Monitor area in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Debounce counters for geometry saves, by window label (only the latest
+/// event of each window saves)
+#[derive(Default)]
+pub struct WindowGeometryState {
+    generations: Mutex<HashMap<String, u64>>,
+}
+
+impl WindowGeometryState {
+    /// Start a new debounce round for `label` and return its generation
+    fn next(&self, label: &str) -> u64 {
+        let Ok(mut generations) = self.generations.lock() else {
+            return 0;
+        };
+        let generation = generations.entry(label.to_string()).or_default();
+        *generation += 1;
+        *generation
+    }
+
+    /// Whether no newer event for `label` came in since `generation`
+    fn is_latest(&self, label: &str, generation: u64) -> bool {
+        self.generations
+            .lock()
+            .is_ok_and(|generations| generations.get(label) == Some(&generation))
+    }
+}
+
+fn overlap(start_a: i32, len_a: u32, start_b: i32, len_b: u32) -> i32 {
+    let end = (start_a as i64 + len_a as i64).min(start_b as i64 + len_b as i64);
+    (end - (start_a.max(start_b) as i64)).max(0) as i32
+}
+
+/// Whether the window's top edge and enough of its width sit on some monitor,
+/// so the user can still grab and drag it
+pub fn is_on_screen(dims: &WindowDimensions, monitors: &[MonitorBounds]) -> bool {
+    let min_width = MIN_VISIBLE.min(dims.width as i32);
+
+    monitors.iter().any(|monitor| {
+        let top_visible = dims.y >= monitor.y
+            && (dims.y as i64) < monitor.y as i64 + monitor.height as i64 - MIN_VISIBLE as i64;
+        top_visible && overlap(dims.x, dims.width, monitor.x, monitor.width) >= min_width
+    })
+}
+
+/// Whether the window fills a whole monitor (e.g. stretched for ROI selection)
+pub fn covers_monitor(dims: &WindowDimensions, monitors: &[MonitorBounds]) -> bool {
+    monitors.iter().any(|monitor| {
+        overlap(dims.x, dims.width, monitor.x, monitor.width) >= monitor.width as i32
+            && overlap(dims.y, dims.height, monitor.y, monitor.height) >= monitor.height as i32
+    })
+}
+
+/// Connected monitors in logical pixels
+pub fn monitor_bounds(app: &AppHandle) -> Vec<MonitorBounds> {
+    let monitors = match app.available_monitors() {
+        Ok(monitors) => monitors,
+        Err(e) => {
+            eprintln!("⚠️ Failed to list monitors: {}", e);
+            return Vec::new();
+        }
+    };
+
+    monitors
+        .iter()
+        .map(|monitor| {
+            let scale = monitor.scale_factor();
+            let position = monitor.position().to_logical::<i32>(scale);
+            let size = monitor.size().to_logical::<u32>(scale);
+            MonitorBounds {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            }
+        })
+        .collect()
+}
+
+/// Window geometry in logical pixels (matches what the frontend sets)
+pub fn current_geometry(window: &Window) -> Result<WindowDimensions, String> {
+    let scale = window.scale_factor().map_err(|e| format!("Failed to get scale factor: {}", e))?;
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to get window position: {}", e))?
+        .to_logical::<i32>(scale);
+    let size = window
+        .inner_size()
+        .map_err(|e| format!("Failed to get window size: {}", e))?
+        .to_logical::<u32>(scale);
+
+    Ok(WindowDimensions {
+        width: size.width,
+        height: size.height,
+        x: position.x,
+        y: position.y,
+    })
+}
+
+/// Save the window geometry for the current mode once moves/resizes stop
+pub fn schedule_save(window: &Window) {
+    let Some(state) = window.app_handle().try_state::<WindowGeometryState>() else {
+        return;
+    };
+    let generation = state.next(window.label());
+    let window = window.clone();
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DEBOUNCE).await;
+
+        let latest = window
+            .app_handle()
+            .try_state::<WindowGeometryState>()
+            .is_some_and(|state| state.is_latest(window.label(), generation));
+        if !latest {
+            return;
+        }
+
+        if let Err(e) = save_geometry(&window) {
+            eprintln!("⚠️ Failed to save window geometry: {}", e);
+        }
+    });
+}
+
+fn save_geometry(window: &Window) -> Result<(), String> {
+    // Minimized windows report bogus positions (-32000 on Windows)
+    if window.is_minimized().unwrap_or(false) || window.is_maximized().unwrap_or(false) {
+        return Ok(());
+    }
+
+    let geometry = current_geometry(window)?;
+    let monitors = monitor_bounds(window.app_handle());
+    if covers_monitor(&geometry, &monitors) || !is_on_screen(&geometry, &monitors) {
+        return Ok(());
+    }
+
+    let config_state = window
+        .app_handle()
+        .try_state::<ConfigManagerState>()
+        .ok_or("Config manager not available")?;
+    let manager = config_state
+        .lock()
+        .map_err(|e| format!("Failed to lock config manager: {}", e))?;
+
+    // Secondary windows have their own geometry; the main window saves per mode
+    let mut config = manager.load()?;
+    let mode = config.window.current_mode.clone();
+    let saved = match window.label() {
+        OVERLAY_WINDOW => &mut config.window.overlay,
+        STATS_WINDOW => &mut config.window.stats,
+        _ => config.window.dimensions_mut(&mode),
+    };
+    if *saved == geometry {
+        return Ok(());
+    }
+
+    *saved = geometry;
+    manager.save(&config)?;
+
+    #[cfg(debug_assertions)]
+    println!("🪟 Saved '{}' window geometry", window.label());
+
+    Ok(())
+}
+
+/// Move the window to the saved position of the current mode
+/// Returns false when the saved position would be off-screen (e.g. monitor unplugged).
+/// Size is left to the frontend, which sizes the window per panel.
+pub fn restore(window: &WebviewWindow, config: &WindowConfig) -> Result<bool, String> {
+    let dims = config.dimensions(&config.current_mode);
+    let monitors = monitor_bounds(window.app_handle());
+
+    if !is_on_screen(dims, &monitors) {
+        println!("🪟 Saved window position ({}, {}) is off-screen, keeping default", dims.x, dims.y);
+        return Ok(false);
+    }
+
+    window
+        .set_position(LogicalPosition::new(dims.x, dims.y))
+        .map_err(|e| format!("Failed to restore window position: {}", e))?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(x: i32, y: i32, width: u32, height: u32) -> MonitorBounds {
+        MonitorBounds { x, y, width, height }
+    }
+
+    fn window(x: i32, y: i32, width: u32, height: u32) -> WindowDimensions {
+        WindowDimensions { width, height, x, y }
+    }
+
+    #[test]
+    fn test_on_screen_with_second_monitor() {
+        let monitors = [monitor(0, 0, 1920, 1080), monitor(1920, 0, 2560, 1440)];
+
+        assert!(is_on_screen(&window(100, 100, 540, 130), &monitors));
+        assert!(is_on_screen(&window(3000, 1200, 540, 130), &monitors));
+        // Mostly hanging off the right edge of the second monitor but still grabbable
+        assert!(is_on_screen(&window(4400, 100, 540, 130), &monitors));
+    }
+
+    #[test]
+    fn test_off_screen_after_monitor_removed() {
+        let monitors = [monitor(0, 0, 1920, 1080)];
+
+        assert!(!is_on_screen(&window(3000, 100, 540, 130), &monitors));
+        assert!(!is_on_screen(&window(100, -200, 540, 130), &monitors));
+        assert!(!is_on_screen(&window(100, 1060, 540, 130), &monitors));
+        assert!(!is_on_screen(&window(100, 100, 540, 130), &[]));
+    }
+
+    #[test]
+    fn test_covers_monitor() {
+        let monitors = [monitor(0, 0, 1920, 1080), monitor(1920, 0, 2560, 1440)];
+
+        assert!(covers_monitor(&window(0, 0, 1920, 1080), &monitors));
+        assert!(!covers_monitor(&window(0, 0, 1000, 700), &monitors));
+    }
+
+    #[test]
+    fn test_debounce_is_per_window() {
+        let state = WindowGeometryState::default();
+        let main = state.next(MAIN_WINDOW);
+        let overlay = state.next(OVERLAY_WINDOW);
+
+        // Moving the overlay doesn't cancel the main window's pending save
+        assert!(state.is_latest(MAIN_WINDOW, main));
+        assert!(state.is_latest(OVERLAY_WINDOW, overlay));
+
+        let main_again = state.next(MAIN_WINDOW);
+        assert!(!state.is_latest(MAIN_WINDOW, main));
+        assert!(state.is_latest(MAIN_WINDOW, main_again));
+    }
+}
@@ -123,7 +123,7 @@ export function CompactRoiManager({ onSelectingChange }: CompactRoiManagerProps)
 
     // Step 5: Capture the clean screen and show preview
     try {
-      const bytes = await captureRegion(roi);
+      const { bytes } = await captureRegion(roi);
       const dataUrl = bytesToDataUrl(bytes);
 
       // Save to temp folder via Tauri command
@@ -191,7 +191,7 @@ export function CompactRoiManager({ onSelectingChange }: CompactRoiManagerProps)
       }
 
       // Capture the region in real-time
-      const bytes = await captureRegion(captureRoi);
+      const { bytes } = await captureRegion(captureRoi);
       const dataUrl = bytesToDataUrl(bytes);
 
       setPreviewImage(dataUrl);
@@ -86,7 +86,7 @@ export function RoiDemo({ onSelectingChange }: RoiDemoProps) {
     onSelectingChange?.(false);
 
     try {
-      const bytes = await captureRegion(roi);
+      const { bytes } = await captureRegion(roi);
       const dataUrl = bytesToDataUrl(bytes);
       setCapturedImage(dataUrl);
       setError(null);
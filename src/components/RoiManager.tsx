@@ -96,7 +96,7 @@ export function RoiManager({ onSelectingChange }: RoiManagerProps) {
       for (const [type, roi] of Object.entries(rois)) {
         if (roi && !previews[type as RoiType]) {
           try {
-            const bytes = await captureRegion(roi);
+            const { bytes } = await captureRegion(roi);
             const dataUrl = bytesToDataUrl(bytes);
             setPreviews((prev) => ({ ...prev, [type]: dataUrl }));
           } catch (err) {
@@ -127,7 +127,7 @@ export function RoiManager({ onSelectingChange }: RoiManagerProps) {
 
     // Generate preview
     try {
-      const bytes = await captureRegion(roi);
+      const { bytes } = await captureRegion(roi);
       const dataUrl = bytesToDataUrl(bytes);
       setPreviews((prev) => ({ ...prev, [currentRoiType]: dataUrl }));
     } catch (err) {
@@ -50,23 +50,44 @@ export async function getScreenDimensions(): Promise<[number, number]> {
 }
 
 /**
- * Capture a specific region and return as PNG bytes
+ * Encoded image and the size it was encoded at
  */
-export async function captureRegion(roi: Roi): Promise<number[]> {
-  return invoke('capture_region', { roi });
+export interface CapturedImage {
+  bytes: Uint8Array;
+  width: number;
+  height: number;
+}
+
+/**
+ * Split a capture response: width (u32 LE), height (u32 LE), then the image bytes
+ */
+function parseCapturedImage(buffer: ArrayBuffer): CapturedImage {
+  const view = new DataView(buffer);
+  return {
+    width: view.getUint32(0, true),
+    height: view.getUint32(4, true),
+    bytes: new Uint8Array(buffer, 8),
+  };
+}
+
+/**
+ * Capture a specific region as PNG
+ */
+export async function captureRegion(roi: Roi): Promise<CapturedImage> {
+  return parseCapturedImage(await invoke<ArrayBuffer>('capture_region', { roi }));
 }
 
 /**
- * Capture full screen and return as PNG bytes
+ * Capture full screen as PNG (shrunk to fit the default payload cap)
  */
-export async function captureFullScreen(): Promise<number[]> {
-  return invoke('capture_full_screen');
+export async function captureFullScreen(): Promise<CapturedImage> {
+  return parseCapturedImage(await invoke<ArrayBuffer>('capture_full_screen'));
 }
 
 /**
  * Convert PNG bytes to base64 data URL for display
  */
-export function bytesToDataUrl(bytes: number[]): string {
+export function bytesToDataUrl(bytes: number[] | Uint8Array): string {
   const uint8Array = new Uint8Array(bytes);
 
   // Convert to base64 in chunks to avoid stack overflow with large images
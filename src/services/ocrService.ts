@@ -15,7 +15,7 @@ import {
 /**
  * Convert raw PNG bytes to base64 string
  */
-function bytesToBase64(bytes: number[]): string {
+function bytesToBase64(bytes: number[] | Uint8Array): string {
   const uint8Array = new Uint8Array(bytes);
   let binary = '';
   const chunkSize = 8192;
@@ -62,7 +62,7 @@ export class OcrService {
       }
 
       try {
-        const bytes = await captureRegion(roi);
+        const { bytes } = await captureRegion(roi);
         const base64 = bytesToBase64(bytes);
         const result = await recognizeLevel(base64);
         const elapsed = Date.now() - startTime;
@@ -102,7 +102,7 @@ export class OcrService {
       }
 
       try {
-        const bytes = await captureRegion(roi);
+        const { bytes } = await captureRegion(roi);
         const base64 = bytesToBase64(bytes);
         const result = await recognizeExp(base64);
         const elapsed = Date.now() - startTime;
@@ -142,7 +142,7 @@ export class OcrService {
       }
 
       try {
-        const bytes = await captureRegion(roi);
+        const { bytes } = await captureRegion(roi);
         const base64 = bytesToBase64(bytes);
         const result = await recognizeHpPotionCount(base64);
         const elapsed = Date.now() - startTime;
@@ -182,7 +182,7 @@ export class OcrService {
       }
 
       try {
-        const bytes = await captureRegion(roi);
+        const { bytes } = await captureRegion(roi);
         const base64 = bytesToBase64(bytes);
         const result = await recognizeMpPotionCount(base64);
         const elapsed = Date.now() - startTime;